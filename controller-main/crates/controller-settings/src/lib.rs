@@ -79,6 +79,9 @@ pub struct Settings {
     #[serde(default)]
     pub call_in: Option<CallIn>,
 
+    #[serde(default)]
+    pub janus_admin: Option<JanusAdmin>,
+
     #[serde(default)]
     pub defaults: Defaults,
 
@@ -93,6 +96,9 @@ pub struct Settings {
     #[serde(default)]
     pub tariffs: Tariffs,
 
+    #[serde(default)]
+    pub recording: Recording,
+
     #[serde(flatten)]
     pub extensions: HashMap<String, config::Value>,
 }
@@ -292,6 +298,10 @@ pub struct RabbitMqConfig {
     /// Recording is disabled if this isn't set
     #[serde(default)]
     pub recording_task_queue: Option<String>,
+
+    /// Dialing out to phone numbers is disabled if this isn't set
+    #[serde(default)]
+    pub dial_out_task_queue: Option<String>,
 }
 
 impl Default for RabbitMqConfig {
@@ -302,6 +312,7 @@ impl Default for RabbitMqConfig {
             max_channels_per_connection: rabbitmq_default_max_channels(),
             mail_task_queue: None,
             recording_task_queue: None,
+            dial_out_task_queue: None,
         }
     }
 }
@@ -400,6 +411,41 @@ pub struct CallIn {
     pub default_country_code: phonenumber::country::Id,
 }
 
+#[derive(Clone, Debug, Deserialize)]
+pub struct JanusAdmin {
+    pub connections: Vec<JanusAdminConnection>,
+}
+
+/// Admin endpoint of a janus instance
+///
+/// The routing keys and exchange identify the `room_server` connection of the same instance.
+#[derive(Clone, Debug, Deserialize)]
+pub struct JanusAdminConnection {
+    /// Websocket url of the janus admin endpoint
+    pub url: url::Url,
+    /// The `admin_secret` configured in janus
+    #[serde(default)]
+    pub secret: Option<String>,
+    #[serde(default = "default_to_janus_routing_key")]
+    pub to_routing_key: String,
+    #[serde(default = "default_janus_exchange")]
+    pub exchange: String,
+    #[serde(default = "default_from_janus_routing_key")]
+    pub from_routing_key: String,
+}
+
+fn default_to_janus_routing_key() -> String {
+    "to-janus".to_owned()
+}
+
+fn default_janus_exchange() -> String {
+    "janus-exchange".to_owned()
+}
+
+fn default_from_janus_routing_key() -> String {
+    "from-janus".to_owned()
+}
+
 /// The namespace that is used by default
 pub const DEFAULT_NAMESPACE: &str = "core";
 
@@ -490,6 +536,51 @@ pub struct Tenants {
     pub assignment: TenantAssignment,
 }
 
+#[derive(Debug, Clone, Deserialize)]
+pub struct Recording {
+    #[serde(default)]
+    pub themes: Vec<RecordingTheme>,
+    /// Time in seconds a recorder has to join the room after it has been requested
+    #[serde(
+        deserialize_with = "duration_from_secs",
+        default = "default_recorder_timeout"
+    )]
+    pub recorder_timeout: Duration,
+}
+
+impl Default for Recording {
+    fn default() -> Self {
+        Self {
+            themes: Vec::new(),
+            recorder_timeout: default_recorder_timeout(),
+        }
+    }
+}
+
+fn default_recorder_timeout() -> Duration {
+    Duration::from_secs(60)
+}
+
+impl Recording {
+    /// Returns the theme of the tenant with the given OIDC tenant id
+    pub fn theme(&self, oidc_tenant_id: &str) -> Option<&serde_json::Value> {
+        self.themes
+            .iter()
+            .find(|theme| theme.tenant == oidc_tenant_id)
+            .map(|theme| &theme.theme)
+    }
+}
+
+/// Branding of the recordings of a tenant
+///
+/// The theme is passed to the recorder as is, which is responsible for validating it.
+#[derive(Debug, Clone, Deserialize)]
+pub struct RecordingTheme {
+    /// The OIDC tenant id of the tenant
+    pub tenant: String,
+    pub theme: serde_json::Value,
+}
+
 #[derive(Debug, Clone, Deserialize)]
 #[serde(rename_all = "snake_case", tag = "assignment")]
 pub enum TariffAssignment {
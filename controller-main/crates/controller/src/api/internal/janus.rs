@@ -0,0 +1,369 @@
+// SPDX-FileCopyrightText: OpenTalk GmbH <mail@opentalk.eu>
+//
+// SPDX-License-Identifier: EUPL-1.2
+
+use crate::api::v1::response::ApiError;
+use crate::settings::SharedSettingsActix;
+use actix_web::get;
+use actix_web::web::{Data, Json, Path, Query, ReqData};
+use anyhow::Context;
+use db_storage::users::User;
+use janus_client::admin::{AdminClient, AdminConfig, HandleFlags};
+use janus_client::{HandleId, SessionId};
+use janus_media::{get_publisher_info, mcu_id, MediaSessionKey};
+use kustos::prelude::*;
+use serde::{Deserialize, Serialize};
+use signaling_core::control::storage::get_all_participants;
+use signaling_core::{RedisConnection, SignalingRoomId};
+use std::collections::HashMap;
+use types::core::{BreakoutRoomId, ParticipantId, RoomId};
+use types::signaling::media::MediaSessionType;
+
+/// Videoroom handles keyed by the mcu id and the janus room id
+///
+/// Janus room ids are only unique per janus instance.
+type HandlesByRoom = HashMap<(String, u64), Vec<HandleHealth>>;
+
+#[derive(Debug, Deserialize)]
+pub struct JanusHealthQuery {
+    /// Inspect the given breakout room instead of the main room
+    #[serde(default)]
+    pub breakout_room: Option<BreakoutRoomId>,
+}
+
+/// Health view of the janus resources used by a room
+#[derive(Debug, Serialize)]
+pub struct JanusHealth {
+    /// All publishers of the room and the handles attached to their janus room
+    pub publishers: Vec<PublisherHealth>,
+    /// Admin endpoints which could not be queried
+    pub unreachable: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PublisherHealth {
+    pub participant_id: ParticipantId,
+    pub media_session_type: MediaSessionType,
+    pub mcu_id: String,
+    pub janus_room_id: u64,
+    /// Publisher and subscriber handles inside the janus room
+    pub handles: Vec<HandleHealth>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct HandleHealth {
+    pub session_id: SessionId,
+    pub handle_id: HandleId,
+    /// publisher|subscriber
+    pub kind: Option<String>,
+    /// ICE state of the PeerConnection, e.g. connected or failed
+    pub ice_state: Option<String>,
+    pub flags: HandleFlags,
+}
+
+/// API Endpoint *GET /rooms/{room_id}/janus*
+///
+/// Aggregates information of all configured janus admin endpoints for the publishers of the
+/// given room. Requires the current user to be allowed to modify the room.
+///
+/// Janus instances which cannot be reached are listed in [`JanusHealth::unreachable`] instead of
+/// failing the whole request.
+#[get("/rooms/{room_id}/janus")]
+pub async fn health(
+    settings: SharedSettingsActix,
+    redis_conn: Data<RedisConnection>,
+    room_id: Path<RoomId>,
+    query: Query<JanusHealthQuery>,
+    current_user: ReqData<User>,
+    authz: Data<Authz>,
+) -> Result<Json<JanusHealth>, ApiError> {
+    let room_id = room_id.into_inner();
+    let settings = settings.load_full();
+
+    let admin_settings = settings.janus_admin.as_ref().ok_or_else(|| {
+        ApiError::not_found().with_message("No janus admin endpoints configured")
+    })?;
+
+    if !authz
+        .check_user(current_user.id, room_id.resource_id(), AccessMethod::PATCH)
+        .await?
+    {
+        return Err(ApiError::forbidden());
+    }
+
+    let mut redis_conn = (**redis_conn).clone();
+
+    let room = SignalingRoomId::new(room_id, query.breakout_room);
+    let participants = get_all_participants(&mut redis_conn, room).await?;
+
+    let mut publishers = Vec::new();
+    for participant_id in participants {
+        for media_session_type in [MediaSessionType::Video, MediaSessionType::Screen] {
+            let info = get_publisher_info(
+                &mut redis_conn,
+                MediaSessionKey(participant_id, media_session_type),
+            )
+            .await
+            .context("Failed to get publisher info")?;
+
+            if let Some(info) = info {
+                publishers.push(PublisherHealth {
+                    participant_id,
+                    media_session_type,
+                    mcu_id: info.mcu_id.into_owned(),
+                    janus_room_id: info.room_id.into(),
+                    handles: Vec::new(),
+                });
+            }
+        }
+    }
+
+    let mut unreachable = Vec::new();
+
+    if !publishers.is_empty() {
+        let mut handles_by_room = HandlesByRoom::new();
+
+        for connection in &admin_settings.connections {
+            let config = AdminConfig::new(connection.url.as_str(), connection.secret.clone());
+            let mcu_id = mcu_id(
+                &connection.to_routing_key,
+                &connection.exchange,
+                &connection.from_routing_key,
+            );
+
+            if let Err(e) = collect_handles(config, &mcu_id, &mut handles_by_room).await {
+                log::warn!("Failed to query janus admin {}, {}", connection.url, e);
+                unreachable.push(connection.url.to_string());
+            }
+        }
+
+        assign_handles(&mut publishers, handles_by_room);
+    }
+
+    Ok(Json(JanusHealth {
+        publishers,
+        unreachable,
+    }))
+}
+
+/// Moves the handles of each publisher's janus room to the publisher
+fn assign_handles(publishers: &mut [PublisherHealth], mut handles_by_room: HandlesByRoom) {
+    for publisher in publishers {
+        publisher.handles = handles_by_room
+            .remove(&(publisher.mcu_id.clone(), publisher.janus_room_id))
+            .unwrap_or_default();
+    }
+}
+
+/// Collects all videoroom handles of the janus instance of the given mcu grouped by their janus room
+async fn collect_handles(
+    config: AdminConfig,
+    mcu_id: &str,
+    handles_by_room: &mut HandlesByRoom,
+) -> Result<(), janus_client::error::Error> {
+    let admin = AdminClient::connect(config).await?;
+
+    for session_id in admin.list_sessions().await? {
+        for handle_id in admin.list_handles(session_id).await? {
+            // Handles may vanish between listing and querying them
+            let info = match admin.handle_info(session_id, handle_id).await {
+                Ok(info) => info,
+                Err(janus_client::error::Error::JanusError(_)) => continue,
+                Err(e) => return Err(e),
+            };
+
+            if let Some(room) = info.videoroom_room() {
+                let kind = info
+                    .plugin_specific
+                    .as_ref()
+                    .and_then(|specific| specific.get("type"))
+                    .and_then(|kind| kind.as_str())
+                    .map(ToOwned::to_owned);
+
+                let key = (mcu_id.to_owned(), room);
+
+                handles_by_room.entry(key).or_default().push(HandleHealth {
+                    session_id,
+                    handle_id,
+                    kind,
+                    ice_state: info.ice_state().map(ToOwned::to_owned),
+                    flags: info.flags,
+                });
+            }
+        }
+    }
+
+    if let Err(e) = admin.destroy().await {
+        log::debug!("Failed to close janus admin connection, {}", e);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::api::v1::rooms::RoomsPoliciesBuilderExt;
+    use actix_web::dev::Service;
+    use actix_web::test::{call_and_read_body_json, call_service, init_service, TestRequest};
+    use actix_web::{App, HttpMessage};
+    use arc_swap::ArcSwap;
+    use controller_settings::{JanusAdmin, JanusAdminConnection, Settings};
+    use janus_media::{set_publisher_info, PublisherInfo};
+    use pretty_assertions::assert_eq;
+    use serial_test::serial;
+    use signaling_core::control::storage::add_participant_to_set;
+    use std::borrow::Cow;
+    use std::sync::Arc;
+    use test_util::{TestContext, ROOM_ID, USER_1};
+
+    fn handle(handle_id: u64) -> HandleHealth {
+        HandleHealth {
+            session_id: SessionId::new(1),
+            handle_id: HandleId::new(handle_id),
+            kind: Some("publisher".into()),
+            ice_state: Some("connected".into()),
+            flags: HandleFlags::default(),
+        }
+    }
+
+    fn publisher(mcu_id: &str, janus_room_id: u64) -> PublisherHealth {
+        PublisherHealth {
+            participant_id: ParticipantId::generate(),
+            media_session_type: MediaSessionType::Video,
+            mcu_id: mcu_id.into(),
+            janus_room_id,
+            handles: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn handles_of_equal_room_ids_on_different_mcus() {
+        let mut publishers = [publisher("mcu-a", 7), publisher("mcu-b", 7)];
+
+        let handles_by_room = HandlesByRoom::from([
+            (("mcu-a".to_owned(), 7), vec![handle(1)]),
+            (("mcu-b".to_owned(), 7), vec![handle(2), handle(3)]),
+            (("mcu-b".to_owned(), 8), vec![handle(4)]),
+        ]);
+
+        assign_handles(&mut publishers, handles_by_room);
+
+        let handle_ids = |publisher: &PublisherHealth| {
+            publisher
+                .handles
+                .iter()
+                .map(|handle| handle.handle_id)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(handle_ids(&publishers[0]), vec![HandleId::new(1)]);
+        assert_eq!(
+            handle_ids(&publishers[1]),
+            vec![HandleId::new(2), HandleId::new(3)]
+        );
+    }
+
+    #[actix_rt::test]
+    #[serial]
+    async fn health_endpoint() {
+        let test_ctx = TestContext::new().await;
+        let mut redis_conn = test_ctx.redis_conn.clone();
+
+        let user = test_ctx
+            .db_ctx
+            .create_test_user(USER_1.n, Vec::new())
+            .await
+            .unwrap();
+        let room = test_ctx
+            .db_ctx
+            .create_test_room(ROOM_ID, user.id, false)
+            .await
+            .unwrap();
+
+        test_ctx
+            .authz
+            .add_policies(
+                PoliciesBuilder::new()
+                    .grant_user_access(user.id)
+                    .room_write_access(room.id)
+                    .finish(),
+            )
+            .await
+            .unwrap();
+
+        add_participant_to_set(
+            &mut redis_conn,
+            SignalingRoomId::new(room.id, None),
+            USER_1.participant_id,
+        )
+        .await
+        .unwrap();
+
+        set_publisher_info(
+            &mut redis_conn,
+            MediaSessionKey(USER_1.participant_id, MediaSessionType::Video),
+            &PublisherInfo {
+                room_id: 42.into(),
+                mcu_id: Cow::Borrowed("to-janus-janus-exchange-from-janus"),
+                loop_index: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        let settings = Settings::load("../../extra/example.toml").unwrap();
+        let shared_settings = Data::new(ArcSwap::from_pointee(settings.clone()));
+
+        let app = init_service(
+            App::new()
+                .app_data(shared_settings.clone())
+                .app_data(Data::new(redis_conn))
+                .app_data(Data::from(test_ctx.authz.clone()))
+                .wrap_fn(move |req, srv| {
+                    req.extensions_mut().insert(user.clone());
+                    srv.call(req)
+                })
+                .service(health),
+        )
+        .await;
+
+        let uri = format!("/rooms/{}/janus", room.id);
+
+        // Not available without admin endpoints
+        let response = call_service(&app, TestRequest::get().uri(&uri).to_request()).await;
+        assert_eq!(response.status(), 404);
+
+        shared_settings.store(Arc::new(Settings {
+            janus_admin: Some(JanusAdmin {
+                connections: vec![JanusAdminConnection {
+                    url: "ws://127.0.0.1:1".parse().unwrap(),
+                    secret: None,
+                    to_routing_key: "to-janus".into(),
+                    exchange: "janus-exchange".into(),
+                    from_routing_key: "from-janus".into(),
+                }],
+            }),
+            ..settings
+        }));
+
+        let response: serde_json::Value =
+            call_and_read_body_json(&app, TestRequest::get().uri(&uri).to_request()).await;
+
+        assert_eq!(
+            response,
+            serde_json::json!({
+                "publishers": [
+                    {
+                        "participant_id": USER_1.participant_id,
+                        "media_session_type": "video",
+                        "mcu_id": "to-janus-janus-exchange-from-janus",
+                        "janus_room_id": 42,
+                        "handles": [],
+                    }
+                ],
+                "unreachable": ["ws://127.0.0.1:1/"],
+            })
+        );
+    }
+}
@@ -6,10 +6,12 @@
 //!
 //! Current Endpoints. See their respective function:
 //! - `/rooms/{room_id}` ([DELETE](rooms::delete)
+//! - `/rooms/{room_id}/janus` ([GET](janus::health)
 
 use actix_http::body::BoxBody;
 use actix_web::{HttpResponse, Responder};
 
+pub mod janus;
 pub mod rooms;
 
 /// Represents a 204 No Content HTTP Response
@@ -10,4 +10,4 @@ mod ws_modules;
 
 pub(crate) use ws::ws_service;
 pub use ws::{SignalingModules, SignalingProtocols};
-pub use ws_modules::{breakout, echo, moderation, recording};
+pub use ws_modules::{breakout, dial_out, echo, moderation, recording};
@@ -0,0 +1,16 @@
+// SPDX-FileCopyrightText: OpenTalk GmbH <mail@opentalk.eu>
+//
+// SPDX-License-Identifier: EUPL-1.2
+
+use serde::{Deserialize, Serialize};
+use types::{core::DialOutId, signaling::dial_out::CallStatus};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub enum Message {
+    /// Sent to the participant who requested the call when the SIP gateway reported progress
+    CallUpdated {
+        call_id: DialOutId,
+        number: String,
+        status: CallStatus,
+    },
+}
@@ -0,0 +1,302 @@
+// SPDX-FileCopyrightText: OpenTalk GmbH <mail@opentalk.eu>
+//
+// SPDX-License-Identifier: EUPL-1.2
+
+//! Lets moderators call a phone number through the SIP gateway
+//!
+//! The call is requested via RabbitMQ. The SIP gateway reports the progress of the call via the
+//! `services/dial_out` endpoints and joins the room as a SIP participant once the callee answered.
+
+use anyhow::{Context, Result};
+use futures::{stream::once, FutureExt};
+use lapin::BasicProperties;
+use lapin_pool::{RabbitMqChannel, RabbitMqPool};
+use signaling_core::{
+    control, DestroyContext, Event, InitContext, ModuleContext, SignalingModule,
+    SignalingModuleInitData, SignalingRoomId,
+};
+use std::{sync::Arc, time::Duration};
+use tokio::time::sleep;
+use types::{
+    core::{DialOutId, ParticipantId},
+    signaling::{
+        dial_out::{
+            command::{self, DialOutCommand},
+            event::{CallUpdated, DialOutEvent, Error},
+            CallStatus, NAMESPACE,
+        },
+        Role,
+    },
+};
+
+use crate::api::util::parse_phone_number;
+
+pub(crate) mod exchange;
+mod rabbitmq;
+pub(crate) mod storage;
+
+/// Requests which are not picked up by a SIP gateway within this time are dropped
+const DIAL_OUT_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Calls which no SIP gateway reported progress for within this time are considered failed
+///
+/// Leaves a SIP gateway which picked up the request just before it expired time to report the
+/// ringing phone.
+const DIAL_OUT_PROGRESS_TIMEOUT: Duration = Duration::from_secs(60);
+
+pub struct DialOut {
+    id: ParticipantId,
+    room: SignalingRoomId,
+    params: DialOutParams,
+
+    /// RabbitMQ channel used to send the dial-out requests over
+    rabbitmq_channel: RabbitMqChannel,
+}
+
+#[derive(Clone)]
+pub struct DialOutParams {
+    pub queue: String,
+    /// Used to parse phone numbers which are not in the international format
+    pub default_country_code: phonenumber::country::Id,
+}
+
+/// Fired when a SIP gateway had time to report the progress of a call requested by this
+/// participant
+pub struct ProgressTimeout {
+    call_id: DialOutId,
+    number: String,
+}
+
+#[async_trait::async_trait(?Send)]
+impl SignalingModule for DialOut {
+    const NAMESPACE: &'static str = NAMESPACE;
+
+    type Params = (Arc<RabbitMqPool>, DialOutParams);
+
+    type Incoming = DialOutCommand;
+    type Outgoing = DialOutEvent;
+    type ExchangeMessage = exchange::Message;
+
+    type ExtEvent = ProgressTimeout;
+
+    type FrontendData = ();
+    type PeerFrontendData = ();
+
+    async fn init(
+        ctx: InitContext<'_, Self>,
+        params: &Self::Params,
+        _protocol: &'static str,
+    ) -> Result<Option<Self>> {
+        let (rabbitmq_pool, params) = params;
+
+        let rabbitmq_channel = rabbitmq_pool.create_channel().await?;
+
+        Ok(Some(Self {
+            id: ctx.participant_id(),
+            room: ctx.room_id(),
+            params: params.clone(),
+            rabbitmq_channel,
+        }))
+    }
+
+    async fn on_event(
+        &mut self,
+        mut ctx: ModuleContext<'_, Self>,
+        event: Event<'_, Self>,
+    ) -> Result<()> {
+        match event {
+            Event::WsMessage(DialOutCommand::DialOut(command::DialOut {
+                number,
+                display_name,
+            })) => {
+                if ctx.role() != Role::Moderator {
+                    ctx.ws_send(Error::InsufficientPermissions);
+                    return Ok(());
+                }
+
+                let tariff =
+                    control::storage::get_tariff(ctx.redis_conn(), self.room.room_id()).await?;
+                if tariff.disabled_modules().contains(NAMESPACE) {
+                    ctx.ws_send(Error::DisabledByTariff);
+                    return Ok(());
+                }
+
+                let number = match parse_phone_number(&number, self.params.default_country_code) {
+                    Some(number) => number.format().mode(phonenumber::Mode::E164).to_string(),
+                    None => {
+                        ctx.ws_send(Error::InvalidNumber);
+                        return Ok(());
+                    }
+                };
+
+                let display_name = match callee_display_name(display_name.as_deref(), &number) {
+                    Some(display_name) => display_name,
+                    None => {
+                        ctx.ws_send(Error::InvalidDisplayName);
+                        return Ok(());
+                    }
+                };
+
+                self.request_call(&mut ctx, number, display_name).await?;
+            }
+            Event::Exchange(exchange::Message::CallUpdated {
+                call_id,
+                number,
+                status,
+            }) => {
+                ctx.ws_send(CallUpdated {
+                    call_id,
+                    number,
+                    status,
+                });
+            }
+            Event::Ext(ProgressTimeout { call_id, number }) => {
+                if !storage::take_pending(ctx.redis_conn(), call_id).await? {
+                    // A SIP gateway reported progress in time
+                    return Ok(());
+                }
+
+                log::warn!("No SIP gateway placed call {call_id} in time");
+
+                storage::del_call(ctx.redis_conn(), call_id).await?;
+
+                ctx.ws_send(CallUpdated {
+                    call_id,
+                    number,
+                    status: CallStatus::Failed,
+                });
+            }
+            // Ignore
+            Event::RaiseHand => {}
+            Event::LowerHand => {}
+            Event::Joined { .. } => {}
+            Event::Leaving => {}
+            Event::ParticipantJoined(..) => {}
+            Event::ParticipantLeft(_) => {}
+            Event::ParticipantUpdated(..) => {}
+            Event::RoleUpdated(_) => {}
+        }
+
+        Ok(())
+    }
+
+    async fn on_destroy(self, _ctx: DestroyContext<'_>) {}
+
+    async fn build_params(init: SignalingModuleInitData) -> Result<Option<Self::Params>> {
+        let settings = init.shared_settings.load_full();
+
+        // Numbers are parsed with the country code of the call-in settings
+        match (&settings.rabbit_mq.dial_out_task_queue, &settings.call_in) {
+            (Some(queue), Some(call_in)) => Ok(Some((
+                init.rabbitmq_pool.clone(),
+                DialOutParams {
+                    queue: queue.clone(),
+                    default_country_code: call_in.default_country_code,
+                },
+            ))),
+            (Some(_), None) => {
+                log::warn!("Dialing out is disabled, it requires the call_in settings");
+                Ok(None)
+            }
+            (None, _) => Ok(None),
+        }
+    }
+}
+
+impl DialOut {
+    /// Request the SIP gateway via RabbitMQ to call the given number
+    ///
+    /// The request expires if no SIP gateway picks it up within [`DIAL_OUT_REQUEST_TIMEOUT`], the
+    /// call fails if no progress is reported within [`DIAL_OUT_PROGRESS_TIMEOUT`].
+    async fn request_call(
+        &self,
+        ctx: &mut ModuleContext<'_, Self>,
+        number: String,
+        display_name: String,
+    ) -> Result<()> {
+        let call_id = DialOutId::generate();
+
+        storage::set_call(
+            ctx.redis_conn(),
+            call_id,
+            &storage::DialOutCall {
+                room_id: self.room.room_id(),
+                breakout_room: self.room.breakout_room_id(),
+                requester: self.id,
+                number: number.clone(),
+            },
+        )
+        .await?;
+
+        self.rabbitmq_channel
+            .basic_publish(
+                "",
+                &self.params.queue,
+                Default::default(),
+                &serde_json::to_vec(&rabbitmq::StartDialOut {
+                    call_id,
+                    number: number.clone(),
+                    display_name,
+                })
+                .context("failed to serialize StartDialOut")?,
+                BasicProperties::default()
+                    .with_expiration(DIAL_OUT_REQUEST_TIMEOUT.as_millis().to_string().into()),
+            )
+            .await?;
+
+        ctx.ws_send(CallUpdated {
+            call_id,
+            number: number.clone(),
+            status: CallStatus::Dialing,
+        });
+
+        ctx.add_event_stream(once(
+            sleep(DIAL_OUT_PROGRESS_TIMEOUT).map(move |_| ProgressTimeout { call_id, number }),
+        ));
+
+        Ok(())
+    }
+}
+
+/// The name the callee joins the room with, the number is used if no name is given
+///
+/// Returns `None` if the given name is empty or too long.
+fn callee_display_name(display_name: Option<&str>, number: &str) -> Option<String> {
+    match display_name.map(str::trim) {
+        Some(display_name) if display_name.is_empty() || display_name.len() > 100 => None,
+        Some(display_name) => Some(display_name.to_string()),
+        None => Some(number.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn callee_display_name_defaults_to_number() {
+        assert_eq!(
+            callee_display_name(None, "+4930123456"),
+            Some("+4930123456".into())
+        );
+    }
+
+    #[test]
+    fn callee_display_name_is_trimmed() {
+        assert_eq!(
+            callee_display_name(Some("  Jane Doe "), "+4930123456"),
+            Some("Jane Doe".into())
+        );
+    }
+
+    #[test]
+    fn invalid_callee_display_names() {
+        assert_eq!(callee_display_name(Some("   "), "+4930123456"), None);
+        assert_eq!(
+            callee_display_name(Some(&"a".repeat(101)), "+4930123456"),
+            None
+        );
+        assert!(callee_display_name(Some(&"a".repeat(100)), "+4930123456").is_some());
+    }
+}
@@ -0,0 +1,16 @@
+// SPDX-FileCopyrightText: OpenTalk GmbH <mail@opentalk.eu>
+//
+// SPDX-License-Identifier: EUPL-1.2
+
+use serde::Serialize;
+use types::core::DialOutId;
+
+/// Message sent to the SIP gateway instructing it to call the given number
+#[derive(Debug, Serialize)]
+pub struct StartDialOut {
+    pub call_id: DialOutId,
+    /// The phone number in E.164 format
+    pub number: String,
+    /// The name the callee joins the room with
+    pub display_name: String,
+}
@@ -0,0 +1,145 @@
+// SPDX-FileCopyrightText: OpenTalk GmbH <mail@opentalk.eu>
+//
+// SPDX-License-Identifier: EUPL-1.2
+
+use anyhow::{Context, Result};
+use redis::AsyncCommands;
+use redis_args::{FromRedisValue, ToRedisArgs};
+use serde::{Deserialize, Serialize};
+use signaling_core::{RedisConnection, SignalingRoomId};
+use types::core::{BreakoutRoomId, DialOutId, ParticipantId, RoomId};
+
+/// Calls are forgotten one day after they have been requested
+const CALL_EXPIRY_SECONDS: u64 = 24 * 60 * 60;
+
+/// Stores the [`DialOutCall`] of an outbound call until it is over.
+#[derive(ToRedisArgs)]
+#[to_redis_args(fmt = "opentalk-signaling:dial_out={call_id}")]
+struct DialOutCallKey {
+    call_id: DialOutId,
+}
+
+/// Exists while no SIP gateway reported progress of an outbound call
+#[derive(ToRedisArgs)]
+#[to_redis_args(fmt = "opentalk-signaling:dial_out={call_id}:pending")]
+struct PendingCallKey {
+    call_id: DialOutId,
+}
+
+/// An outbound call requested by a moderator
+#[derive(Debug, Clone, Deserialize, Serialize, ToRedisArgs, FromRedisValue)]
+#[to_redis_args(serde)]
+#[from_redis_value(serde)]
+pub(crate) struct DialOutCall {
+    pub room_id: RoomId,
+    pub breakout_room: Option<BreakoutRoomId>,
+    /// The participant who requested the call and receives its progress
+    pub requester: ParticipantId,
+    /// The called phone number in E.164 format
+    pub number: String,
+}
+
+impl DialOutCall {
+    pub(crate) fn room(&self) -> SignalingRoomId {
+        SignalingRoomId::new(self.room_id, self.breakout_room)
+    }
+}
+
+/// Store a requested call, it is pending until [`take_pending`] is called for it
+pub(crate) async fn set_call(
+    redis_conn: &mut RedisConnection,
+    call_id: DialOutId,
+    call: &DialOutCall,
+) -> Result<()> {
+    redis::pipe()
+        .atomic()
+        .set_ex(DialOutCallKey { call_id }, call, CALL_EXPIRY_SECONDS)
+        .ignore()
+        .set_ex(PendingCallKey { call_id }, true, CALL_EXPIRY_SECONDS)
+        .ignore()
+        .query_async(redis_conn)
+        .await
+        .context("Failed to set dial-out call")
+}
+
+/// Mark the call as no longer pending
+///
+/// Returns `true` if the call was pending, only one caller gets `true` for each call.
+pub(crate) async fn take_pending(
+    redis_conn: &mut RedisConnection,
+    call_id: DialOutId,
+) -> Result<bool> {
+    let deleted: i32 = redis_conn
+        .del(PendingCallKey { call_id })
+        .await
+        .context("Failed to take pending dial-out call")?;
+
+    Ok(deleted == 1)
+}
+
+pub(crate) async fn get_call(
+    redis_conn: &mut RedisConnection,
+    call_id: DialOutId,
+) -> Result<Option<DialOutCall>> {
+    redis_conn
+        .get(DialOutCallKey { call_id })
+        .await
+        .context("Failed to get dial-out call")
+}
+
+pub(crate) async fn del_call(redis_conn: &mut RedisConnection, call_id: DialOutId) -> Result<()> {
+    redis::pipe()
+        .atomic()
+        .del(DialOutCallKey { call_id })
+        .ignore()
+        .del(PendingCallKey { call_id })
+        .ignore()
+        .query_async(redis_conn)
+        .await
+        .context("Failed to delete dial-out call")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use serial_test::serial;
+
+    fn call() -> DialOutCall {
+        DialOutCall {
+            room_id: RoomId::from_u128(1),
+            breakout_room: None,
+            requester: ParticipantId::from_u128(2),
+            number: "+4930123456".into(),
+        }
+    }
+
+    #[actix_rt::test]
+    #[serial]
+    async fn pending_call_is_taken_once() {
+        let mut redis_conn = test_util::redis::setup().await;
+        let call_id = DialOutId::from_u128(3);
+
+        set_call(&mut redis_conn, call_id, &call()).await.unwrap();
+
+        assert!(take_pending(&mut redis_conn, call_id).await.unwrap());
+        assert!(!take_pending(&mut redis_conn, call_id).await.unwrap());
+
+        let stored = get_call(&mut redis_conn, call_id).await.unwrap().unwrap();
+        assert_eq!(stored.number, call().number);
+        assert_eq!(stored.room().room_id(), RoomId::from_u128(1));
+    }
+
+    #[actix_rt::test]
+    #[serial]
+    async fn deleted_call_is_not_pending() {
+        let mut redis_conn = test_util::redis::setup().await;
+        let call_id = DialOutId::from_u128(4);
+
+        set_call(&mut redis_conn, call_id, &call()).await.unwrap();
+        del_call(&mut redis_conn, call_id).await.unwrap();
+
+        assert!(get_call(&mut redis_conn, call_id).await.unwrap().is_none());
+        assert!(!take_pending(&mut redis_conn, call_id).await.unwrap());
+    }
+}
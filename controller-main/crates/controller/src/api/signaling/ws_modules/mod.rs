@@ -3,6 +3,7 @@
 // SPDX-License-Identifier: EUPL-1.2
 
 pub mod breakout;
+pub mod dial_out;
 pub mod echo;
 pub mod moderation;
 pub mod recording;
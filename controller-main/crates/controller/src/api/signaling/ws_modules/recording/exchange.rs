@@ -4,6 +4,10 @@
 
 use super::RecordingId;
 use serde::{Deserialize, Serialize};
+use types::{
+    core::StreamingTargetId,
+    signaling::recording::{RecordingScope, StreamStatus},
+};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub enum Message {
@@ -11,6 +15,19 @@ pub enum Message {
     Stop,
 
     /// Messages sent to participants to signal changes in the recording
-    Started(RecordingId),
+    Started(RecordingId, RecordingScope),
     Stopped(RecordingId),
+
+    /// Messages sent to all participants including the recorder to pause or resume the recording
+    Paused(RecordingId),
+    Resumed(RecordingId),
+
+    /// Signals the recorder to start streaming to the given targets
+    StartStreams(Vec<StreamingTargetId>),
+
+    /// Sent to all participants including the recorder when the status of a stream changed
+    StreamUpdated(StreamingTargetId, StreamStatus),
+
+    /// Signals the recorder to change the layout
+    SetLayout(serde_json::Value),
 }
@@ -3,26 +3,36 @@
 // SPDX-License-Identifier: EUPL-1.2
 
 use anyhow::{Context, Result};
+use database::Db;
+use db_storage::{streaming_targets::RoomStreamingTargetRecord, tenants::Tenant};
+use futures::{stream::once, FutureExt};
+use lapin::BasicProperties;
 use lapin_pool::{RabbitMqChannel, RabbitMqPool};
 use signaling_core::{
     control, DestroyContext, Event, InitContext, ModuleContext, Participant, SignalingModule,
     SignalingModuleInitData, SignalingRoomId,
 };
 use std::sync::Arc;
+use tokio::time::sleep;
 use types::{
-    core::ParticipantId,
+    core::{ParticipantId, StreamingTargetId, TenantId},
     signaling::{
         recording::{
             command::{self, RecordingCommand},
-            event::{Error, RecordingEvent, Started, Stopped},
+            event::{
+                Error, Paused, RecorderStreamingTarget, RecordingEvent, Resumed, SetLayout,
+                StartStreams, Started, Stopped, StreamUpdated,
+            },
             peer_state::RecordingPeerState,
             state::RecordingState,
-            RecordingId, RecordingStatus, NAMESPACE,
+            RecordingId, RecordingScope, RecordingStatus, StreamStatus, NAMESPACE,
         },
         Role,
     },
 };
 
+use crate::settings::Recording as RecordingSettings;
+
 mod exchange;
 mod rabbitmq;
 mod storage;
@@ -30,16 +40,22 @@ mod storage;
 pub struct Recording {
     id: ParticipantId,
     room: SignalingRoomId,
+    tenant_id: TenantId,
     i_am_the_recorder: bool,
     params: RecordingParams,
+    db: Arc<Db>,
 
     /// RabbitMQ channel used to send the recording start command over
     rabbitmq_channel: RabbitMqChannel,
 }
 
+/// Fired when the recorder requested by this participant had time to join the room
+pub struct RecorderTimeout;
+
 #[derive(Clone)]
 pub struct RecordingParams {
     pub queue: String,
+    pub settings: Arc<RecordingSettings>,
 }
 
 #[async_trait::async_trait(?Send)]
@@ -52,7 +68,7 @@ impl SignalingModule for Recording {
     type Outgoing = RecordingEvent;
     type ExchangeMessage = exchange::Message;
 
-    type ExtEvent = ();
+    type ExtEvent = RecorderTimeout;
 
     type FrontendData = RecordingState;
     type PeerFrontendData = RecordingPeerState;
@@ -69,8 +85,10 @@ impl SignalingModule for Recording {
         Ok(Some(Self {
             id: ctx.participant_id(),
             room: ctx.room_id(),
+            tenant_id: ctx.room().tenant_id,
             i_am_the_recorder: matches!(ctx.participant(), Participant::Recorder),
             params: params.clone(),
+            db: ctx.db().clone(),
             rabbitmq_channel,
         }))
     }
@@ -87,17 +105,39 @@ impl SignalingModule for Recording {
                 participants,
             } => {
                 if self.i_am_the_recorder {
-                    let recording_id = RecordingId::from(self.id);
-                    storage::set_recording(ctx.redis_conn(), self.room, recording_id).await?;
+                    storage::set_recorder(ctx.redis_conn(), self.room, self.id).await?;
 
-                    ctx.exchange_publish(
-                        control::exchange::current_room_all_participants(self.room),
-                        exchange::Message::Started(recording_id),
-                    );
+                    let state = storage::get_state(ctx.redis_conn(), self.room).await?;
+                    if state == Some(RecordingStatus::Initializing) {
+                        let recording_id = RecordingId::from(self.id);
+                        let scope = storage::get_scope(ctx.redis_conn(), self.room).await?;
+                        storage::set_recording(ctx.redis_conn(), self.room, recording_id).await?;
+
+                        ctx.exchange_publish(
+                            control::exchange::current_room_all_participants(self.room),
+                            exchange::Message::Started(recording_id, scope),
+                        );
+                    }
+
+                    let starting: Vec<StreamingTargetId> =
+                        storage::get_streams(ctx.redis_conn(), self.room)
+                            .await?
+                            .into_iter()
+                            .filter(|(_, status)| *status == StreamStatus::Starting)
+                            .map(|(target_id, _)| target_id)
+                            .collect();
+
+                    if !starting.is_empty() {
+                        self.start_streams(&mut ctx, starting).await?;
+                    } else if state.is_none() {
+                        // Everything has been stopped before the recorder joined
+                        ctx.exit(None);
+                    }
                 } else {
-                    *frontend_data = Some(RecordingState(
-                        storage::get_state(ctx.redis_conn(), self.room).await?,
-                    ));
+                    *frontend_data = Some(RecordingState {
+                        recording: storage::get_state(ctx.redis_conn(), self.room).await?,
+                        streams: storage::get_streams(ctx.redis_conn(), self.room).await?,
+                    });
                 }
 
                 let participant_ids: Vec<ParticipantId> = participants.keys().copied().collect();
@@ -124,10 +164,24 @@ impl SignalingModule for Recording {
             }
             Event::Leaving => {
                 if self.i_am_the_recorder {
-                    ctx.exchange_publish(
-                        control::exchange::current_room_all_participants(self.room),
-                        exchange::Message::Stopped(RecordingId::from(self.id)),
-                    );
+                    if let Some(RecordingStatus::Recording(id) | RecordingStatus::Paused(id)) =
+                        storage::get_state(ctx.redis_conn(), self.room).await?
+                    {
+                        ctx.exchange_publish(
+                            control::exchange::current_room_all_participants(self.room),
+                            exchange::Message::Stopped(id),
+                        );
+                    }
+
+                    for target_id in storage::get_streams(ctx.redis_conn(), self.room)
+                        .await?
+                        .into_keys()
+                    {
+                        ctx.exchange_publish(
+                            control::exchange::current_room_all_participants(self.room),
+                            exchange::Message::StreamUpdated(target_id, StreamStatus::Inactive),
+                        );
+                    }
                 } else {
                     control::storage::remove_attribute(
                         ctx.redis_conn(),
@@ -158,30 +212,46 @@ impl SignalingModule for Recording {
             }
             Event::RoleUpdated(_) => {}
             Event::WsMessage(msg) => match msg {
-                RecordingCommand::Start => {
+                RecordingCommand::Start(command::Start { scope }) => {
                     if ctx.role() != Role::Moderator {
                         ctx.ws_send(Error::InsufficientPermissions);
                         return Ok(());
                     }
 
-                    if !storage::try_init(ctx.redis_conn(), self.room).await? {
+                    if let RecordingScope::Participants { participant_ids } = &scope {
+                        if participant_ids.is_empty() {
+                            ctx.ws_send(Error::InvalidScope);
+                            return Ok(());
+                        }
+                    }
+
+                    if !storage::try_init(
+                        ctx.redis_conn(),
+                        self.room,
+                        self.params.settings.recorder_timeout,
+                    )
+                    .await?
+                    {
                         ctx.ws_send(Error::AlreadyRecording);
                         return Ok(());
                     }
 
-                    self.rabbitmq_channel
-                        .basic_publish(
-                            "",
-                            &self.params.queue,
-                            Default::default(),
-                            &serde_json::to_vec(&rabbitmq::StartRecording {
-                                room: self.room.room_id(),
-                                breakout: self.room.breakout_room_id(),
-                            })
-                            .context("failed to serialize StartRecording")?,
-                            Default::default(),
-                        )
-                        .await?;
+                    // A recorder which is already streaming also takes over the recording
+                    if let Some(recorder) =
+                        storage::get_recorder(ctx.redis_conn(), self.room).await?
+                    {
+                        let recording_id = RecordingId::from(recorder);
+                        storage::set_recording(ctx.redis_conn(), self.room, recording_id).await?;
+
+                        ctx.exchange_publish(
+                            control::exchange::current_room_all_participants(self.room),
+                            exchange::Message::Started(recording_id, scope),
+                        );
+                    } else {
+                        // The recorder starts the recording with this scope when it joined
+                        storage::set_scope(ctx.redis_conn(), self.room, &scope).await?;
+                        self.request_recorder(&mut ctx).await?;
+                    }
                 }
                 RecordingCommand::Stop(command::Stop { recording_id }) => {
                     if ctx.role() != Role::Moderator {
@@ -191,7 +261,8 @@ impl SignalingModule for Recording {
 
                     if !matches!(
                         storage::get_state(ctx.redis_conn(), self.room).await?,
-                        Some(RecordingStatus::Recording(id)) if id == recording_id
+                        Some(RecordingStatus::Recording(id) | RecordingStatus::Paused(id))
+                            if id == recording_id
                     ) {
                         ctx.ws_send(Error::InvalidRecordingId);
                         return Ok(());
@@ -205,6 +276,56 @@ impl SignalingModule for Recording {
                         exchange::Message::Stop,
                     );
                 }
+                RecordingCommand::Pause(command::Pause { recording_id }) => {
+                    if ctx.role() != Role::Moderator {
+                        ctx.ws_send(Error::InsufficientPermissions);
+                        return Ok(());
+                    }
+
+                    match storage::get_state(ctx.redis_conn(), self.room).await? {
+                        Some(RecordingStatus::Recording(id)) if id == recording_id => {}
+                        Some(RecordingStatus::Paused(id)) if id == recording_id => {
+                            ctx.ws_send(Error::AlreadyPaused);
+                            return Ok(());
+                        }
+                        _ => {
+                            ctx.ws_send(Error::InvalidRecordingId);
+                            return Ok(());
+                        }
+                    }
+
+                    storage::set_paused(ctx.redis_conn(), self.room, recording_id).await?;
+
+                    ctx.exchange_publish(
+                        control::exchange::current_room_all_participants(self.room),
+                        exchange::Message::Paused(recording_id),
+                    );
+                }
+                RecordingCommand::Resume(command::Resume { recording_id }) => {
+                    if ctx.role() != Role::Moderator {
+                        ctx.ws_send(Error::InsufficientPermissions);
+                        return Ok(());
+                    }
+
+                    match storage::get_state(ctx.redis_conn(), self.room).await? {
+                        Some(RecordingStatus::Paused(id)) if id == recording_id => {}
+                        Some(RecordingStatus::Recording(id)) if id == recording_id => {
+                            ctx.ws_send(Error::NotPaused);
+                            return Ok(());
+                        }
+                        _ => {
+                            ctx.ws_send(Error::InvalidRecordingId);
+                            return Ok(());
+                        }
+                    }
+
+                    storage::set_recording(ctx.redis_conn(), self.room, recording_id).await?;
+
+                    ctx.exchange_publish(
+                        control::exchange::current_room_all_participants(self.room),
+                        exchange::Message::Resumed(recording_id),
+                    );
+                }
                 RecordingCommand::SetConsent(command::SetConsent { consent }) => {
                     control::storage::set_attribute(
                         ctx.redis_conn(),
@@ -217,18 +338,159 @@ impl SignalingModule for Recording {
 
                     ctx.invalidate_data();
                 }
+                RecordingCommand::StartStream(command::StartStream { mut target_ids }) => {
+                    if ctx.role() != Role::Moderator {
+                        ctx.ws_send(Error::InsufficientPermissions);
+                        return Ok(());
+                    }
+
+                    target_ids.sort();
+                    target_ids.dedup();
+
+                    let room_targets = self.load_streaming_targets().await?;
+                    if target_ids.is_empty()
+                        || !target_ids
+                            .iter()
+                            .all(|id| room_targets.iter().any(|target| target.id == *id))
+                    {
+                        ctx.ws_send(Error::InvalidStreamingTargetId);
+                        return Ok(());
+                    }
+
+                    let streams = storage::get_streams(ctx.redis_conn(), self.room).await?;
+
+                    let mut new_targets = Vec::new();
+                    for target_id in target_ids {
+                        let status = match streams.get(&target_id) {
+                            Some(StreamStatus::Starting | StreamStatus::Active) => continue,
+                            Some(StreamStatus::Paused) => StreamStatus::Active,
+                            Some(StreamStatus::Inactive) | None => {
+                                new_targets.push(target_id);
+                                StreamStatus::Starting
+                            }
+                        };
+
+                        storage::set_stream(ctx.redis_conn(), self.room, target_id, status).await?;
+
+                        ctx.exchange_publish(
+                            control::exchange::current_room_all_participants(self.room),
+                            exchange::Message::StreamUpdated(target_id, status),
+                        );
+                    }
+
+                    if new_targets.is_empty() {
+                        return Ok(());
+                    }
+
+                    if let Some(recorder) =
+                        storage::get_recorder(ctx.redis_conn(), self.room).await?
+                    {
+                        ctx.exchange_publish(
+                            control::exchange::current_room_by_participant_id(self.room, recorder),
+                            exchange::Message::StartStreams(new_targets),
+                        );
+                    } else {
+                        self.request_recorder(&mut ctx).await?;
+                    }
+                }
+                RecordingCommand::PauseStream(command::PauseStream { target_ids }) => {
+                    if ctx.role() != Role::Moderator {
+                        ctx.ws_send(Error::InsufficientPermissions);
+                        return Ok(());
+                    }
+
+                    let streams = storage::get_streams(ctx.redis_conn(), self.room).await?;
+                    if !target_ids
+                        .iter()
+                        .all(|id| streams.get(id) == Some(&StreamStatus::Active))
+                    {
+                        ctx.ws_send(Error::InvalidStreamingTargetId);
+                        return Ok(());
+                    }
+
+                    for target_id in target_ids {
+                        storage::set_stream(
+                            ctx.redis_conn(),
+                            self.room,
+                            target_id,
+                            StreamStatus::Paused,
+                        )
+                        .await?;
+
+                        ctx.exchange_publish(
+                            control::exchange::current_room_all_participants(self.room),
+                            exchange::Message::StreamUpdated(target_id, StreamStatus::Paused),
+                        );
+                    }
+                }
+                RecordingCommand::StopStream(command::StopStream { target_ids }) => {
+                    if ctx.role() != Role::Moderator {
+                        ctx.ws_send(Error::InsufficientPermissions);
+                        return Ok(());
+                    }
+
+                    let streams = storage::get_streams(ctx.redis_conn(), self.room).await?;
+                    if !target_ids.iter().all(|id| streams.contains_key(id)) {
+                        ctx.ws_send(Error::InvalidStreamingTargetId);
+                        return Ok(());
+                    }
+
+                    for target_id in target_ids {
+                        storage::del_stream(ctx.redis_conn(), self.room, target_id).await?;
+
+                        ctx.exchange_publish(
+                            control::exchange::current_room_all_participants(self.room),
+                            exchange::Message::StreamUpdated(target_id, StreamStatus::Inactive),
+                        );
+                    }
+                }
+                RecordingCommand::SetLayout(command::SetLayout { layout }) => {
+                    if ctx.role() != Role::Moderator {
+                        ctx.ws_send(Error::InsufficientPermissions);
+                        return Ok(());
+                    }
+
+                    // The recorder validates the layout itself
+                    match storage::get_recorder(ctx.redis_conn(), self.room).await? {
+                        Some(recorder) => ctx.exchange_publish(
+                            control::exchange::current_room_by_participant_id(self.room, recorder),
+                            exchange::Message::SetLayout(layout),
+                        ),
+                        None => ctx.ws_send(Error::NoRecorder),
+                    }
+                }
             },
             Event::Exchange(msg) => match msg {
                 exchange::Message::Stop => {
-                    if self.i_am_the_recorder {
+                    if !self.i_am_the_recorder {
+                        return Ok(());
+                    }
+
+                    if storage::get_streams(ctx.redis_conn(), self.room)
+                        .await?
+                        .is_empty()
+                    {
                         // TODO(kbalt): A bit of a nuclear solution to end the recording
                         ctx.exit(None);
+                    } else if let Some(
+                        RecordingStatus::Recording(id) | RecordingStatus::Paused(id),
+                    ) = storage::get_state(ctx.redis_conn(), self.room).await?
+                    {
+                        // Keep the recorder in the room for the running livestreams
+                        storage::del_state(ctx.redis_conn(), self.room).await?;
+
+                        ctx.exchange_publish(
+                            control::exchange::current_room_all_participants(self.room),
+                            exchange::Message::Stopped(id),
+                        );
                     }
                 }
-                exchange::Message::Started(recording_id) => {
-                    if !self.i_am_the_recorder {
-                        ctx.ws_send(Started { recording_id });
-                    }
+                exchange::Message::Started(recording_id, scope) => {
+                    // The recorder receives this event as well to start writing the recording
+                    ctx.ws_send(Started {
+                        recording_id,
+                        scope,
+                    });
                 }
                 exchange::Message::Stopped(recording_id) => {
                     if !self.i_am_the_recorder {
@@ -239,12 +501,75 @@ impl SignalingModule for Recording {
                             "recording_consent",
                         )
                         .await?;
+                    }
+
+                    ctx.ws_send(Stopped { recording_id });
+                }
+                exchange::Message::Paused(recording_id) => {
+                    // The recorder receives this event as well to stop feeding its sinks
+                    ctx.ws_send(Paused { recording_id });
+                }
+                exchange::Message::Resumed(recording_id) => {
+                    ctx.ws_send(Resumed { recording_id });
+                }
+                exchange::Message::StartStreams(target_ids) => {
+                    if self.i_am_the_recorder {
+                        self.start_streams(&mut ctx, target_ids).await?;
+                    }
+                }
+                exchange::Message::StreamUpdated(target_id, status) => {
+                    // The recorder receives this event as well to pause or release the stream
+                    ctx.ws_send(StreamUpdated { target_id, status });
 
-                        ctx.ws_send(Stopped { recording_id });
+                    if self.i_am_the_recorder
+                        && status == StreamStatus::Inactive
+                        && storage::get_state(ctx.redis_conn(), self.room)
+                            .await?
+                            .is_none()
+                        && storage::get_streams(ctx.redis_conn(), self.room)
+                            .await?
+                            .is_empty()
+                    {
+                        // Neither recording nor streaming anymore
+                        ctx.exit(None);
+                    }
+                }
+                exchange::Message::SetLayout(layout) => {
+                    if self.i_am_the_recorder {
+                        ctx.ws_send(SetLayout { layout });
                     }
                 }
             },
-            Event::Ext(_) => {}
+            Event::Ext(RecorderTimeout) => {
+                if !storage::try_cancel_recorder_request(ctx.redis_conn(), self.room).await? {
+                    // The recorder joined in time
+                    return Ok(());
+                }
+
+                log::warn!("No recorder joined room {} in time", self.room);
+
+                if storage::get_state(ctx.redis_conn(), self.room).await?
+                    == Some(RecordingStatus::Initializing)
+                {
+                    storage::del_state(ctx.redis_conn(), self.room).await?;
+                }
+
+                for (target_id, status) in storage::get_streams(ctx.redis_conn(), self.room).await?
+                {
+                    if status != StreamStatus::Starting {
+                        continue;
+                    }
+
+                    storage::del_stream(ctx.redis_conn(), self.room, target_id).await?;
+
+                    ctx.exchange_publish(
+                        control::exchange::current_room_all_participants(self.room),
+                        exchange::Message::StreamUpdated(target_id, StreamStatus::Inactive),
+                    );
+                }
+
+                ctx.ws_send(Error::NoRecorderAvailable);
+            }
         }
 
         Ok(())
@@ -255,23 +580,163 @@ impl SignalingModule for Recording {
             if let Err(e) = storage::del_state(ctx.redis_conn(), self.room).await {
                 log::error!("failed to delete state, {:?}", e);
             }
+
+            if let Err(e) = storage::del_streams(ctx.redis_conn(), self.room).await {
+                log::error!("failed to delete streams, {:?}", e);
+            }
+
+            if let Err(e) = storage::del_recorder(ctx.redis_conn(), self.room).await {
+                log::error!("failed to delete recorder, {:?}", e);
+            }
         }
     }
 
     async fn build_params(init: SignalingModuleInitData) -> Result<Option<Self::Params>> {
-        if let Some(queue) = init
-            .shared_settings
-            .load_full()
-            .rabbit_mq
-            .recording_task_queue
-            .clone()
-        {
+        let settings = init.shared_settings.load_full();
+
+        if let Some(queue) = settings.rabbit_mq.recording_task_queue.clone() {
             Ok(Some((
                 init.rabbitmq_pool.clone(),
-                RecordingParams { queue },
+                RecordingParams {
+                    queue,
+                    settings: Arc::new(settings.recording.clone()),
+                },
             )))
         } else {
             Ok(None)
         }
     }
 }
+
+impl Recording {
+    /// Request a recorder for this room via RabbitMQ unless one has been requested already
+    ///
+    /// The request expires if no recorder picks it up within the recorder timeout, the
+    /// participant is notified with [`Error::NoRecorderAvailable`] then.
+    async fn request_recorder(&self, ctx: &mut ModuleContext<'_, Self>) -> Result<()> {
+        let timeout = self.params.settings.recorder_timeout;
+
+        if !storage::try_request_recorder(ctx.redis_conn(), self.room, timeout).await? {
+            return Ok(());
+        }
+
+        let theme = self.load_theme().await?;
+
+        self.rabbitmq_channel
+            .basic_publish(
+                "",
+                &self.params.queue,
+                Default::default(),
+                &serde_json::to_vec(&rabbitmq::StartRecording {
+                    room: self.room.room_id(),
+                    breakout: self.room.breakout_room_id(),
+                    theme,
+                })
+                .context("failed to serialize StartRecording")?,
+                // Recorders over capacity requeue the request until it expires
+                BasicProperties::default().with_expiration(timeout.as_millis().to_string().into()),
+            )
+            .await?;
+
+        ctx.add_event_stream(once(sleep(timeout).map(|_| RecorderTimeout)));
+
+        Ok(())
+    }
+
+    /// Hand the given streaming targets to the recorder and mark their streams as active
+    ///
+    /// Only called by the recorder participant.
+    async fn start_streams(
+        &self,
+        ctx: &mut ModuleContext<'_, Self>,
+        target_ids: Vec<StreamingTargetId>,
+    ) -> Result<()> {
+        let room_targets = self.load_streaming_targets().await?;
+
+        let mut targets = Vec::new();
+        let mut removed = Vec::new();
+        for target_id in target_ids {
+            match room_targets.iter().find(|target| target.id == target_id) {
+                Some(target) => targets.push(target.clone()),
+                None => removed.push(target_id),
+            }
+        }
+
+        // Targets may have been deleted since the stream was requested
+        for target_id in removed {
+            storage::del_stream(ctx.redis_conn(), self.room, target_id).await?;
+
+            ctx.exchange_publish(
+                control::exchange::current_room_all_participants(self.room),
+                exchange::Message::StreamUpdated(target_id, StreamStatus::Inactive),
+            );
+        }
+
+        if targets.is_empty() {
+            return Ok(());
+        }
+
+        for target in &targets {
+            storage::set_stream(ctx.redis_conn(), self.room, target.id, StreamStatus::Active)
+                .await?;
+
+            ctx.exchange_publish(
+                control::exchange::current_room_all_participants(self.room),
+                exchange::Message::StreamUpdated(target.id, StreamStatus::Active),
+            );
+        }
+
+        ctx.ws_send(StartStreams { targets });
+
+        Ok(())
+    }
+
+    /// Load the recording theme which is configured for the room's tenant
+    async fn load_theme(&self) -> Result<Option<serde_json::Value>> {
+        if self.params.settings.themes.is_empty() {
+            return Ok(None);
+        }
+
+        let mut conn = self.db.get_conn().await?;
+
+        let tenant = Tenant::get(&mut conn, self.tenant_id)
+            .await
+            .context("Failed to get tenant")?;
+
+        Ok(self
+            .params
+            .settings
+            .theme(tenant.oidc_tenant_id.as_ref())
+            .cloned())
+    }
+
+    /// Load all streaming targets of the room from the database
+    async fn load_streaming_targets(&self) -> Result<Vec<RecorderStreamingTarget>> {
+        let mut conn = self.db.get_conn().await?;
+
+        let records = RoomStreamingTargetRecord::get_all_for_room(&mut conn, self.room.room_id())
+            .await
+            .context("Failed to get streaming targets")?;
+
+        records
+            .into_iter()
+            .map(|record| {
+                // The database stores the endpoint and public url already derived from the kind
+                Ok(RecorderStreamingTarget {
+                    id: record.id,
+                    name: record.name,
+                    kind: record.kind,
+                    streaming_endpoint: record
+                        .streaming_endpoint
+                        .parse()
+                        .context("invalid streaming endpoint url entry in db")?,
+                    streaming_key: record.streaming_key,
+                    public_url: record
+                        .public_url
+                        .parse()
+                        .context("invalid public url entry in db")?,
+                })
+            })
+            .collect()
+    }
+}
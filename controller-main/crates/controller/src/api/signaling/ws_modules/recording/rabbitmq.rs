@@ -10,4 +10,7 @@ use types::core::{BreakoutRoomId, RoomId};
 pub struct StartRecording {
     pub room: RoomId,
     pub breakout: Option<BreakoutRoomId>,
+    /// Branding of the recording's output picture, configured per tenant
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub theme: Option<serde_json::Value>,
 }
@@ -2,11 +2,16 @@
 //
 // SPDX-License-Identifier: EUPL-1.2
 
-use anyhow::{Context, Result};
-use redis::AsyncCommands;
+use anyhow::{bail, Context, Result};
+use redis::{AsyncCommands, Script};
 use redis_args::ToRedisArgs;
 use signaling_core::{RedisConnection, SignalingRoomId};
-use types::signaling::recording::RecordingStatus;
+use std::collections::BTreeMap;
+use std::time::Duration;
+use types::{
+    core::{ParticipantId, StreamingTargetId},
+    signaling::recording::{RecordingScope, RecordingStatus, StreamStatus},
+};
 
 use super::RecordingId;
 
@@ -17,14 +22,82 @@ struct RecordingStateKey {
     room_id: SignalingRoomId,
 }
 
+/// Stores the [`RecordingScope`] of a recording of this room until the recorder started it.
+#[derive(ToRedisArgs)]
+#[to_redis_args(fmt = "opentalk-signaling:room={room_id}:recording:scope")]
+struct RecordingScopeKey {
+    room_id: SignalingRoomId,
+}
+
+/// Stores the [`StreamStatus`] of every streaming target of this room which has a stream.
+#[derive(ToRedisArgs)]
+#[to_redis_args(fmt = "opentalk-signaling:room={room_id}:recording:streams")]
+struct RecordingStreamsKey {
+    room_id: SignalingRoomId,
+}
+
+/// Stores the participant id of the recorder of this room, or [`RECORDER_REQUESTED`] while
+/// the recorder has been requested but not yet joined.
+#[derive(ToRedisArgs)]
+#[to_redis_args(fmt = "opentalk-signaling:room={room_id}:recording:recorder")]
+struct RecorderKey {
+    room_id: SignalingRoomId,
+}
+
+const RECORDER_REQUESTED: &str = "requested";
+
+/// Deletes the recorder key if the recorder has been requested but not yet joined
+const CANCEL_REQUEST_SCRIPT: &str = r"
+if redis.call('get',KEYS[1]) == ARGV[1] then
+    return redis.call('del',KEYS[1])
+else
+    return 0
+end";
+
+/// Set the key if it doesn't exist yet, it expires after the given duration.
+///
+/// Returns `false` if the key already exists.
+async fn set_nx_px(
+    redis_conn: &mut RedisConnection,
+    key: impl redis::ToRedisArgs,
+    value: impl redis::ToRedisArgs,
+    expiry: Duration,
+) -> Result<bool> {
+    let value: redis::Value = redis::cmd("SET")
+        .arg(key)
+        .arg(value)
+        .arg("PX")
+        .arg(expiry.as_millis() as u64)
+        .arg("NX")
+        .query_async(redis_conn)
+        .await?;
+
+    match value {
+        redis::Value::Okay => Ok(true),
+        redis::Value::Nil => Ok(false),
+        _ => bail!("got invalid value from SET PX NX: {:?}", value),
+    }
+}
+
+/// Initializes the recording state of the room.
+///
+/// The state expires after `timeout` unless the recording has been started in the meantime,
+/// as the participant waiting for the recorder may leave before the recorder joined.
+///
+/// Returns `false` if the room is already recording.
 pub(super) async fn try_init(
     redis_conn: &mut RedisConnection,
     room_id: SignalingRoomId,
+    timeout: Duration,
 ) -> Result<bool> {
-    redis_conn
-        .set_nx(RecordingStateKey { room_id }, RecordingStatus::Initializing)
-        .await
-        .context("Failed to initialize recording state")
+    set_nx_px(
+        redis_conn,
+        RecordingStateKey { room_id },
+        RecordingStatus::Initializing,
+        timeout,
+    )
+    .await
+    .context("Failed to initialize recording state")
 }
 
 pub(super) async fn set_recording(
@@ -41,6 +114,17 @@ pub(super) async fn set_recording(
         .context("Failed to set recording state to 'recording'")
 }
 
+pub(super) async fn set_paused(
+    redis_conn: &mut RedisConnection,
+    room_id: SignalingRoomId,
+    id: RecordingId,
+) -> Result<()> {
+    redis_conn
+        .set(RecordingStateKey { room_id }, RecordingStatus::Paused(id))
+        .await
+        .context("Failed to set recording state to 'paused'")
+}
+
 pub(super) async fn get_state(
     redis_conn: &mut RedisConnection,
     room_id: SignalingRoomId,
@@ -56,7 +140,145 @@ pub(super) async fn del_state(
     room_id: SignalingRoomId,
 ) -> Result<()> {
     redis_conn
-        .del(RecordingStateKey { room_id })
+        .del((RecordingStateKey { room_id }, RecordingScopeKey { room_id }))
         .await
         .context("Failed to delete recording state")
 }
+
+pub(super) async fn set_scope(
+    redis_conn: &mut RedisConnection,
+    room_id: SignalingRoomId,
+    scope: &RecordingScope,
+) -> Result<()> {
+    redis_conn
+        .set(RecordingScopeKey { room_id }, scope)
+        .await
+        .context("Failed to set recording scope")
+}
+
+/// Get the scope of the recording, all participants are recorded if none has been set
+pub(super) async fn get_scope(
+    redis_conn: &mut RedisConnection,
+    room_id: SignalingRoomId,
+) -> Result<RecordingScope> {
+    let scope: Option<RecordingScope> = redis_conn
+        .get(RecordingScopeKey { room_id })
+        .await
+        .context("Failed to get recording scope")?;
+
+    Ok(scope.unwrap_or_default())
+}
+
+pub(super) async fn get_streams(
+    redis_conn: &mut RedisConnection,
+    room_id: SignalingRoomId,
+) -> Result<BTreeMap<StreamingTargetId, StreamStatus>> {
+    redis_conn
+        .hgetall(RecordingStreamsKey { room_id })
+        .await
+        .context("Failed to get recording streams")
+}
+
+pub(super) async fn set_stream(
+    redis_conn: &mut RedisConnection,
+    room_id: SignalingRoomId,
+    target_id: StreamingTargetId,
+    status: StreamStatus,
+) -> Result<()> {
+    redis_conn
+        .hset(RecordingStreamsKey { room_id }, target_id, status)
+        .await
+        .context("Failed to set recording stream status")
+}
+
+pub(super) async fn del_stream(
+    redis_conn: &mut RedisConnection,
+    room_id: SignalingRoomId,
+    target_id: StreamingTargetId,
+) -> Result<()> {
+    redis_conn
+        .hdel(RecordingStreamsKey { room_id }, target_id)
+        .await
+        .context("Failed to delete recording stream")
+}
+
+pub(super) async fn del_streams(
+    redis_conn: &mut RedisConnection,
+    room_id: SignalingRoomId,
+) -> Result<()> {
+    redis_conn
+        .del(RecordingStreamsKey { room_id })
+        .await
+        .context("Failed to delete recording streams")
+}
+
+/// Marks the recorder of the room as requested.
+///
+/// The request expires after `timeout`, the recorder replaces it when it joined.
+///
+/// Returns `false` if a recorder has already been requested or joined the room.
+pub(super) async fn try_request_recorder(
+    redis_conn: &mut RedisConnection,
+    room_id: SignalingRoomId,
+    timeout: Duration,
+) -> Result<bool> {
+    set_nx_px(
+        redis_conn,
+        RecorderKey { room_id },
+        RECORDER_REQUESTED,
+        timeout,
+    )
+    .await
+    .context("Failed to request recorder")
+}
+
+/// Withdraws the request of a recorder which has not joined the room yet.
+///
+/// Returns `false` if the recorder has joined the room in the meantime.
+pub(super) async fn try_cancel_recorder_request(
+    redis_conn: &mut RedisConnection,
+    room_id: SignalingRoomId,
+) -> Result<bool> {
+    let deleted: i32 = Script::new(CANCEL_REQUEST_SCRIPT)
+        .key(RecorderKey { room_id })
+        .arg(RECORDER_REQUESTED)
+        .invoke_async(redis_conn)
+        .await
+        .context("Failed to cancel recorder request")?;
+
+    Ok(deleted == 1)
+}
+
+pub(super) async fn set_recorder(
+    redis_conn: &mut RedisConnection,
+    room_id: SignalingRoomId,
+    recorder: ParticipantId,
+) -> Result<()> {
+    redis_conn
+        .set(RecorderKey { room_id }, recorder)
+        .await
+        .context("Failed to set recorder")
+}
+
+/// Returns the participant id of the recorder if it has joined the room.
+pub(super) async fn get_recorder(
+    redis_conn: &mut RedisConnection,
+    room_id: SignalingRoomId,
+) -> Result<Option<ParticipantId>> {
+    let recorder: Option<String> = redis_conn
+        .get(RecorderKey { room_id })
+        .await
+        .context("Failed to get recorder")?;
+
+    Ok(recorder.and_then(|recorder| recorder.parse().ok()))
+}
+
+pub(super) async fn del_recorder(
+    redis_conn: &mut RedisConnection,
+    room_id: SignalingRoomId,
+) -> Result<()> {
+    redis_conn
+        .del(RecorderKey { room_id })
+        .await
+        .context("Failed to delete recorder")
+}
@@ -0,0 +1,242 @@
+// SPDX-FileCopyrightText: OpenTalk GmbH <mail@opentalk.eu>
+//
+// SPDX-License-Identifier: EUPL-1.2
+
+use crate::api::signaling::dial_out::{exchange, storage};
+use crate::api::signaling::ticket::start_or_continue_signaling_session;
+use crate::api::v1::response::{ApiError, NoContent};
+use crate::settings::SharedSettingsActix;
+use actix_web::dev::HttpServiceFactory;
+use actix_web::error::Result;
+use actix_web::post;
+use actix_web::web::{Data, Json};
+use signaling_core::{control, ExchangeHandle, Participant, RedisConnection};
+use types::api::v1::services::{DialOutProgressBody, DialOutStartBody, ServiceStartResponse};
+use types::core::{DialOutId, Timestamp};
+use types::signaling::{
+    dial_out::{CallStatus, NAMESPACE},
+    NamespacedEvent,
+};
+
+use super::call_in::REQUIRED_CALL_IN_ROLE;
+
+/// API Endpoint *POST services/dial_out/start* for the SIP gateway
+///
+/// Called when the callee of an outbound call answered, returns the ticket to join the room with.
+#[post("/start")]
+pub async fn start(
+    settings: SharedSettingsActix,
+    redis_ctx: Data<RedisConnection>,
+    body: Json<DialOutStartBody>,
+) -> Result<Json<ServiceStartResponse>, ApiError> {
+    if settings.load().rabbit_mq.dial_out_task_queue.is_none() {
+        return Err(ApiError::not_found());
+    }
+
+    let mut redis_conn = (**redis_ctx).clone();
+
+    let call = storage::get_call(&mut redis_conn, body.call_id)
+        .await?
+        .ok_or_else(unknown_call_error)?;
+
+    let (ticket, resumption) = start_or_continue_signaling_session(
+        &mut redis_conn,
+        Participant::Sip,
+        call.room_id,
+        call.breakout_room,
+        None,
+    )
+    .await?;
+
+    Ok(Json(ServiceStartResponse { ticket, resumption }))
+}
+
+/// API Endpoint *POST services/dial_out/progress* for the SIP gateway
+///
+/// Forwards the progress of an outbound call to the participant who requested it.
+#[post("/progress")]
+pub async fn progress(
+    settings: SharedSettingsActix,
+    redis_ctx: Data<RedisConnection>,
+    exchange_handle: Data<ExchangeHandle>,
+    body: Json<DialOutProgressBody>,
+) -> Result<NoContent, ApiError> {
+    if settings.load().rabbit_mq.dial_out_task_queue.is_none() {
+        return Err(ApiError::not_found());
+    }
+
+    let mut redis_conn = (**redis_ctx).clone();
+    let DialOutProgressBody { call_id, status } = body.into_inner();
+
+    let call = update_call(&mut redis_conn, call_id, status).await?;
+
+    let message = NamespacedEvent {
+        namespace: NAMESPACE,
+        timestamp: Timestamp::now(),
+        payload: exchange::Message::CallUpdated {
+            call_id,
+            number: call.number.clone(),
+            status,
+        },
+    };
+
+    if let Err(e) = exchange_handle.publish(
+        control::exchange::current_room_by_participant_id(call.room(), call.requester),
+        serde_json::to_string(&message).expect("Failed to convert namespaced to json"),
+    ) {
+        log::warn!("Failed to publish progress of call {call_id} to exchange, {e}");
+    }
+
+    Ok(NoContent)
+}
+
+/// Mark the call as placed by a SIP gateway and forget it once it is over
+async fn update_call(
+    redis_conn: &mut RedisConnection,
+    call_id: DialOutId,
+    status: CallStatus,
+) -> Result<storage::DialOutCall, ApiError> {
+    let call = storage::get_call(redis_conn, call_id)
+        .await?
+        .ok_or_else(unknown_call_error)?;
+
+    storage::take_pending(redis_conn, call_id).await?;
+
+    if status.is_final() {
+        storage::del_call(redis_conn, call_id).await?;
+    }
+
+    Ok(call)
+}
+
+fn unknown_call_error() -> ApiError {
+    ApiError::not_found()
+        .with_code("unknown_call")
+        .with_message("given call id does not belong to a requested call")
+}
+
+pub fn services() -> impl HttpServiceFactory {
+    // The SIP gateway which handles calls into the rooms also places the outbound calls
+    actix_web::web::scope("/dial_out")
+        .wrap(super::RequiredRealmRole::new(REQUIRED_CALL_IN_ROLE))
+        .service(start)
+        .service(progress)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use actix_web::test::{call_and_read_body_json, call_service, init_service, TestRequest};
+    use actix_web::App;
+    use arc_swap::ArcSwap;
+    use controller_settings::{RabbitMqConfig, Settings};
+    use pretty_assertions::assert_eq;
+    use serial_test::serial;
+    use std::sync::Arc;
+    use test_util::TestContext;
+    use types::core::{ParticipantId, RoomId};
+
+    const CALL_ID: DialOutId = DialOutId::from_u128(1);
+
+    fn call() -> storage::DialOutCall {
+        storage::DialOutCall {
+            room_id: RoomId::from_u128(2),
+            breakout_room: None,
+            requester: ParticipantId::from_u128(3),
+            number: "+4930123456".into(),
+        }
+    }
+
+    #[actix_rt::test]
+    #[serial]
+    async fn start_endpoint() {
+        let test_ctx = TestContext::new().await;
+        let mut redis_conn = test_ctx.redis_conn.clone();
+
+        storage::set_call(&mut redis_conn, CALL_ID, &call())
+            .await
+            .unwrap();
+
+        let settings = Settings::load("../../extra/example.toml").unwrap();
+        let shared_settings = Data::new(ArcSwap::from_pointee(settings.clone()));
+
+        let app = init_service(
+            App::new()
+                .app_data(shared_settings.clone())
+                .app_data(Data::new(redis_conn))
+                .service(start),
+        )
+        .await;
+
+        let request = || {
+            TestRequest::post()
+                .uri("/start")
+                .set_json(DialOutStartBody { call_id: CALL_ID })
+                .to_request()
+        };
+
+        // Not available without the dial-out queue
+        let response = call_service(&app, request()).await;
+        assert_eq!(response.status(), 404);
+
+        shared_settings.store(Arc::new(Settings {
+            rabbit_mq: RabbitMqConfig {
+                dial_out_task_queue: Some("opentalk_dial_out".into()),
+                ..settings.rabbit_mq.clone()
+            },
+            ..settings
+        }));
+
+        let response: serde_json::Value = call_and_read_body_json(&app, request()).await;
+        assert!(response["ticket"].is_string());
+        assert!(response["resumption"].is_string());
+
+        let response = call_service(
+            &app,
+            TestRequest::post()
+                .uri("/start")
+                .set_json(DialOutStartBody {
+                    call_id: DialOutId::from_u128(4),
+                })
+                .to_request(),
+        )
+        .await;
+        assert_eq!(response.status(), 404);
+    }
+
+    #[actix_rt::test]
+    #[serial]
+    async fn progress_updates_call() {
+        let mut redis_conn = test_util::redis::setup().await;
+
+        storage::set_call(&mut redis_conn, CALL_ID, &call())
+            .await
+            .unwrap();
+
+        let updated = update_call(&mut redis_conn, CALL_ID, CallStatus::Ringing)
+            .await
+            .unwrap();
+        assert_eq!(updated.number, call().number);
+
+        // The call has been placed, it does not time out anymore
+        assert!(!storage::take_pending(&mut redis_conn, CALL_ID)
+            .await
+            .unwrap());
+        assert!(storage::get_call(&mut redis_conn, CALL_ID)
+            .await
+            .unwrap()
+            .is_some());
+
+        update_call(&mut redis_conn, CALL_ID, CallStatus::Ended)
+            .await
+            .unwrap();
+        assert!(storage::get_call(&mut redis_conn, CALL_ID)
+            .await
+            .unwrap()
+            .is_none());
+
+        assert!(update_call(&mut redis_conn, CALL_ID, CallStatus::Ended)
+            .await
+            .is_err());
+    }
+}
@@ -13,6 +13,7 @@ use futures::future::Either;
 use std::rc::Rc;
 
 pub mod call_in;
+pub mod dial_out;
 pub mod recording;
 
 /// Middleware factory for [`RequiredRealmRoleMiddleware`]
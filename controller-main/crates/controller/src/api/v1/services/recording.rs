@@ -4,26 +4,108 @@
 
 use crate::api::signaling::ticket::start_or_continue_signaling_session;
 use crate::api::v1::response::ApiError;
-use crate::api::v1::response::NoContent;
 use crate::settings::SharedSettingsActix;
 use actix_web::dev::HttpServiceFactory;
-use actix_web::post;
 use actix_web::web::Payload;
 use actix_web::web::Query;
-use actix_web::web::{Data, Json};
+use actix_web::web::{Data, Json, Path};
+use actix_web::{get, post, put};
+use anyhow::Context;
+use bytes::{Bytes, BytesMut};
 use database::Db;
+use db_storage::assets::Asset;
 use db_storage::rooms::Room;
-use futures::TryStreamExt;
-use signaling_core::assets::save_asset;
-use signaling_core::{ObjectStorage, Participant, RedisConnection};
-use types::api::v1::services::UploadRenderQuery;
+use futures::{StreamExt, TryStreamExt};
+use r3dlock::{Mutex, MutexGuard};
+use redis::AsyncCommands;
+use redis_args::{FromRedisValue, ToRedisArgs};
+use serde::{Deserialize, Serialize};
+use signaling_core::assets::{asset_key, register_asset, save_linked_asset};
+use signaling_core::{ObjectStorage, Participant, RedisConnection, MIN_PART_SIZE};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tokio::time::interval;
+use types::api::v1::assets::MediaMetadata;
+use types::api::v1::services::{
+    RenderKind, UploadChunkQuery, UploadRenderQuery, UploadRenderResponse, UploadStatus,
+};
 use types::api::v1::services::{ServiceStartResponse, StartBody};
+use types::core::{AssetId, RoomId, UploadId};
 
 // Note to devs:
 // Please update `docs/admin/keycloak.md` service login documentation as well if
 // you change something here
 const REQUIRED_RECORDING_ROLE: &str = "opentalk-recorder";
 
+/// Unfinished uploads expire one week after their last chunk, completed uploads one week after
+/// their completion
+const UPLOAD_EXPIRY_SECONDS: u64 = 7 * 24 * 60 * 60;
+
+/// Largest chunk accepted by the `PUT /uploads/{upload_id}` endpoint
+const MAX_CHUNK_SIZE: usize = 64 * 1024 * 1024;
+
+/// Time after which the [`UploadLock`] expires, long enough to upload a chunk of
+/// [`MAX_CHUNK_SIZE`] to the object storage
+const UPLOAD_LOCK_TIME: Duration = Duration::from_secs(10 * 60);
+
+/// Interval in which the multipart uploads of expired uploads are aborted
+const ABORT_EXPIRED_UPLOADS_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Typed redis key for a resumable upload containing [`UploadState`]
+#[derive(Debug, Copy, Clone, ToRedisArgs)]
+#[to_redis_args(fmt = "opentalk-recording:upload={upload_id}")]
+struct UploadRedisKey {
+    upload_id: UploadId,
+}
+
+/// Lock which serializes the requests changing an upload
+#[derive(Debug, Copy, Clone, ToRedisArgs)]
+#[to_redis_args(fmt = "opentalk-recording:upload={upload_id}.lock")]
+struct UploadLock {
+    upload_id: UploadId,
+}
+
+/// Typed redis key for a sorted set of all unfinished uploads scored by their expiry timestamp
+#[derive(Debug, Copy, Clone, ToRedisArgs)]
+#[to_redis_args(fmt = "opentalk-recording:uploads")]
+struct ExpiringUploads;
+
+/// Data stored behind the [`UploadRedisKey`]
+#[derive(Debug, Clone, Deserialize, Serialize, ToRedisArgs, FromRedisValue)]
+#[to_redis_args(serde)]
+#[from_redis_value(serde)]
+struct UploadState {
+    room_id: RoomId,
+    filename: String,
+    kind: RenderKind,
+    linked_asset_id: Option<AssetId>,
+    /// Duration and resolution of an uploaded recording
+    #[serde(default)]
+    media: Option<MediaMetadata>,
+    /// The asset which is created when the upload is complete
+    asset_id: AssetId,
+    /// The multipart upload into the S3 bucket
+    multipart_upload_id: String,
+    /// The etag and size of every uploaded part
+    parts: Vec<UploadedPart>,
+    /// Set once the asset has been created
+    complete: bool,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+struct UploadedPart {
+    e_tag: String,
+    size: u64,
+}
+
+impl UploadState {
+    /// The number of bytes received so far
+    fn offset(&self) -> u64 {
+        self.parts.iter().map(|part| part.size).sum()
+    }
+}
+
 #[post("/start")]
 pub async fn start(
     settings: SharedSettingsActix,
@@ -59,22 +141,451 @@ pub async fn upload_render(
     db: Data<Db>,
     query: Query<UploadRenderQuery>,
     data: Payload,
-) -> Result<NoContent, ApiError> {
-    // Assert that the room exists
-    Room::get(&mut db.get_conn().await?, query.room_id).await?;
+) -> Result<Json<UploadRenderResponse>, ApiError> {
+    check_upload_target(&db, &query).await?;
 
-    save_asset(
+    let asset_id = save_linked_asset(
         &storage,
         db.into_inner(),
         query.room_id,
         Some("recording"),
         &query.filename,
-        "recording-render",
+        query.kind.asset_kind(),
+        query.linked_asset_id,
+        query.media(),
         data.into_stream().map_err(anyhow::Error::from),
     )
     .await?;
 
-    Ok(NoContent)
+    Ok(Json(UploadRenderResponse { asset_id }))
+}
+
+/// Start a resumable upload of a recording file
+///
+/// The file is uploaded in chunks with `PUT /uploads/{upload_id}` and turned into an asset with
+/// `POST /uploads/{upload_id}/complete`.
+#[post("/uploads")]
+pub async fn start_upload(
+    storage: Data<ObjectStorage>,
+    db: Data<Db>,
+    redis_ctx: Data<RedisConnection>,
+    query: Query<UploadRenderQuery>,
+) -> Result<Json<UploadStatus>, ApiError> {
+    let mut redis_conn = (**redis_ctx).clone();
+    let query = query.into_inner();
+
+    check_upload_target(&db, &query).await?;
+
+    let asset_id = AssetId::generate();
+    let multipart_upload_id = storage
+        .create_multipart_upload(&asset_key(&asset_id))
+        .await?;
+
+    let upload_id = UploadId::generate();
+    let media = query.media();
+    let state = UploadState {
+        room_id: query.room_id,
+        filename: query.filename,
+        kind: query.kind,
+        linked_asset_id: query.linked_asset_id,
+        media,
+        asset_id,
+        multipart_upload_id,
+        parts: Vec::new(),
+        complete: false,
+    };
+
+    set_upload(&mut redis_conn, upload_id, &state).await?;
+
+    Ok(Json(UploadStatus {
+        upload_id,
+        offset: 0,
+    }))
+}
+
+/// Get the offset a resumable upload continues at
+#[get("/uploads/{upload_id}")]
+pub async fn get_upload(
+    redis_ctx: Data<RedisConnection>,
+    upload_id: Path<UploadId>,
+) -> Result<Json<UploadStatus>, ApiError> {
+    let mut redis_conn = (**redis_ctx).clone();
+    let upload_id = upload_id.into_inner();
+
+    let state = get_upload_state(&mut redis_conn, upload_id).await?;
+
+    Ok(Json(UploadStatus {
+        upload_id,
+        offset: state.offset(),
+    }))
+}
+
+/// Append a chunk to a resumable upload
+///
+/// The chunk has to start at the offset of the upload. All chunks except the last one need at
+/// least 5 MiB, no chunk may exceed 64 MiB.
+#[put("/uploads/{upload_id}")]
+pub async fn upload_chunk(
+    storage: Data<ObjectStorage>,
+    redis_ctx: Data<RedisConnection>,
+    upload_id: Path<UploadId>,
+    query: Query<UploadChunkQuery>,
+    mut data: Payload,
+) -> Result<Json<UploadStatus>, ApiError> {
+    let mut redis_conn = (**redis_ctx).clone();
+    let upload_id = upload_id.into_inner();
+
+    // Receive the whole chunk before locking the upload, as clients may send slowly
+    let mut chunk = BytesMut::new();
+    while let Some(bytes) = data.next().await {
+        chunk.extend_from_slice(&bytes.context("failed to read chunk")?);
+
+        if chunk.len() > MAX_CHUNK_SIZE {
+            return Err(ApiError::bad_request()
+                .with_code("chunk_too_large")
+                .with_message("chunks may not exceed 64 MiB"));
+        }
+    }
+
+    if chunk.is_empty() {
+        return Err(ApiError::bad_request()
+            .with_code("empty_chunk")
+            .with_message("the chunk contains no data"));
+    }
+
+    let mut mutex = upload_mutex(upload_id);
+    let guard = lock_upload(&mut mutex, &mut redis_conn).await?;
+
+    let result = append_chunk(
+        &storage,
+        &mut redis_conn,
+        upload_id,
+        query.offset,
+        chunk.freeze(),
+    )
+    .await;
+
+    unlock_upload(guard, &mut redis_conn).await;
+
+    let offset = result?;
+
+    Ok(Json(UploadStatus { upload_id, offset }))
+}
+
+/// Upload the chunk as next part of the upload
+///
+/// Must be called while holding the [`UploadLock`]. Returns the new offset of the upload.
+async fn append_chunk(
+    storage: &ObjectStorage,
+    redis_conn: &mut RedisConnection,
+    upload_id: UploadId,
+    offset: u64,
+    chunk: Bytes,
+) -> Result<u64, ApiError> {
+    let mut state = get_upload_state(redis_conn, upload_id).await?;
+
+    if state.complete {
+        return Err(ApiError::conflict()
+            .with_code("upload_complete")
+            .with_message("the upload has been completed already"));
+    }
+
+    let expected = state.offset();
+    if offset != expected {
+        return Err(ApiError::conflict()
+            .with_code("offset_mismatch")
+            .with_message(format!("the upload continues at offset {expected}")));
+    }
+
+    if state
+        .parts
+        .last()
+        .is_some_and(|part| part.size < MIN_PART_SIZE as u64)
+    {
+        return Err(ApiError::bad_request()
+            .with_code("chunk_too_small")
+            .with_message("only the last chunk may be smaller than 5 MiB"));
+    }
+
+    let size = chunk.len() as u64;
+    let part_number = i32::try_from(state.parts.len() + 1).context("too many chunks")?;
+    let e_tag = storage
+        .upload_part(
+            &asset_key(&state.asset_id),
+            &state.multipart_upload_id,
+            part_number,
+            chunk,
+        )
+        .await?;
+
+    // Another request may have changed the upload if the lock expired during a slow upload
+    let current = get_upload_state(redis_conn, upload_id).await?;
+    if current.complete || current.offset() != expected {
+        log::warn!("Upload {upload_id} changed while uploading part {part_number}");
+
+        return Err(ApiError::conflict()
+            .with_code("upload_busy")
+            .with_message("another request changed the upload"));
+    }
+
+    state.parts.push(UploadedPart { e_tag, size });
+
+    set_upload(redis_conn, upload_id, &state).await?;
+
+    Ok(state.offset())
+}
+
+/// Complete a resumable upload and store the uploaded file as asset
+///
+/// Completing an upload again returns the same asset.
+#[post("/uploads/{upload_id}/complete")]
+pub async fn complete_upload(
+    storage: Data<ObjectStorage>,
+    db: Data<Db>,
+    redis_ctx: Data<RedisConnection>,
+    upload_id: Path<UploadId>,
+) -> Result<Json<UploadRenderResponse>, ApiError> {
+    let mut redis_conn = (**redis_ctx).clone();
+    let upload_id = upload_id.into_inner();
+
+    let mut mutex = upload_mutex(upload_id);
+    let guard = lock_upload(&mut mutex, &mut redis_conn).await?;
+
+    let result = finish_upload(&storage, db.into_inner(), &mut redis_conn, upload_id).await;
+
+    unlock_upload(guard, &mut redis_conn).await;
+
+    let asset_id = result?;
+
+    Ok(Json(UploadRenderResponse { asset_id }))
+}
+
+/// Complete the multipart upload and register the asset
+///
+/// Must be called while holding the [`UploadLock`]. Returns the id of the created asset.
+async fn finish_upload(
+    storage: &ObjectStorage,
+    db: Arc<Db>,
+    redis_conn: &mut RedisConnection,
+    upload_id: UploadId,
+) -> Result<AssetId, ApiError> {
+    let mut state = get_upload_state(redis_conn, upload_id).await?;
+
+    if state.complete {
+        return Ok(state.asset_id);
+    }
+
+    let key = asset_key(&state.asset_id);
+    if state.parts.is_empty() {
+        // S3 cannot complete multipart uploads without parts
+        storage
+            .abort_multipart_upload(&key, &state.multipart_upload_id)
+            .await?;
+        storage
+            .put(&key, futures::stream::empty::<anyhow::Result<Bytes>>())
+            .await?;
+    } else {
+        storage
+            .complete_multipart_upload(
+                &key,
+                &state.multipart_upload_id,
+                state
+                    .parts
+                    .iter()
+                    .zip(1..)
+                    .map(|(part, part_number)| (part_number, part.e_tag.clone())),
+            )
+            .await?;
+    }
+
+    let asset_id = register_asset(
+        storage,
+        db,
+        state.room_id,
+        state.asset_id,
+        Some("recording"),
+        &state.filename,
+        state.kind.asset_kind(),
+        state.linked_asset_id,
+        state.media,
+    )
+    .await?;
+
+    state.complete = true;
+    set_upload(redis_conn, upload_id, &state).await?;
+
+    Ok(asset_id)
+}
+
+/// Assert that the room exists and the linked asset belongs to the room
+async fn check_upload_target(db: &Db, query: &UploadRenderQuery) -> Result<(), ApiError> {
+    let mut conn = db.get_conn().await?;
+
+    Room::get(&mut conn, query.room_id).await?;
+
+    if let Some(linked_asset_id) = query.linked_asset_id {
+        Asset::get(&mut conn, linked_asset_id, query.room_id).await?;
+    }
+
+    Ok(())
+}
+
+async fn get_upload_state(
+    redis_conn: &mut RedisConnection,
+    upload_id: UploadId,
+) -> Result<UploadState, ApiError> {
+    let key = UploadRedisKey { upload_id };
+    let state: Option<UploadState> = redis_conn.get(key).await.map_err(|e| {
+        log::error!("Unable to load upload state from redis, {}", e);
+        ApiError::internal()
+    })?;
+
+    state.ok_or_else(|| {
+        ApiError::not_found()
+            .with_code("unknown_upload")
+            .with_message("the upload does not exist or has expired")
+    })
+}
+
+/// Store the state of an upload
+///
+/// Unfinished uploads are kept in [`ExpiringUploads`] until they expire, then
+/// [`abort_expired_uploads`] aborts their multipart upload. Completed uploads expire in redis.
+async fn set_upload(
+    redis_conn: &mut RedisConnection,
+    upload_id: UploadId,
+    state: &UploadState,
+) -> Result<(), ApiError> {
+    let key = UploadRedisKey { upload_id };
+    let mut pipe = redis::pipe();
+    pipe.atomic();
+
+    if state.complete {
+        pipe.set_ex(key, state, UPLOAD_EXPIRY_SECONDS)
+            .ignore()
+            .zrem(ExpiringUploads, upload_id)
+            .ignore();
+    } else {
+        let expires_at = chrono::Utc::now().timestamp() + UPLOAD_EXPIRY_SECONDS as i64;
+        pipe.set(key, state)
+            .ignore()
+            .zadd(ExpiringUploads, upload_id, expires_at)
+            .ignore();
+    }
+
+    pipe.query_async(redis_conn).await.map_err(|e| {
+        log::error!("Unable to store upload state in redis, {}", e);
+        ApiError::internal()
+    })
+}
+
+/// Periodically abort the multipart uploads of expired uploads until the controller shuts down
+pub async fn abort_expired_uploads_task(
+    storage: Arc<ObjectStorage>,
+    mut redis_conn: RedisConnection,
+    mut shutdown: broadcast::Receiver<()>,
+) {
+    let mut interval = interval(ABORT_EXPIRED_UPLOADS_INTERVAL);
+
+    loop {
+        tokio::select! {
+            _ = interval.tick() => {}
+            _ = shutdown.recv() => return,
+        }
+
+        if let Err(e) = abort_expired_uploads(&storage, &mut redis_conn).await {
+            log::warn!("Unable to abort expired uploads, {:?}", e);
+        }
+    }
+}
+
+/// Abort the multipart uploads of all unfinished uploads which have expired and remove them
+async fn abort_expired_uploads(
+    storage: &ObjectStorage,
+    redis_conn: &mut RedisConnection,
+) -> anyhow::Result<()> {
+    let expired: Vec<UploadId> = redis_conn
+        .zrangebyscore(ExpiringUploads, "-inf", chrono::Utc::now().timestamp())
+        .await
+        .context("Failed to get expired uploads")?;
+
+    for upload_id in expired {
+        let mut mutex = upload_mutex(upload_id);
+        let guard = match mutex.lock(redis_conn).await {
+            Ok(guard) => guard,
+            Err(r3dlock::Error::CouldNotAcquireLock) => {
+                // A chunk is being uploaded right now, the upload is not expired anymore
+                continue;
+            }
+            Err(e) => return Err(e).context("Failed to lock expired upload"),
+        };
+
+        let result = abort_upload(storage, redis_conn, upload_id).await;
+
+        unlock_upload(guard, redis_conn).await;
+
+        result?;
+    }
+
+    Ok(())
+}
+
+/// Abort the multipart upload of the given upload and remove its state
+///
+/// Must be called while holding the [`UploadLock`].
+async fn abort_upload(
+    storage: &ObjectStorage,
+    redis_conn: &mut RedisConnection,
+    upload_id: UploadId,
+) -> anyhow::Result<()> {
+    let key = UploadRedisKey { upload_id };
+    let state: Option<UploadState> = redis_conn
+        .get(key)
+        .await
+        .context("Failed to get expired upload state")?;
+
+    if let Some(state) = state.filter(|state| !state.complete) {
+        log::info!("Aborting expired upload {upload_id}");
+        storage
+            .abort_multipart_upload(&asset_key(&state.asset_id), &state.multipart_upload_id)
+            .await?;
+    }
+
+    redis::pipe()
+        .atomic()
+        .del(key)
+        .ignore()
+        .zrem(ExpiringUploads, upload_id)
+        .ignore()
+        .query_async(redis_conn)
+        .await
+        .context("Failed to remove expired upload")
+}
+
+fn upload_mutex(upload_id: UploadId) -> Mutex<UploadLock> {
+    Mutex::new(UploadLock { upload_id }).with_lock_time(UPLOAD_LOCK_TIME)
+}
+
+/// Lock the given upload, fails with a conflict while another request holds the lock
+async fn lock_upload<'m>(
+    mutex: &'m mut Mutex<UploadLock>,
+    redis_conn: &mut RedisConnection,
+) -> Result<MutexGuard<'m, UploadLock>, ApiError> {
+    mutex.lock(redis_conn).await.map_err(|e| match e {
+        r3dlock::Error::CouldNotAcquireLock => ApiError::conflict()
+            .with_code("upload_busy")
+            .with_message("another request is changing the upload"),
+        e => {
+            log::error!("Unable to lock upload, {}", e);
+            ApiError::internal()
+        }
+    })
+}
+
+async fn unlock_upload(guard: MutexGuard<'_, UploadLock>, redis_conn: &mut RedisConnection) {
+    if let Err(e) = guard.unlock(redis_conn).await {
+        log::error!("Unable to unlock upload, {}", e);
+    }
 }
 
 pub fn services() -> impl HttpServiceFactory {
@@ -82,4 +593,8 @@ pub fn services() -> impl HttpServiceFactory {
         .wrap(super::RequiredRealmRole::new(REQUIRED_RECORDING_ROLE))
         .service(start)
         .service(upload_render)
+        .service(start_upload)
+        .service(get_upload)
+        .service(upload_chunk)
+        .service(complete_upload)
 }
@@ -4,7 +4,7 @@
 
 //! Contains invite related REST endpoints.
 use super::{
-    response::{ApiError, NoContent},
+    response::{error::ValidationErrorEntry, ApiError, NoContent, CODE_INVALID_VALUE},
     DefaultApiResult,
 };
 use crate::api::v1::ApiResponse;
@@ -25,11 +25,12 @@ use types::{
             GetRoomStreamingTargetResponse, GetRoomStreamingTargetsResponse,
             PostRoomStreamingTargetRequest, PostRoomStreamingTargetResponse,
         },
-        streaming_targets::{RoomAndStreamingTargetId, UpdateStreamingTargetKind},
+        streaming_targets::RoomAndStreamingTargetId,
+    },
+    common::streaming::{
+        RoomStreamingTarget, StreamingTarget, StreamingTargetError, StreamingTargetKind,
     },
-    common::streaming::{RoomStreamingTarget, StreamingTarget, StreamingTargetKind},
     core::RoomId,
-    core::StreamingKind,
 };
 
 /// API Endpoint *GET /rooms/{room_id}/streaming_targets*
@@ -63,34 +64,41 @@ pub(super) async fn get_room_streaming_targets(
 
     let room_streaming_targets = streaming_targets
         .into_iter()
-        .map(|st| {
-            let streaming_endpoint = st
-                .streaming_endpoint
-                .parse()
-                .context("invalid streaming endpoint url entry in db")?;
-            let public_url = st
-                .public_url
-                .parse()
-                .context("invalid public url entry in db")?;
-
-            let room_streaming_target = RoomStreamingTarget {
-                id: st.id,
-                streaming_target: StreamingTarget {
-                    name: st.name,
-                    kind: StreamingTargetKind::Custom {
-                        streaming_endpoint,
-                        streaming_key: st.streaming_key,
-                        public_url,
-                    },
-                },
-            };
-
-            Ok(room_streaming_target)
-        })
+        .map(room_streaming_target_from_record)
         .collect::<Result<Vec<_>, ApiError>>()?;
     Ok(room_streaming_targets)
 }
 
+/// Build a [`RoomStreamingTarget`] from its database record
+fn room_streaming_target_from_record(
+    record: RoomStreamingTargetRecord,
+) -> Result<RoomStreamingTarget, ApiError> {
+    let kind = StreamingTargetKind::from_parts(
+        record.kind,
+        &record.streaming_endpoint,
+        record.streaming_key,
+        &record.public_url,
+    )
+    .context("invalid streaming target entry in db")?;
+
+    Ok(RoomStreamingTarget {
+        id: record.id,
+        streaming_target: StreamingTarget {
+            name: record.name,
+            kind,
+        },
+    })
+}
+
+/// Map an invalid streaming target to a 422 Unprocessable Entity error
+fn invalid_streaming_target(error: StreamingTargetError) -> ApiError {
+    ApiError::unprocessable_entities([ValidationErrorEntry::new(
+        error.field,
+        CODE_INVALID_VALUE,
+        Some(error.reason),
+    )])
+}
+
 /// API Endpoint *POST /rooms/{room_id}/streaming_targets*
 ///
 /// Creates a new streaming target for the given room
@@ -117,28 +125,26 @@ pub(super) async fn insert_room_streaming_target(
     room_id: RoomId,
     streaming_target: StreamingTarget,
 ) -> Result<RoomStreamingTarget, ApiError> {
-    let streaming_target_clone = streaming_target.clone();
-
-    let streaming_target_record = match streaming_target.kind {
-        StreamingTargetKind::Custom {
-            streaming_endpoint,
-            streaming_key,
-            public_url,
-        } => RoomStreamingTargetNew {
-            room_id,
-            name: streaming_target_clone.name.clone(),
-            kind: StreamingKind::Custom,
-            streaming_endpoint: streaming_endpoint.into(),
-            streaming_key: streaming_key.into(),
-            public_url: public_url.into(),
-        },
+    let kind = &streaming_target.kind;
+    kind.validate().map_err(invalid_streaming_target)?;
+
+    let streaming_target_record = RoomStreamingTargetNew {
+        room_id,
+        name: streaming_target.name.clone(),
+        kind: kind.streaming_kind(),
+        streaming_endpoint: kind
+            .streaming_endpoint()
+            .map_err(invalid_streaming_target)?
+            .into(),
+        streaming_key: kind.streaming_key().clone().into(),
+        public_url: kind.public_url().map_err(invalid_streaming_target)?.into(),
     }
     .insert(conn)
     .await?;
 
     let room_streaming_target = RoomStreamingTarget {
         id: streaming_target_record.id,
-        streaming_target: streaming_target_clone,
+        streaming_target,
     };
     Ok(room_streaming_target)
 }
@@ -162,23 +168,7 @@ pub async fn get_streaming_target(
         RoomStreamingTargetRecord::get(&mut conn, streaming_target_id, room_id).await?;
 
     Ok(ApiResponse::new(GetRoomStreamingTargetResponse(
-        RoomStreamingTarget {
-            id: room_streaming_target.id,
-            streaming_target: StreamingTarget {
-                name: room_streaming_target.name,
-                kind: StreamingTargetKind::Custom {
-                    streaming_endpoint: room_streaming_target
-                        .streaming_endpoint
-                        .parse()
-                        .context("invalid streaming endpoint url entry in db")?,
-                    streaming_key: room_streaming_target.streaming_key,
-                    public_url: room_streaming_target
-                        .public_url
-                        .parse()
-                        .context("invalid public url entry in db")?,
-                },
-            },
-        },
+        room_streaming_target_from_record(room_streaming_target)?,
     )))
 }
 
@@ -204,18 +194,27 @@ pub async fn patch_streaming_target(
     } = path_params.into_inner();
 
     let (kind, streaming_endpoint, streaming_key, public_url) = match update_streaming_target.kind {
-        Some(kind) => match kind {
-            UpdateStreamingTargetKind::Custom {
-                streaming_endpoint,
-                streaming_key,
-                public_url,
-            } => (
-                Some(StreamingKind::Custom),
-                streaming_endpoint.map(|s| s.into()),
-                streaming_key.map(|s| s.into()),
-                public_url.map(|s| s.into()),
-            ),
-        },
+        Some(update_kind) => {
+            let current = room_streaming_target_from_record(
+                RoomStreamingTargetRecord::get(&mut conn, streaming_target_id, room_id).await?,
+            )?;
+
+            let kind = update_kind
+                .apply(current.streaming_target.kind)
+                .map_err(invalid_streaming_target)?;
+            kind.validate().map_err(invalid_streaming_target)?;
+
+            (
+                Some(kind.streaming_kind()),
+                Some(
+                    kind.streaming_endpoint()
+                        .map_err(invalid_streaming_target)?
+                        .into(),
+                ),
+                Some(kind.streaming_key().clone().into()),
+                Some(kind.public_url().map_err(invalid_streaming_target)?.into()),
+            )
+        }
         None => (None, None, None, None),
     };
 
@@ -231,27 +230,7 @@ pub async fn patch_streaming_target(
         .apply(&mut conn, room_id, streaming_target_id)
         .await?;
 
-    let kind = match room_streaming_target_table.kind {
-        StreamingKind::Custom => StreamingTargetKind::Custom {
-            streaming_endpoint: room_streaming_target_table
-                .streaming_endpoint
-                .parse()
-                .context("invalid streaming endpoint url entry in db")?,
-            streaming_key: room_streaming_target_table.streaming_key,
-            public_url: room_streaming_target_table
-                .public_url
-                .parse()
-                .context("invalid public url entry in db")?,
-        },
-    };
-
-    let room_streaming_target = RoomStreamingTarget {
-        id: room_streaming_target_table.id,
-        streaming_target: StreamingTarget {
-            name: room_streaming_target_table.name,
-            kind,
-        },
-    };
+    let room_streaming_target = room_streaming_target_from_record(room_streaming_target_table)?;
 
     Ok(ApiResponse::new(ChangeRoomStreamingTargetResponse(
         room_streaming_target,
@@ -46,7 +46,7 @@ use actix_web::web::Data;
 use actix_web::{web, App, HttpServer, Scope};
 use anyhow::{anyhow, Context, Result};
 use api::signaling::echo::Echo;
-use api::signaling::{recording::Recording, SignalingModules};
+use api::signaling::{dial_out::DialOut, recording::Recording, SignalingModules};
 use arc_swap::ArcSwap;
 use async_trait::async_trait;
 use database::Db;
@@ -131,6 +131,7 @@ impl<M: RegisterModules> RegisterModules for ControllerModules<M> {
         registrar.register::<BreakoutRooms>().await?;
         registrar.register::<ModerationModule>().await?;
         registrar.register::<Recording>().await?;
+        registrar.register::<DialOut>().await?;
         M::register(registrar).await
     }
 }
@@ -291,6 +292,12 @@ impl Controller {
     pub async fn run(self) -> Result<()> {
         let signaling_modules = Arc::new(self.signaling);
 
+        actix_rt::spawn(api::v1::services::recording::abort_expired_uploads_task(
+            self.storage.clone(),
+            self.redis.clone(),
+            self.shutdown.subscribe(),
+        ));
+
         // Start HTTP Server
         let http_server = {
             let settings = self.shared_settings.clone();
@@ -510,6 +517,7 @@ fn v1_scope(
                     oidc_ctx.clone(),
                 ))
                 .service(api::v1::services::call_in::services())
+                .service(api::v1::services::dial_out::services())
                 .service(api::v1::services::recording::services()),
         )
         .service(
@@ -582,7 +590,8 @@ fn internal_scope(settings: SharedSettings, db: Data<Db>, oidc_ctx: Data<OidcCon
                 db,
                 oidc_ctx,
             })
-            .service(api::internal::rooms::delete),
+            .service(api::internal::rooms::delete)
+            .service(api::internal::janus::health),
     )
 }
 
@@ -13,7 +13,7 @@ use diesel::{
 use diesel_async::scoped_futures::ScopedFutureExt;
 use diesel_async::AsyncConnection;
 use diesel_async::RunQueryDsl;
-use types::api::v1::assets::AssetResource;
+use types::api::v1::assets::{AssetResource, MediaMetadata};
 use types::core::{AssetId, RoomId, TenantId};
 
 /// Diesel resource struct
@@ -26,9 +26,24 @@ pub struct Asset {
     pub kind: String,
     pub filename: String,
     pub tenant_id: TenantId,
+    pub linked_asset_id: Option<AssetId>,
+    pub duration_ms: Option<i64>,
+    pub width: Option<i32>,
+    pub height: Option<i32>,
 }
 
 impl Asset {
+    /// The duration and resolution of a media asset
+    pub fn media(&self) -> Option<MediaMetadata> {
+        let duration_ms = u64::try_from(self.duration_ms?).ok()?;
+
+        Some(MediaMetadata {
+            duration_ms,
+            width: self.width.and_then(|width| u32::try_from(width).ok()),
+            height: self.height.and_then(|height| u32::try_from(height).ok()),
+        })
+    }
+
     #[tracing::instrument(err, skip_all)]
     pub async fn get(conn: &mut DbConnection, id: AssetId, room_id: RoomId) -> Result<Self> {
         //FIXME: The inner_join below (as well as the room_id parameter) can be removed when assets have their own
@@ -113,12 +128,15 @@ impl Asset {
         Ok(resources_with_total)
     }
 
+    /// Delete the asset and the assets linked to it
+    ///
+    /// Returns the ids of all deleted assets.
     #[tracing::instrument(err, skip_all)]
     pub async fn delete_by_id(
         conn: &mut DbConnection,
         asset_id: AssetId,
         room_id: RoomId,
-    ) -> Result<()> {
+    ) -> Result<Vec<AssetId>> {
         conn.transaction(|conn| {
             async move {
                 //FIXME: This check (as well as the room_id parameter) can be removed when assets have their own permission
@@ -134,11 +152,18 @@ impl Asset {
                     .execute(conn)
                     .await?;
 
+                let linked_asset_ids: Vec<AssetId> = assets::table
+                    .filter(assets::linked_asset_id.eq(asset_id))
+                    .select(assets::id)
+                    .load(conn)
+                    .await?;
+
+                // The linked assets are deleted by the database as well
                 diesel::delete(assets::table.filter(assets::id.eq(asset_id)))
                     .execute(conn)
                     .await?;
 
-                Ok(())
+                Ok(std::iter::once(asset_id).chain(linked_asset_ids).collect())
             }
             .scope_boxed()
         })
@@ -157,11 +182,15 @@ impl Asset {
 
 impl From<Asset> for AssetResource {
     fn from(value: Asset) -> Self {
+        let media = value.media();
+
         Self {
             id: value.id,
             filename: value.filename,
             namespace: value.namespace,
             created_at: value.created_at,
+            linked_asset_id: value.linked_asset_id,
+            media,
         }
     }
 }
@@ -180,6 +209,10 @@ pub struct NewAsset {
     pub kind: String,
     pub filename: String,
     pub tenant_id: TenantId,
+    pub linked_asset_id: Option<AssetId>,
+    pub duration_ms: Option<i64>,
+    pub width: Option<i32>,
+    pub height: Option<i32>,
 }
 
 impl NewAsset {
@@ -0,0 +1,4 @@
+ALTER TYPE streaming_kind ADD VALUE 'youtube';
+ALTER TYPE streaming_kind ADD VALUE 'twitch';
+ALTER TYPE streaming_kind ADD VALUE 'owncast';
+ALTER TYPE streaming_kind ADD VALUE 'peertube';
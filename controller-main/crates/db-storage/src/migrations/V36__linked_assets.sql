@@ -0,0 +1 @@
+ALTER TABLE assets ADD COLUMN linked_asset_id UUID REFERENCES assets(id) ON DELETE CASCADE;
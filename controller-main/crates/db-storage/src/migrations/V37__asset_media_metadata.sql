@@ -0,0 +1,4 @@
+ALTER TABLE assets
+    ADD COLUMN duration_ms BIGINT,
+    ADD COLUMN width INTEGER,
+    ADD COLUMN height INTEGER;
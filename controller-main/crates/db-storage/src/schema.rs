@@ -14,6 +14,10 @@ diesel::table! {
         #[max_length = 512]
         filename -> Varchar,
         tenant_id -> Uuid,
+        linked_asset_id -> Nullable<Uuid>,
+        duration_ms -> Nullable<Int8>,
+        width -> Nullable<Int4>,
+        height -> Nullable<Int4>,
     }
 }
 
@@ -0,0 +1,81 @@
+// SPDX-FileCopyrightText: OpenTalk GmbH <mail@opentalk.eu>
+//
+// SPDX-License-Identifier: EUPL-1.2
+
+use crate::common::make_user;
+use opentalk_db_storage::assets::{Asset, NewAsset};
+use opentalk_db_storage::rooms::NewRoom;
+use pretty_assertions::assert_eq;
+use serial_test::serial;
+use types::core::{AssetId, TenantId};
+
+mod common;
+
+fn new_asset(id: u128, tenant_id: TenantId, linked_asset_id: Option<AssetId>) -> NewAsset {
+    NewAsset {
+        id: AssetId::from_u128(id),
+        namespace: Some("recording".into()),
+        kind: "record".into(),
+        filename: "recording.mp4".into(),
+        tenant_id,
+        linked_asset_id,
+        duration_ms: None,
+        width: None,
+        height: None,
+    }
+}
+
+#[tokio::test]
+#[serial]
+async fn delete_linked_assets() {
+    let db_ctx = test_util::database::DatabaseContext::new(true).await;
+
+    let mut conn = db_ctx.db.get_conn().await.unwrap();
+
+    let user = make_user(&mut conn, "Test", "Tester", "Test Tester").await;
+
+    let room = NewRoom {
+        created_by: user.id,
+        password: None,
+        waiting_room: false,
+        tenant_id: user.tenant_id,
+    }
+    .insert(&mut conn)
+    .await
+    .unwrap();
+
+    let recording = new_asset(1, user.tenant_id, None)
+        .insert_for_room(&mut conn, room.id)
+        .await
+        .unwrap();
+    let chapters = new_asset(2, user.tenant_id, Some(recording.id))
+        .insert_for_room(&mut conn, room.id)
+        .await
+        .unwrap();
+    let timeline = new_asset(3, user.tenant_id, Some(recording.id))
+        .insert_for_room(&mut conn, room.id)
+        .await
+        .unwrap();
+    let other = new_asset(4, user.tenant_id, None)
+        .insert_for_room(&mut conn, room.id)
+        .await
+        .unwrap();
+
+    let mut deleted = Asset::delete_by_id(&mut conn, recording.id, room.id)
+        .await
+        .unwrap();
+    deleted.sort();
+
+    let mut expected = vec![recording.id, chapters.id, timeline.id];
+    expected.sort();
+
+    assert_eq!(deleted, expected);
+
+    // The linked assets are gone, unrelated assets are kept
+    assert_eq!(
+        Asset::get_all_ids_for_room(&mut conn, room.id)
+            .await
+            .unwrap(),
+        vec![other.id]
+    );
+}
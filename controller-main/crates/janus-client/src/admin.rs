@@ -0,0 +1,493 @@
+// SPDX-FileCopyrightText: OpenTalk GmbH <mail@opentalk.eu>
+//
+// SPDX-License-Identifier: EUPL-1.2
+
+//! Client for the Janus Admin/Monitor API
+//!
+//! The admin API is served by janus on a separate websocket endpoint using the
+//! `janus-admin-protocol` subprotocol. Requests are not bound to a session, each request is
+//! answered by a single response with the same transaction identifier.
+//!
+//! See [Janus Admin API Docs](https://janus.conf.meetecho.com/docs/admin.html) for more information
+//!
+//! # Examples
+//!```no_run
+//! # use janus_client::admin::{AdminClient, AdminConfig};
+//! # tokio_test::block_on(async {
+//! let admin = AdminClient::connect(AdminConfig::new("ws://janus:7188", Some("janusoverlord".into())))
+//!     .await
+//!     .unwrap();
+//!
+//! for session in admin.list_sessions().await.unwrap() {
+//!     for handle in admin.list_handles(session).await.unwrap() {
+//!         let info = admin.handle_info(session, handle).await.unwrap();
+//!         println!("{:?} {:?}", info.plugin, info.ice_state());
+//!     }
+//! }
+//! # });
+//! ```
+use crate::error::{Error, JanusError};
+use crate::types::{HandleId, SessionId};
+use futures::prelude::*;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_repr::{Deserialize_repr, Serialize_repr};
+use std::convert::TryInto;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tokio::net::TcpStream;
+use tokio::sync::Mutex;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::http::header::SEC_WEBSOCKET_PROTOCOL;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+
+/// Time to wait for the response of an admin request
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Configuration of a connection to the Janus admin API
+#[derive(Debug, Clone)]
+pub struct AdminConfig {
+    /// Websocket url of the admin endpoint, e.g. `ws://localhost:7188`
+    pub url: String,
+    /// The `admin_secret` configured in janus, if any
+    pub secret: Option<String>,
+}
+
+impl AdminConfig {
+    pub fn new<S: Into<String>>(url: S, secret: Option<String>) -> Self {
+        Self {
+            url: url.into(),
+            secret,
+        }
+    }
+}
+
+/// Log levels of janus
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize_repr, Deserialize_repr)]
+#[repr(u8)]
+pub enum LogLevel {
+    None = 0,
+    Fatal = 1,
+    Error = 2,
+    Warn = 3,
+    Info = 4,
+    Verbose = 5,
+    Huge = 6,
+    Debug = 7,
+}
+
+/// Outgoing admin API requests
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "janus", rename_all = "snake_case")]
+enum AdminRequest {
+    ListSessions,
+    ListHandles {
+        session_id: SessionId,
+    },
+    HandleInfo {
+        session_id: SessionId,
+        handle_id: HandleId,
+    },
+    SetLogLevel {
+        level: LogLevel,
+    },
+}
+
+/// Envelope of an outgoing admin API request
+#[derive(Debug, Serialize)]
+struct AdminMessage<'s> {
+    #[serde(flatten)]
+    request: AdminRequest,
+    transaction: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    admin_secret: Option<&'s str>,
+}
+
+/// Envelope of an incoming admin API response
+#[derive(Debug, Deserialize)]
+struct AdminResponse {
+    janus: String,
+    #[serde(default)]
+    transaction: Option<String>,
+    #[serde(default)]
+    error: Option<JanusError>,
+    #[serde(flatten)]
+    data: serde_json::Value,
+}
+
+#[derive(Debug, Deserialize)]
+struct ListSessionsResponse {
+    sessions: Vec<SessionId>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ListHandlesResponse {
+    handles: Vec<HandleId>,
+}
+
+#[derive(Debug, Deserialize)]
+struct HandleInfoResponse {
+    info: HandleInfo,
+}
+
+#[derive(Debug, Deserialize)]
+struct SetLogLevelResponse {
+    level: LogLevel,
+}
+
+/// Information about a handle as returned by a `handle_info` request
+///
+/// Only the commonly used fields are parsed, everything else is kept in [`HandleInfo::other`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HandleInfo {
+    /// Opaque identifier set by the client which attached the handle
+    #[serde(default)]
+    pub opaque_id: Option<String>,
+    /// Package name of the plugin the handle is attached to
+    #[serde(default)]
+    pub plugin: Option<String>,
+    /// Plugin specific information about the handle, e.g. the room of a videoroom handle
+    #[serde(default)]
+    pub plugin_specific: Option<serde_json::Value>,
+    /// WebRTC related flags of the handle
+    #[serde(default)]
+    pub flags: HandleFlags,
+    /// The media streams of the PeerConnection
+    #[serde(default)]
+    pub streams: Vec<HandleStream>,
+    /// All remaining fields of the handle info
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+impl HandleInfo {
+    /// Returns the videoroom room of this handle, if it is attached to the videoroom plugin
+    pub fn videoroom_room(&self) -> Option<u64> {
+        self.plugin_specific.as_ref()?.get("room")?.as_u64()
+    }
+
+    /// Returns the ICE state of the first component of the first stream
+    pub fn ice_state(&self) -> Option<&str> {
+        self.streams
+            .first()?
+            .components
+            .first()
+            .map(|component| component.state.as_str())
+    }
+}
+
+/// WebRTC related flags of a handle
+#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HandleFlags {
+    #[serde(rename = "got-offer")]
+    pub got_offer: bool,
+    #[serde(rename = "got-answer")]
+    pub got_answer: bool,
+    pub negotiated: bool,
+    #[serde(rename = "ice-restart")]
+    pub ice_restart: bool,
+    pub ready: bool,
+    pub stopped: bool,
+    pub alert: bool,
+    pub cleaning: bool,
+}
+
+/// A media stream of a handles PeerConnection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HandleStream {
+    pub id: u64,
+    #[serde(default)]
+    pub components: Vec<HandleComponent>,
+}
+
+/// An ICE component of a media stream
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HandleComponent {
+    pub id: u64,
+    /// ICE state, e.g. connected, ready or failed
+    pub state: String,
+}
+
+/// Janus Admin API Client
+///
+/// Requests are sent one at a time, concurrent calls wait for the previous request to complete.
+#[derive(Debug)]
+pub struct AdminClient {
+    stream: Mutex<WebSocketStream<MaybeTlsStream<TcpStream>>>,
+    secret: Option<String>,
+    transaction: AtomicU64,
+}
+
+impl AdminClient {
+    /// Connects to the admin API described by the given config
+    pub async fn connect(config: AdminConfig) -> Result<Self, Error> {
+        let mut req = config.url.into_client_request()?;
+        req.headers_mut().insert(
+            SEC_WEBSOCKET_PROTOCOL,
+            "janus-admin-protocol"
+                .try_into()
+                .expect("'janus-admin-protocol' to be a valid header-value"),
+        );
+
+        let (stream, _) = tokio_tungstenite::connect_async(req).await?;
+
+        Ok(Self {
+            stream: Mutex::new(stream),
+            secret: config.secret,
+            transaction: AtomicU64::new(0),
+        })
+    }
+
+    /// Lists the ids of all sessions of the janus instance
+    pub async fn list_sessions(&self) -> Result<Vec<SessionId>, Error> {
+        let response: ListSessionsResponse = self.request(AdminRequest::ListSessions).await?;
+
+        Ok(response.sessions)
+    }
+
+    /// Lists the ids of all handles of the given session
+    pub async fn list_handles(&self, session_id: SessionId) -> Result<Vec<HandleId>, Error> {
+        let response: ListHandlesResponse = self
+            .request(AdminRequest::ListHandles { session_id })
+            .await?;
+
+        Ok(response.handles)
+    }
+
+    /// Returns detailed information about the given handle
+    pub async fn handle_info(
+        &self,
+        session_id: SessionId,
+        handle_id: HandleId,
+    ) -> Result<HandleInfo, Error> {
+        let response: HandleInfoResponse = self
+            .request(AdminRequest::HandleInfo {
+                session_id,
+                handle_id,
+            })
+            .await?;
+
+        Ok(response.info)
+    }
+
+    /// Changes the log level of janus
+    ///
+    /// Returns the log level janus reported after the change
+    pub async fn set_log_level(&self, level: LogLevel) -> Result<LogLevel, Error> {
+        let response: SetLogLevelResponse =
+            self.request(AdminRequest::SetLogLevel { level }).await?;
+
+        Ok(response.level)
+    }
+
+    /// Closes the websocket connection
+    pub async fn destroy(&self) -> Result<(), Error> {
+        self.stream.lock().await.close(None).await?;
+
+        Ok(())
+    }
+
+    async fn request<T: DeserializeOwned>(&self, request: AdminRequest) -> Result<T, Error> {
+        let transaction = self
+            .transaction
+            .fetch_add(1, Ordering::Relaxed)
+            .to_string();
+
+        let message = serde_json::to_string(&AdminMessage {
+            request,
+            transaction: transaction.clone(),
+            admin_secret: self.secret.as_deref(),
+        })?;
+
+        let mut stream = self.stream.lock().await;
+
+        stream.send(Message::Text(message)).await?;
+
+        let response = tokio::time::timeout(REQUEST_TIMEOUT, async {
+            while let Some(message) = stream.next().await {
+                let text = match message? {
+                    Message::Text(text) => text,
+                    Message::Close(_) => return Err(Error::NotConnected),
+                    _ => continue,
+                };
+
+                let response: AdminResponse = serde_json::from_str(&text)?;
+
+                if response.transaction.as_deref() == Some(transaction.as_str()) {
+                    return Ok(response);
+                }
+
+                log::debug!("Ignoring admin message with unknown transaction: {}", text);
+            }
+
+            Err(Error::NotConnected)
+        })
+        .await
+        .map_err(|_| Error::Timeout)??;
+
+        match (response.janus.as_str(), response.error) {
+            ("success", _) => Ok(serde_json::from_value(response.data)?),
+            (_, Some(error)) => Err(Error::JanusError(error)),
+            _ => Err(Error::InvalidResponse),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::assert_eq_json;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+    use tokio::net::TcpListener;
+
+    #[test]
+    fn test_handle_info_request() {
+        let message = AdminMessage {
+            request: AdminRequest::HandleInfo {
+                session_id: SessionId::new(123),
+                handle_id: HandleId::new(456),
+            },
+            transaction: "1".into(),
+            admin_secret: Some("janusoverlord"),
+        };
+
+        assert_eq_json!(
+            message,
+            {
+                "janus": "handle_info",
+                "session_id": 123,
+                "handle_id": 456,
+                "transaction": "1",
+                "admin_secret": "janusoverlord"
+            }
+        );
+    }
+
+    #[test]
+    fn test_set_log_level_request() {
+        let message = AdminMessage {
+            request: AdminRequest::SetLogLevel {
+                level: LogLevel::Verbose,
+            },
+            transaction: "2".into(),
+            admin_secret: None,
+        };
+
+        assert_eq_json!(
+            message,
+            {
+                "janus": "set_log_level",
+                "level": 5,
+                "transaction": "2"
+            }
+        );
+    }
+
+    #[test]
+    fn parse_handle_info() {
+        let json = r#"{
+            "session_id": 1,
+            "handle_id": 2,
+            "opaque_id": "opentalk",
+            "plugin": "janus.plugin.videoroom",
+            "plugin_specific": { "type": "publisher", "room": 1234, "id": 1 },
+            "flags": {
+                "got-offer": true,
+                "got-answer": true,
+                "negotiated": true,
+                "ice-restart": false,
+                "ready": true,
+                "stopped": false,
+                "alert": false,
+                "cleaning": false,
+                "trickle": true
+            },
+            "ice-mode": "full",
+            "streams": [
+                { "id": 1, "ready": -1, "components": [ { "id": 1, "state": "connected" } ] }
+            ]
+        }"#;
+
+        let info: HandleInfo = serde_json::from_str(json).unwrap();
+
+        assert_eq!(info.plugin.as_deref(), Some("janus.plugin.videoroom"));
+        assert_eq!(info.videoroom_room(), Some(1234));
+        assert_eq!(info.ice_state(), Some("connected"));
+        assert!(info.flags.ready);
+        assert_eq!(info.other.get("ice-mode"), Some(&json!("full")));
+    }
+
+    /// Spawns a mocked admin endpoint which answers every request with the result of `respond`
+    async fn mock_admin<F>(respond: F) -> String
+    where
+        F: Fn(serde_json::Value) -> serde_json::Value + Send + 'static,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            let (socket, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(socket).await.unwrap();
+
+            while let Some(Ok(Message::Text(text))) = ws.next().await {
+                let request: serde_json::Value = serde_json::from_str(&text).unwrap();
+                let mut response = respond(request.clone());
+                response["transaction"] = request["transaction"].clone();
+
+                ws.send(Message::Text(response.to_string())).await.unwrap();
+            }
+        });
+
+        format!("ws://{addr}")
+    }
+
+    #[tokio::test]
+    async fn list_sessions_and_handles() {
+        let url = mock_admin(|request| {
+            assert_eq!(request["admin_secret"], "secret");
+
+            match request["janus"].as_str().unwrap() {
+                "list_sessions" => json!({ "janus": "success", "sessions": [1, 2] }),
+                "list_handles" => json!({
+                    "janus": "success",
+                    "session_id": request["session_id"],
+                    "handles": [10, 11]
+                }),
+                _ => panic!("unexpected request {request}"),
+            }
+        })
+        .await;
+
+        let admin = AdminClient::connect(AdminConfig::new(url, Some("secret".into())))
+            .await
+            .unwrap();
+
+        let sessions = admin.list_sessions().await.unwrap();
+        assert_eq!(sessions, vec![SessionId::new(1), SessionId::new(2)]);
+
+        let handles = admin.list_handles(sessions[0]).await.unwrap();
+        assert_eq!(handles, vec![HandleId::new(10), HandleId::new(11)]);
+    }
+
+    #[tokio::test]
+    async fn error_response() {
+        let url = mock_admin(|_| {
+            json!({
+                "janus": "error",
+                "error": { "code": 403, "reason": "Unauthorized request (wrong or missing secret/token)" }
+            })
+        })
+        .await;
+
+        let admin = AdminClient::connect(AdminConfig::new(url, None))
+            .await
+            .unwrap();
+
+        let result = admin.set_log_level(LogLevel::Debug).await;
+
+        assert!(matches!(result, Err(Error::JanusError(_))));
+    }
+}
@@ -77,6 +77,13 @@ impl JanusPluginError {
     }
 }
 
+/// Error codes returned by janus and its plugins
+///
+/// The streaming plugin reuses the codes 450 and 452-460 of the janus core with a different
+/// meaning, they deserialize to the core variant with the same code. E.g. the streaming plugin's
+/// "no such mountpoint" error (455) becomes [`JanusInternalError::ErrorInvalidJsonObject`], the
+/// [`JanusPluginError::reason`] describes the actual error. The streaming plugin's unknown error
+/// (470) becomes [`JanusInternalError::ErrorTokenNotFound`].
 #[derive(Serialize_repr, Deserialize_repr, PartialEq, Eq, Debug, Clone, Copy)]
 #[repr(u32)]
 pub enum JanusInternalError {
@@ -107,6 +114,9 @@ pub enum JanusInternalError {
     ErrorUnexpectedAnswer = 469,
     ErrorTokenNotFound = 470,
 
+    /// The only code of the streaming plugin that has no janus core counterpart
+    StreamingErrorInvalidJson = 451,
+
     VideoroomErrorUnknownError = 499,
 
     EchotestErrorNoMessage = 411,
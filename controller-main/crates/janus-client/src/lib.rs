@@ -94,8 +94,13 @@
 //! Supported Janus plugins can be enabled with the following cargo features
 //! - `echotest` for the EchoTest Janus plugin
 //! - `videoroom` for the VideoRoom Janus plugin
+//! - `streaming` for the Streaming Janus plugin
 //!
-//! By default `echotest` and `videoroom` are enabled.
+//! By default `echotest`, `videoroom` and `streaming` are enabled.
+//!
+//! # Admin API
+//! The [`admin`] module contains a client for the Janus Admin/Monitor API which can be used to
+//! inspect sessions and handles of a janus instance.
 
 use crate::client::{InnerClient, InnerHandle, InnerSession};
 use crate::outgoing::TrickleMessage;
@@ -107,6 +112,7 @@ use transport::TransportConfig;
 
 #[macro_use]
 mod macros;
+pub mod admin;
 mod client;
 pub mod error;
 pub mod transport;
@@ -15,6 +15,13 @@ use std::convert::TryFrom;
 #[cfg(feature = "echotest")]
 pub use echotest::{EchoPluginData, EchoPluginDataEvent, EchoPluginUnnamed};
 
+#[cfg(feature = "streaming")]
+pub use streaming::{
+    Mountpoint, MountpointPort, StreamingPluginData, StreamingPluginDataCreated,
+    StreamingPluginDataDestroyed, StreamingPluginEvent, StreamingPluginEventResult,
+    StreamingResult, StreamingStatus,
+};
+
 #[cfg(feature = "videoroom")]
 pub use videoroom::{
     VideoRoomPluginData, VideoRoomPluginDataAttached, VideoRoomPluginDataCreated,
@@ -25,6 +32,8 @@ pub use videoroom::{
 
 #[cfg(feature = "echotest")]
 mod echotest;
+#[cfg(feature = "streaming")]
+mod streaming;
 #[cfg(feature = "videoroom")]
 mod videoroom;
 
@@ -188,6 +197,14 @@ impl JanusMessage {
                     ))),
                 ..
             }) => Err(error::Error::JanusPluginError(error)),
+            #[cfg(feature = "streaming")]
+            JanusMessage::Event(Event {
+                plugindata:
+                    PluginData::Streaming(StreamingPluginData::Event(StreamingPluginEvent::Error(
+                        error,
+                    ))),
+                ..
+            }) => Err(error::Error::JanusPluginError(error)),
             msg => Ok(msg),
         }
     }
@@ -247,4 +264,7 @@ pub enum PluginData {
     #[cfg(feature = "echotest")]
     #[serde(rename = "janus.plugin.echotest")]
     EchoTest(EchoPluginData),
+    #[cfg(feature = "streaming")]
+    #[serde(rename = "janus.plugin.streaming")]
+    Streaming(StreamingPluginData),
 }
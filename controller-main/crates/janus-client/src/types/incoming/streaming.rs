@@ -0,0 +1,261 @@
+// SPDX-FileCopyrightText: OpenTalk GmbH <mail@opentalk.eu>
+//
+// SPDX-License-Identifier: EUPL-1.2
+
+//! Datatypes for the Streaming plugin
+
+use crate::error::{self, JanusPluginError};
+use crate::types::MountpointId;
+use crate::PluginData;
+use serde::{self, Deserialize};
+use std::convert::TryFrom;
+
+/// Plugin response types
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "streaming")]
+pub enum StreamingPluginData {
+    #[serde(rename = "created")]
+    Created(StreamingPluginDataCreated),
+    #[serde(rename = "destroyed")]
+    Destroyed(StreamingPluginDataDestroyed),
+    #[serde(rename = "event")]
+    Event(StreamingPluginEvent),
+}
+
+/// Information about a mountpoint returned on creation
+#[derive(Debug, Clone, Deserialize)]
+pub struct Mountpoint {
+    /// unique numeric ID of the mountpoint
+    pub id: MountpointId,
+    /// live|on demand
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// Description of the mountpoint
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Whether the mountpoint is hidden from 'list' requests
+    #[serde(default)]
+    pub is_private: bool,
+    /// The ports janus listens on for each stream of the mountpoint
+    #[serde(default)]
+    pub ports: Vec<MountpointPort>,
+}
+
+/// The port a single stream of a mountpoint is bound to
+#[derive(Debug, Clone, Deserialize)]
+pub struct MountpointPort {
+    /// audio|video|data
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// mid of the stream
+    pub mid: String,
+    /// The bound RTP port
+    pub port: u16,
+}
+
+// Created response type
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum StreamingPluginDataCreated {
+    Ok {
+        created: String,
+        permanent: bool,
+        stream: Mountpoint,
+    },
+    Err(JanusPluginError),
+}
+
+impl TryFrom<PluginData> for StreamingPluginDataCreated {
+    type Error = error::Error;
+
+    fn try_from(value: PluginData) -> Result<Self, Self::Error> {
+        match value {
+            PluginData::Streaming(StreamingPluginData::Created(e)) => Ok(e),
+            _ => Err(error::Error::InvalidResponse),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct StreamingPluginDataDestroyed {
+    pub id: MountpointId,
+}
+
+impl TryFrom<PluginData> for StreamingPluginDataDestroyed {
+    type Error = error::Error;
+
+    fn try_from(value: PluginData) -> Result<Self, Self::Error> {
+        match value {
+            PluginData::Streaming(StreamingPluginData::Destroyed(e)) => Ok(e),
+            _ => Err(error::Error::InvalidResponse),
+        }
+    }
+}
+
+/// Event types, normally are received via the "incoming channel"
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum StreamingPluginEvent {
+    Result(StreamingPluginEventResult),
+    /// Errors returned for a specific plugin.
+    /// E.g. No such mountpoint errors
+    Error(JanusPluginError),
+}
+
+/// Status of a watching handle
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum StreamingStatus {
+    Preparing,
+    Starting,
+    Started,
+    Stopping,
+    Stopped,
+    Pausing,
+    Updating,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct StreamingResult {
+    pub status: StreamingStatus,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct StreamingPluginEventResult {
+    pub result: StreamingResult,
+}
+
+impl TryFrom<PluginData> for StreamingPluginEventResult {
+    type Error = error::Error;
+
+    fn try_from(value: PluginData) -> Result<Self, Self::Error> {
+        match value {
+            PluginData::Streaming(StreamingPluginData::Event(StreamingPluginEvent::Result(e))) => {
+                Ok(e)
+            }
+            _ => Err(error::Error::InvalidResponse),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::types::incoming::{Event, PluginSuccess};
+    use crate::types::{HandleId, SessionId, Success, TransactionId};
+    use crate::JanusMessage;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parse_mountpoint_created() {
+        let json = r#"{
+        "janus": "success",
+        "session_id": 1181318522471683,
+        "transaction": "2",
+        "sender": 7519437590873898,
+        "plugindata": {
+            "plugin": "janus.plugin.streaming",
+            "data": {
+                "streaming": "created",
+                "created": "external",
+                "permanent": false,
+                "stream": {
+                    "id": 4720732281562341,
+                    "type": "live",
+                    "description": "external",
+                    "is_private": true,
+                    "ports": [
+                        { "type": "audio", "mid": "a", "port": 5002 },
+                        { "type": "video", "mid": "v", "port": 5004 }
+                    ]
+                }
+            }
+        }
+        }"#;
+
+        let parsed_result: JanusMessage = serde_json::from_str(json).unwrap();
+        match parsed_result {
+            JanusMessage::Success(Success::Plugin(PluginSuccess {
+                sender,
+                transaction,
+                plugindata:
+                    PluginData::Streaming(StreamingPluginData::Created(
+                        StreamingPluginDataCreated::Ok {
+                            permanent, stream, ..
+                        },
+                    )),
+                jsep: None,
+                session_id,
+            })) => {
+                assert_eq!(sender.unwrap(), HandleId::new(7519437590873898));
+                assert_eq!(session_id.unwrap(), SessionId::new(1181318522471683));
+                assert_eq!(transaction, TransactionId("2".into()));
+                assert_eq!(stream.id, 4720732281562341.into());
+                assert_eq!(stream.ports.len(), 2);
+                assert_eq!(stream.ports[1].port, 5004);
+                assert!(!permanent);
+            }
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn parse_watch_preparing() {
+        let json = r#"{
+            "janus": "event",
+            "session_id": 280835257881068,
+            "transaction": "3",
+            "sender": 1739642080530886,
+            "plugindata": {
+                "plugin": "janus.plugin.streaming",
+                "data": {
+                    "streaming": "event",
+                    "result": { "status": "preparing" }
+                }
+            },
+            "jsep": { "type": "offer", "sdp": "v=0" }
+        }"#;
+
+        let parsed_result: JanusMessage = serde_json::from_str(json).unwrap();
+        match parsed_result {
+            JanusMessage::Event(Event {
+                plugindata:
+                    PluginData::Streaming(StreamingPluginData::Event(StreamingPluginEvent::Result(
+                        StreamingPluginEventResult { result },
+                    ))),
+                jsep: Some(jsep),
+                ..
+            }) => {
+                assert_eq!(result.status, StreamingStatus::Preparing);
+                assert_eq!(jsep.sdp(), "v=0");
+            }
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn parse_no_such_mountpoint() {
+        let json = r#"{
+            "janus": "event",
+            "session_id": 280835257881068,
+            "transaction": "4",
+            "sender": 1739642080530886,
+            "plugindata": {
+                "plugin": "janus.plugin.streaming",
+                "data": {
+                    "streaming": "event",
+                    "error_code": 455,
+                    "error": "No such mountpoint/stream 12"
+                }
+            }
+        }"#;
+
+        let parsed_result: JanusMessage = serde_json::from_str(json).unwrap();
+        let result = parsed_result.into_result();
+
+        assert!(matches!(
+            result,
+            Err(crate::error::Error::JanusPluginError(_))
+        ));
+    }
+}
@@ -126,6 +126,9 @@ pub enum JanusPlugin {
     #[cfg(feature = "echotest")]
     #[serde(rename = "janus.plugin.echotest")]
     Echotest,
+    #[cfg(feature = "streaming")]
+    #[serde(rename = "janus.plugin.streaming")]
+    Streaming,
 }
 
 /// A Janus API session identifier
@@ -216,6 +219,32 @@ impl From<FeedId> for u64 {
     }
 }
 
+/// A Mountpoint identifier of the streaming plugin
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct MountpointId(u64);
+impl MountpointId {
+    pub fn new(value: u64) -> Self {
+        Self(value)
+    }
+}
+
+impl From<u64> for MountpointId {
+    fn from(val: u64) -> Self {
+        Self::new(val)
+    }
+}
+
+impl From<MountpointId> for u64 {
+    fn from(value: MountpointId) -> Self {
+        value.0
+    }
+}
+impl std::fmt::Display for MountpointId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
 /// A transaction identifier
 ///
 /// Used to match an async request to Janus to the response
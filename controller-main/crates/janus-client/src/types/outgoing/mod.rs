@@ -14,11 +14,18 @@ use crate::{
 #[cfg(feature = "echotest")]
 use echotest::EchoPluginBody;
 use serde::{self, Serialize};
+#[cfg(feature = "streaming")]
+use streaming::StreamingPluginBody;
 #[cfg(feature = "videoroom")]
 use videoroom::VideoRoomPluginBody;
 
 #[cfg(feature = "echotest")]
 pub use echotest::EchoPluginUnnamed;
+#[cfg(feature = "streaming")]
+pub use streaming::{
+    StreamingMediaType, StreamingMountpointType, StreamingPluginCreate, StreamingPluginDestroy,
+    StreamingPluginMedia, StreamingPluginStart, StreamingPluginStop, StreamingPluginWatch,
+};
 #[cfg(feature = "videoroom")]
 pub use videoroom::{
     VideoRoomPluginConfigure, VideoRoomPluginConfigurePublisher,
@@ -29,6 +36,8 @@ pub use videoroom::{
 
 #[cfg(feature = "echotest")]
 pub(crate) mod echotest;
+#[cfg(feature = "streaming")]
+pub(crate) mod streaming;
 #[cfg(feature = "videoroom")]
 pub(crate) mod videoroom;
 
@@ -137,6 +146,9 @@ pub enum PluginBody {
     #[cfg(feature = "echotest")]
     #[serde(rename = "janus.plugin.echotest")]
     EchoTest(EchoPluginBody),
+    #[cfg(feature = "streaming")]
+    #[serde(rename = "janus.plugin.streaming")]
+    Streaming(StreamingPluginBody),
 }
 
 #[cfg(test)]
@@ -0,0 +1,349 @@
+// SPDX-FileCopyrightText: OpenTalk GmbH <mail@opentalk.eu>
+//
+// SPDX-License-Identifier: EUPL-1.2
+
+//! Outgoing Streaming plugin datatypes
+//!
+use crate::{
+    incoming,
+    outgoing::PluginBody,
+    types::{AudioCodec, MountpointId, VideoCodec},
+    PluginRequest,
+};
+use serde::{self, Serialize};
+
+/// Plugin request body for the streaming plugin
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "request")]
+pub enum StreamingPluginBody {
+    /// Create request, sync
+    #[serde(rename = "create")]
+    Create(StreamingPluginCreate),
+    /// Destroy request, sync
+    #[serde(rename = "destroy")]
+    Destroy(StreamingPluginDestroy),
+    /// Watch request, async
+    #[serde(rename = "watch")]
+    Watch(StreamingPluginWatch),
+    /// Start request, async
+    #[serde(rename = "start")]
+    Start(StreamingPluginStart),
+    /// Stop request, async
+    #[serde(rename = "stop")]
+    Stop(StreamingPluginStop),
+}
+
+/// The kind of media a mountpoint stream carries
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum StreamingMediaType {
+    Audio,
+    Video,
+}
+
+/// A single RTP media stream of a mountpoint
+///
+/// See [Janus Streaming Plugin Docs](https://janus.conf.meetecho.com/docs/streaming.html) for more information
+#[derive(Debug, Clone, Serialize)]
+pub struct StreamingPluginMedia {
+    /// audio|video
+    #[serde(rename = "type")]
+    pub kind: StreamingMediaType,
+    /// Unique mid to assign to this stream in negotiated PeerConnections
+    pub mid: String,
+    /// Local port for receiving the RTP stream, 0 lets janus pick a random port
+    pub port: u16,
+    /// RTP payload type of the stream
+    pub pt: u8,
+    /// Codec name as it appears in the rtpmap, e.g. opus or vp8
+    pub codec: String,
+    /// Multicast group to bind to; optional
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mcast: Option<String>,
+}
+
+impl StreamingPluginMedia {
+    /// Creates a new audio stream description for the given codec
+    pub fn audio(mid: impl Into<String>, port: u16, pt: u8, codec: AudioCodec) -> Self {
+        Self {
+            kind: StreamingMediaType::Audio,
+            mid: mid.into(),
+            port,
+            pt,
+            codec: codec.to_string(),
+            mcast: None,
+        }
+    }
+
+    /// Creates a new video stream description for the given codec
+    pub fn video(mid: impl Into<String>, port: u16, pt: u8, codec: VideoCodec) -> Self {
+        Self {
+            kind: StreamingMediaType::Video,
+            mid: mid.into(),
+            port,
+            pt,
+            codec: codec.to_string(),
+            mcast: None,
+        }
+    }
+}
+
+/// Type of mountpoint to create
+#[derive(Debug, Clone, Copy, Default, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum StreamingMountpointType {
+    /// A mountpoint that is fed by external RTP streams
+    #[default]
+    Rtp,
+}
+
+/// Create a new RTP mountpoint
+///
+/// See [Janus Streaming Plugin Docs](https://janus.conf.meetecho.com/docs/streaming.html#streamapi) for more information
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct StreamingPluginCreate {
+    /// Type of the mountpoint
+    #[serde(rename = "type")]
+    pub kind: StreamingMountpointType,
+    /// Unique ID of the mountpoint, janus chooses a random one if not set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<MountpointId>,
+    /// Unique name of the mountpoint; optional
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Description of the mountpoint; optional
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Private mountpoints don't appear when you do a 'list' request, defaults to false if not set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_private: Option<bool>,
+    /// Optional password needed for manipulating (e.g. destroying) the mountpoint
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+    /// Whether the mountpoint should be saved to the config file, default=false
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub permanent: Option<bool>,
+    /// The RTP streams this mountpoint receives
+    pub media: Vec<StreamingPluginMedia>,
+}
+
+impl PluginRequest for StreamingPluginCreate {
+    type PluginResponse = incoming::StreamingPluginDataCreated;
+}
+
+impl From<StreamingPluginCreate> for PluginBody {
+    fn from(value: StreamingPluginCreate) -> Self {
+        PluginBody::Streaming(StreamingPluginBody::Create(value))
+    }
+}
+
+/// Destroy a mountpoint
+#[derive(Debug, Clone, Serialize)]
+pub struct StreamingPluginDestroy {
+    /// unique numeric ID of the mountpoint to destroy
+    pub id: MountpointId,
+    /// mountpoint secret, mandatory if configured
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+    /// true|false, whether the mountpoint should be also removed from the config file, default=false
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub permanent: Option<bool>,
+}
+
+impl PluginRequest for StreamingPluginDestroy {
+    type PluginResponse = incoming::StreamingPluginDataDestroyed;
+}
+
+impl From<StreamingPluginDestroy> for PluginBody {
+    fn from(value: StreamingPluginDestroy) -> Self {
+        PluginBody::Streaming(StreamingPluginBody::Destroy(value))
+    }
+}
+
+/// Watch a mountpoint
+///
+/// Janus responds with a SDP offer which has to be answered using [`StreamingPluginStart`]
+#[derive(Debug, Clone, Serialize)]
+pub struct StreamingPluginWatch {
+    /// unique ID of the mountpoint to subscribe to
+    pub id: MountpointId,
+    /// PIN required to access the mountpoint; optional
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pin: Option<String>,
+    /// Whether the media streams of the mountpoint should be offered, default=true
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offer_audio: Option<bool>,
+    /// Whether the video stream of the mountpoint should be offered, default=true
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offer_video: Option<bool>,
+}
+
+impl StreamingPluginWatch {
+    pub fn new(id: MountpointId) -> Self {
+        Self {
+            id,
+            pin: None,
+            offer_audio: None,
+            offer_video: None,
+        }
+    }
+}
+
+impl PluginRequest for StreamingPluginWatch {
+    type PluginResponse = incoming::StreamingPluginEventResult;
+    const IS_ASYNC: bool = true;
+}
+
+impl From<StreamingPluginWatch> for PluginBody {
+    fn from(value: StreamingPluginWatch) -> Self {
+        PluginBody::Streaming(StreamingPluginBody::Watch(value))
+    }
+}
+
+/// Start
+///
+/// Completes the negotiation of a watch request, must be sent together with the SDP answer
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct StreamingPluginStart {}
+
+impl PluginRequest for StreamingPluginStart {
+    type PluginResponse = incoming::StreamingPluginEventResult;
+    const IS_ASYNC: bool = true;
+}
+
+impl From<StreamingPluginStart> for PluginBody {
+    fn from(value: StreamingPluginStart) -> Self {
+        PluginBody::Streaming(StreamingPluginBody::Start(value))
+    }
+}
+
+/// Stop
+///
+/// Stops the media delivery and tears down the PeerConnection of the handle
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct StreamingPluginStop {}
+
+impl PluginRequest for StreamingPluginStop {
+    type PluginResponse = incoming::StreamingPluginEventResult;
+    const IS_ASYNC: bool = true;
+}
+
+impl From<StreamingPluginStop> for PluginBody {
+    fn from(value: StreamingPluginStop) -> Self {
+        PluginBody::Streaming(StreamingPluginBody::Stop(value))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::assert_eq_json;
+    use crate::types::outgoing::{JanusRequest, PluginMessage};
+    use crate::types::{HandleId, SessionId};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_create() {
+        let plugin_message = JanusRequest::PluginMessage(PluginMessage {
+            session_id: SessionId::new(234),
+            handle_id: HandleId::new(2123),
+            body: PluginBody::Streaming(StreamingPluginBody::Create(StreamingPluginCreate {
+                description: Some("external stream".into()),
+                is_private: Some(true),
+                media: vec![
+                    StreamingPluginMedia::audio("a", 5002, 111, AudioCodec::Opus),
+                    StreamingPluginMedia::video("v", 5004, 96, VideoCodec::Vp8),
+                ],
+                ..Default::default()
+            })),
+            jsep: None,
+        });
+
+        assert_eq_json!(
+            plugin_message,
+            {
+                "janus": "message",
+                "handle_id": 2123,
+                "session_id": 234,
+                "body": {
+                    "request": "create",
+                    "type": "rtp",
+                    "description": "external stream",
+                    "is_private": true,
+                    "media": [
+                        {
+                            "type": "audio",
+                            "mid": "a",
+                            "port": 5002,
+                            "pt": 111,
+                            "codec": "opus"
+                        },
+                        {
+                            "type": "video",
+                            "mid": "v",
+                            "port": 5004,
+                            "pt": 96,
+                            "codec": "vp8"
+                        }
+                    ]
+                }
+            }
+        );
+    }
+
+    #[test]
+    fn test_watch() {
+        let plugin_message = JanusRequest::PluginMessage(PluginMessage {
+            session_id: SessionId::new(234),
+            handle_id: HandleId::new(2123),
+            body: StreamingPluginWatch {
+                offer_video: Some(false),
+                ..StreamingPluginWatch::new(12.into())
+            }
+            .into(),
+            jsep: None,
+        });
+
+        assert_eq_json!(
+            plugin_message,
+            {
+                "janus": "message",
+                "handle_id": 2123,
+                "session_id": 234,
+                "body": {
+                    "request": "watch",
+                    "id": 12,
+                    "offer_video": false
+                }
+            }
+        );
+    }
+
+    #[test]
+    fn test_destroy() {
+        let plugin_message = JanusRequest::PluginMessage(PluginMessage {
+            session_id: SessionId::new(234),
+            handle_id: HandleId::new(2123),
+            body: StreamingPluginDestroy {
+                id: 12.into(),
+                secret: None,
+                permanent: None,
+            }
+            .into(),
+            jsep: None,
+        });
+
+        assert_eq_json!(
+            plugin_message,
+            {
+                "janus": "message",
+                "handle_id": 2123,
+                "session_id": 234,
+                "body": {
+                    "request": "destroy",
+                    "id": 12
+                }
+            }
+        );
+    }
+}
@@ -5,7 +5,11 @@
 use serde::{Deserialize, Serialize};
 use types::{
     core::ParticipantId,
-    signaling::media::{command::ParticipantSelection, event::RequestMute},
+    signaling::media::{
+        command::{ExternalStreamTarget, ParticipantSelection},
+        event::RequestMute,
+        ExternalStream,
+    },
 };
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -15,4 +19,6 @@ pub enum Message {
     RequestMute(RequestMute),
     PresenterGranted(ParticipantSelection),
     PresenterRevoked(ParticipantSelection),
+    ExternalStreamStarted(ExternalStream),
+    ExternalStreamStopped(ExternalStreamTarget),
 }
@@ -11,8 +11,8 @@ use anyhow::{bail, Context, Result};
 use controller_settings::SharedSettings;
 use focus::FocusDetection;
 use mcu::{
-    LinkDirection, McuPool, MediaSessionKey, PublishConfiguration, Request, Response,
-    TrickleMessage, WebRtcEvent,
+    ExternalStreamMountpoint, LinkDirection, McuPool, MediaSessionKey, PublishConfiguration,
+    Request, Response, TrickleMessage, WebRtcEvent,
 };
 use sessions::MediaSessions;
 use signaling_core::{
@@ -25,14 +25,16 @@ use types::{
     core::ParticipantId,
     signaling::{
         media::{
-            command::{self, MediaCommand, Target, TargetConfigure, TargetSubscribe},
+            command::{
+                self, ExternalStreamSource, MediaCommand, Target, TargetConfigure, TargetSubscribe,
+            },
             event::{
                 self, Error, FocusUpdate, Link, MediaEvent, MediaStatus, Sdp, SdpCandidate, Source,
             },
             peer_state::MediaPeerState,
             state::MediaState,
-            MediaSessionState, MediaSessionType, ParticipantMediaState, TrickleCandidate,
-            NAMESPACE,
+            ExternalStream, MediaSessionState, MediaSessionType, ParticipantMediaState,
+            TrickleCandidate, NAMESPACE,
         },
         Role,
     },
@@ -48,6 +50,12 @@ mod sessions;
 mod settings;
 mod storage;
 
+pub use mcu::{get_publisher_info, mcu_id, set_publisher_info, MediaSessionKey, PublisherInfo};
+
+/// Mids of the audio and video streams of external stream mountpoints
+const AUDIO_MID: &str = "a";
+const VIDEO_MID: &str = "v";
+
 pub struct Media {
     id: ParticipantId,
     room: SignalingRoomId,
@@ -314,6 +322,12 @@ impl SignalingModule for Media {
                     subscribe.target.target,
                 )
                 .await?
+                    && !storage::is_external_stream(
+                        ctx.redis_conn(),
+                        self.room,
+                        subscribe.target.target,
+                    )
+                    .await?
                 {
                     // just discard, shouldn't happen often
                     return Ok(());
@@ -366,6 +380,45 @@ impl SignalingModule for Media {
                     exchange::Message::PresenterRevoked(selection),
                 )
             }
+            Event::WsMessage(MediaCommand::StartExternalStream(source)) => {
+                if ctx.role() != Role::Moderator {
+                    ctx.ws_send(Error::PermissionDenied);
+
+                    return Ok(());
+                }
+
+                if let Err(e) = self.handle_start_external_stream(&mut ctx, source).await {
+                    log::error!("Failed to start external stream, {:?}", e);
+                    ctx.ws_send(Error::InvalidExternalStream);
+                }
+            }
+            Event::WsMessage(MediaCommand::StopExternalStream(target)) => {
+                if ctx.role() != Role::Moderator {
+                    ctx.ws_send(Error::PermissionDenied);
+
+                    return Ok(());
+                }
+
+                if !storage::remove_external_stream(ctx.redis_conn(), self.room, target.id).await?
+                {
+                    ctx.ws_send(Error::InvalidExternalStream);
+
+                    return Ok(());
+                }
+
+                if let Err(e) = self
+                    .mcu
+                    .destroy_external_stream(MediaSessionKey(target.id, MediaSessionType::Video))
+                    .await
+                {
+                    log::error!("Failed to destroy external stream {}, {:?}", target.id, e);
+                }
+
+                ctx.exchange_publish(
+                    control::exchange::current_room_all_participants(self.room),
+                    exchange::Message::ExternalStreamStopped(target),
+                )
+            }
 
             Event::Ext((media_session_key, message)) => match message {
                 WebRtcEvent::AssociatedMcuDied => {
@@ -438,6 +491,14 @@ impl SignalingModule for Media {
             Event::Exchange(exchange::Message::RequestMute(request_mute)) => {
                 ctx.ws_send(request_mute);
             }
+            Event::Exchange(exchange::Message::ExternalStreamStarted(external_stream)) => {
+                ctx.ws_send(MediaEvent::ExternalStreamStarted(external_stream));
+            }
+            Event::Exchange(exchange::Message::ExternalStreamStopped(target)) => {
+                self.media.remove_subscribers(target.id).await;
+
+                ctx.ws_send(MediaEvent::ExternalStreamStopped(target));
+            }
             Event::Exchange(exchange::Message::PresenterGranted(selection)) => {
                 if !selection.participant_ids.contains(&self.id) {
                     return Ok(());
@@ -559,7 +620,13 @@ impl SignalingModule for Media {
                 let is_presenter =
                     storage::is_presenter(ctx.redis_conn(), self.room, self.id).await?;
 
-                *frontend_data = Some(MediaState { is_presenter })
+                let external_streams =
+                    storage::get_external_streams(ctx.redis_conn(), self.room).await?;
+
+                *frontend_data = Some(MediaState {
+                    is_presenter,
+                    external_streams,
+                })
             }
             Event::Leaving => {
                 if let Err(e) =
@@ -590,6 +657,8 @@ impl SignalingModule for Media {
                     e
                 );
             }
+
+            self.destroy_external_streams(&mut ctx).await;
         }
     }
 
@@ -645,6 +714,136 @@ impl Media {
         Ok(())
     }
 
+    /// Creates a streaming mountpoint for the given source and announces it to the room
+    async fn handle_start_external_stream(
+        &self,
+        ctx: &mut ModuleContext<'_, Self>,
+        source: ExternalStreamSource,
+    ) -> Result<()> {
+        let mut media = Vec::new();
+
+        if let Some(audio) = source.audio {
+            let codec = audio
+                .codec
+                .parse::<janus_client::AudioCodec>()
+                .context("Invalid audio codec")?;
+
+            media.push(janus_client::outgoing::StreamingPluginMedia::audio(
+                AUDIO_MID,
+                audio.port,
+                audio.payload_type,
+                codec,
+            ));
+        }
+
+        if let Some(video) = source.video {
+            let codec = video
+                .codec
+                .parse::<janus_client::VideoCodec>()
+                .context("Invalid video codec")?;
+
+            media.push(janus_client::outgoing::StreamingPluginMedia::video(
+                VIDEO_MID,
+                video.port,
+                video.payload_type,
+                codec,
+            ));
+        }
+
+        if media.is_empty() {
+            bail!("External stream requires at least one audio or video source");
+        }
+
+        let id = ParticipantId::generate();
+
+        let ExternalStreamMountpoint { mountpoint, host } = self
+            .mcu
+            .new_external_stream(MediaSessionKey(id, MediaSessionType::Video), media)
+            .await?;
+
+        log::debug!(
+            "Created mountpoint {} for external stream {}, ports: {:?}",
+            mountpoint.id,
+            id,
+            mountpoint.ports
+        );
+
+        // Janus picks the ports of streams which were requested with port 0
+        let port = |mid: &str| {
+            mountpoint
+                .ports
+                .iter()
+                .find(|port| port.mid == mid)
+                .map(|port| port.port)
+        };
+
+        let external_stream = ExternalStream {
+            id,
+            display_name: source.display_name,
+            host,
+            audio_port: port(AUDIO_MID),
+            video_port: port(VIDEO_MID),
+        };
+
+        if let Err(e) =
+            storage::add_external_stream(ctx.redis_conn(), self.room, &external_stream).await
+        {
+            // The mountpoint is only torn down with the room if it is known in redis
+            if let Err(e) = self
+                .mcu
+                .destroy_external_stream(MediaSessionKey(id, MediaSessionType::Video))
+                .await
+            {
+                log::error!("Failed to destroy external stream {}, {:?}", id, e);
+            }
+
+            return Err(e);
+        }
+
+        ctx.exchange_publish(
+            control::exchange::current_room_all_participants(self.room),
+            exchange::Message::ExternalStreamStarted(external_stream),
+        );
+
+        Ok(())
+    }
+
+    /// Tears down all mountpoints of the external streams in the room
+    async fn destroy_external_streams(&self, ctx: &mut DestroyContext<'_>) {
+        let external_streams =
+            match storage::get_external_streams(ctx.redis_conn(), self.room).await {
+                Ok(external_streams) => external_streams,
+                Err(e) => {
+                    log::error!("Media module failed to get external streams, {:?}", e);
+                    return;
+                }
+            };
+
+        for external_stream in external_streams {
+            if let Err(e) = self
+                .mcu
+                .destroy_external_stream(MediaSessionKey(
+                    external_stream.id,
+                    MediaSessionType::Video,
+                ))
+                .await
+            {
+                log::error!(
+                    "Failed to destroy external stream {} on room destroy, {:?}",
+                    external_stream.id,
+                    e
+                );
+            }
+        }
+
+        if let Err(e) = storage::delete_external_streams_key(ctx.redis_conn(), self.room).await {
+            log::error!(
+                "Media module failed to remove external streams key on room destroy, {}",
+                e
+            );
+        }
+    }
+
     /// Gracefully removes the media session that is associated with the provided MediaSessionKey
     ///
     /// Send detach and destroy messages to janus in order to remove a media session gracefully.
@@ -8,10 +8,12 @@ use anyhow::{bail, Context, Result};
 use controller_settings::SharedSettings;
 use futures::{ready, stream::FuturesUnordered};
 use janus_client::outgoing::{
-    VideoRoomPluginConfigurePublisher, VideoRoomPluginConfigureSubscriber,
+    StreamingPluginMedia, VideoRoomPluginConfigurePublisher, VideoRoomPluginConfigureSubscriber,
 };
 use janus_client::types::{SdpAnswer, SdpOffer};
-use janus_client::{ClientId, JanusMessage, JsepType, RoomId as JanusRoomId, TrickleCandidate};
+use janus_client::{
+    ClientId, JanusMessage, JsepType, MountpointId, RoomId as JanusRoomId, TrickleCandidate,
+};
 use lapin_pool::{RabbitMqChannel, RabbitMqPool};
 use redis::AsyncCommands;
 use serde::{Deserialize, Serialize};
@@ -40,6 +42,11 @@ pub use self::types::*;
 /// This information is used when creating a subscriber
 const PUBLISHER_INFO: &str = "opentalk-signaling:mcu:publishers";
 
+/// Redis key of the external stream => McuId/MountpointId mapping
+///
+/// This information is used when creating a subscriber for an external stream
+const EXTERNAL_STREAM_INFO: &str = "opentalk-signaling:mcu:external_streams";
+
 /// Redis key for a sorted set of mcu-clients.
 ///
 /// The score represents the amounts of subscribers on that mcu and is used to choose the least
@@ -54,13 +61,66 @@ fn mcu_load_key(mcu_id: &McuId, loop_index: Option<usize>) -> String {
     }
 }
 
+/// The janus instance and room a publisher is located in
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PublisherInfo<'i> {
+    pub room_id: JanusRoomId,
+    /// See [`mcu_id`]
+    pub mcu_id: Cow<'i, str>,
+    pub loop_index: Option<usize>,
+}
+
+/// Returns where the publisher of the given media session is located, if it is published
+pub async fn get_publisher_info(
+    redis: &mut RedisConnection,
+    media_session_key: MediaSessionKey,
+) -> Result<Option<PublisherInfo<'static>>> {
+    let publisher_info_json: Option<String> = redis
+        .hget(PUBLISHER_INFO, media_session_key.to_string())
+        .await
+        .with_context(|| {
+            format!("Failed to get mcu id for media session key {media_session_key}",)
+        })?;
+
+    publisher_info_json
+        .map(|json| serde_json::from_str(&json).context("Failed to deserialize publisher info"))
+        .transpose()
+}
+
+/// Stores where the publisher of the given media session is located
+pub async fn set_publisher_info(
+    redis: &mut RedisConnection,
+    media_session_key: MediaSessionKey,
+    info: &PublisherInfo<'_>,
+) -> Result<()> {
+    let info = serde_json::to_string(info).context("Failed to serialize publisher info")?;
+
+    redis
+        .hset(PUBLISHER_INFO, media_session_key.to_string(), info)
+        .await
+        .context("Failed to set publisher info")
+}
+
+/// The id of the mcu that is connected via the given janus rabbitmq routing keys and exchange
+pub fn mcu_id(to_janus_key: &str, janus_exchange_key: &str, from_janus_key: &str) -> String {
+    McuId::generate_id_string(to_janus_key, janus_exchange_key, from_janus_key)
+}
+
 #[derive(Debug, Serialize, Deserialize)]
-struct PublisherInfo<'i> {
-    room_id: JanusRoomId,
+struct ExternalStreamInfo<'i> {
+    mountpoint_id: MountpointId,
     mcu_id: Cow<'i, str>,
     loop_index: Option<usize>,
 }
 
+/// A streaming mountpoint created for an external stream
+pub struct ExternalStreamMountpoint {
+    pub mountpoint: janus_client::incoming::Mountpoint,
+
+    /// The host the janus instance receives the RTP packets on, if configured
+    pub host: Option<String>,
+}
+
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
 struct McuId(Arc<str>);
 
@@ -307,17 +367,16 @@ impl McuPool {
 
         let (destroy, destroy_sig) = oneshot::channel();
 
-        let info = serde_json::to_string(&PublisherInfo {
-            room_id,
-            mcu_id: Cow::Borrowed(client.id.0.as_ref()),
-            loop_index,
-        })
-        .context("Failed to serialize publisher info")?;
-
-        redis
-            .hset(PUBLISHER_INFO, media_session_key.to_string(), info)
-            .await
-            .context("Failed to set publisher info")?;
+        set_publisher_info(
+            &mut redis,
+            media_session_key,
+            &PublisherInfo {
+                room_id,
+                mcu_id: Cow::Borrowed(client.id.0.as_ref()),
+                loop_index,
+            },
+        )
+        .await?;
 
         redis
             .zincr(MCU_LOAD, mcu_load_key(&client.id, loop_index), 1)
@@ -428,29 +487,50 @@ impl McuPool {
     ) -> Result<JanusSubscriber> {
         let mut redis = self.redis.clone();
 
-        let publisher_info_json: String = redis
-            .hget(PUBLISHER_INFO, media_session_key.to_string())
-            .await
-            .with_context(|| {
-                format!("Failed to get mcu id for media session key {media_session_key}",)
-            })?;
+        let publisher_info = get_publisher_info(&mut redis, media_session_key).await?;
 
-        let info: PublisherInfo = serde_json::from_str(&publisher_info_json)
-            .context("Failed to deserialize publisher info")?;
+        let (mcu_id, loop_index, kind) = if let Some(info) = publisher_info {
+            (
+                info.mcu_id.into_owned(),
+                info.loop_index,
+                SubscriberKind::VideoRoom(info.room_id),
+            )
+        } else {
+            let external_stream_info_json: String = redis
+                .hget(EXTERNAL_STREAM_INFO, media_session_key.to_string())
+                .await
+                .with_context(|| {
+                    format!("Failed to get mcu id for media session key {media_session_key}",)
+                })?;
+
+            let info: ExternalStreamInfo = serde_json::from_str(&external_stream_info_json)
+                .context("Failed to deserialize external stream info")?;
+
+            (
+                info.mcu_id.into_owned(),
+                info.loop_index,
+                SubscriberKind::Streaming(info.mountpoint_id),
+            )
+        };
 
         let clients = self.clients.read().await;
         let client = clients
-            .get(info.mcu_id.as_ref())
+            .get(mcu_id.as_str())
             .context("Publisher stored unknown mcu id")?;
 
+        let plugin = match kind {
+            SubscriberKind::VideoRoom(_) => janus_client::JanusPlugin::VideoRoom,
+            SubscriberKind::Streaming(_) => janus_client::JanusPlugin::Streaming,
+        };
+
         let handle = client
             .session
-            .attach_to_plugin(janus_client::JanusPlugin::VideoRoom, info.loop_index)
+            .attach_to_plugin(plugin, loop_index)
             .await
-            .context("Failed to attach to videoroom plugin")?;
+            .with_context(|| format!("Failed to attach to {plugin:?} plugin"))?;
 
         redis
-            .zincr(MCU_LOAD, mcu_load_key(&client.id, info.loop_index), 1)
+            .zincr(MCU_LOAD, mcu_load_key(&client.id, loop_index), 1)
             .await
             .context("Failed to increment handle count")?;
 
@@ -466,9 +546,9 @@ impl McuPool {
 
         let subscriber = JanusSubscriber {
             handle: handle.clone(),
-            room_id: info.room_id,
+            kind,
             mcu_id: client.id.clone(),
-            loop_index: info.loop_index,
+            loop_index,
             media_session_key,
             redis,
             destroy,
@@ -476,6 +556,128 @@ impl McuPool {
 
         Ok(subscriber)
     }
+
+    /// Creates a streaming mountpoint which receives the given RTP media
+    ///
+    /// The mountpoint is registered under the given `media_session_key` so that participants
+    /// can subscribe to it like to any other publisher.
+    pub async fn new_external_stream(
+        &self,
+        media_session_key: MediaSessionKey,
+        media: Vec<StreamingPluginMedia>,
+    ) -> Result<ExternalStreamMountpoint> {
+        let mut redis = self.redis.clone();
+
+        let clients = self.clients.read().await;
+        let (client, loop_index) = self
+            .choose_client(&mut redis, &clients)
+            .await
+            .context("Failed to choose McuClient")?;
+
+        let handle = client
+            .session
+            .attach_to_plugin(janus_client::JanusPlugin::Streaming, loop_index)
+            .await
+            .context("Failed to attach session to streaming plugin")?;
+
+        let request = janus_client::outgoing::StreamingPluginCreate {
+            description: Some(media_session_key.to_string()),
+            is_private: Some(true),
+            media,
+            ..Default::default()
+        };
+
+        let response = handle.send(request).await;
+
+        if let Err(e) = handle.detach(false).await {
+            log::error!("Failed to detach streaming handle, {}", e);
+        }
+
+        let mountpoint = match response? {
+            (janus_client::incoming::StreamingPluginDataCreated::Ok { stream, .. }, _) => stream,
+            (janus_client::incoming::StreamingPluginDataCreated::Err(e), _) => {
+                bail!("Failed to create mountpoint, got error response: {}", e);
+            }
+        };
+
+        log::trace!(
+            "Using Janus mountpoint {} for external stream {}",
+            mountpoint.id,
+            media_session_key.0,
+        );
+
+        let info = serde_json::to_string(&ExternalStreamInfo {
+            mountpoint_id: mountpoint.id,
+            mcu_id: Cow::Borrowed(client.id.0.as_ref()),
+            loop_index,
+        })
+        .context("Failed to serialize external stream info")?;
+
+        redis
+            .hset(EXTERNAL_STREAM_INFO, media_session_key.to_string(), info)
+            .await
+            .context("Failed to set external stream info")?;
+
+        Ok(ExternalStreamMountpoint {
+            mountpoint,
+            host: client.config.rtp_host.clone(),
+        })
+    }
+
+    /// Destroys the streaming mountpoint registered under the given `media_session_key`
+    ///
+    /// Subscribers of the mountpoint receive a hangup and are cleaned up by their owners.
+    pub async fn destroy_external_stream(&self, media_session_key: MediaSessionKey) -> Result<()> {
+        let mut redis = self.redis.clone();
+
+        let external_stream_info_json: Option<String> = redis
+            .hget(EXTERNAL_STREAM_INFO, media_session_key.to_string())
+            .await
+            .context("Failed to get external stream info")?;
+
+        let external_stream_info_json = match external_stream_info_json {
+            Some(json) => json,
+            None => {
+                log::debug!("No mountpoint found for external stream {media_session_key}");
+                return Ok(());
+            }
+        };
+
+        redis
+            .hdel::<_, _, ()>(EXTERNAL_STREAM_INFO, media_session_key.to_string())
+            .await
+            .context("Failed to remove external stream info")?;
+
+        let info: ExternalStreamInfo = serde_json::from_str(&external_stream_info_json)
+            .context("Failed to deserialize external stream info")?;
+
+        let clients = self.clients.read().await;
+        let client = clients
+            .get(info.mcu_id.as_ref())
+            .context("External stream stored unknown mcu id")?;
+
+        let handle = client
+            .session
+            .attach_to_plugin(janus_client::JanusPlugin::Streaming, info.loop_index)
+            .await
+            .context("Failed to attach session to streaming plugin")?;
+
+        let response = handle
+            .send(janus_client::outgoing::StreamingPluginDestroy {
+                id: info.mountpoint_id,
+                secret: None,
+                permanent: None,
+            })
+            .await;
+
+        if let Err(e) = handle.detach(false).await {
+            log::error!("Failed to detach streaming handle, {}", e);
+        }
+
+        response.context("Failed to destroy mountpoint")?;
+
+        Ok(())
+    }
 }
 
 async fn global_reconnect_task(mcu_pool: Arc<McuPool>, mut receiver: mpsc::Receiver<Connection>) {
@@ -1011,9 +1213,18 @@ impl JanusPublisher {
     }
 }
 
+/// The janus plugin resource a [`JanusSubscriber`] receives its media from
+#[derive(Debug, Clone, Copy)]
+enum SubscriberKind {
+    /// The videoroom of a participant's publisher
+    VideoRoom(JanusRoomId),
+    /// The streaming mountpoint of an external stream
+    Streaming(MountpointId),
+}
+
 pub struct JanusSubscriber {
     handle: janus_client::Handle,
-    room_id: JanusRoomId,
+    kind: SubscriberKind,
     mcu_id: McuId,
     loop_index: Option<usize>,
     media_session_key: MediaSessionKey,
@@ -1033,9 +1244,15 @@ impl JanusSubscriber {
                 Ok(Response::SdpOffer(response))
             }
             Request::SdpAnswer(e) => {
-                send_answer(&self.handle, (JsepType::Answer, e).into())
-                    .await
-                    .context("Failed to send SDP answer")?;
+                match self.kind {
+                    SubscriberKind::VideoRoom(_) => {
+                        send_answer(&self.handle, (JsepType::Answer, e).into()).await
+                    }
+                    SubscriberKind::Streaming(_) => {
+                        send_streaming_answer(&self.handle, (JsepType::Answer, e).into()).await
+                    }
+                }
+                .context("Failed to send SDP answer")?;
 
                 Ok(Response::None)
             }
@@ -1079,9 +1296,16 @@ impl JanusSubscriber {
 
     /// Joins the room of the publisher this [JanusSubscriber](JanusSubscriber) is subscriber to
     async fn join_room(&self, without_video: bool) -> Result<janus_client::Jsep> {
+        let room_id = match self.kind {
+            SubscriberKind::VideoRoom(room_id) => room_id,
+            SubscriberKind::Streaming(mountpoint_id) => {
+                return self.watch_mountpoint(mountpoint_id, without_video).await
+            }
+        };
+
         let feed = janus_client::FeedId::new(self.media_session_key.1.into());
         let join_request =
-            janus_client::outgoing::VideoRoomPluginJoinSubscriber::builder(self.room_id, feed)
+            janus_client::outgoing::VideoRoomPluginJoinSubscriber::builder(room_id, feed)
                 .offer_video(if without_video { Some(false) } else { None })
                 .build();
 
@@ -1100,8 +1324,38 @@ impl JanusSubscriber {
         }
     }
 
+    /// Starts watching the mountpoint of the external stream this [JanusSubscriber] is subscribed to
+    async fn watch_mountpoint(
+        &self,
+        mountpoint_id: MountpointId,
+        without_video: bool,
+    ) -> Result<janus_client::Jsep> {
+        let watch_request = janus_client::outgoing::StreamingPluginWatch {
+            offer_video: if without_video { Some(false) } else { None },
+            ..janus_client::outgoing::StreamingPluginWatch::new(mountpoint_id)
+        };
+
+        match self.handle.send(watch_request).await {
+            Ok((_, Some(jsep))) => {
+                log::debug!("Watch mountpoint got Jsep/SDP: {:?}", jsep);
+                Ok(jsep)
+            }
+            Ok((data, None)) => bail!(
+                "Got invalid response on watch_mountpoint, missing jsep. Got {:?}",
+                data
+            ),
+            Err(e) => bail!("Failed to watch mountpoint, {}", e),
+        }
+    }
+
     /// Configure the subscriber
     async fn configure_subscriber(&self, configuration: SubscriberConfiguration) -> Result<()> {
+        if let SubscriberKind::Streaming(_) = self.kind {
+            // External streams are not simulcast, there is nothing to configure
+            log::debug!("Ignoring configure request for external stream subscriber");
+            return Ok(());
+        }
+
         let configure_request = VideoRoomPluginConfigureSubscriber::builder()
             .substream(configuration.substream)
             .video(configuration.video)
@@ -1124,6 +1378,10 @@ impl JanusSubscriber {
 
     /// Restart the webrtc session and return a new SDP Offer
     pub async fn restart(&self) -> Result<String> {
+        if let SubscriberKind::Streaming(_) = self.kind {
+            bail!("Restarting subscribers of external streams is not supported");
+        }
+
         let configure_request = VideoRoomPluginConfigureSubscriber::builder()
             .restart(Some(true))
             .build();
@@ -1358,6 +1616,19 @@ async fn send_answer(handle: &janus_client::Handle, answer: SdpAnswer) -> Result
     }
 }
 
+async fn send_streaming_answer(handle: &janus_client::Handle, answer: SdpAnswer) -> Result<()> {
+    match handle
+        .send_with_jsep(
+            janus_client::outgoing::StreamingPluginStart {},
+            answer.into(),
+        )
+        .await
+    {
+        Ok(_) => Ok(()),
+        Err(e) => bail!("Failed to send sdp answer, {}", e),
+    }
+}
+
 async fn send_candidate(
     handle: &janus_client::Handle,
     candidate: janus_client::TrickleCandidate,
@@ -58,6 +58,9 @@ pub struct Connection {
     pub from_routing_key: String,
     #[serde(default)]
     pub event_loops: Option<usize>,
+    /// The host external RTP streams are sent to, reported to the moderator starting the stream
+    #[serde(default)]
+    pub rtp_host: Option<String>,
 }
 
 const fn default_max_video_bitrate() -> u64 {
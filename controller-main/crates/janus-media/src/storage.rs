@@ -6,7 +6,10 @@ use anyhow::{Context, Result};
 use redis::AsyncCommands;
 use redis_args::ToRedisArgs;
 use signaling_core::{RedisConnection, SignalingRoomId};
-use types::{core::ParticipantId, signaling::media::ParticipantMediaState};
+use types::{
+    core::ParticipantId,
+    signaling::media::{ExternalStream, ParticipantMediaState},
+};
 
 /// Data related to a module inside a participant
 // TODO can this be removed?
@@ -116,3 +119,69 @@ pub async fn delete_presenter_key(
 
     Ok(())
 }
+
+#[derive(ToRedisArgs)]
+#[to_redis_args(fmt = "opentalk-signaling:room={room}:namespace=media:external_streams")]
+struct ExternalStreams {
+    room: SignalingRoomId,
+}
+
+#[tracing::instrument(level = "debug", skip(redis_conn))]
+pub async fn add_external_stream(
+    redis_conn: &mut RedisConnection,
+    room: SignalingRoomId,
+    external_stream: &ExternalStream,
+) -> Result<()> {
+    redis_conn
+        .hset(ExternalStreams { room }, external_stream.id, external_stream)
+        .await
+        .context("Failed to add external stream")
+}
+
+#[tracing::instrument(level = "debug", skip(redis_conn))]
+pub async fn remove_external_stream(
+    redis_conn: &mut RedisConnection,
+    room: SignalingRoomId,
+    id: ParticipantId,
+) -> Result<bool> {
+    let removed: u64 = redis_conn
+        .hdel(ExternalStreams { room }, id)
+        .await
+        .context("Failed to remove external stream")?;
+
+    Ok(removed > 0)
+}
+
+#[tracing::instrument(level = "debug", skip(redis_conn))]
+pub async fn is_external_stream(
+    redis_conn: &mut RedisConnection,
+    room: SignalingRoomId,
+    id: ParticipantId,
+) -> Result<bool> {
+    redis_conn
+        .hexists(ExternalStreams { room }, id)
+        .await
+        .context("Failed to check for external stream")
+}
+
+#[tracing::instrument(level = "debug", skip(redis_conn))]
+pub async fn get_external_streams(
+    redis_conn: &mut RedisConnection,
+    room: SignalingRoomId,
+) -> Result<Vec<ExternalStream>> {
+    redis_conn
+        .hvals(ExternalStreams { room })
+        .await
+        .context("Failed to get external streams")
+}
+
+#[tracing::instrument(level = "debug", skip(redis_conn))]
+pub async fn delete_external_streams_key(
+    redis_conn: &mut RedisConnection,
+    room: SignalingRoomId,
+) -> Result<()> {
+    redis_conn
+        .del(ExternalStreams { room })
+        .await
+        .context("Failed to delete external streams")
+}
@@ -30,6 +30,7 @@ end";
 pub struct Mutex<K> {
     key: K,
 
+    lock_time: Duration,
     wait_time: Range<Duration>,
     tries: usize,
 }
@@ -42,6 +43,7 @@ pub struct MutexGuard<'a, K> {
     key: &'a K,
     canary: Vec<u8>,
     created: Instant,
+    lock_time: Duration,
     locked: bool,
 }
 
@@ -54,7 +56,7 @@ impl<K> MutexGuard<'_, K> {
     }
 
     fn is_expired(&self) -> bool {
-        self.created.elapsed() > LOCK_TIME
+        self.created.elapsed() > self.lock_time
     }
 
     fn is_locked_internal(&self) -> bool {
@@ -114,11 +116,18 @@ where
     {
         Self {
             key,
+            lock_time: LOCK_TIME,
             wait_time: Duration::from_millis(10)..Duration::from_millis(50),
             tries: 10,
         }
     }
 
+    /// Set the duration after which the lock expires if it has not been unlocked
+    pub fn with_lock_time(mut self, lock_time: Duration) -> Self {
+        self.lock_time = lock_time;
+        self
+    }
+
     /// Set a duration range to randomly wait between retries
     pub fn with_wait_time(mut self, range: Range<Duration>) -> Self {
         self.wait_time = range;
@@ -154,7 +163,7 @@ where
                 .arg(&canary[..])
                 .arg("NX")
                 .arg("PX")
-                .arg(LOCK_TIME.as_millis() as u64)
+                .arg(self.lock_time.as_millis() as u64)
                 .query_async(redis)
                 .await?;
 
@@ -163,6 +172,7 @@ where
                     key: &self.key,
                     canary,
                     created,
+                    lock_time: self.lock_time,
                     locked: true,
                 };
                 return Ok(guard);
@@ -236,4 +246,33 @@ mod tests {
 
         assert_eq!(guard2, Error::CouldNotAcquireLock);
     }
+
+    #[tokio::test]
+    async fn test_lock_time() {
+        let redis_url =
+            std::env::var("REDIS_ADDR").unwrap_or_else(|_| "redis://localhost:6379/".to_owned());
+        let redis = redis::Client::open(redis_url).expect("Invalid redis url");
+
+        let mut redis_conn = redis
+            .get_multiplexed_async_connection()
+            .await
+            .expect("Failed to get redis connection");
+
+        let mut mutex1 =
+            Mutex::new("test3-MY-REDIS-LOCK").with_lock_time(Duration::from_millis(100));
+        let mut mutex2 = Mutex::new("test3-MY-REDIS-LOCK").with_retries(0);
+
+        let guard1 = mutex1.lock(&mut redis_conn).await.unwrap();
+        sleep(Duration::from_millis(200)).await;
+        assert!(!guard1.is_locked());
+
+        // The expired lock can be taken by others
+        let guard2 = mutex2.lock(&mut redis_conn).await.unwrap();
+        guard2.unlock(&mut redis_conn).await.unwrap();
+
+        assert_eq!(
+            guard1.unlock(&mut redis_conn).await.err().unwrap(),
+            Error::AlreadyExpired
+        );
+    }
 }
@@ -13,7 +13,10 @@ use db_storage::{
     rooms::Room,
 };
 use futures::Stream;
-use types::core::{AssetId, RoomId};
+use types::{
+    api::v1::assets::MediaMetadata,
+    core::{AssetId, RoomId},
+};
 
 use crate::ObjectStorage;
 
@@ -29,10 +32,28 @@ pub async fn save_asset(
     kind: impl Into<String>,
     data: impl Stream<Item = Result<Bytes>> + Unpin,
 ) -> Result<AssetId> {
-    let namespace = namespace.map(Into::into);
-    let filename = filename.into();
-    let kind = kind.into();
+    save_linked_asset(
+        storage, db, room_id, namespace, filename, kind, None, None, data,
+    )
+    .await
+}
 
+/// Save an asset which belongs to another asset in the long term storage
+///
+/// Works like [`save_asset`], the link is removed when the linked asset gets deleted. Recordings
+/// carry their duration and resolution as media metadata.
+#[allow(clippy::too_many_arguments)]
+pub async fn save_linked_asset(
+    storage: &ObjectStorage,
+    db: Arc<Db>,
+    room_id: RoomId,
+    namespace: Option<&str>,
+    filename: impl Into<String>,
+    kind: impl Into<String>,
+    linked_asset_id: Option<AssetId>,
+    media: Option<MediaMetadata>,
+    data: impl Stream<Item = Result<Bytes>> + Unpin,
+) -> Result<AssetId> {
     let asset_id = AssetId::generate();
 
     // upload to s3 storage
@@ -41,6 +62,39 @@ pub async fn save_asset(
         .await
         .context("failed to upload asset file to storage")?;
 
+    register_asset(
+        storage,
+        db,
+        room_id,
+        asset_id,
+        namespace,
+        filename,
+        kind,
+        linked_asset_id,
+        media,
+    )
+    .await
+}
+
+/// Create the database entry of an asset which has been uploaded to the S3 bucket already
+///
+/// The uploaded file is deleted from the S3 bucket if the database entry cannot be created.
+#[allow(clippy::too_many_arguments)]
+pub async fn register_asset(
+    storage: &ObjectStorage,
+    db: Arc<Db>,
+    room_id: RoomId,
+    asset_id: AssetId,
+    namespace: Option<&str>,
+    filename: impl Into<String>,
+    kind: impl Into<String>,
+    linked_asset_id: Option<AssetId>,
+    media: Option<MediaMetadata>,
+) -> Result<AssetId> {
+    let namespace = namespace.map(Into::into);
+    let filename = filename.into();
+    let kind = kind.into();
+
     // create db entry
     let mut db_conn = db.get_conn().await?;
 
@@ -52,6 +106,14 @@ pub async fn save_asset(
         filename,
         kind,
         tenant_id: room.tenant_id,
+        linked_asset_id,
+        duration_ms: media.and_then(|media| i64::try_from(media.duration_ms).ok()),
+        width: media
+            .and_then(|media| media.width)
+            .and_then(|width| i32::try_from(width).ok()),
+        height: media
+            .and_then(|media| media.height)
+            .and_then(|height| i32::try_from(height).ok()),
     }
     .insert_for_room(&mut db_conn, room_id)
     .await;
@@ -79,16 +141,20 @@ pub async fn get_asset(storage: &ObjectStorage, asset_id: &AssetId) -> Result<By
     storage.get(asset_key(asset_id)).await
 }
 
-/// Delete an asset from the object storage
+/// Delete an asset and the assets linked to it from the object storage
 pub async fn delete_asset(
     storage: &ObjectStorage,
     db: Arc<Db>,
     room_id: RoomId,
     asset_id: AssetId,
 ) -> Result<()> {
-    Asset::delete_by_id(&mut db.get_conn().await?, asset_id, room_id).await?;
+    let asset_ids = Asset::delete_by_id(&mut db.get_conn().await?, asset_id, room_id).await?;
+
+    for asset_id in asset_ids {
+        storage.delete(asset_key(&asset_id)).await?;
+    }
 
-    storage.delete(asset_key(&asset_id)).await
+    Ok(())
 }
 
 pub fn asset_key(asset_id: &AssetId) -> String {
@@ -31,7 +31,7 @@ pub use exchange_task::{ExchangeHandle, ExchangeTask, SubscriberHandle};
 pub use init_context::{ExchangeBinding, InitContext};
 pub use metrics::SignalingMetrics;
 pub use module_context::{ExchangePublish, ModuleContext};
-pub use object_storage::ObjectStorage;
+pub use object_storage::{ObjectStorage, MIN_PART_SIZE};
 pub use participant::Participant;
 pub use redis_wrapper::{RedisConnection, RedisMetrics};
 pub use signaling_module::{SignalingModule, SignalingModuleInitData};
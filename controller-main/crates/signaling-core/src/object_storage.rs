@@ -17,7 +17,9 @@ use controller_settings::MinIO;
 use futures::{Stream, StreamExt};
 use url::Url;
 
-const CHUNK_SIZE: usize = 5_242_880; // 5 MebiByte (minimum for aws s3)
+/// Minimum size of all but the last part of a multipart upload
+pub const MIN_PART_SIZE: usize = 5_242_880; // 5 MebiByte (minimum for aws s3)
+const CHUNK_SIZE: usize = MIN_PART_SIZE;
 
 pub struct ObjectStorage {
     /// The s3 client
@@ -261,6 +263,102 @@ impl ObjectStorage {
         Ok(file_size)
     }
 
+    /// Start a multipart upload which is continued with [`ObjectStorage::upload_part`]
+    ///
+    /// Returns the id of the multipart upload
+    pub async fn create_multipart_upload(&self, key: &str) -> Result<String> {
+        let output = self
+            .client
+            .create_multipart_upload()
+            .bucket(&self.bucket)
+            .key(key)
+            .send()
+            .await
+            .context("failed to create multipart upload")?;
+
+        output
+            .upload_id
+            .context("no upload_id in create_multipart_upload response")
+    }
+
+    /// Upload a part of a multipart upload
+    ///
+    /// All parts except the last one must have at least 5 MebiByte.
+    ///
+    /// Returns the etag of the uploaded part
+    pub async fn upload_part(
+        &self,
+        key: &str,
+        upload_id: &str,
+        part_number: i32,
+        data: Bytes,
+    ) -> Result<String> {
+        let part = self
+            .client
+            .upload_part()
+            .bucket(&self.bucket)
+            .key(key)
+            .upload_id(upload_id)
+            .part_number(part_number)
+            .content_length(data.len() as i64)
+            .body(data.into())
+            .send()
+            .await
+            .context("failed to upload part")?;
+
+        part.e_tag()
+            .map(ToOwned::to_owned)
+            .context("missing etag in upload_part response")
+    }
+
+    /// Complete a multipart upload from the part numbers and etags of all uploaded parts
+    pub async fn complete_multipart_upload(
+        &self,
+        key: &str,
+        upload_id: &str,
+        parts: impl IntoIterator<Item = (i32, String)>,
+    ) -> Result<()> {
+        let parts = parts
+            .into_iter()
+            .map(|(part_number, e_tag)| {
+                CompletedPart::builder()
+                    .e_tag(e_tag)
+                    .part_number(part_number)
+                    .build()
+            })
+            .collect();
+
+        self.client
+            .complete_multipart_upload()
+            .bucket(&self.bucket)
+            .key(key)
+            .upload_id(upload_id)
+            .multipart_upload(
+                CompletedMultipartUpload::builder()
+                    .set_parts(Some(parts))
+                    .build(),
+            )
+            .send()
+            .await
+            .context("failed to complete multipart upload")?;
+
+        Ok(())
+    }
+
+    /// Abort a multipart upload and discard its uploaded parts
+    pub async fn abort_multipart_upload(&self, key: &str, upload_id: &str) -> Result<()> {
+        self.client
+            .abort_multipart_upload()
+            .bucket(&self.bucket)
+            .key(key)
+            .upload_id(upload_id)
+            .send()
+            .await
+            .context("failed to abort multipart upload")?;
+
+        Ok(())
+    }
+
     pub async fn get(&self, key: String) -> Result<ByteStream> {
         let data = self
             .client
@@ -26,4 +26,34 @@ pub struct AssetResource {
 
     /// The timestamp the asset was created
     pub created_at: DateTime<Utc>,
+
+    /// The ID of the asset this asset belongs to, e.g. the recording of its chapters
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub linked_asset_id: Option<AssetId>,
+
+    /// Duration and resolution of a recording
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub media: Option<MediaMetadata>,
+}
+
+/// Duration and resolution of a media asset
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MediaMetadata {
+    /// The duration in milliseconds
+    pub duration_ms: u64,
+
+    /// The width of the video, missing if there is no video
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub width: Option<u32>,
+
+    /// The height of the video, missing if there is no video
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub height: Option<u32>,
 }
@@ -5,11 +5,21 @@
 //! This module contains types that are used for OpenTalk API V1 events endpoints.
 
 mod call_in;
+mod dial_out;
 mod recording;
+mod render_kind;
 mod service_start_response;
+mod upload_chunk_query;
 mod upload_render_query;
+mod upload_render_response;
+mod upload_status;
 
 pub use call_in::StartRequestBody;
+pub use dial_out::{DialOutProgressBody, DialOutStartBody};
 pub use recording::StartBody;
+pub use render_kind::RenderKind;
 pub use service_start_response::ServiceStartResponse;
+pub use upload_chunk_query::UploadChunkQuery;
 pub use upload_render_query::UploadRenderQuery;
+pub use upload_render_response::UploadRenderResponse;
+pub use upload_status::UploadStatus;
@@ -0,0 +1,25 @@
+// SPDX-FileCopyrightText: OpenTalk GmbH <mail@opentalk.eu>
+//
+// SPDX-License-Identifier: EUPL-1.2
+
+#[allow(unused_imports)]
+use crate::imports::*;
+use crate::{core::DialOutId, signaling::dial_out::CallStatus};
+
+/// Body for the `POST /services/dial_out/start` endpoint
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DialOutStartBody {
+    /// The id of the answered call
+    pub call_id: DialOutId,
+}
+
+/// Body for the `POST /services/dial_out/progress` endpoint
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DialOutProgressBody {
+    /// The id of the call
+    pub call_id: DialOutId,
+    /// The new status of the call
+    pub status: CallStatus,
+}
@@ -0,0 +1,37 @@
+// SPDX-FileCopyrightText: OpenTalk GmbH <mail@opentalk.eu>
+//
+// SPDX-License-Identifier: EUPL-1.2
+
+#[allow(unused_imports)]
+use crate::imports::*;
+
+/// Kind of a file uploaded to the `POST /services/recording/upload_render` endpoint
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum RenderKind {
+    /// The rendered recording
+    #[default]
+    Render,
+    /// WebVTT chapters of a recording
+    Chapters,
+    /// JSON timeline of the speakers and screen shares within a recording
+    SpeakerTimeline,
+    /// JPEG of a frame of a recording
+    Thumbnail,
+}
+
+impl RenderKind {
+    /// The kind of the asset which stores the uploaded file
+    pub const fn asset_kind(self) -> &'static str {
+        match self {
+            Self::Render => "recording-render",
+            Self::Chapters => "recording-chapters",
+            Self::SpeakerTimeline => "recording-speaker-timeline",
+            Self::Thumbnail => "recording-thumbnail",
+        }
+    }
+}
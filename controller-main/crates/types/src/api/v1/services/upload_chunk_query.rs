@@ -0,0 +1,14 @@
+// SPDX-FileCopyrightText: OpenTalk GmbH <mail@opentalk.eu>
+//
+// SPDX-License-Identifier: EUPL-1.2
+
+#[allow(unused_imports)]
+use crate::imports::*;
+
+/// Query for the `PUT /services/recording/uploads/{upload_id}` endpoint
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct UploadChunkQuery {
+    /// The position of the chunk within the uploaded file
+    pub offset: u64,
+}
@@ -2,11 +2,14 @@
 //
 // SPDX-License-Identifier: EUPL-1.2
 
-use crate::core::RoomId;
+use super::RenderKind;
+use crate::api::v1::assets::MediaMetadata;
+use crate::core::{AssetId, RoomId};
 #[allow(unused_imports)]
 use crate::imports::*;
 
-/// Response for the `POST /services/recording/upload_render` endpoint
+/// Query for the `POST /services/recording/upload_render` and `POST /services/recording/uploads`
+/// endpoints
 #[derive(Debug, Clone, Eq, PartialEq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct UploadRenderQuery {
@@ -14,4 +17,30 @@ pub struct UploadRenderQuery {
     pub room_id: RoomId,
     /// The filename
     pub filename: String,
+    /// The kind of the uploaded file
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub kind: RenderKind,
+    /// The asset of the recording the uploaded file belongs to
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub linked_asset_id: Option<AssetId>,
+    /// The duration of the uploaded recording in milliseconds
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub duration_ms: Option<u64>,
+    /// The width of the uploaded recording's video
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub width: Option<u32>,
+    /// The height of the uploaded recording's video
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub height: Option<u32>,
+}
+
+impl UploadRenderQuery {
+    /// The duration and resolution of the uploaded recording, if the duration is known
+    pub fn media(&self) -> Option<MediaMetadata> {
+        self.duration_ms.map(|duration_ms| MediaMetadata {
+            duration_ms,
+            width: self.width,
+            height: self.height,
+        })
+    }
 }
@@ -0,0 +1,15 @@
+// SPDX-FileCopyrightText: OpenTalk GmbH <mail@opentalk.eu>
+//
+// SPDX-License-Identifier: EUPL-1.2
+
+use crate::core::AssetId;
+#[allow(unused_imports)]
+use crate::imports::*;
+
+/// Response for the `POST /services/recording/upload_render` endpoint
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct UploadRenderResponse {
+    /// The id of the created asset
+    pub asset_id: AssetId,
+}
@@ -0,0 +1,17 @@
+// SPDX-FileCopyrightText: OpenTalk GmbH <mail@opentalk.eu>
+//
+// SPDX-License-Identifier: EUPL-1.2
+
+use crate::core::UploadId;
+#[allow(unused_imports)]
+use crate::imports::*;
+
+/// Response for the `/services/recording/uploads` endpoints
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct UploadStatus {
+    /// The id of the upload
+    pub upload_id: UploadId,
+    /// The number of bytes received so far, the next chunk has to start here
+    pub offset: u64,
+}
@@ -4,7 +4,10 @@
 
 //! This module contains types that are used for OpenTalk API V1 streaming target endpoints.
 
-use crate::core::{RoomId, StreamingKey, StreamingTargetId};
+use crate::{
+    common::streaming::{StreamingTargetError, StreamingTargetKind},
+    core::{RoomId, StreamingKey, StreamingTargetId},
+};
 use url::Url;
 
 #[allow(unused_imports)]
@@ -53,4 +56,193 @@ pub enum UpdateStreamingTargetKind {
         #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
         public_url: Option<Url>,
     },
+
+    /// A YouTube live stream
+    Youtube {
+        /// The streaming key
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        streaming_key: Option<StreamingKey>,
+        /// The id of the YouTube channel
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        channel_id: Option<String>,
+    },
+
+    /// A Twitch live stream
+    Twitch {
+        /// The streaming key
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        streaming_key: Option<StreamingKey>,
+        /// The name of the Twitch channel
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        channel: Option<String>,
+    },
+
+    /// A live stream to a self hosted Owncast server
+    Owncast {
+        /// The url of the Owncast server
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        server_url: Option<Url>,
+        /// The streaming key
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        streaming_key: Option<StreamingKey>,
+    },
+
+    /// A live of a PeerTube instance
+    Peertube {
+        /// The url of the PeerTube instance
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        instance_url: Option<Url>,
+        /// The streaming key of the live
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        streaming_key: Option<StreamingKey>,
+        /// The id of the live video
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        video_id: Option<String>,
+    },
+}
+
+impl UpdateStreamingTargetKind {
+    /// Apply the update to the current kind of a streaming target
+    ///
+    /// Missing values are taken from `current` if it is of the same kind, all values are
+    /// required when the kind is changed.
+    pub fn apply(
+        self,
+        current: StreamingTargetKind,
+    ) -> Result<StreamingTargetKind, StreamingTargetError> {
+        fn required<T>(value: Option<T>, field: &'static str) -> Result<T, StreamingTargetError> {
+            value.ok_or(StreamingTargetError {
+                field,
+                reason: "required when changing the kind of the streaming target",
+            })
+        }
+
+        match (self, current) {
+            (
+                Self::Custom {
+                    streaming_endpoint,
+                    streaming_key,
+                    public_url,
+                },
+                StreamingTargetKind::Custom {
+                    streaming_endpoint: current_endpoint,
+                    streaming_key: current_key,
+                    public_url: current_public_url,
+                },
+            ) => Ok(StreamingTargetKind::Custom {
+                streaming_endpoint: streaming_endpoint.unwrap_or(current_endpoint),
+                streaming_key: streaming_key.unwrap_or(current_key),
+                public_url: public_url.unwrap_or(current_public_url),
+            }),
+            (
+                Self::Custom {
+                    streaming_endpoint,
+                    streaming_key,
+                    public_url,
+                },
+                _,
+            ) => Ok(StreamingTargetKind::Custom {
+                streaming_endpoint: required(streaming_endpoint, "streaming_endpoint")?,
+                streaming_key: required(streaming_key, "streaming_key")?,
+                public_url: required(public_url, "public_url")?,
+            }),
+            (
+                Self::Youtube {
+                    streaming_key,
+                    channel_id,
+                },
+                StreamingTargetKind::Youtube {
+                    streaming_key: current_key,
+                    channel_id: current_channel_id,
+                },
+            ) => Ok(StreamingTargetKind::Youtube {
+                streaming_key: streaming_key.unwrap_or(current_key),
+                channel_id: channel_id.unwrap_or(current_channel_id),
+            }),
+            (
+                Self::Youtube {
+                    streaming_key,
+                    channel_id,
+                },
+                _,
+            ) => Ok(StreamingTargetKind::Youtube {
+                streaming_key: required(streaming_key, "streaming_key")?,
+                channel_id: required(channel_id, "channel_id")?,
+            }),
+            (
+                Self::Twitch {
+                    streaming_key,
+                    channel,
+                },
+                StreamingTargetKind::Twitch {
+                    streaming_key: current_key,
+                    channel: current_channel,
+                },
+            ) => Ok(StreamingTargetKind::Twitch {
+                streaming_key: streaming_key.unwrap_or(current_key),
+                channel: channel.unwrap_or(current_channel),
+            }),
+            (
+                Self::Twitch {
+                    streaming_key,
+                    channel,
+                },
+                _,
+            ) => Ok(StreamingTargetKind::Twitch {
+                streaming_key: required(streaming_key, "streaming_key")?,
+                channel: required(channel, "channel")?,
+            }),
+            (
+                Self::Owncast {
+                    server_url,
+                    streaming_key,
+                },
+                StreamingTargetKind::Owncast {
+                    server_url: current_server_url,
+                    streaming_key: current_key,
+                },
+            ) => Ok(StreamingTargetKind::Owncast {
+                server_url: server_url.unwrap_or(current_server_url),
+                streaming_key: streaming_key.unwrap_or(current_key),
+            }),
+            (
+                Self::Owncast {
+                    server_url,
+                    streaming_key,
+                },
+                _,
+            ) => Ok(StreamingTargetKind::Owncast {
+                server_url: required(server_url, "server_url")?,
+                streaming_key: required(streaming_key, "streaming_key")?,
+            }),
+            (
+                Self::Peertube {
+                    instance_url,
+                    streaming_key,
+                    video_id,
+                },
+                StreamingTargetKind::Peertube {
+                    instance_url: current_instance_url,
+                    streaming_key: current_key,
+                    video_id: current_video_id,
+                },
+            ) => Ok(StreamingTargetKind::Peertube {
+                instance_url: instance_url.unwrap_or(current_instance_url),
+                streaming_key: streaming_key.unwrap_or(current_key),
+                video_id: video_id.unwrap_or(current_video_id),
+            }),
+            (
+                Self::Peertube {
+                    instance_url,
+                    streaming_key,
+                    video_id,
+                },
+                _,
+            ) => Ok(StreamingTargetKind::Peertube {
+                instance_url: required(instance_url, "instance_url")?,
+                streaming_key: required(streaming_key, "streaming_key")?,
+                video_id: required(video_id, "video_id")?,
+            }),
+        }
+    }
 }
@@ -4,13 +4,24 @@
 
 //! This module contains commonly used types for streaming target endpoints.
 
+use thiserror::Error;
 use url::Url;
+use uuid::Uuid;
 
-use crate::core::{StreamingKey, StreamingTargetId};
+use crate::core::{StreamingKey, StreamingKind, StreamingTargetId};
 
 #[allow(unused_imports)]
 use crate::imports::*;
 
+/// The RTMP ingest endpoint of YouTube
+const YOUTUBE_STREAMING_ENDPOINT: &str = "rtmp://a.rtmp.youtube.com/live2";
+
+/// The RTMP ingest endpoint of Twitch
+const TWITCH_STREAMING_ENDPOINT: &str = "rtmp://live.twitch.tv/app";
+
+/// The RTMP port used by self hosted Owncast and PeerTube instances
+const SELF_HOSTED_RTMP_PORT: u16 = 1935;
+
 /// A streaming target kind
 #[derive(Clone, Debug, PartialEq, Eq)]
 #[cfg_attr(
@@ -28,6 +39,321 @@ pub enum StreamingTargetKind {
         /// The url from which the stream can be accessed
         public_url: Url,
     },
+
+    /// A YouTube live stream
+    Youtube {
+        /// The streaming key, e.g. `abcd-efgh-ijkl-mnop-qrst`
+        streaming_key: StreamingKey,
+        /// The id of the YouTube channel, e.g. `UCxxxxxxxxxxxxxxxxxxxxxx`
+        channel_id: String,
+    },
+
+    /// A Twitch live stream
+    Twitch {
+        /// The streaming key, e.g. `live_123456789_abcdefghijklmnopqrstuvwxyzABCD`
+        streaming_key: StreamingKey,
+        /// The name of the Twitch channel
+        channel: String,
+    },
+
+    /// A live stream to a self hosted Owncast server
+    Owncast {
+        /// The url of the Owncast server
+        server_url: Url,
+        /// The streaming key
+        streaming_key: StreamingKey,
+    },
+
+    /// A permanent or normal live of a PeerTube instance
+    Peertube {
+        /// The url of the PeerTube instance
+        instance_url: Url,
+        /// The streaming key of the live
+        streaming_key: StreamingKey,
+        /// The short or full id of the live video
+        video_id: String,
+    },
+}
+
+/// Error returned when a [`StreamingTargetKind`] contains invalid values
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("Invalid value for field `{field}`, {reason}")]
+pub struct StreamingTargetError {
+    /// The name of the invalid field
+    pub field: &'static str,
+    /// Why the value is invalid
+    pub reason: &'static str,
+}
+
+impl StreamingTargetError {
+    const fn new(field: &'static str, reason: &'static str) -> Self {
+        Self { field, reason }
+    }
+}
+
+impl StreamingTargetKind {
+    /// The [`StreamingKind`] which is stored in the database for this kind
+    pub fn streaming_kind(&self) -> StreamingKind {
+        match self {
+            Self::Custom { .. } => StreamingKind::Custom,
+            Self::Youtube { .. } => StreamingKind::Youtube,
+            Self::Twitch { .. } => StreamingKind::Twitch,
+            Self::Owncast { .. } => StreamingKind::Owncast,
+            Self::Peertube { .. } => StreamingKind::Peertube,
+        }
+    }
+
+    /// The streaming key of the target
+    pub fn streaming_key(&self) -> &StreamingKey {
+        match self {
+            Self::Custom { streaming_key, .. }
+            | Self::Youtube { streaming_key, .. }
+            | Self::Twitch { streaming_key, .. }
+            | Self::Owncast { streaming_key, .. }
+            | Self::Peertube { streaming_key, .. } => streaming_key,
+        }
+    }
+
+    /// The ingest endpoint the stream is sent to
+    pub fn streaming_endpoint(&self) -> Result<Url, StreamingTargetError> {
+        match self {
+            Self::Custom {
+                streaming_endpoint, ..
+            } => Ok(streaming_endpoint.clone()),
+            Self::Youtube { .. } => platform_endpoint(YOUTUBE_STREAMING_ENDPOINT),
+            Self::Twitch { .. } => platform_endpoint(TWITCH_STREAMING_ENDPOINT),
+            Self::Owncast { server_url, .. } => self_hosted_endpoint(server_url, "server_url"),
+            Self::Peertube { instance_url, .. } => {
+                self_hosted_endpoint(instance_url, "instance_url")
+            }
+        }
+    }
+
+    /// The url from which the stream can be watched
+    pub fn public_url(&self) -> Result<Url, StreamingTargetError> {
+        let invalid = |field| StreamingTargetError::new(field, "unable to build the public url");
+
+        match self {
+            Self::Custom { public_url, .. } => Ok(public_url.clone()),
+            Self::Youtube { channel_id, .. } => Url::parse(&format!(
+                "https://www.youtube.com/channel/{channel_id}/live"
+            ))
+            .map_err(|_| invalid("channel_id")),
+            Self::Twitch { channel, .. } => Url::parse(&format!("https://www.twitch.tv/{channel}"))
+                .map_err(|_| invalid("channel")),
+            Self::Owncast { server_url, .. } => Ok(server_url.clone()),
+            Self::Peertube {
+                instance_url,
+                video_id,
+                ..
+            } => instance_url
+                .join(&format!("/w/{video_id}"))
+                .map_err(|_| invalid("video_id")),
+        }
+    }
+
+    /// Validate the values against the formats expected by the platform
+    pub fn validate(&self) -> Result<(), StreamingTargetError> {
+        match self {
+            Self::Custom { .. } => Ok(()),
+            Self::Youtube {
+                streaming_key,
+                channel_id,
+            } => {
+                if !is_youtube_key(streaming_key.as_ref()) {
+                    return Err(StreamingTargetError::new(
+                        "streaming_key",
+                        "expected five groups of four letters or digits separated by `-`",
+                    ));
+                }
+
+                if !is_youtube_channel_id(channel_id) {
+                    return Err(StreamingTargetError::new(
+                        "channel_id",
+                        "expected 24 characters starting with `UC`",
+                    ));
+                }
+
+                Ok(())
+            }
+            Self::Twitch {
+                streaming_key,
+                channel,
+            } => {
+                if !is_twitch_key(streaming_key.as_ref()) {
+                    return Err(StreamingTargetError::new(
+                        "streaming_key",
+                        "expected a key starting with `live_`",
+                    ));
+                }
+
+                if !is_twitch_channel(channel) {
+                    return Err(StreamingTargetError::new(
+                        "channel",
+                        "expected 4 to 25 letters, digits or underscores",
+                    ));
+                }
+
+                Ok(())
+            }
+            Self::Owncast {
+                server_url,
+                streaming_key,
+            } => {
+                validate_instance_url(server_url, "server_url")?;
+
+                let key: &str = streaming_key.as_ref();
+                if key.is_empty() || key.chars().any(char::is_whitespace) {
+                    return Err(StreamingTargetError::new(
+                        "streaming_key",
+                        "expected a non-empty key without whitespace",
+                    ));
+                }
+
+                Ok(())
+            }
+            Self::Peertube {
+                instance_url,
+                streaming_key,
+                video_id,
+            } => {
+                validate_instance_url(instance_url, "instance_url")?;
+
+                if Uuid::parse_str(streaming_key.as_ref()).is_err() {
+                    return Err(StreamingTargetError::new(
+                        "streaming_key",
+                        "expected a UUID",
+                    ));
+                }
+
+                if video_id.is_empty()
+                    || !video_id
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || c == '-')
+                {
+                    return Err(StreamingTargetError::new(
+                        "video_id",
+                        "expected letters, digits or `-`",
+                    ));
+                }
+
+                Ok(())
+            }
+        }
+    }
+
+    /// Rebuild a streaming target kind from the values stored in the database
+    ///
+    /// Platform specific values are derived from the stored public url.
+    pub fn from_parts(
+        kind: StreamingKind,
+        streaming_endpoint: &str,
+        streaming_key: StreamingKey,
+        public_url: &str,
+    ) -> Result<Self, StreamingTargetError> {
+        let public_url = Url::parse(public_url)
+            .map_err(|_| StreamingTargetError::new("public_url", "invalid url"))?;
+
+        let path_segment = |index| {
+            public_url
+                .path_segments()
+                .and_then(|mut segments| segments.nth(index))
+                .filter(|segment| !segment.is_empty())
+                .map(ToOwned::to_owned)
+                .ok_or(StreamingTargetError::new(
+                    "public_url",
+                    "unexpected url for the streaming kind",
+                ))
+        };
+
+        match kind {
+            StreamingKind::Custom => Ok(Self::Custom {
+                streaming_endpoint: Url::parse(streaming_endpoint)
+                    .map_err(|_| StreamingTargetError::new("streaming_endpoint", "invalid url"))?,
+                streaming_key,
+                public_url,
+            }),
+            StreamingKind::Youtube => Ok(Self::Youtube {
+                streaming_key,
+                channel_id: path_segment(1)?,
+            }),
+            StreamingKind::Twitch => Ok(Self::Twitch {
+                streaming_key,
+                channel: path_segment(0)?,
+            }),
+            StreamingKind::Owncast => Ok(Self::Owncast {
+                server_url: public_url,
+                streaming_key,
+            }),
+            StreamingKind::Peertube => {
+                let video_id = path_segment(1)?;
+
+                let mut instance_url = public_url;
+                instance_url.set_path("/");
+
+                Ok(Self::Peertube {
+                    instance_url,
+                    streaming_key,
+                    video_id,
+                })
+            }
+        }
+    }
+}
+
+fn platform_endpoint(endpoint: &str) -> Result<Url, StreamingTargetError> {
+    Url::parse(endpoint)
+        .map_err(|_| StreamingTargetError::new("streaming_endpoint", "invalid platform endpoint"))
+}
+
+fn self_hosted_endpoint(url: &Url, field: &'static str) -> Result<Url, StreamingTargetError> {
+    let host = url
+        .host_str()
+        .ok_or(StreamingTargetError::new(field, "the url has no host"))?;
+
+    Url::parse(&format!("rtmp://{host}:{SELF_HOSTED_RTMP_PORT}/live"))
+        .map_err(|_| StreamingTargetError::new(field, "unable to build the streaming endpoint"))
+}
+
+fn validate_instance_url(url: &Url, field: &'static str) -> Result<(), StreamingTargetError> {
+    if !matches!(url.scheme(), "http" | "https") || url.host_str().is_none() {
+        return Err(StreamingTargetError::new(
+            field,
+            "expected a http or https url with a host",
+        ));
+    }
+
+    Ok(())
+}
+
+fn is_youtube_key(key: &str) -> bool {
+    let groups: Vec<&str> = key.split('-').collect();
+
+    groups.len() == 5
+        && groups
+            .iter()
+            .all(|group| group.len() == 4 && group.chars().all(|c| c.is_ascii_alphanumeric()))
+}
+
+fn is_youtube_channel_id(channel_id: &str) -> bool {
+    channel_id.len() == 24
+        && channel_id.starts_with("UC")
+        && channel_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+fn is_twitch_key(key: &str) -> bool {
+    key.strip_prefix("live_").is_some_and(|rest| {
+        !rest.is_empty() && rest.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    })
+}
+
+fn is_twitch_channel(channel: &str) -> bool {
+    (4..=25).contains(&channel.len())
+        && channel
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_')
 }
 
 /// A streaming target
@@ -53,3 +379,101 @@ pub struct RoomStreamingTarget {
     #[cfg_attr(feature = "serde", serde(flatten))]
     pub streaming_target: StreamingTarget,
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn key(key: &str) -> StreamingKey {
+        StreamingKey::from(key.to_owned())
+    }
+
+    #[test]
+    fn youtube() {
+        let kind = StreamingTargetKind::Youtube {
+            streaming_key: key("abcd-efgh-ijkl-mnop-qrst"),
+            channel_id: "UCabcdefghijklmnopqrstuv".to_owned(),
+        };
+
+        assert_eq!(kind.validate(), Ok(()));
+        assert_eq!(
+            kind.streaming_endpoint().unwrap().as_str(),
+            "rtmp://a.rtmp.youtube.com/live2"
+        );
+        let public_url = kind.public_url().unwrap();
+        assert_eq!(
+            public_url.as_str(),
+            "https://www.youtube.com/channel/UCabcdefghijklmnopqrstuv/live"
+        );
+
+        let parsed = StreamingTargetKind::from_parts(
+            StreamingKind::Youtube,
+            YOUTUBE_STREAMING_ENDPOINT,
+            key("abcd-efgh-ijkl-mnop-qrst"),
+            public_url.as_str(),
+        )
+        .unwrap();
+        assert_eq!(parsed, kind);
+    }
+
+    #[test]
+    fn youtube_invalid_key() {
+        let kind = StreamingTargetKind::Youtube {
+            streaming_key: key("abcd-efgh-ijkl"),
+            channel_id: "UCabcdefghijklmnopqrstuv".to_owned(),
+        };
+
+        assert_eq!(kind.validate().unwrap_err().field, "streaming_key");
+    }
+
+    #[test]
+    fn twitch() {
+        let kind = StreamingTargetKind::Twitch {
+            streaming_key: key("live_123456789_abcdefghijklmnop"),
+            channel: "opentalk".to_owned(),
+        };
+
+        assert_eq!(kind.validate(), Ok(()));
+        assert_eq!(
+            kind.public_url().unwrap().as_str(),
+            "https://www.twitch.tv/opentalk"
+        );
+
+        let invalid = StreamingTargetKind::Twitch {
+            streaming_key: key("123456789"),
+            channel: "opentalk".to_owned(),
+        };
+        assert_eq!(invalid.validate().unwrap_err().field, "streaming_key");
+    }
+
+    #[test]
+    fn peertube() {
+        let kind = StreamingTargetKind::Peertube {
+            instance_url: Url::parse("https://peertube.example.org/").unwrap(),
+            streaming_key: key("6d8a1a0c-6bb6-4e0c-8f52-6c5d5e2c4f1a"),
+            video_id: "9c9de5e8-0a1b-484a-b099-e80766180a6d".to_owned(),
+        };
+
+        assert_eq!(kind.validate(), Ok(()));
+        assert_eq!(
+            kind.streaming_endpoint().unwrap().as_str(),
+            "rtmp://peertube.example.org:1935/live"
+        );
+
+        let public_url = kind.public_url().unwrap();
+        assert_eq!(
+            public_url.as_str(),
+            "https://peertube.example.org/w/9c9de5e8-0a1b-484a-b099-e80766180a6d"
+        );
+
+        let parsed = StreamingTargetKind::from_parts(
+            StreamingKind::Peertube,
+            "rtmp://peertube.example.org:1935/live",
+            key("6d8a1a0c-6bb6-4e0c-8f52-6c5d5e2c4f1a"),
+            public_url.as_str(),
+        )
+        .unwrap();
+        assert_eq!(parsed, kind);
+    }
+}
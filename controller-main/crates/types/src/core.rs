@@ -12,6 +12,7 @@ mod bearer_token;
 mod breakout_room_id;
 mod call_in;
 mod date_time_tz;
+mod dial_out_id;
 mod event_id;
 mod event_invite_status;
 mod group_id;
@@ -32,6 +33,7 @@ mod tenant_id;
 mod ticket_token;
 mod time_zone;
 mod timestamp;
+mod upload_id;
 mod user_id;
 
 pub use asset_id::AssetId;
@@ -39,6 +41,7 @@ pub use bearer_token::BearerToken;
 pub use breakout_room_id::BreakoutRoomId;
 pub use call_in::{CallInId, CallInPassword};
 pub use date_time_tz::DateTimeTz;
+pub use dial_out_id::DialOutId;
 pub use event_id::EventId;
 pub use event_invite_status::{EventInviteStatus, EventInviteStatusType};
 pub use group_id::GroupId;
@@ -59,4 +62,5 @@ pub use tenant_id::TenantId;
 pub use ticket_token::TicketToken;
 pub use time_zone::TimeZone;
 pub use timestamp::Timestamp;
+pub use upload_id::UploadId;
 pub use user_id::UserId;
@@ -17,6 +17,10 @@ sql_enum!(
     StreamingKindType,
     {
         Custom = b"custom",
+        Youtube = b"youtube",
+        Twitch = b"twitch",
+        Owncast = b"owncast",
+        Peertube = b"peertube",
     }
 );
 
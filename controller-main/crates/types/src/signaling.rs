@@ -10,6 +10,7 @@ pub mod chat;
 pub mod common;
 pub mod control;
 pub mod core;
+pub mod dial_out;
 pub mod echo;
 pub mod integration;
 pub mod media;
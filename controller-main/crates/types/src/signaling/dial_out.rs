@@ -0,0 +1,15 @@
+// SPDX-FileCopyrightText: OpenTalk GmbH <mail@opentalk.eu>
+//
+// SPDX-License-Identifier: EUPL-1.2
+
+//! Signaling messages for the `dial_out` namespace
+
+mod call_status;
+
+pub mod command;
+pub mod event;
+
+pub use call_status::CallStatus;
+
+/// The namespace string for the signaling module
+pub const NAMESPACE: &str = "dial_out";
@@ -0,0 +1,40 @@
+// SPDX-FileCopyrightText: OpenTalk GmbH <mail@opentalk.eu>
+//
+// SPDX-License-Identifier: EUPL-1.2
+
+#[allow(unused_imports)]
+use crate::imports::*;
+
+/// Progress of an outbound call placed by the SIP gateway
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum CallStatus {
+    /// The call has been requested and waits for the SIP gateway to place it
+    Dialing,
+    /// The callee's phone is ringing
+    Ringing,
+    /// The callee answered, the SIP gateway joins the room on their behalf
+    Answered,
+    /// The callee is busy
+    Busy,
+    /// The callee declined the call
+    Declined,
+    /// The call could not be established
+    Failed,
+    /// The established call has been hung up
+    Ended,
+}
+
+impl CallStatus {
+    /// Returns true if the call is over and no further updates follow
+    pub const fn is_final(&self) -> bool {
+        matches!(
+            self,
+            Self::Busy | Self::Declined | Self::Failed | Self::Ended
+        )
+    }
+}
@@ -0,0 +1,60 @@
+// SPDX-FileCopyrightText: OpenTalk GmbH <mail@opentalk.eu>
+//
+// SPDX-License-Identifier: EUPL-1.2
+
+//! Signaling commands for the `dial_out` namespace
+
+#[allow(unused_imports)]
+use crate::imports::*;
+
+/// Commands for the `dial_out` namespace
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(tag = "action", rename_all = "snake_case")
+)]
+pub enum DialOutCommand {
+    /// Call a phone number and let the callee join the room
+    DialOut(DialOut),
+}
+
+/// Data for the `dial_out` command
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DialOut {
+    /// The phone number to call
+    pub number: String,
+    /// The name of the callee in the room, the phone number is shown if missing
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub display_name: Option<String>,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    #[test]
+    fn dial_out() {
+        let json = json!({
+            "action": "dial_out",
+            "number": "+49 30 123456",
+            "display_name": "Jane Doe"
+        });
+
+        let msg: DialOutCommand = serde_json::from_value(json).unwrap();
+
+        assert_eq!(
+            msg,
+            DialOutCommand::DialOut(DialOut {
+                number: "+49 30 123456".into(),
+                display_name: Some("Jane Doe".into()),
+            })
+        );
+    }
+}
@@ -0,0 +1,110 @@
+// SPDX-FileCopyrightText: OpenTalk GmbH <mail@opentalk.eu>
+//
+// SPDX-License-Identifier: EUPL-1.2
+
+//! Signaling events for the `dial_out` namespace
+
+#[allow(unused_imports)]
+use crate::imports::*;
+
+use crate::core::DialOutId;
+
+use super::CallStatus;
+
+/// Events sent out by the `dial_out` module
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(tag = "message", rename_all = "snake_case")
+)]
+pub enum DialOutEvent {
+    /// The progress of a call requested by the participant changed
+    CallUpdated(CallUpdated),
+
+    /// An error happened when executing a `dial_out` command
+    Error(Error),
+}
+
+/// Data for the `call_updated` dial-out event
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CallUpdated {
+    /// The id of the call
+    pub call_id: DialOutId,
+    /// The called phone number in E.164 format
+    pub number: String,
+    /// The new status of the call
+    pub status: CallStatus,
+}
+
+impl From<CallUpdated> for DialOutEvent {
+    fn from(value: CallUpdated) -> Self {
+        Self::CallUpdated(value)
+    }
+}
+
+/// Error from the `dial_out` module namespace
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(tag = "error", rename_all = "snake_case")
+)]
+pub enum Error {
+    /// The participant has insufficient permissions to perform a command
+    InsufficientPermissions,
+
+    /// The tariff of the room does not include dialing out
+    DisabledByTariff,
+
+    /// The phone number cannot be parsed
+    InvalidNumber,
+
+    /// The display name is empty or too long
+    InvalidDisplayName,
+}
+
+impl From<Error> for DialOutEvent {
+    fn from(value: Error) -> Self {
+        Self::Error(value)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    #[test]
+    fn call_updated() {
+        let expected = json!({
+            "message": "call_updated",
+            "call_id": "00000000-0000-0000-0000-000000000001",
+            "number": "+4930123456",
+            "status": "ringing"
+        });
+
+        let produced = serde_json::to_value(DialOutEvent::CallUpdated(CallUpdated {
+            call_id: DialOutId::from_u128(1),
+            number: "+4930123456".into(),
+            status: CallStatus::Ringing,
+        }))
+        .unwrap();
+
+        assert_eq!(expected, produced);
+    }
+
+    #[test]
+    fn error() {
+        let expected = json!({
+            "message": "error",
+            "error": "disabled_by_tariff"
+        });
+
+        let produced = serde_json::to_value(DialOutEvent::Error(Error::DisabledByTariff)).unwrap();
+
+        assert_eq!(expected, produced);
+    }
+}
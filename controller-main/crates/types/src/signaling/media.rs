@@ -4,6 +4,7 @@
 
 //! Signaling messages for the `media` namespace
 
+mod external_stream;
 mod media_session_state;
 mod media_session_type;
 mod trickle_candidate;
@@ -15,6 +16,7 @@ pub mod state;
 
 use std::collections::HashMap;
 
+pub use external_stream::ExternalStream;
 pub use media_session_state::MediaSessionState;
 pub use media_session_type::{MediaSessionType, MediaSessionTypeParseError};
 pub use trickle_candidate::TrickleCandidate;
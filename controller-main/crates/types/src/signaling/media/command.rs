@@ -59,6 +59,16 @@ pub enum MediaCommand {
 
     /// SDP request to configure subscription
     Configure(TargetConfigure),
+
+    /// Add an external RTP stream to the room as a virtual participant
+    ///
+    /// May only be processed if the issuer is a moderator
+    StartExternalStream(ExternalStreamSource),
+
+    /// Remove an external stream from the room
+    ///
+    /// May only be processed if the issuer is a moderator
+    StopExternalStream(ExternalStreamTarget),
 }
 
 /// Information about a media session
@@ -182,6 +192,50 @@ pub struct SubscriberConfiguration {
     pub substream: Option<u8>,
 }
 
+/// Description of an external media source fed into the room via RTP
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ExternalStreamSource {
+    /// The name that is displayed for the stream
+    pub display_name: String,
+
+    /// The audio RTP stream, if any
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub audio: Option<RtpSource>,
+
+    /// The video RTP stream, if any
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub video: Option<RtpSource>,
+}
+
+/// A single RTP stream received by the SFU
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct RtpSource {
+    /// The port the SFU listens on for the RTP packets, `0` lets the SFU choose a port
+    pub port: u16,
+
+    /// The RTP payload type of the stream
+    pub payload_type: u8,
+
+    /// The codec of the stream, e.g. `"opus"` or `"vp8"`
+    pub codec: String,
+}
+
+/// Specification of an external stream for media commands
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ExternalStreamTarget {
+    /// The id of the virtual participant representing the stream
+    pub id: ParticipantId,
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -481,4 +535,67 @@ mod test {
             panic!()
         }
     }
+
+    #[test]
+    fn start_external_stream() {
+        let json = json!({
+            "action": "start_external_stream",
+            "display_name": "Keynote",
+            "audio": {
+                "port": 5002,
+                "payload_type": 111,
+                "codec": "opus",
+            },
+            "video": {
+                "port": 5004,
+                "payload_type": 96,
+                "codec": "vp8",
+            },
+        });
+
+        let msg: MediaCommand = serde_json::from_value(json).unwrap();
+
+        if let MediaCommand::StartExternalStream(ExternalStreamSource {
+            display_name,
+            audio,
+            video,
+        }) = msg
+        {
+            assert_eq!(display_name, "Keynote");
+            assert_eq!(
+                audio,
+                Some(RtpSource {
+                    port: 5002,
+                    payload_type: 111,
+                    codec: "opus".into(),
+                })
+            );
+            assert_eq!(
+                video,
+                Some(RtpSource {
+                    port: 5004,
+                    payload_type: 96,
+                    codec: "vp8".into(),
+                })
+            );
+        } else {
+            panic!()
+        }
+    }
+
+    #[test]
+    fn stop_external_stream() {
+        let json = json!({
+            "action": "stop_external_stream",
+            "id": "00000000-0000-0000-0000-000000000000",
+        });
+
+        let msg: MediaCommand = serde_json::from_value(json).unwrap();
+
+        if let MediaCommand::StopExternalStream(ExternalStreamTarget { id }) = msg {
+            assert_eq!(id, ParticipantId::nil());
+        } else {
+            panic!()
+        }
+    }
 }
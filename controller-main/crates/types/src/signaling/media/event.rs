@@ -9,7 +9,10 @@ use crate::core::ParticipantId;
 #[allow(unused_imports)]
 use crate::imports::*;
 
-use super::{command::Target, MediaSessionType, TrickleCandidate};
+use super::{
+    command::{ExternalStreamTarget, Target},
+    ExternalStream, MediaSessionType, TrickleCandidate,
+};
 
 /// The direction of a media link
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -72,6 +75,12 @@ pub enum MediaEvent {
     /// Presenter role has been revoked from the participant
     PresenterRevoked,
 
+    /// An external stream has been added to the room
+    ExternalStreamStarted(ExternalStream),
+
+    /// An external stream has been removed from the room
+    ExternalStreamStopped(ExternalStreamTarget),
+
     /// Contains a error about what request failed. See [`Error`]
     Error(Error),
 }
@@ -205,6 +214,9 @@ pub enum Error {
 
     /// Insufficient permissions to perform a command
     PermissionDenied,
+
+    /// Frontend sent an invalid external stream source
+    InvalidExternalStream,
 }
 
 impl From<Error> for MediaEvent {
@@ -456,4 +468,26 @@ mod test {
             })
         );
     }
+
+    #[test]
+    fn external_stream_started() {
+        let started = MediaEvent::ExternalStreamStarted(ExternalStream {
+            id: ParticipantId::nil(),
+            display_name: "Keynote".into(),
+            host: Some("192.0.2.10".into()),
+            audio_port: Some(5002),
+            video_port: None,
+        });
+
+        assert_eq!(
+            serde_json::to_value(started).unwrap(),
+            json!({
+                "message": "external_stream_started",
+                "id": "00000000-0000-0000-0000-000000000000",
+                "display_name": "Keynote",
+                "host": "192.0.2.10",
+                "audio_port": 5002
+            })
+        );
+    }
 }
@@ -0,0 +1,51 @@
+// SPDX-FileCopyrightText: OpenTalk GmbH <mail@opentalk.eu>
+//
+// SPDX-License-Identifier: EUPL-1.2
+
+use crate::core::ParticipantId;
+
+#[allow(unused_imports)]
+use crate::imports::*;
+
+/// An external media source that has been added to the room as a virtual participant
+///
+/// The media of an external stream is subscribed to like the `video` media session of a
+/// regular participant, using the `id` as the target.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "redis",
+    derive(ToRedisArgs, FromRedisValue),
+    to_redis_args(serde),
+    from_redis_value(serde)
+)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ExternalStream {
+    /// The id of the virtual participant representing the stream
+    pub id: ParticipantId,
+
+    /// The name that is displayed for the stream
+    pub display_name: String,
+
+    /// The host the SFU receives the RTP packets of the stream on
+    ///
+    /// Only known if configured for the SFU instance that hosts the stream
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub host: Option<String>,
+
+    /// The port the SFU receives the audio RTP packets on, if the stream has audio
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub audio_port: Option<u16>,
+
+    /// The port the SFU receives the video RTP packets on, if the stream has video
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub video_port: Option<u16>,
+}
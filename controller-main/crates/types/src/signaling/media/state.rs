@@ -7,12 +7,21 @@
 #[allow(unused_imports)]
 use crate::imports::*;
 
+use super::ExternalStream;
+
 /// The state of the `media` module.
 #[derive(Clone, Debug, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct MediaState {
     /// Whether the participant has permission to share the screen
     pub is_presenter: bool,
+
+    /// The external streams that are currently available in the room
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub external_streams: Vec<ExternalStream>,
 }
 
 #[cfg(feature = "serde")]
@@ -5,7 +5,9 @@
 //! Signaling messages for the `recording` namespace
 
 mod recording_id;
+mod recording_scope;
 mod recording_status;
+mod stream_status;
 
 pub mod command;
 pub mod event;
@@ -13,7 +15,9 @@ pub mod peer_state;
 pub mod state;
 
 pub use recording_id::RecordingId;
+pub use recording_scope::RecordingScope;
 pub use recording_status::RecordingStatus;
+pub use stream_status::StreamStatus;
 
 /// The namespace string for the signaling module
 pub const NAMESPACE: &str = "recording";
@@ -7,7 +7,9 @@
 #[allow(unused_imports)]
 use crate::imports::*;
 
-use super::RecordingId;
+use crate::core::StreamingTargetId;
+
+use super::{RecordingId, RecordingScope};
 
 /// Commands for the `recording` namespace
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -18,13 +20,40 @@ use super::RecordingId;
 )]
 pub enum RecordingCommand {
     /// Start a recording
-    Start,
+    Start(Start),
 
     /// Stop a recording
     Stop(Stop),
 
+    /// Pause a running recording
+    Pause(Pause),
+
+    /// Resume a paused recording
+    Resume(Resume),
+
     /// Set the consent status for a specific recording
     SetConsent(SetConsent),
+
+    /// Start or continue livestreams to the given streaming targets of the room
+    StartStream(StartStream),
+
+    /// Pause the livestreams to the given streaming targets
+    PauseStream(PauseStream),
+
+    /// Stop the livestreams to the given streaming targets
+    StopStream(StopStream),
+
+    /// Change the layout of the picture composed by the recorder
+    SetLayout(SetLayout),
+}
+
+/// Data for the `start` recording command
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Start {
+    /// Participants whose media is recorded, all participants if missing
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub scope: RecordingScope,
 }
 
 /// Data for the `stop` recording command
@@ -35,6 +64,22 @@ pub struct Stop {
     pub recording_id: RecordingId,
 }
 
+/// Data for the `pause` recording command
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Pause {
+    /// Id of the recording to be paused
+    pub recording_id: RecordingId,
+}
+
+/// Data for the `resume` recording command
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Resume {
+    /// Id of the recording to be resumed
+    pub recording_id: RecordingId,
+}
+
 /// Data for the `set_consent` recording command
 #[derive(Clone, Debug, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -42,3 +87,122 @@ pub struct SetConsent {
     /// Flag indicating whether the participant consents to being recorded
     pub consent: bool,
 }
+
+/// Data for the `start_stream` recording command
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct StartStream {
+    /// Ids of the streaming targets of the room to stream to
+    pub target_ids: Vec<StreamingTargetId>,
+}
+
+/// Data for the `pause_stream` recording command
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PauseStream {
+    /// Ids of the streaming targets to pause
+    pub target_ids: Vec<StreamingTargetId>,
+}
+
+/// Data for the `stop_stream` recording command
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct StopStream {
+    /// Ids of the streaming targets to stop
+    pub target_ids: Vec<StreamingTargetId>,
+}
+
+/// Data for the `set_layout` recording command
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SetLayout {
+    /// The layout description, which is passed to the recorder as is
+    pub layout: serde_json::Value,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::core::ParticipantId;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    #[test]
+    fn start() {
+        let json = json!({
+            "action": "start"
+        });
+
+        let command: RecordingCommand = serde_json::from_value(json).unwrap();
+
+        assert_eq!(command, RecordingCommand::Start(Start::default()));
+    }
+
+    #[test]
+    fn start_participants() {
+        let json = json!({
+            "action": "start",
+            "scope": {
+                "kind": "participants",
+                "participant_ids": ["00000000-0000-0000-0000-000000000001"]
+            }
+        });
+
+        let command: RecordingCommand = serde_json::from_value(json).unwrap();
+
+        assert_eq!(
+            command,
+            RecordingCommand::Start(Start {
+                scope: RecordingScope::Participants {
+                    participant_ids: vec![ParticipantId::from_u128(1)]
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn start_stream() {
+        let json = json!({
+            "action": "start_stream",
+            "target_ids": ["00000000-0000-0000-0000-000000000001"]
+        });
+
+        let command: RecordingCommand = serde_json::from_value(json).unwrap();
+
+        assert_eq!(
+            command,
+            RecordingCommand::StartStream(StartStream {
+                target_ids: vec![StreamingTargetId::from_u128(1)]
+            })
+        );
+    }
+
+    #[test]
+    fn set_layout() {
+        let json = json!({
+            "action": "set_layout",
+            "layout": { "type": "grid" }
+        });
+
+        let command: RecordingCommand = serde_json::from_value(json).unwrap();
+
+        assert_eq!(
+            command,
+            RecordingCommand::SetLayout(SetLayout {
+                layout: json!({ "type": "grid" })
+            })
+        );
+    }
+
+    #[test]
+    fn pause() {
+        let json = json!({
+            "action": "pause",
+            "recording_id": "00000000-0000-0000-0000-000000000000"
+        });
+
+        let command: RecordingCommand = serde_json::from_value(json).unwrap();
+
+        assert!(matches!(command, RecordingCommand::Pause(_)));
+    }
+}
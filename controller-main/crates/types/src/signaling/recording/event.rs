@@ -7,7 +7,11 @@
 #[allow(unused_imports)]
 use crate::imports::*;
 
-use super::RecordingId;
+use url::Url;
+
+use crate::core::{StreamingKey, StreamingKind, StreamingTargetId};
+
+use super::{RecordingId, RecordingScope, StreamStatus};
 
 /// Events sent out by the `recording` module
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -23,6 +27,25 @@ pub enum RecordingEvent {
     /// A recording has been stopped
     Stopped(Stopped),
 
+    /// A recording has been paused
+    Paused(Paused),
+
+    /// A paused recording has been resumed
+    Resumed(Resumed),
+
+    /// The status of a livestream to a streaming target changed
+    StreamUpdated(StreamUpdated),
+
+    /// Instructs the recorder to start streaming to the given targets
+    ///
+    /// Only sent to the recorder participant.
+    StartStreams(StartStreams),
+
+    /// Instructs the recorder to change the layout of the composed picture
+    ///
+    /// Only sent to the recorder participant.
+    SetLayout(SetLayout),
+
     /// An error happened when executing a `recording` command
     Error(Error),
 }
@@ -33,6 +56,12 @@ pub enum RecordingEvent {
 pub struct Started {
     /// The id of the recording that was started
     pub recording_id: RecordingId,
+    /// Participants whose media is recorded, missing if all participants are recorded
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "RecordingScope::is_all")
+    )]
+    pub scope: RecordingScope,
 }
 
 impl From<Started> for RecordingEvent {
@@ -55,6 +84,96 @@ impl From<Stopped> for RecordingEvent {
     }
 }
 
+/// Data for the `paused` recording event
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Paused {
+    /// The id of the recording that was paused
+    pub recording_id: RecordingId,
+}
+
+impl From<Paused> for RecordingEvent {
+    fn from(value: Paused) -> Self {
+        Self::Paused(value)
+    }
+}
+
+/// Data for the `resumed` recording event
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Resumed {
+    /// The id of the recording that was resumed
+    pub recording_id: RecordingId,
+}
+
+impl From<Resumed> for RecordingEvent {
+    fn from(value: Resumed) -> Self {
+        Self::Resumed(value)
+    }
+}
+
+/// Data for the `stream_updated` recording event
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct StreamUpdated {
+    /// The id of the streaming target
+    pub target_id: StreamingTargetId,
+    /// The new status of the stream
+    pub status: StreamStatus,
+}
+
+impl From<StreamUpdated> for RecordingEvent {
+    fn from(value: StreamUpdated) -> Self {
+        Self::StreamUpdated(value)
+    }
+}
+
+/// Data for the `start_streams` recording event
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct StartStreams {
+    /// The streaming targets including their endpoints and keys
+    pub targets: Vec<RecorderStreamingTarget>,
+}
+
+/// A streaming target with the endpoint resolved from its kind, as needed by the recorder
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct RecorderStreamingTarget {
+    /// The streaming target id
+    pub id: StreamingTargetId,
+    /// The name of the streaming target
+    pub name: String,
+    /// The platform of the streaming target, used to pick the encoder settings
+    pub kind: StreamingKind,
+    /// The ingest endpoint
+    pub streaming_endpoint: Url,
+    /// The streaming key
+    pub streaming_key: StreamingKey,
+    /// The url from which the stream can be watched
+    pub public_url: Url,
+}
+
+impl From<StartStreams> for RecordingEvent {
+    fn from(value: StartStreams) -> Self {
+        Self::StartStreams(value)
+    }
+}
+
+/// Data for the `set_layout` recording event
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SetLayout {
+    /// The layout description as sent by the moderator
+    pub layout: serde_json::Value,
+}
+
+impl From<SetLayout> for RecordingEvent {
+    fn from(value: SetLayout) -> Self {
+        Self::SetLayout(value)
+    }
+}
+
 /// Error from the `recording` module namespace
 #[derive(Clone, Debug, PartialEq, Eq)]
 #[cfg_attr(
@@ -71,6 +190,24 @@ pub enum Error {
 
     /// Invalid recording id used
     InvalidRecordingId,
+
+    /// Attempted to pause a recording which is already paused
+    AlreadyPaused,
+
+    /// Attempted to resume a recording which is not paused
+    NotPaused,
+
+    /// A streaming target id does not belong to the room or has no stream in the required state
+    InvalidStreamingTargetId,
+
+    /// The command requires a recorder, but neither a recording nor a livestream is running
+    NoRecorder,
+
+    /// No recorder joined the room within the configured timeout after it has been requested
+    NoRecorderAvailable,
+
+    /// The recording scope selects no participants
+    InvalidScope,
 }
 
 impl From<Error> for RecordingEvent {
@@ -78,3 +215,73 @@ impl From<Error> for RecordingEvent {
         Self::Error(value)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::core::ParticipantId;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    #[test]
+    fn stream_updated() {
+        let expected = json!({
+            "message": "stream_updated",
+            "target_id": "00000000-0000-0000-0000-000000000001",
+            "status": "paused"
+        });
+
+        let produced = serde_json::to_value(RecordingEvent::StreamUpdated(StreamUpdated {
+            target_id: StreamingTargetId::from_u128(1),
+            status: StreamStatus::Paused,
+        }))
+        .unwrap();
+
+        assert_eq!(expected, produced);
+    }
+
+    #[test]
+    fn started_presenter_screen() {
+        let expected = json!({
+            "message": "started",
+            "recording_id": "00000000-0000-0000-0000-000000000000",
+            "scope": {
+                "kind": "presenter_screen"
+            }
+        });
+
+        let produced = serde_json::to_value(RecordingEvent::Started(Started {
+            recording_id: RecordingId::from(ParticipantId::nil()),
+            scope: RecordingScope::PresenterScreen,
+        }))
+        .unwrap();
+
+        assert_eq!(expected, produced);
+    }
+
+    #[test]
+    fn invalid_streaming_target_id() {
+        let expected = json!({
+            "message": "error",
+            "error": "invalid_streaming_target_id"
+        });
+
+        let produced =
+            serde_json::to_value(RecordingEvent::Error(Error::InvalidStreamingTargetId)).unwrap();
+
+        assert_eq!(expected, produced);
+    }
+
+    #[test]
+    fn no_recorder_available() {
+        let expected = json!({
+            "message": "error",
+            "error": "no_recorder_available"
+        });
+
+        let produced =
+            serde_json::to_value(RecordingEvent::Error(Error::NoRecorderAvailable)).unwrap();
+
+        assert_eq!(expected, produced);
+    }
+}
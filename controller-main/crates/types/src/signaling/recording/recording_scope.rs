@@ -0,0 +1,41 @@
+// SPDX-FileCopyrightText: OpenTalk GmbH <mail@opentalk.eu>
+//
+// SPDX-License-Identifier: EUPL-1.2
+
+#[allow(unused_imports)]
+use crate::imports::*;
+
+use crate::core::ParticipantId;
+
+/// Participants whose media is part of a recording
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(tag = "kind", rename_all = "snake_case")
+)]
+#[cfg_attr(
+    feature = "redis",
+    derive(ToRedisArgs, FromRedisValue),
+    to_redis_args(serde),
+    from_redis_value(serde)
+)]
+pub enum RecordingScope {
+    /// The cameras, screen shares and audio of all participants
+    #[default]
+    All,
+    /// The screen shares and the audio of the participants who share their screen
+    PresenterScreen,
+    /// The cameras, screen shares and audio of the given participants
+    Participants {
+        /// Ids of the recorded participants
+        participant_ids: Vec<ParticipantId>,
+    },
+}
+
+impl RecordingScope {
+    /// Returns `true` if all participants are recorded
+    pub fn is_all(&self) -> bool {
+        matches!(self, Self::All)
+    }
+}
@@ -25,4 +25,6 @@ pub enum RecordingStatus {
     Initializing,
     /// A recorder is connected and capturing the conference
     Recording(RecordingId),
+    /// The recording is paused, the recorder stays connected but does not capture the conference
+    Paused(RecordingId),
 }
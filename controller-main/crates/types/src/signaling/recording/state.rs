@@ -4,20 +4,64 @@
 
 //! Frontend data for `recording` namespace
 
+use std::collections::BTreeMap;
+
 #[allow(unused_imports)]
 use crate::imports::*;
 
-use super::RecordingStatus;
+use crate::core::StreamingTargetId;
+
+use super::{RecordingStatus, StreamStatus};
 
 /// The state of the `recording` module.
 ///
 /// This struct is sent to the participant in the `join_success` message
 /// when they join successfully to the meeting.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-pub struct RecordingState(pub Option<RecordingStatus>);
+pub struct RecordingState {
+    /// Status of the file recording, `None` if the room is not recorded
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub recording: Option<RecordingStatus>,
+
+    /// Status of the livestreams to the streaming targets of the room
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "BTreeMap::is_empty")
+    )]
+    pub streams: BTreeMap<StreamingTargetId, StreamStatus>,
+}
 
 #[cfg(feature = "serde")]
 impl SignalingModuleFrontendData for RecordingState {
     const NAMESPACE: Option<&'static str> = Some(super::NAMESPACE);
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{core::ParticipantId, signaling::recording::RecordingId};
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    #[test]
+    fn recording_with_streams() {
+        let expected = json!({
+            "state": "recording",
+            "recording_id": "00000000-0000-0000-0000-000000000000",
+            "streams": {
+                "00000000-0000-0000-0000-000000000001": "active"
+            }
+        });
+
+        let produced = serde_json::to_value(RecordingState {
+            recording: Some(RecordingStatus::Recording(RecordingId::from(
+                ParticipantId::nil(),
+            ))),
+            streams: BTreeMap::from([(StreamingTargetId::from_u128(1), StreamStatus::Active)]),
+        })
+        .unwrap();
+
+        assert_eq!(expected, produced);
+    }
+}
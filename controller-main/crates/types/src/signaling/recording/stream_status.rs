@@ -0,0 +1,30 @@
+// SPDX-FileCopyrightText: OpenTalk GmbH <mail@opentalk.eu>
+//
+// SPDX-License-Identifier: EUPL-1.2
+
+#[allow(unused_imports)]
+use crate::imports::*;
+
+/// Status of a livestream to a streaming target
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(rename_all = "snake_case")
+)]
+#[cfg_attr(
+    feature = "redis",
+    derive(ToRedisArgs, FromRedisValue),
+    to_redis_args(serde),
+    from_redis_value(serde)
+)]
+pub enum StreamStatus {
+    /// Waiting for a recorder to connect and start the stream
+    Starting,
+    /// The recorder is streaming the conference to the target
+    Active,
+    /// The stream is paused, the target receives no media until the stream is started again
+    Paused,
+    /// The stream has been stopped
+    Inactive,
+}
@@ -0,0 +1,88 @@
+// SPDX-FileCopyrightText: OpenTalk GmbH <mail@opentalk.eu>
+//
+// SPDX-License-Identifier: EUPL-1.2
+
+//! Outbound calls requested by moderators via RabbitMQ
+
+use crate::settings::RabbitMqSettings;
+use anyhow::{Context, Result};
+use futures::StreamExt;
+use lapin::options::{BasicAckOptions, BasicConsumeOptions, QueueDeclareOptions};
+use lapin::types::FieldTable;
+use lapin::Consumer;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Request of the controller to call a phone number
+#[derive(Debug, Deserialize)]
+pub struct DialOutRequest {
+    pub call_id: Uuid,
+    /// The phone number in E.164 format
+    pub number: String,
+    /// The name the callee joins the room with
+    pub display_name: String,
+}
+
+/// Progress of an outbound call which is reported to the controller
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CallStatus {
+    Ringing,
+    Answered,
+    Busy,
+    Declined,
+    Failed,
+    Ended,
+}
+
+/// Connect to RabbitMQ and consume the dial-out queue
+pub async fn connect(settings: &RabbitMqSettings) -> Result<Consumer> {
+    let connection = lapin::Connection::connect(
+        &settings.uri,
+        lapin::ConnectionProperties::default()
+            .with_executor(tokio_executor_trait::Tokio::current())
+            .with_reactor(tokio_reactor_trait::Tokio),
+    )
+    .await
+    .context("Failed to connect to RabbitMQ")?;
+
+    let channel = connection.create_channel().await?;
+
+    let queue = channel
+        .queue_declare(
+            &settings.dial_out_queue,
+            QueueDeclareOptions::default(),
+            FieldTable::default(),
+        )
+        .await?;
+
+    channel
+        .basic_consume(
+            queue.name().as_str(),
+            "",
+            BasicConsumeOptions::default(),
+            FieldTable::default(),
+        )
+        .await
+        .context("Failed to create consumer for the dial-out queue")
+}
+
+/// Receive the next request from the dial-out queue
+///
+/// Returns `None` when the consumer has been closed.
+pub async fn receive(consumer: &mut Consumer) -> Option<Result<DialOutRequest>> {
+    let delivery = match consumer.next().await? {
+        Ok(delivery) => delivery,
+        Err(e) => return Some(Err(e.into())),
+    };
+
+    // Calls are not retried, another attempt could ring the callee a second time
+    if let Err(e) = delivery.ack(BasicAckOptions::default()).await {
+        return Some(Err(anyhow::Error::from(e).context("failed to ACK")));
+    }
+
+    Some(
+        serde_json::from_slice(&delivery.data)
+            .with_context(|| format!("Failed to parse dial-out request {:?}", delivery.data)),
+    )
+}
@@ -4,6 +4,7 @@
 
 //! HTTP calls made by this library (except for websockets)
 
+use crate::dial_out::CallStatus;
 use crate::settings::{AuthSettings, ControllerSettings};
 use crate::websocket::Ticket;
 use anyhow::{bail, Result};
@@ -15,6 +16,7 @@ use serde::{Deserialize, Serialize};
 use std::future::Future;
 use std::pin::Pin;
 use tokio::sync::RwLock;
+use uuid::Uuid;
 
 pub struct HttpClient {
     client: reqwest::Client,
@@ -81,11 +83,84 @@ impl HttpClient {
         id: &str,
         pin: &str,
     ) -> Result<Ticket> {
+        let response = self
+            .post(settings, "call_in/start", &StartRequest { id, pin })
+            .await?;
+
+        match response.status() {
+            StatusCode::OK => {
+                let response = response.json::<StartResponse>().await?;
+
+                Ok(Ticket(response.ticket))
+            }
+            StatusCode::BAD_REQUEST => {
+                let ApiError { code } = response.json::<ApiError>().await?;
+
+                if code == "invalid_credentials" {
+                    bail!(InvalidCredentials);
+                }
+
+                bail!("unexpected error code {code:?}")
+            }
+            code => bail!("unexpected status code {code:?}"),
+        }
+    }
+
+    /// Request a signaling ticket for the answered outbound call `call_id`
+    pub async fn dial_out_start(
+        &self,
+        settings: &ControllerSettings,
+        call_id: Uuid,
+    ) -> Result<Ticket> {
+        let response = self
+            .post(settings, "dial_out/start", &DialOutStartRequest { call_id })
+            .await?;
+
+        match response.status() {
+            StatusCode::OK => {
+                let response = response.json::<StartResponse>().await?;
+
+                Ok(Ticket(response.ticket))
+            }
+            code => bail!("unexpected status code {code:?}"),
+        }
+    }
+
+    /// Report the progress of the outbound call `call_id` to the moderator who requested it
+    pub async fn dial_out_progress(
+        &self,
+        settings: &ControllerSettings,
+        call_id: Uuid,
+        status: CallStatus,
+    ) -> Result<()> {
+        let response = self
+            .post(
+                settings,
+                "dial_out/progress",
+                &DialOutProgressRequest { call_id, status },
+            )
+            .await?;
+
+        match response.status() {
+            StatusCode::NO_CONTENT => Ok(()),
+            code => bail!("unexpected status code {code:?}"),
+        }
+    }
+
+    /// Send a POST request to the controller's `services` endpoint at `path`
+    ///
+    /// Refreshes the access token when it has expired.
+    async fn post<T: Serialize + ?Sized>(
+        &self,
+        settings: &ControllerSettings,
+        path: &str,
+        body: &T,
+    ) -> Result<reqwest::Response> {
         let uri = if settings.insecure {
             log::warn!("using insecure connection");
-            format!("http://{}/v1/services/call_in/start", settings.domain)
+            format!("http://{}/v1/services/{path}", settings.domain)
         } else {
-            format!("https://{}/v1/services/call_in/start", settings.domain)
+            format!("https://{}/v1/services/{path}", settings.domain)
         };
 
         // max 10 tries
@@ -101,33 +176,20 @@ impl HttpClient {
                 .post(&uri)
                 .header("content-type", "application/json")
                 .bearer_auth(token.secret())
-                .json(&StartRequest { id, pin })
+                .json(body)
                 .send()
                 .await?;
 
-            match response.status() {
-                StatusCode::OK => {
-                    let response = response.json::<StartResponse>().await?;
+            if response.status() != StatusCode::UNAUTHORIZED {
+                return Ok(response);
+            }
 
-                    return Ok(Ticket(response.ticket));
-                }
-                StatusCode::UNAUTHORIZED => {
-                    let ApiError { code } = response.json::<ApiError>().await?;
-
-                    if code == "unauthorized" {
-                        self.refresh_access_tokens(token).await?;
-                    } else {
-                        bail!(InvalidCredentials);
-                    }
-                }
-                StatusCode::BAD_REQUEST => {
-                    let ApiError { code } = response.json::<ApiError>().await?;
+            let ApiError { code } = response.json::<ApiError>().await?;
 
-                    if code == "invalid_credentials" {
-                        bail!(InvalidCredentials);
-                    }
-                }
-                code => bail!("unexpected status code {code:?}"),
+            if code == "unauthorized" {
+                self.refresh_access_tokens(token).await?;
+            } else {
+                bail!(InvalidCredentials);
             }
         }
 
@@ -146,6 +208,17 @@ struct StartRequest<'s> {
     pin: &'s str,
 }
 
+#[derive(Serialize)]
+struct DialOutStartRequest {
+    call_id: Uuid,
+}
+
+#[derive(Serialize)]
+struct DialOutProgressRequest {
+    call_id: Uuid,
+    status: CallStatus,
+}
+
 #[derive(Deserialize)]
 struct ApiError {
     code: String,
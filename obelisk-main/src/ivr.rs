@@ -0,0 +1,207 @@
+// SPDX-FileCopyrightText: OpenTalk GmbH <mail@opentalk.eu>
+//
+// SPDX-License-Identifier: EUPL-1.2
+
+//! Interactive voice response: the languages of the prompts and the DTMF key mapping
+
+use crate::media::track::Prompts;
+use crate::settings::{DtmfKey, IvrKeys, IvrSettings};
+use anyhow::{bail, Context, Result};
+use std::sync::Arc;
+
+/// Language of the built-in prompts
+const BUILTIN_LANGUAGE: &str = "de";
+
+pub struct Ivr {
+    pub keys: IvrKeys,
+    default_language: usize,
+    languages: Vec<Language>,
+}
+
+pub struct Language {
+    pub name: String,
+    key: Option<DtmfKey>,
+    numbers: Vec<String>,
+    pub prompts: Arc<Prompts>,
+}
+
+impl Ivr {
+    /// Validate the settings and load the prompts of all languages
+    pub fn load(settings: &IvrSettings) -> Result<Self> {
+        validate_keys(&settings.keys)?;
+
+        let mut languages = Vec::new();
+
+        for (name, language) in &settings.languages {
+            let prompts = Prompts::load(&language.prompts)
+                .with_context(|| format!("Failed to load the prompts of language {name:?}"))?;
+
+            if let Some(key) = language.key {
+                if let Some(other) = languages.iter().find(|l: &&Language| l.key == Some(key)) {
+                    bail!(
+                        "Languages {:?} and {name:?} are selected by the same key {key}",
+                        other.name
+                    );
+                }
+            }
+
+            languages.push(Language {
+                name: name.clone(),
+                key: language.key,
+                numbers: language.numbers.clone(),
+                prompts: Arc::new(prompts),
+            });
+        }
+
+        if !languages.iter().any(|l| l.name == BUILTIN_LANGUAGE) {
+            languages.push(Language {
+                name: BUILTIN_LANGUAGE.into(),
+                key: None,
+                numbers: Vec::new(),
+                prompts: Arc::new(Prompts::builtin()),
+            });
+        }
+
+        for language in &languages {
+            let missing = language.prompts.missing();
+            if !missing.is_empty() {
+                log::warn!(
+                    "Language {:?} is missing the prompts {missing:?}",
+                    language.name
+                );
+            }
+
+            if !language.prompts.announces_participant_count() {
+                log::warn!(
+                    "Language {:?} cannot announce the participant count, the key {} is ignored",
+                    language.name,
+                    settings.keys.participant_count
+                );
+            }
+        }
+
+        let Some(default_language) = languages
+            .iter()
+            .position(|l| l.name == settings.default_language)
+        else {
+            bail!("Unknown default language {:?}", settings.default_language);
+        };
+
+        Ok(Self {
+            keys: settings.keys,
+            default_language,
+            languages,
+        })
+    }
+
+    pub fn default_language(&self) -> &Language {
+        &self.languages[self.default_language]
+    }
+
+    /// Returns the language of the called number, or the default language
+    pub fn language_for_number(&self, number: Option<&str>) -> &Language {
+        number
+            .and_then(|number| {
+                self.languages
+                    .iter()
+                    .find(|l| l.numbers.iter().any(|n| n == number))
+            })
+            .unwrap_or_else(|| self.default_language())
+    }
+
+    /// Returns the language selected by the given key
+    pub fn language_for_key(&self, key: DtmfKey) -> Option<&Language> {
+        self.languages.iter().find(|l| l.key == Some(key))
+    }
+}
+
+fn validate_keys(keys: &IvrKeys) -> Result<()> {
+    // Digits are part of the conference ID and passcode
+    for (name, key) in [
+        ("reset_input", keys.reset_input),
+        ("select_language", keys.select_language),
+        ("leave_waiting_room", keys.leave_waiting_room),
+    ] {
+        if key.digit().is_some() {
+            bail!("ivr.keys.{name} must not be a digit, got {key}");
+        }
+    }
+
+    if keys.reset_input == keys.select_language {
+        bail!("ivr.keys.reset_input and ivr.keys.select_language must differ");
+    }
+
+    let commands = [
+        ("silence", keys.silence),
+        ("toggle_mute", keys.toggle_mute),
+        ("toggle_hand", keys.toggle_hand),
+        ("participant_count", keys.participant_count),
+        ("mute_status", keys.mute_status),
+    ];
+
+    for (i, (name, key)) in commands.iter().enumerate() {
+        if let Some((other, _)) = commands[i + 1..].iter().find(|(_, k)| k == key) {
+            bail!("ivr.keys.{name} and ivr.keys.{other} are both mapped to {key}");
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn language(name: &str, numbers: &[&str], prompts: &Arc<Prompts>) -> Language {
+        Language {
+            name: name.into(),
+            key: None,
+            numbers: numbers.iter().map(|number| number.to_string()).collect(),
+            prompts: prompts.clone(),
+        }
+    }
+
+    #[test]
+    fn default_keys_are_valid() {
+        assert!(validate_keys(&IvrKeys::default()).is_ok());
+    }
+
+    #[test]
+    fn invalid_keys() {
+        let digit_reset = IvrKeys {
+            reset_input: DtmfKey::from_event(5).unwrap(),
+            ..Default::default()
+        };
+        assert!(validate_keys(&digit_reset).is_err());
+
+        let same_input_keys = IvrKeys {
+            select_language: IvrKeys::default().reset_input,
+            ..Default::default()
+        };
+        assert!(validate_keys(&same_input_keys).is_err());
+
+        let same_command_keys = IvrKeys {
+            mute_status: IvrKeys::default().toggle_mute,
+            ..Default::default()
+        };
+        assert!(validate_keys(&same_command_keys).is_err());
+    }
+
+    #[test]
+    fn language_for_number() {
+        let prompts = Arc::new(Prompts::builtin());
+
+        let ivr = Ivr {
+            keys: IvrKeys::default(),
+            default_language: 1,
+            languages: vec![
+                language("en", &["+4930123456", "+4930123457"], &prompts),
+                language("de", &[], &prompts),
+            ],
+        };
+
+        assert_eq!(ivr.language_for_number(Some("+4930123457")).name, "en");
+        assert_eq!(ivr.language_for_number(Some("+4930999999")).name, "de");
+        assert_eq!(ivr.language_for_number(None).name, "de");
+    }
+}
@@ -5,7 +5,9 @@
 use anyhow::{bail, Result};
 use std::sync::Arc;
 
+mod dial_out;
 mod http;
+mod ivr;
 mod media;
 mod settings;
 mod signaling;
@@ -19,6 +21,7 @@ fn check_plugins() -> Result<()> {
         "webrtc",
         "udp",
         "rtp",
+        "srtp",
         "alaw",
         "mulaw",
         "app",
@@ -66,6 +69,8 @@ async fn main() -> Result<()> {
 
     let settings = settings::Settings::load("config.toml")?;
 
+    media::check_codecs(&settings.sip.codecs)?;
+
     media::port_pool::PortPool::init(
         settings.sip.rtp_port_range.start,
         settings.sip.rtp_port_range.end,
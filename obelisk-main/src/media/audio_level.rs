@@ -0,0 +1,112 @@
+// SPDX-FileCopyrightText: OpenTalk GmbH <mail@opentalk.eu>
+//
+// SPDX-License-Identifier: EUPL-1.2
+
+//! Audio level of Opus which is published without transcoding
+//!
+//! The transcoding publish lets the `level` element provide the `ssrc-audio-level` header
+//! extension (RFC 6464). Opus received from the SIP peer is not decoded for the publish, its
+//! level is measured on a decoded copy and written into the RTP packets instead.
+
+use gst::prelude::*;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::Arc;
+
+/// ID of the ssrc-audio-level header extension within the caps of the publish bin
+pub const EXTENSION_ID: u8 = 1;
+
+/// Level of digital silence in -dBov
+const SILENCE: u8 = 127;
+
+/// Audio which is louder than this level in -dBov is flagged as voice activity
+const VOICE_ACTIVITY_THRESHOLD: u8 = 50;
+
+/// The last measured audio level in -dBov, shared between the measuring and the writing pad
+#[derive(Clone)]
+pub struct AudioLevel(Arc<AtomicU8>);
+
+impl Default for AudioLevel {
+    fn default() -> Self {
+        Self(Arc::new(AtomicU8::new(SILENCE)))
+    }
+}
+
+impl AudioLevel {
+    /// Measure the level of the interleaved S16LE audio passing the given pad
+    pub fn measure(&self, pad: &gst::Pad) {
+        let level = self.0.clone();
+
+        pad.add_probe(gst::PadProbeType::BUFFER, move |_, info| {
+            if let Some(map) = info.buffer().and_then(|buffer| buffer.map_readable().ok()) {
+                level.store(level_of(map.as_slice()), Ordering::Relaxed);
+            }
+
+            gst::PadProbeReturn::Ok
+        });
+    }
+
+    /// Write the last measured level into the RTP packets passing the given pad
+    pub fn write(&self, pad: &gst::Pad) {
+        let level = self.0.clone();
+
+        pad.add_probe(gst::PadProbeType::BUFFER, move |_, info| {
+            let level = level.load(Ordering::Relaxed);
+            let voice_activity = if level < VOICE_ACTIVITY_THRESHOLD {
+                0x80
+            } else {
+                0
+            };
+
+            if let Some(buffer) = info.buffer_mut() {
+                let res = gst_rtp::RTPBuffer::from_buffer_writable(buffer).and_then(|mut rtp| {
+                    rtp.add_extension_onebyte_header(EXTENSION_ID, &[voice_activity | level])
+                });
+
+                if let Err(e) = res {
+                    log::debug!("Failed to add the audio level to a RTP packet, {e}");
+                }
+            }
+
+            gst::PadProbeReturn::Ok
+        });
+    }
+}
+
+/// Level of interleaved S16LE samples in -dBov
+fn level_of(samples: &[u8]) -> u8 {
+    let (sum, count) = samples
+        .chunks_exact(2)
+        .map(|sample| f64::from(i16::from_le_bytes([sample[0], sample[1]])) / 32768.0)
+        .fold((0.0, 0u32), |(sum, count), sample| {
+            (sum + sample * sample, count + 1)
+        });
+
+    if sum == 0.0 {
+        return SILENCE;
+    }
+
+    let rms = (sum / f64::from(count)).sqrt();
+
+    (-20.0 * rms.log10()).round().clamp(0.0, f64::from(SILENCE)) as u8
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn samples(values: &[i16]) -> Vec<u8> {
+        values
+            .iter()
+            .flat_map(|value| value.to_le_bytes())
+            .collect()
+    }
+
+    #[test]
+    fn audio_level() {
+        assert_eq!(level_of(&[]), SILENCE);
+        assert_eq!(level_of(&samples(&[0, 0, 0, 0])), SILENCE);
+        assert_eq!(level_of(&samples(&[i16::MIN, i16::MIN])), 0);
+        assert_eq!(level_of(&samples(&[16384, -16384])), 6);
+        assert_eq!(level_of(&samples(&[1, -1])), 90);
+    }
+}
@@ -2,8 +2,10 @@
 //
 // SPDX-License-Identifier: EUPL-1.2
 
+use crate::settings::SipSettings;
 use crate::websocket::TrickleCandidate;
 use anyhow::{bail, Context, Result};
+use audio_level::AudioLevel;
 use bytes::Bytes;
 use glib::source::Continue;
 use gst::glib;
@@ -15,12 +17,13 @@ use std::sync::Arc;
 use tokio::sync::{mpsc, oneshot, Notify};
 use uuid::Uuid;
 
+mod audio_level;
 pub mod port_pool;
 mod sip_bin;
 pub mod track;
 mod webrtc_subscribe;
 
-pub use sip_bin::init_custom_rtpdtmfdepay;
+pub use sip_bin::{check_codecs, init_custom_rtpdtmfdepay, SdpError};
 pub use track::{Track, TrackController};
 
 /// Webrtc session of an event
@@ -47,6 +50,8 @@ pub struct MediaPipeline {
     events_rx: mpsc::UnboundedReceiver<MediaEvent>,
     pipeline: gst::Pipeline,
     sip_bin: SipBin,
+    /// The SIP peer sends Opus which is published without transcoding
+    passthrough: bool,
 }
 
 impl Drop for MediaPipeline {
@@ -65,6 +70,7 @@ impl MediaPipeline {
     /// - string containing the SIP SDP Answer
     pub async fn new(
         pub_addr: IpAddr,
+        sip_settings: &SipSettings,
         sip_sdp_offer: Bytes,
         ready_to_send: Arc<Notify>,
     ) -> Result<(Self, TrackController, String)> {
@@ -73,7 +79,7 @@ impl MediaPipeline {
         let pipeline = gst::Pipeline::new(None);
 
         // Create the SIP-BIN, it has a single sink for audio and zero or more audio sources
-        let (sip_bin, answer) = SipBin::new(pub_addr, sip_sdp_offer, ready_to_send)?;
+        let (sip_bin, answer) = SipBin::new(pub_addr, sip_settings, sip_sdp_offer, ready_to_send)?;
         pipeline.add(&sip_bin.bin)?;
 
         // Create a audiomixer to mix all webrtc-subscriptions into a single audio stream, which can be sent to the SIP
@@ -96,29 +102,38 @@ impl MediaPipeline {
 
         // Create a second audiomixer to combine all audio streams received by us from the SIP peer, to be later
         // sent via webrtc.
+        // Opus streams can't be mixed without decoding them, these are combined using a funnel instead.
         // As long as there is no publishing webrtc connection the audio stream is dumped into a fakesink.
-        let publisher_mixer = gst::ElementFactory::make("audiomixer")
-            .name("publisher-audiomix")
-            .property("ignore-inactive-pads", true)
-            .build()?;
+        let passthrough = sip_bin.is_passthrough();
+
+        let publisher_input = if passthrough {
+            gst::ElementFactory::make("funnel")
+                .name("publisher-input")
+                .build()?
+        } else {
+            gst::ElementFactory::make("audiomixer")
+                .name("publisher-input")
+                .property("ignore-inactive-pads", true)
+                .build()?
+        };
         let fakesink = gst::ElementFactory::make("fakesink")
             .name("publisher-fakesink")
             .build()?;
-        pipeline.add_many(&[&publisher_mixer, &fakesink])?;
-        publisher_mixer.link(&fakesink)?;
+        pipeline.add_many(&[&publisher_input, &fakesink])?;
+        publisher_input.link(&fakesink)?;
 
         // Handle pads added to sip-bin
-        let publisher_mixer_weak = publisher_mixer.downgrade();
+        let publisher_input_weak = publisher_input.downgrade();
         sip_bin.bin.connect_pad_added(move |_, pad| {
-            // Connect the pad directly to the audiomixer
-            let Some(mixer) = publisher_mixer_weak.upgrade() else {
+            // Connect the pad directly to the publisher-input
+            let Some(publisher_input) = publisher_input_weak.upgrade() else {
                 return;
             };
 
             let res = (|| {
-                let sink_pad = mixer
+                let sink_pad = publisher_input
                     .request_pad_simple("sink_%u")
-                    .context("Failed to request pad from audiomixer")?;
+                    .context("Failed to request pad from publisher-input")?;
                 pad.link(&sink_pad).context("Failed to link")
             })();
 
@@ -128,7 +143,7 @@ impl MediaPipeline {
         });
 
         // TODO: Handle pads removed by the sip-bin (tough edge case)
-        // Currently pad from the sip-bin are directly linked to the publisher-audiomix's requested pads. Not handling
+        // Currently pad from the sip-bin are directly linked to the publisher-input's requested pads. Not handling
         // these removed pads leads to these request-pads accumulating, though this should not be a huge problem since
         // it shouldn't happen very often and the audiomixer has `ignore-inactive-pads` set to true.
 
@@ -193,6 +208,7 @@ impl MediaPipeline {
             events_tx,
             pipeline,
             sip_bin,
+            passthrough,
         };
 
         Ok((this, controller, answer))
@@ -254,7 +270,29 @@ impl MediaPipeline {
             bail!("publish bin has already been created once");
         }
 
-        let publish_bin = r#"
+        // Opus received from the SIP peer only needs to be payloaded again, a decoded copy is
+        // used to measure its audio level
+        let publish_bin = if self.passthrough {
+            r#"
+            webrtcbin name=webrtc-publish bundle-policy=max-bundle
+
+            valve name=audio-input !
+                tee name=passthrough-tee !
+                queue !
+                rtpopuspay name=passthrough-pay pt=97 !
+                application/x-rtp,media=audio,encoding-name=OPUS,payload=97,extmap-1=(string)<"", urn:ietf:params:rtp-hdrext:ssrc-audio-level, "vad=on"> !
+                queue !
+                webrtc-publish.
+
+            passthrough-tee. !
+                queue leaky=downstream !
+                opusdec !
+                audioconvert !
+                audio/x-raw,format=S16LE !
+                fakesink name=passthrough-level sync=false async=false
+        "#
+        } else {
+            r#"
             webrtcbin name=webrtc-publish bundle-policy=max-bundle
 
             volume name=audio-input !
@@ -268,7 +306,8 @@ impl MediaPipeline {
                 application/x-rtp,media=audio,encoding-name=OPUS,payload=97,extmap-1=(string)<"", urn:ietf:params:rtp-hdrext:ssrc-audio-level, "vad=on"> !
                 queue !
                 webrtc-publish.
-        "#;
+        "#
+        };
 
         let publish_bin = gst::parse_bin_from_description_with_name(publish_bin, false, "publish")
             .context("failed to parse webrtc-publish bin")?;
@@ -289,22 +328,39 @@ impl MediaPipeline {
         let webrtc_publish_audio_sink =
             gst::GhostPad::with_target(Some("audio-sink"), &audio_input_sink)?;
 
+        if self.passthrough {
+            let audio_level = AudioLevel::default();
+
+            audio_level.measure(
+                &publish_bin
+                    .by_name("passthrough-level")
+                    .and_then(|sink| sink.static_pad("sink"))
+                    .context("No passthrough-level sink pad found")?,
+            );
+            audio_level.write(
+                &publish_bin
+                    .by_name("passthrough-pay")
+                    .and_then(|pay| pay.static_pad("src"))
+                    .context("No passthrough-pay src pad found")?,
+            );
+        }
+
         publish_bin.add_pad(&webrtc_publish_audio_sink)?;
 
-        let mixer = self
+        let publisher_input = self
             .pipeline
-            .by_name("publisher-audiomix")
-            .context("Failed to get publisher-audiomix, has the sip-bin been created?")?;
+            .by_name("publisher-input")
+            .context("Failed to get publisher-input, has the sip-bin been created?")?;
         let publisher_fakesink = self
             .pipeline
             .by_name("publisher-fakesink")
             .context("Failed to get publisher-fakesink")?;
 
-        mixer.unlink(&publisher_fakesink);
+        publisher_input.unlink(&publisher_fakesink);
         self.pipeline.remove(&publisher_fakesink)?;
         publisher_fakesink.set_state(gst::State::Null)?;
 
-        mixer.link_pads(Some("src"), &publish_bin, Some("audio-sink"))?;
+        publisher_input.link_pads(Some("src"), &publish_bin, Some("audio-sink"))?;
 
         // Set transceiver direction to sendonly
         let transceiver = webrtc
@@ -559,8 +615,13 @@ impl MediaPipeline {
     }
 
     pub fn on_publish_mute(&mut self, mute: bool) -> Result<()> {
-        if let Some(volume) = self.pipeline.by_name("audio-input") {
-            volume.set_property("mute", mute);
+        if let Some(audio_input) = self.pipeline.by_name("audio-input") {
+            // The passthrough publish uses a valve instead of a volume element
+            if self.passthrough {
+                audio_input.set_property("drop", mute);
+            } else {
+                audio_input.set_property("mute", mute);
+            }
         }
 
         Ok(())
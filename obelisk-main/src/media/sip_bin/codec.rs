@@ -2,39 +2,137 @@
 //
 // SPDX-License-Identifier: EUPL-1.2
 
+use crate::settings::AudioCodec;
+use anyhow::{bail, Result};
+use bytesstr::BytesStr;
 use sdp_types::attributes::fmtp::Fmtp;
 use sdp_types::attributes::rtpmap::RtpMap;
 use sdp_types::msg::MediaScope;
 
 struct CodecEntry {
+    codec: AudioCodec,
     static_payload: Option<u32>,
     name: &'static str,
+    /// Names of the gstreamer elements used by the codec, checked on startup
+    elements: &'static [&'static str],
     create: fn(rtpmap: Option<&RtpMap>, fmtp: Option<&Fmtp>) -> Option<SdpCodecInfo>,
 }
 
-/// List of all codecs and their constructor function sorted by priority
+/// List of all codecs and their constructor function
+///
+/// The priority is defined by the codec preference of the SIP settings.
 static CODEC_LIST: &[CodecEntry] = &[
     CodecEntry {
+        codec: AudioCodec::Opus,
+        static_payload: None,
+        name: "opus",
+        elements: &["rtpopuspay", "rtpopusdepay", "opusenc", "opusdec"],
+        create: opus_create,
+    },
+    CodecEntry {
+        codec: AudioCodec::AmrWb,
+        static_payload: None,
+        name: "AMR-WB",
+        elements: &["rtpamrpay", "rtpamrdepay", "voamrwbenc", "avdec_amrwb"],
+        create: amr_wb_create,
+    },
+    CodecEntry {
+        codec: AudioCodec::G722,
         static_payload: Some(9),
         name: "G722",
+        elements: &["rtpg722pay", "rtpg722depay", "avenc_g722", "avdec_g722"],
         create: g722_create,
     },
     CodecEntry {
+        codec: AudioCodec::Pcma,
         static_payload: Some(8),
         name: "PCMA",
+        elements: &["rtppcmapay", "rtppcmadepay", "alawenc", "alawdec"],
         create: pcma_create,
     },
     CodecEntry {
+        codec: AudioCodec::Pcmu,
         static_payload: Some(0),
         name: "PCMU",
+        elements: &["rtppcmupay", "rtppcmudepay", "mulawenc", "mulawdec"],
         create: pcmu_create,
     },
 ];
 
+fn opus_create(rtpmap: Option<&RtpMap>, _: Option<&Fmtp>) -> Option<SdpCodecInfo> {
+    let rtpmap = rtpmap?.clone();
+
+    if rtpmap.clock_rate != 48000 {
+        return None;
+    }
+
+    Some(SdpCodecInfo {
+        codec: AudioCodec::Opus,
+        gst_elements: GstCodecInfo {
+            payload_caps: PayloadCaps {
+                payload: rtpmap.payload,
+                caps: gst::Caps::builder("application/x-rtp")
+                    .field("media", "audio")
+                    .field("encoding-name", "OPUS")
+                    .field("clock-rate", 48000i32)
+                    .build(),
+            },
+            rtppay: "rtpopuspay",
+            rtpdepay: "rtpopusdepay",
+            encoder: "opusenc",
+            // Opus is also used by the webrtc publish and is not decoded
+            decoder: None,
+        },
+        rtpmap,
+        fmtp: None,
+    })
+}
+
+fn amr_wb_create(rtpmap: Option<&RtpMap>, fmtp: Option<&Fmtp>) -> Option<SdpCodecInfo> {
+    let rtpmap = rtpmap?.clone();
+
+    // The gstreamer (de)payloaders only support the octet-aligned mode
+    let octet_aligned = fmtp.map_or(false, |fmtp| {
+        fmtp.params
+            .split(';')
+            .any(|param| param.trim() == "octet-align=1")
+    });
+
+    if rtpmap.clock_rate != 16000 || !octet_aligned {
+        return None;
+    }
+
+    Some(SdpCodecInfo {
+        codec: AudioCodec::AmrWb,
+        gst_elements: GstCodecInfo {
+            payload_caps: PayloadCaps {
+                payload: rtpmap.payload,
+                caps: gst::Caps::builder("application/x-rtp")
+                    .field("media", "audio")
+                    .field("encoding-name", "AMR-WB")
+                    .field("clock-rate", 16000i32)
+                    .field("encoding-params", "1")
+                    .field("octet-align", "1")
+                    .build(),
+            },
+            rtppay: "rtpamrpay",
+            rtpdepay: "rtpamrdepay",
+            encoder: "voamrwbenc",
+            decoder: Some("avdec_amrwb"),
+        },
+        fmtp: Some(Fmtp {
+            format: rtpmap.payload,
+            params: BytesStr::from_static("octet-align=1"),
+        }),
+        rtpmap,
+    })
+}
+
 fn g722_create(rtpmap: Option<&RtpMap>, _: Option<&Fmtp>) -> Option<SdpCodecInfo> {
     let rtpmap = rtpmap?.clone();
 
     Some(SdpCodecInfo {
+        codec: AudioCodec::G722,
         rtpmap,
         fmtp: None,
         gst_elements: GstCodecInfo {
@@ -49,7 +147,7 @@ fn g722_create(rtpmap: Option<&RtpMap>, _: Option<&Fmtp>) -> Option<SdpCodecInfo
             rtppay: "rtpg722pay",
             rtpdepay: "rtpg722depay",
             encoder: "avenc_g722",
-            decoder: "avdec_g722",
+            decoder: Some("avdec_g722"),
         },
     })
 }
@@ -63,6 +161,7 @@ fn pcma_create(rtpmap: Option<&RtpMap>, _: Option<&Fmtp>) -> Option<SdpCodecInfo
     });
 
     Some(SdpCodecInfo {
+        codec: AudioCodec::Pcma,
         rtpmap,
         fmtp: None,
         gst_elements: GstCodecInfo {
@@ -77,7 +176,7 @@ fn pcma_create(rtpmap: Option<&RtpMap>, _: Option<&Fmtp>) -> Option<SdpCodecInfo
             rtppay: "rtppcmapay",
             rtpdepay: "rtppcmadepay",
             encoder: "alawenc",
-            decoder: "alawdec",
+            decoder: Some("alawdec"),
         },
     })
 }
@@ -91,6 +190,7 @@ fn pcmu_create(rtpmap: Option<&RtpMap>, _: Option<&Fmtp>) -> Option<SdpCodecInfo
     });
 
     Some(SdpCodecInfo {
+        codec: AudioCodec::Pcmu,
         rtpmap,
         fmtp: None,
         gst_elements: GstCodecInfo {
@@ -105,12 +205,14 @@ fn pcmu_create(rtpmap: Option<&RtpMap>, _: Option<&Fmtp>) -> Option<SdpCodecInfo
             rtppay: "rtppcmupay",
             rtpdepay: "rtppcmudepay",
             encoder: "mulawenc",
-            decoder: "mulawdec",
+            decoder: Some("mulawdec"),
         },
     })
 }
 
 pub struct SdpCodecInfo {
+    pub codec: AudioCodec,
+
     pub rtpmap: RtpMap,
     pub fmtp: Option<Fmtp>,
 
@@ -128,7 +230,8 @@ pub struct GstCodecInfo {
     pub rtpdepay: &'static str,
 
     pub encoder: &'static str,
-    pub decoder: &'static str,
+    /// Decoder of the received audio, `None` if it is forwarded to the webrtc publish as is
+    pub decoder: Option<&'static str>,
 }
 
 /// Used by rtpbin's request-pt-map callback to provide caps
@@ -139,8 +242,32 @@ pub struct PayloadCaps {
     pub caps: gst::Caps,
 }
 
-pub fn choose_codec(offer: &MediaScope) -> Option<SdpCodecInfo> {
-    for entry in CODEC_LIST {
+/// Check that the gstreamer elements of all given codecs are available
+pub fn check_codecs(codecs: &[AudioCodec]) -> Result<()> {
+    let missing = CODEC_LIST
+        .iter()
+        .filter(|entry| codecs.contains(&entry.codec))
+        .flat_map(|entry| entry.elements)
+        .filter(|element| gst::ElementFactory::find(element).is_none())
+        .collect::<Vec<_>>();
+
+    if !missing.is_empty() {
+        bail!(
+            "Missing gstreamer elements for the configured codecs: {:?}",
+            missing
+        );
+    } else {
+        Ok(())
+    }
+}
+
+/// Choose the first codec of `preference` which is contained in the offer
+pub fn choose_codec(offer: &MediaScope, preference: &[AudioCodec]) -> Option<SdpCodecInfo> {
+    let entries = preference
+        .iter()
+        .filter_map(|codec| CODEC_LIST.iter().find(|entry| entry.codec == *codec));
+
+    for entry in entries {
         // first check all payload numbers in the m= line
         for payload in &offer.desc.fmts {
             let static_pt_matches = if let Some(static_payload) = entry.static_payload {
@@ -173,3 +300,97 @@ pub fn choose_codec(offer: &MediaScope) -> Option<SdpCodecInfo> {
 
     None
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use sdp_types::attributes::direction::Direction;
+    use sdp_types::media::{MediaDescription, MediaType, TransportProtocol};
+
+    fn rtpmap(payload: u32, encoding: &'static str, clock_rate: u32) -> RtpMap {
+        RtpMap {
+            payload,
+            encoding: BytesStr::from_static(encoding),
+            clock_rate,
+            params: None,
+        }
+    }
+
+    fn offer(fmts: Vec<u32>, rtpmaps: Vec<RtpMap>, fmtps: Vec<Fmtp>) -> MediaScope {
+        MediaScope {
+            desc: MediaDescription {
+                media_type: MediaType::Audio,
+                port: 5004,
+                ports_num: None,
+                proto: TransportProtocol::RtpAvp,
+                fmts,
+            },
+            direction: Direction::SendRecv,
+            connection: None,
+            bandwidth: vec![],
+            rtcp_attr: None,
+            rtpmaps,
+            fmtps,
+            ice_ufrag: None,
+            ice_pwd: None,
+            ice_candidates: vec![],
+            ice_end_of_candidates: false,
+            attributes: vec![],
+        }
+    }
+
+    #[test]
+    fn codec_preference() {
+        gst::init().unwrap();
+
+        // Static payload types may be offered without a rtpmap
+        let offer = offer(
+            vec![111, 9, 8, 0],
+            vec![rtpmap(111, "opus", 48000), rtpmap(9, "G722", 8000)],
+            vec![],
+        );
+
+        let all = [
+            AudioCodec::Opus,
+            AudioCodec::AmrWb,
+            AudioCodec::G722,
+            AudioCodec::Pcma,
+            AudioCodec::Pcmu,
+        ];
+        let chosen = |preference: &[AudioCodec]| {
+            choose_codec(&offer, preference).map(|info| info.rtpmap.payload)
+        };
+
+        assert_eq!(chosen(&all), Some(111));
+        assert_eq!(chosen(&[AudioCodec::Pcmu, AudioCodec::Pcma]), Some(0));
+        assert_eq!(chosen(&[AudioCodec::G722, AudioCodec::Pcma]), Some(9));
+        assert_eq!(chosen(&[AudioCodec::AmrWb]), None);
+        assert_eq!(chosen(&[]), None);
+    }
+
+    #[test]
+    fn codec_parameters() {
+        gst::init().unwrap();
+
+        // Opus must use a 48 kHz clock
+        let opus = offer(vec![96], vec![rtpmap(96, "OPUS", 16000)], vec![]);
+        assert!(choose_codec(&opus, &[AudioCodec::Opus]).is_none());
+
+        // AMR-WB is only supported in the octet-aligned mode
+        let bandwidth_efficient = offer(vec![97], vec![rtpmap(97, "AMR-WB", 16000)], vec![]);
+        assert!(choose_codec(&bandwidth_efficient, &[AudioCodec::AmrWb]).is_none());
+
+        let octet_aligned = offer(
+            vec![97],
+            vec![rtpmap(97, "AMR-WB", 16000)],
+            vec![Fmtp {
+                format: 97,
+                params: BytesStr::from_static("mode-change-capability=2; octet-align=1"),
+            }],
+        );
+        let info = choose_codec(&octet_aligned, &[AudioCodec::AmrWb]).unwrap();
+        assert_eq!(info.rtpmap.payload, 97);
+        assert_eq!(info.gst_elements.payload_caps.payload, 97);
+        assert_eq!(&*info.fmtp.unwrap().params, "octet-align=1");
+    }
+}
@@ -3,7 +3,8 @@
 // SPDX-License-Identifier: EUPL-1.2
 
 use crate::media::port_pool::PortPool;
-use anyhow::{Context, Result};
+use crate::settings::{MediaEncryption, SipSettings};
+use anyhow::{bail, Context, Result};
 use bytes::Bytes;
 use bytesstr::BytesStr;
 use codec::{GstCodecInfo, PayloadCaps};
@@ -13,6 +14,7 @@ use gst::prelude::*;
 use parking_lot::Mutex;
 use sdp::SessionInfo;
 use sdp_types::attributes::direction::Direction;
+use srtp::Crypto;
 use std::collections::HashMap;
 use std::net::{IpAddr, SocketAddr};
 use std::sync::{Arc, Weak};
@@ -22,8 +24,11 @@ use tokio::sync::Notify;
 mod codec;
 mod custom_rtpdtmfdepay;
 mod sdp;
+mod srtp;
 
+pub use codec::check_codecs;
 pub use custom_rtpdtmfdepay::init_custom_rtpdtmfdepay;
+pub use sdp::Error as SdpError;
 
 pub struct SipBin {
     pub(crate) bin: gst::Bin,
@@ -34,8 +39,14 @@ pub struct SipBin {
     local_rtp_addr: SocketAddr,
     local_rtcp_addr: SocketAddr,
 
+    media_encryption: MediaEncryption,
+    srtp_key: [u8; srtp::KEY_LEN],
+
     current_session_info: SessionInfo,
 
+    /// Remote SRTP key used by the srtpdec, set if the media is encrypted
+    srtp_remote: Option<Arc<Mutex<Crypto>>>,
+
     /// Keep the only strong reference of the element_chains map to drop all elements when this sip-bin is dropped
     _element_chains: Arc<Mutex<HashMap<String, ElementChain>>>,
 }
@@ -46,6 +57,7 @@ impl SipBin {
     /// Usually called by the first SIP INVITE.
     pub fn new(
         pub_addr: IpAddr,
+        settings: &SipSettings,
         offer: Bytes,
         ready_to_send: Arc<Notify>,
     ) -> Result<(SipBin, String)> {
@@ -72,8 +84,13 @@ impl SipBin {
         let id = now;
         let version = now + 1;
 
+        let srtp_key = srtp::generate_key();
+
         let local_info = sdp::LocalInfo {
             offer,
+            codecs: &settings.codecs,
+            media_encryption: settings.media_encryption,
+            srtp_key: &srtp_key,
             id,
             version,
             rtp_addr: local_rtp_addr,
@@ -83,12 +100,22 @@ impl SipBin {
         let current_session_info = sdp::respond(local_info)?;
         let element_chains = Arc::new(Mutex::new(HashMap::new()));
 
+        let srtp_remote = current_session_info
+            .srtp
+            .as_ref()
+            .map(|srtp| Arc::new(Mutex::new(srtp.remote.clone())));
+
         let bin = create_sip_bin(
             Arc::downgrade(&element_chains),
             socket_pair.rtp_socket,
             socket_pair.rtcp_socket,
             current_session_info.telephone_event_pt,
             current_session_info.gst_elements.clone(),
+            current_session_info
+                .srtp
+                .as_ref()
+                .map(|srtp| &srtp.local)
+                .zip(srtp_remote.clone()),
             ready_to_send,
         )
         .context("Failed to create sip_bin")?;
@@ -99,7 +126,10 @@ impl SipBin {
             version,
             local_rtp_addr,
             local_rtcp_addr,
+            media_encryption: settings.media_encryption,
+            srtp_key,
             current_session_info,
+            srtp_remote,
             _element_chains: element_chains,
         };
 
@@ -141,8 +171,13 @@ impl SipBin {
 
         self.version += 1;
 
+        // The elements of the sip-bin and the publish are created for the codec chosen by the first
+        // offer, offers which no longer contain it are rejected
         let local_info = sdp::LocalInfo {
             offer,
+            codecs: std::slice::from_ref(&self.current_session_info.codec),
+            media_encryption: self.media_encryption,
+            srtp_key: &self.srtp_key,
             id: self.id,
             version: self.version,
             rtp_addr: self.local_rtp_addr,
@@ -151,6 +186,22 @@ impl SipBin {
 
         let session_info = sdp::respond(local_info)?;
 
+        // The SRTP elements are only added when creating the sip-bin, encryption cannot be turned on or off later
+        match (&self.srtp_remote, &session_info.srtp) {
+            (Some(remote), Some(srtp)) => {
+                if *remote.lock() != srtp.remote {
+                    let srtpdec = self
+                        .bin
+                        .by_name("srtpdec")
+                        .context("failed to get srtpdec from sip_bin")?;
+
+                    srtp::update_decoder(&srtpdec, remote, srtp.remote.clone());
+                }
+            }
+            (None, None) => {}
+            _ => bail!("Changing the media encryption of a call is not supported"),
+        }
+
         let send_data = matches!(
             session_info.local_sdp.media_scopes[0].direction,
             Direction::SendRecv | Direction::SendOnly
@@ -184,6 +235,13 @@ impl SipBin {
         Ok(self.current_session_info.local_sdp.to_string())
     }
 
+    /// Returns true if the audio received from the SIP peer is Opus, which is not decoded
+    ///
+    /// The audio sources of the sip-bin then provide `audio/x-opus` instead of `audio/x-raw`.
+    pub fn is_passthrough(&self) -> bool {
+        self.current_session_info.gst_elements.decoder.is_none()
+    }
+
     fn set_destination(
         &mut self,
         remote_rtp_addr: SocketAddr,
@@ -244,6 +302,31 @@ impl SipBin {
     }
 }
 
+/// Pipeline description of receiving & sending the RTP/RTCP packets of the rtpbin
+const RTP_TRANSPORT: &str = r#"
+        udpsrc name=rtp-udp-src ! queue ! watchdog name=rtp-watchdog timeout=10000 ! session.recv_rtp_sink_0
+        udpsrc name=rtcp-udp-src caps="application/x-rtcp" ! queue ! session.recv_rtcp_sink_0
+
+        session.send_rtp_src_0 ! watchdog name=send-rtp-watchdog timeout=10000 ! udpsink host=127.0.0.1 port=9 name=rtp-udpsink
+        session.send_rtcp_src_0 ! udpsink host=127.0.0.1 port=9 name=rtcp-udpsink
+"#;
+
+/// Same as [`RTP_TRANSPORT`], but the packets pass a srtpdec after being received and a srtpenc before being sent
+const SRTP_TRANSPORT: &str = r#"
+        srtpdec name=srtpdec
+        srtpenc name=srtpenc
+
+        udpsrc name=rtp-udp-src caps="application/x-srtp" ! queue ! srtpdec.rtp_sink
+        srtpdec.rtp_src ! watchdog name=rtp-watchdog timeout=10000 ! session.recv_rtp_sink_0
+        udpsrc name=rtcp-udp-src caps="application/x-srtcp" ! queue ! srtpdec.rtcp_sink
+        srtpdec.rtcp_src ! session.recv_rtcp_sink_0
+
+        session.send_rtp_src_0 ! srtpenc.rtp_sink_0
+        srtpenc.rtp_src_0 ! watchdog name=send-rtp-watchdog timeout=10000 ! udpsink host=127.0.0.1 port=9 name=rtp-udpsink
+        session.send_rtcp_src_0 ! srtpenc.rtcp_sink_0
+        srtpenc.rtcp_src_0 ! udpsink host=127.0.0.1 port=9 name=rtcp-udpsink
+"#;
+
 /// Creates a gst sip-bin
 ///
 /// The default state has:
@@ -255,23 +338,27 @@ fn create_sip_bin(
     rtcp_socket: gio::Socket,
     telephone_event_pt: u32,
     gst_elements: GstCodecInfo,
+    srtp: Option<(&Crypto, Arc<Mutex<Crypto>>)>,
     ready_to_send: Arc<Notify>,
 ) -> Result<gst::Bin> {
+    let transport = if srtp.is_some() {
+        SRTP_TRANSPORT
+    } else {
+        RTP_TRANSPORT
+    };
+
     // Create pipeline textual description
     let sip_bin = format!(
         r#"
         rtpbin name=session autoremove=true
 
-        queue name=audio-sink min-threshold-time=20000000 ! audio/x-raw ! clocksync sync=true ! valve name=audio-input-valve ! audioconvert ! audioresample  ! {encoder} ! {rtppay} ! session.send_rtp_sink_0
-
-        udpsrc name=rtp-udp-src ! queue ! watchdog name=rtp-watchdog timeout=10000 ! session.recv_rtp_sink_0
-        udpsrc name=rtcp-udp-src caps="application/x-rtcp" ! queue ! session.recv_rtcp_sink_0
+        queue name=audio-sink min-threshold-time=20000000 ! audio/x-raw ! clocksync sync=true ! valve name=audio-input-valve ! audioconvert ! audioresample  ! {encoder} ! {rtppay} pt={pt} ! session.send_rtp_sink_0
 
-        session.send_rtp_src_0 ! watchdog name=send-rtp-watchdog timeout=10000 ! udpsink host=127.0.0.1 port=9 name=rtp-udpsink
-        session.send_rtcp_src_0 ! udpsink host=127.0.0.1 port=9 name=rtcp-udpsink
+        {transport}
         "#,
         encoder = gst_elements.encoder,
         rtppay = gst_elements.rtppay,
+        pt = gst_elements.payload_caps.payload,
     );
 
     // Parse pipeline
@@ -329,6 +416,18 @@ fn create_sip_bin(
         on_request_pt_map(gst_elements.payload_caps, telephone_event_pt),
     );
 
+    if let Some((local, remote)) = srtp {
+        let srtpenc = sip_bin
+            .by_name("srtpenc")
+            .context("failed to get srtpenc")?;
+        let srtpdec = sip_bin
+            .by_name("srtpdec")
+            .context("failed to get srtpdec")?;
+
+        srtp::configure_encoder(&srtpenc, local);
+        srtp::configure_decoder(&srtpdec, remote);
+    }
+
     Ok(sip_bin)
 }
 
@@ -343,7 +442,7 @@ impl Drop for SipBin {
 enum ElementChain {
     Audio {
         depay: gst::Element,
-        decode: gst::Element,
+        decode: Option<gst::Element>,
         queue: gst::Element,
         ghost_pad: gst::GhostPad,
     },
@@ -450,22 +549,25 @@ fn try_on_pad_added(
 
     //  media payload
     if expected_media_pt as i32 == payload {
-        // Create a chain of rtp-depay ! decode ! queue
+        // Create a chain of rtp-depay ! decode ! queue, the decoder is left out for passthrough codecs
         let depay = gst::ElementFactory::make(gst_elements.rtpdepay)
             .build()
             .context("Failed to create rtp de-payloader element")?;
-        let decode = gst::ElementFactory::make(gst_elements.decoder)
-            .build()
+        let decode = gst_elements
+            .decoder
+            .map(|decoder| gst::ElementFactory::make(decoder).build())
+            .transpose()
             .context("Failed to create audio decoder element")?;
         let queue = gst::ElementFactory::make("queue")
             .build()
             .context("Failed to create queue")?;
 
+        let chain = audio_chain(&depay, decode.as_ref(), &queue);
+
         sip_bin
-            .add_many(&[&depay, &decode, &queue])
+            .add_many(&chain)
             .context("Failed to add audio deocder elements")?;
-        gst::Element::link_many(&[&depay, &decode, &queue])
-            .context("Failed to link audio deocder elements")?;
+        gst::Element::link_many(&chain).context("Failed to link audio deocder elements")?;
 
         // Link the incoming newly added pad to the audiodepay
         let depay_sink = depay
@@ -480,9 +582,9 @@ fn try_on_pad_added(
         let ghost_pad = gst::GhostPad::with_target(None, &queue_src)?;
         sip_bin.add_pad(&ghost_pad)?;
 
-        depay.sync_state_with_parent()?;
-        decode.sync_state_with_parent()?;
-        queue.sync_state_with_parent()?;
+        for element in chain {
+            element.sync_state_with_parent()?;
+        }
 
         element_chains.lock().insert(
             pad.name().to_string(),
@@ -580,13 +682,15 @@ fn try_on_pad_removed(
             queue,
             ghost_pad,
         } => {
-            gst::Element::unlink_many(&[&depay, &decode, &queue]);
+            let chain = audio_chain(&depay, decode.as_ref(), &queue);
+
+            gst::Element::unlink_many(&chain);
             sip_bin.remove_pad(&ghost_pad)?;
-            sip_bin.remove_many(&[&depay, &decode, &queue])?;
+            sip_bin.remove_many(&chain)?;
 
-            depay.set_state(gst::State::Null)?;
-            decode.set_state(gst::State::Null)?;
-            queue.set_state(gst::State::Null)?;
+            for element in chain {
+                element.set_state(gst::State::Null)?;
+            }
         }
         ElementChain::Dtmf { depay, fakesink } => {
             gst::Element::unlink_many(&[&depay, &fakesink]);
@@ -603,3 +707,15 @@ fn try_on_pad_removed(
 
     Ok(())
 }
+
+/// Returns the elements of an audio chain in the order they are linked
+fn audio_chain<'a>(
+    depay: &'a gst::Element,
+    decode: Option<&'a gst::Element>,
+    queue: &'a gst::Element,
+) -> Vec<&'a gst::Element> {
+    std::iter::once(depay)
+        .chain(decode)
+        .chain(std::iter::once(queue))
+        .collect()
+}
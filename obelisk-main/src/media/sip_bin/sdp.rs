@@ -3,6 +3,8 @@
 // SPDX-License-Identifier: EUPL-1.2
 
 use super::codec::{choose_codec, GstCodecInfo};
+use super::srtp::{self, Crypto};
+use crate::settings::{AudioCodec, MediaEncryption};
 use bytesstr::BytesStr;
 use sdp_types::attributes::direction::Direction;
 use sdp_types::attributes::ice;
@@ -13,15 +15,22 @@ use sdp_types::msg::MediaScope;
 use sdp_types::msg::Message;
 use sdp_types::origin::Origin;
 use sdp_types::time::Time;
-use sdp_types::TaggedAddress;
+use sdp_types::{TaggedAddress, UnknownAttribute};
 use std::io;
 use std::net::SocketAddr;
 use std::net::{IpAddr, ToSocketAddrs};
 
 /// The local info used to negotiate the SDP session
-pub struct LocalInfo {
+pub struct LocalInfo<'a> {
     pub offer: Message,
 
+    /// Supported codecs, sorted by preference
+    pub codecs: &'a [AudioCodec],
+
+    pub media_encryption: MediaEncryption,
+    /// Local SRTP master key & salt, used if the offer uses SRTP
+    pub srtp_key: &'a [u8; srtp::KEY_LEN],
+
     pub id: u64,
     pub version: u64,
 
@@ -33,6 +42,9 @@ pub struct LocalInfo {
 pub struct SessionInfo {
     pub local_sdp: Message,
 
+    /// The negotiated codec
+    pub codec: AudioCodec,
+
     pub remote_id: u64,
     pub remote_version: u64,
 
@@ -41,18 +53,31 @@ pub struct SessionInfo {
     pub rtcp_addr: SocketAddr,
 
     pub gst_elements: GstCodecInfo,
+
+    /// Set if the media is encrypted using SRTP
+    pub srtp: Option<SrtpInfo>,
+}
+
+/// Keys of the SRTP session negotiated with SDES
+pub struct SrtpInfo {
+    pub local: Crypto,
+    pub remote: Crypto,
 }
 
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
     #[error("invalid offer")]
     InvalidOffer,
+    #[error("offer contains none of the supported codecs")]
+    UnsupportedCodec,
+    #[error("offer does not use SRTP, but media encryption is required")]
+    EncryptionRequired,
     #[error(transparent)]
     Io(#[from] io::Error),
 }
 
 /// Negotiate a audio codec & DTMF (telephone-event) session
-pub fn respond(local_info: LocalInfo) -> Result<SessionInfo, Error> {
+pub fn respond(local_info: LocalInfo<'_>) -> Result<SessionInfo, Error> {
     let mut offer = local_info.offer;
 
     if offer.media_scopes.len() != 1 {
@@ -65,7 +90,33 @@ pub fn respond(local_info: LocalInfo) -> Result<SessionInfo, Error> {
         return Err(Error::InvalidOffer);
     }
 
-    let codec_info = choose_codec(&media).ok_or(Error::InvalidOffer)?;
+    let codec_info = choose_codec(&media, local_info.codecs).ok_or(Error::UnsupportedCodec)?;
+
+    // DTLS-SRTP is not supported, only keys exchanged with SDES (a=crypto)
+    let srtp = match &media.desc.proto {
+        TransportProtocol::RtpAvp if local_info.media_encryption == MediaEncryption::Required => {
+            return Err(Error::EncryptionRequired);
+        }
+        TransportProtocol::RtpAvp => None,
+        TransportProtocol::RtpSavp => {
+            // Choose the first supported crypto attribute, they are sorted by the offerer's preference
+            let remote = media
+                .attributes
+                .iter()
+                .filter(|attr| attr.name.eq_ignore_ascii_case("crypto"))
+                .find_map(|attr| attr.value.as_deref().and_then(Crypto::parse))
+                .ok_or(Error::InvalidOffer)?;
+
+            let local = Crypto {
+                tag: remote.tag,
+                suite: remote.suite,
+                key: *local_info.srtp_key,
+            };
+
+            Some(SrtpInfo { local, remote })
+        }
+        _ => return Err(Error::InvalidOffer),
+    };
 
     let remote_id: u64 = offer
         .origin
@@ -109,7 +160,11 @@ pub fn respond(local_info: LocalInfo) -> Result<SessionInfo, Error> {
             media_type: MediaType::Audio,
             port: local_info.rtp_addr.port(),
             ports_num: None,
-            proto: TransportProtocol::RtpAvp,
+            proto: if srtp.is_some() {
+                TransportProtocol::RtpSavp
+            } else {
+                TransportProtocol::RtpAvp
+            },
             fmts: vec![codec_info.rtpmap.payload],
         },
         direction,
@@ -129,11 +184,17 @@ pub fn respond(local_info: LocalInfo) -> Result<SessionInfo, Error> {
         ice_pwd: None,
         ice_candidates: vec![],
         ice_end_of_candidates: false,
-        attributes: vec![],
+        attributes: srtp
+            .iter()
+            .map(|srtp| UnknownAttribute {
+                name: BytesStr::from_static("crypto"),
+                value: Some(srtp.local.to_string().into()),
+            })
+            .collect(),
     };
 
     if let Some(fmtp) = codec_info.fmtp {
-        media.fmtps.push(fmtp);
+        answer_media.fmtps.push(fmtp);
     }
 
     let mut telephone_event_pt = None;
@@ -180,6 +241,8 @@ pub fn respond(local_info: LocalInfo) -> Result<SessionInfo, Error> {
     Ok(SessionInfo {
         local_sdp,
 
+        codec: codec_info.codec,
+
         remote_id,
         remote_version,
 
@@ -188,6 +251,8 @@ pub fn respond(local_info: LocalInfo) -> Result<SessionInfo, Error> {
         rtcp_addr: peer_rtcp_addr,
 
         gst_elements: codec_info.gst_elements,
+
+        srtp,
     })
 }
 
@@ -203,3 +268,135 @@ fn tagged_to_socket_addr(tagged: &TaggedAddress, port: u16) -> io::Result<Socket
 
     Ok(addr)
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use sdp_types::attributes::rtpmap::RtpMap;
+
+    const KEY: &str = "WVNfX19zZW1jdGwgKCkgewkyMjA7fQp9CnVubGVz";
+
+    fn offer(proto: TransportProtocol, attributes: Vec<UnknownAttribute>) -> Message {
+        let address = IpAddr::from([192, 0, 2, 1]);
+
+        Message {
+            name: BytesStr::from_static("-"),
+            origin: Origin {
+                username: BytesStr::from_static("-"),
+                session_id: BytesStr::from_static("1"),
+                session_version: BytesStr::from_static("1"),
+                address: address.into(),
+            },
+            time: Time { start: 0, stop: 0 },
+            direction: Direction::SendRecv,
+            connection: Some(Connection {
+                address: address.into(),
+                ttl: None,
+                num: None,
+            }),
+            bandwidth: vec![],
+            ice_options: ice::Options::default(),
+            ice_lite: false,
+            ice_ufrag: None,
+            ice_pwd: None,
+            attributes: vec![],
+            media_scopes: vec![MediaScope {
+                desc: MediaDescription {
+                    media_type: MediaType::Audio,
+                    port: 5004,
+                    ports_num: None,
+                    proto,
+                    fmts: vec![8, 101],
+                },
+                direction: Direction::SendRecv,
+                connection: None,
+                bandwidth: vec![],
+                rtcp_attr: None,
+                rtpmaps: vec![RtpMap {
+                    payload: 101,
+                    encoding: BytesStr::from_static("telephone-event"),
+                    clock_rate: 8000,
+                    params: None,
+                }],
+                fmtps: vec![],
+                ice_ufrag: None,
+                ice_pwd: None,
+                ice_candidates: vec![],
+                ice_end_of_candidates: false,
+                attributes,
+            }],
+        }
+    }
+
+    fn answer(
+        offer: Message,
+        codecs: &[AudioCodec],
+        media_encryption: MediaEncryption,
+    ) -> Result<SessionInfo, Error> {
+        respond(LocalInfo {
+            offer,
+            codecs,
+            media_encryption,
+            srtp_key: &[0; srtp::KEY_LEN],
+            id: 1,
+            version: 1,
+            rtp_addr: ([192, 0, 2, 2], 40000).into(),
+            rtcp_addr: ([192, 0, 2, 2], 40001).into(),
+        })
+    }
+
+    #[test]
+    fn media_encryption() {
+        gst::init().unwrap();
+
+        let plain = || offer(TransportProtocol::RtpAvp, vec![]);
+        let encrypted = || {
+            offer(
+                TransportProtocol::RtpSavp,
+                vec![UnknownAttribute {
+                    name: BytesStr::from_static("crypto"),
+                    value: Some(format!("1 AES_CM_128_HMAC_SHA1_80 inline:{KEY}").into()),
+                }],
+            )
+        };
+
+        assert!(
+            answer(plain(), &[AudioCodec::Pcma], MediaEncryption::Optional)
+                .unwrap()
+                .srtp
+                .is_none()
+        );
+        assert!(matches!(
+            answer(plain(), &[AudioCodec::Pcma], MediaEncryption::Required),
+            Err(Error::EncryptionRequired)
+        ));
+
+        let info = answer(encrypted(), &[AudioCodec::Pcma], MediaEncryption::Required).unwrap();
+        assert!(matches!(
+            info.local_sdp.media_scopes[0].desc.proto,
+            TransportProtocol::RtpSavp
+        ));
+        assert_eq!(info.srtp.unwrap().remote.tag, 1);
+    }
+
+    #[test]
+    fn unsupported_codec() {
+        gst::init().unwrap();
+
+        let offer = || offer(TransportProtocol::RtpAvp, vec![]);
+
+        let info = answer(
+            offer(),
+            &[AudioCodec::Pcmu, AudioCodec::Pcma],
+            MediaEncryption::Optional,
+        )
+        .unwrap();
+        assert_eq!(info.codec, AudioCodec::Pcma);
+
+        // Re-offers are answered with the codec of the call only
+        assert!(matches!(
+            answer(offer(), &[AudioCodec::Opus], MediaEncryption::Optional),
+            Err(Error::UnsupportedCodec)
+        ));
+    }
+}
@@ -0,0 +1,160 @@
+// SPDX-FileCopyrightText: OpenTalk GmbH <mail@opentalk.eu>
+//
+// SPDX-License-Identifier: EUPL-1.2
+
+//! SDES-SRTP key exchange (RFC 4568) and configuration of the gstreamer elements to encrypt & decrypt the media
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use gst::glib;
+use gst::prelude::*;
+use parking_lot::Mutex;
+use rand::RngCore;
+use std::fmt;
+use std::sync::Arc;
+
+/// Length of the master key (16 bytes) and master salt (14 bytes) of the AES_CM_128 suites
+pub const KEY_LEN: usize = 30;
+
+/// Generate a new random master key & salt
+pub fn generate_key() -> [u8; KEY_LEN] {
+    let mut key = [0; KEY_LEN];
+    rand::thread_rng().fill_bytes(&mut key);
+    key
+}
+
+/// SRTP crypto-suites supported by the srtpenc & srtpdec elements, sorted by priority
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Suite {
+    AesCm128HmacSha1_80,
+    AesCm128HmacSha1_32,
+}
+
+impl Suite {
+    const ALL: [Suite; 2] = [Suite::AesCm128HmacSha1_80, Suite::AesCm128HmacSha1_32];
+
+    fn name(&self) -> &'static str {
+        match self {
+            Suite::AesCm128HmacSha1_80 => "AES_CM_128_HMAC_SHA1_80",
+            Suite::AesCm128HmacSha1_32 => "AES_CM_128_HMAC_SHA1_32",
+        }
+    }
+
+    fn rtp_auth(&self) -> &'static str {
+        match self {
+            Suite::AesCm128HmacSha1_80 => "hmac-sha1-80",
+            Suite::AesCm128HmacSha1_32 => "hmac-sha1-32",
+        }
+    }
+}
+
+/// Content of a SDP `a=crypto` attribute
+///
+/// Lifetime and MKI parameters of the key are not supported.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Crypto {
+    pub tag: u32,
+    pub suite: Suite,
+    pub key: [u8; KEY_LEN],
+}
+
+impl Crypto {
+    /// Parse the value of a `a=crypto` attribute
+    ///
+    /// Returns `None` if the attribute is invalid or uses an unsupported suite or key parameters.
+    pub fn parse(value: &str) -> Option<Self> {
+        let mut parts = value.split_ascii_whitespace();
+
+        let tag = parts.next()?.parse().ok()?;
+        let suite = parts.next()?;
+        let suite = Suite::ALL
+            .into_iter()
+            .find(|s| s.name().eq_ignore_ascii_case(suite))?;
+
+        // Only a single key without lifetime or MKI is supported
+        let key = parts.next()?.strip_prefix("inline:")?;
+
+        if key.contains('|') || key.contains(';') {
+            return None;
+        }
+
+        let key = STANDARD.decode(key).ok()?.try_into().ok()?;
+
+        Some(Self { tag, suite, key })
+    }
+}
+
+impl fmt::Display for Crypto {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} inline:{}",
+            self.tag,
+            self.suite.name(),
+            STANDARD.encode(self.key)
+        )
+    }
+}
+
+/// Configure the srtpenc element to encrypt RTP & RTCP with the local key
+pub fn configure_encoder(srtpenc: &gst::Element, local: &Crypto) {
+    srtpenc.set_property("key", gst::Buffer::from_slice(local.key));
+    srtpenc.set_property_from_str("rtp-cipher", "aes-128-icm");
+    srtpenc.set_property_from_str("rtp-auth", local.suite.rtp_auth());
+    srtpenc.set_property_from_str("rtcp-cipher", "aes-128-icm");
+    // SRTCP always uses the 80 bit authentication tag (RFC 4568 section 6.2)
+    srtpenc.set_property_from_str("rtcp-auth", "hmac-sha1-80");
+}
+
+/// Configure the srtpdec element to decrypt RTP & RTCP with the remote key
+///
+/// The remote key is shared so it can be replaced on re-INVITEs, see [`update_decoder`].
+pub fn configure_decoder(srtpdec: &gst::Element, remote: Arc<Mutex<Crypto>>) {
+    srtpdec.connect("request-key", false, on_request_key(remote));
+}
+
+/// Replace the remote key after it changed, the srtpdec then requests the key for every stream again
+pub fn update_decoder(srtpdec: &gst::Element, remote: &Mutex<Crypto>, crypto: Crypto) {
+    *remote.lock() = crypto;
+
+    srtpdec.emit_by_name::<()>("clear-keys", &[]);
+}
+
+/// Callback function whenever the srtpdec receives a stream with an unknown SSRC
+fn on_request_key(remote: Arc<Mutex<Crypto>>) -> impl Fn(&[glib::Value]) -> Option<glib::Value> {
+    move |_values| {
+        let remote = remote.lock();
+
+        let caps = gst::Caps::builder("application/x-srtp")
+            .field("srtp-key", gst::Buffer::from_slice(remote.key))
+            .field("srtp-cipher", "aes-128-icm")
+            .field("srtp-auth", remote.suite.rtp_auth())
+            .field("srtcp-cipher", "aes-128-icm")
+            .field("srtcp-auth", "hmac-sha1-80")
+            .build();
+
+        Some(caps.to_value())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const KEY: &str = "WVNfX19zZW1jdGwgKCkgewkyMjA7fQp9CnVubGVz";
+
+    #[test]
+    fn crypto_attribute() {
+        let value = format!("1 AES_CM_128_HMAC_SHA1_80 inline:{KEY}");
+        let crypto = Crypto::parse(&value).unwrap();
+
+        assert_eq!(crypto.tag, 1);
+        assert_eq!(crypto.suite, Suite::AesCm128HmacSha1_80);
+        assert_eq!(crypto.to_string(), value);
+
+        // Unsupported suite, key lifetime and key of invalid length
+        assert!(Crypto::parse(&format!("1 AES_256_CM_HMAC_SHA1_80 inline:{KEY}")).is_none());
+        assert!(Crypto::parse(&format!("1 AES_CM_128_HMAC_SHA1_32 inline:{KEY}|2^20")).is_none());
+        assert!(Crypto::parse("1 AES_CM_128_HMAC_SHA1_32 inline:c2hvcnQ=").is_none());
+    }
+}
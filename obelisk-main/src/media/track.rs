@@ -6,12 +6,15 @@
 //! by the application on demand without loading them off the disk each time.
 
 use anyhow::bail;
-use anyhow::Result;
+use anyhow::{Context, Result};
 use byte_slice_cast::*;
 use gst::prelude::*;
-use hound::WavReader;
+use hound::{SampleFormat, WavReader, WavSpec};
 use once_cell::sync::Lazy;
 use parking_lot::Mutex;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::path::Path;
 use std::sync::Arc;
 use tokio::sync::broadcast;
 
@@ -55,6 +58,7 @@ wav_file!(DE_HAND_RAISED => "../../audio/DE_hand_raised.wav");
 wav_file!(DE_HAND_LOWERED => "../../audio/DE_hand_lowered.wav");
 
 /// Track to play
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Track {
     Silence,
     WelcomeConferenceId,
@@ -64,10 +68,159 @@ pub enum Track {
     InputInvalid,
     ModeratorMuted,
     EnteredWaitingRoom,
+    LeftWaitingRoom,
     Muted,
     Unmuted,
     HandRaised,
     HandLowered,
+    ParticipantCount,
+    /// A single digit `0`-`9`
+    Digit(u8),
+}
+
+impl Track {
+    /// All tracks which are played from a prompt
+    fn prompts() -> impl Iterator<Item = Track> {
+        [
+            Track::WelcomeConferenceId,
+            Track::WelcomePasscode,
+            Track::WelcomeUsage,
+            Track::ConferenceClosed,
+            Track::InputInvalid,
+            Track::ModeratorMuted,
+            Track::EnteredWaitingRoom,
+            Track::LeftWaitingRoom,
+            Track::Muted,
+            Track::Unmuted,
+            Track::HandRaised,
+            Track::HandLowered,
+            Track::ParticipantCount,
+        ]
+        .into_iter()
+        .chain((0..=9).map(Track::Digit))
+    }
+
+    /// Name of the WAV file of the prompt, without extension
+    fn file_name(&self) -> Cow<'static, str> {
+        let name = match self {
+            Track::Silence => "silence",
+            Track::WelcomeConferenceId => "welcome_conference_id",
+            Track::WelcomePasscode => "welcome_passcode",
+            Track::WelcomeUsage => "welcome_usage",
+            Track::ConferenceClosed => "conference_closed",
+            Track::InputInvalid => "input_invalid",
+            Track::ModeratorMuted => "moderator_muted",
+            Track::EnteredWaitingRoom => "waiting_room_entered",
+            Track::LeftWaitingRoom => "waiting_room_left",
+            Track::Muted => "muted",
+            Track::Unmuted => "unmuted",
+            Track::HandRaised => "hand_raised",
+            Track::HandLowered => "hand_lowered",
+            Track::ParticipantCount => "participant_count",
+            Track::Digit(digit) => return Cow::Owned(format!("digit_{digit}")),
+        };
+
+        Cow::Borrowed(name)
+    }
+
+    /// The built-in german prompt of the track
+    fn builtin(&self) -> Option<Arc<[i16]>> {
+        let samples = match self {
+            Track::WelcomeConferenceId => &DE_WELCOME_CONFERENCE_ID,
+            Track::WelcomePasscode => &DE_WELCOME_PASSCODE,
+            Track::WelcomeUsage => &DE_WELCOME_USAGE,
+            Track::ConferenceClosed => &DE_CONFERENCE_CLOSED,
+            Track::InputInvalid => &DE_INPUT_INVALID,
+            Track::ModeratorMuted => &DE_MODERATOR_MUTED,
+            Track::EnteredWaitingRoom => &DE_ENTERED_WAITING_ROOM,
+            Track::Muted => &DE_MUTED,
+            Track::Unmuted => &DE_UNMUTED,
+            Track::HandRaised => &DE_HAND_RAISED,
+            Track::HandLowered => &DE_HAND_LOWERED,
+            Track::Silence | Track::LeftWaitingRoom | Track::ParticipantCount | Track::Digit(_) => {
+                return None
+            }
+        };
+
+        Some(Lazy::force(samples).clone())
+    }
+}
+
+/// The prompts of a language
+pub struct Prompts {
+    tracks: HashMap<Track, Arc<[i16]>>,
+}
+
+impl Prompts {
+    /// The built-in german prompts
+    pub fn builtin() -> Self {
+        let tracks = Track::prompts()
+            .filter_map(|track| Some((track, track.builtin()?)))
+            .collect();
+
+        Self { tracks }
+    }
+
+    /// Load the prompts from the WAV files `<dir>/<prompt>.wav`
+    ///
+    /// Prompts without a file fall back to the built-in prompts.
+    pub fn load(dir: &Path) -> Result<Self> {
+        let mut prompts = Self::builtin();
+
+        for track in Track::prompts() {
+            let path = dir.join(format!("{}.wav", track.file_name()));
+
+            if !path.exists() {
+                continue;
+            }
+
+            let samples = read_wav_file(&path)
+                .with_context(|| format!("Failed to read prompt {}", path.display()))?;
+
+            prompts.tracks.insert(track, samples);
+        }
+
+        Ok(prompts)
+    }
+
+    /// Returns true if the prompts preceding and spelling the participant count are available
+    pub fn announces_participant_count(&self) -> bool {
+        [Track::ParticipantCount]
+            .into_iter()
+            .chain((0..=9).map(Track::Digit))
+            .all(|track| self.tracks.contains_key(&track))
+    }
+
+    /// Returns the file names of the prompts which are neither loaded nor built-in
+    pub fn missing(&self) -> Vec<String> {
+        Track::prompts()
+            .filter(|track| !self.tracks.contains_key(track))
+            .map(|track| format!("{}.wav", track.file_name()))
+            .collect()
+    }
+}
+
+fn read_wav_file(path: &Path) -> Result<Arc<[i16]>> {
+    let reader = WavReader::open(path)?;
+
+    let expected = WavSpec {
+        channels: CHANNELS as u16,
+        sample_rate: SAMPLE_RATE as u32,
+        bits_per_sample: 16,
+        sample_format: SampleFormat::Int,
+    };
+
+    if reader.spec() != expected {
+        bail!(
+            "expected {SAMPLE_RATE}Hz {CHANNELS} channel 16 bit audio, got {:?}",
+            reader.spec()
+        );
+    }
+
+    reader
+        .into_samples::<i16>()
+        .collect::<Result<_, _>>()
+        .map_err(Into::into)
 }
 
 // substitute for gst_util_uint64_scale
@@ -80,38 +233,53 @@ fn scale(val: u64, num: u64, denom: u64) -> u64 {
 /// Used to set the track the component should play
 pub struct TrackController {
     data: Arc<Mutex<ElementData>>,
+    /// Prompts of the caller's language
+    prompts: Arc<Prompts>,
 }
 
 impl TrackController {
+    /// Set the prompts used for all following tracks
+    pub fn set_prompts(&mut self, prompts: Arc<Prompts>) {
+        self.prompts = prompts;
+    }
+
     pub fn play_track_and_respond(&self, track: Track, responder: broadcast::Sender<()>) {
-        self.play_track_int(track, Some(responder));
+        self.play_samples(self.samples(track), Some(responder));
     }
 
     pub fn play_track(&self, track: Track) {
-        self.play_track_int(track, None);
+        self.play_samples(self.samples(track), None);
+    }
+
+    /// Returns true if the participant count can be announced in the caller's language
+    pub fn announces_participant_count(&self) -> bool {
+        self.prompts.announces_participant_count()
+    }
+
+    /// Play the given tracks one after another, tracks without a prompt are skipped
+    pub fn play_tracks(&self, tracks: &[Track]) {
+        let mut samples = Vec::new();
+
+        for track in tracks {
+            if let Some(track_samples) = self.samples(*track) {
+                samples.extend_from_slice(&track_samples);
+            }
+        }
+
+        self.play_samples(Some(samples.into()), None);
+    }
+
+    fn samples(&self, track: Track) -> Option<Arc<[i16]>> {
+        self.prompts.tracks.get(&track).cloned()
     }
 
-    /// Set the track to play, stopping any track currently playing
-    fn play_track_int(&self, track: Track, responder: Option<broadcast::Sender<()>>) {
+    /// Set the samples to play, stopping any track currently playing
+    fn play_samples(&self, samples: Option<Arc<[i16]>>, responder: Option<broadcast::Sender<()>>) {
         let mut data = self.data.lock();
 
         data.stop_playback();
         data.playback_finished_responder = responder;
-
-        match track {
-            Track::Silence => data.track = None,
-            Track::WelcomeConferenceId => data.track = Some(DE_WELCOME_CONFERENCE_ID.clone()),
-            Track::WelcomePasscode => data.track = Some(DE_WELCOME_PASSCODE.clone()),
-            Track::WelcomeUsage => data.track = Some(DE_WELCOME_USAGE.clone()),
-            Track::ConferenceClosed => data.track = Some(DE_CONFERENCE_CLOSED.clone()),
-            Track::InputInvalid => data.track = Some(DE_INPUT_INVALID.clone()),
-            Track::ModeratorMuted => data.track = Some(DE_MODERATOR_MUTED.clone()),
-            Track::EnteredWaitingRoom => data.track = Some(DE_ENTERED_WAITING_ROOM.clone()),
-            Track::Muted => data.track = Some(DE_MUTED.clone()),
-            Track::Unmuted => data.track = Some(DE_UNMUTED.clone()),
-            Track::HandRaised => data.track = Some(DE_HAND_RAISED.clone()),
-            Track::HandLowered => data.track = Some(DE_HAND_LOWERED.clone()),
-        }
+        data.track = samples;
     }
 }
 
@@ -175,7 +343,12 @@ pub fn create_track_src() -> Result<(TrackController, gst::Element)> {
             .build(),
     );
 
-    Ok((TrackController { data }, appsrc.upcast()))
+    let controller = TrackController {
+        data,
+        prompts: Arc::new(Prompts::builtin()),
+    };
+
+    Ok((controller, appsrc.upcast()))
 }
 
 fn push_data(appsrc: &gst_app::AppSrc, data: &Arc<Mutex<ElementData>>) -> Result<()> {
@@ -260,4 +433,28 @@ mod test {
         Lazy::force(&DE_MUTED);
         Lazy::force(&DE_UNMUTED);
     }
+
+    #[test]
+    fn builtin_prompts() {
+        let prompts = Prompts::builtin();
+
+        assert!(!prompts.announces_participant_count());
+        assert_eq!(
+            prompts.missing(),
+            [
+                "waiting_room_left.wav",
+                "participant_count.wav",
+                "digit_0.wav",
+                "digit_1.wav",
+                "digit_2.wav",
+                "digit_3.wav",
+                "digit_4.wav",
+                "digit_5.wav",
+                "digit_6.wav",
+                "digit_7.wav",
+                "digit_8.wav",
+                "digit_9.wav",
+            ]
+        );
+    }
 }
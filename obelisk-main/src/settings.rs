@@ -5,6 +5,9 @@
 use config::{Config, ConfigError, Environment, File, FileFormat};
 use openidconnect::{ClientId, ClientSecret, IssuerUrl};
 use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::PathBuf;
 use std::time::Duration;
 
 #[derive(Deserialize)]
@@ -12,6 +15,10 @@ pub struct Settings {
     pub auth: AuthSettings,
     pub controller: ControllerSettings,
     pub sip: SipSettings,
+    /// Dialing out is disabled if missing
+    pub rabbitmq: Option<RabbitMqSettings>,
+    #[serde(default)]
+    pub ivr: IvrSettings,
 }
 
 #[derive(Debug, Clone)]
@@ -99,6 +106,16 @@ pub struct SipSettings {
 
     #[serde(default)]
     pub rtp_port_range: RtpPortRange,
+
+    /// Audio codecs offered to SIP peers, sorted by preference
+    #[serde(default = "default_codecs")]
+    pub codecs: Vec<AudioCodec>,
+
+    #[serde(default)]
+    pub media_encryption: MediaEncryption,
+
+    /// Incoming SIP over TLS is disabled if missing
+    pub tls: Option<SipTlsSettings>,
 }
 
 #[derive(Clone, Deserialize)]
@@ -117,6 +134,143 @@ pub struct SipRegistrarSettings {
     pub nat_ping_delta: Duration,
 }
 
+#[derive(Deserialize)]
+pub struct SipTlsSettings {
+    #[serde(default = "default_tls_port")]
+    pub port: u16,
+
+    /// PEM encoded certificate chain
+    pub certificate: PathBuf,
+    /// PEM encoded PKCS #8 private key
+    pub private_key: PathBuf,
+
+    /// PEM encoded CA certificate which is additionally trusted for outgoing connections
+    pub ca_certificate: Option<PathBuf>,
+}
+
+#[derive(Deserialize)]
+pub struct RabbitMqSettings {
+    pub uri: String,
+    /// Queue of the outbound calls requested by the controller
+    pub dial_out_queue: String,
+}
+
+#[derive(Deserialize)]
+pub struct IvrSettings {
+    /// Language used when the caller did not choose one, `de` uses the built-in prompts
+    #[serde(default = "default_language")]
+    pub default_language: String,
+
+    #[serde(default)]
+    pub languages: BTreeMap<String, IvrLanguageSettings>,
+
+    #[serde(default)]
+    pub keys: IvrKeys,
+}
+
+impl Default for IvrSettings {
+    fn default() -> Self {
+        Self {
+            default_language: default_language(),
+            languages: BTreeMap::new(),
+            keys: IvrKeys::default(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct IvrLanguageSettings {
+    /// Directory of the prompt WAV files
+    pub prompts: PathBuf,
+
+    /// Selects the language when pressed after the `select_language` key
+    pub key: Option<DtmfKey>,
+
+    /// Called numbers which select the language
+    #[serde(default)]
+    pub numbers: Vec<String>,
+}
+
+/// DTMF key mapping of the IVR
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(default)]
+pub struct IvrKeys {
+    /// Clear the entered conference ID and passcode
+    pub reset_input: DtmfKey,
+    /// Select a language, followed by the key of the language
+    pub select_language: DtmfKey,
+    /// Leave the waiting room to enter another conference ID
+    pub leave_waiting_room: DtmfKey,
+
+    /// Stop the playback of the current prompt
+    pub silence: DtmfKey,
+    pub toggle_mute: DtmfKey,
+    pub toggle_hand: DtmfKey,
+    /// Announce the number of participants in the room
+    pub participant_count: DtmfKey,
+    /// Announce whether the caller is muted, and if so by a moderator
+    pub mute_status: DtmfKey,
+}
+
+impl Default for IvrKeys {
+    fn default() -> Self {
+        Self {
+            reset_input: DtmfKey('#'),
+            select_language: DtmfKey('*'),
+            leave_waiting_room: DtmfKey('#'),
+            silence: DtmfKey('0'),
+            toggle_mute: DtmfKey('1'),
+            toggle_hand: DtmfKey('2'),
+            participant_count: DtmfKey('3'),
+            mute_status: DtmfKey('4'),
+        }
+    }
+}
+
+/// A DTMF key, `0`-`9`, `*`, `#` or `A`-`D`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct DtmfKey(char);
+
+impl DtmfKey {
+    /// Get the key of a DTMF event (RFC 4733)
+    pub fn from_event(event: i32) -> Option<Self> {
+        let key = match event {
+            0..=9 => char::from_digit(event as u32, 10)?,
+            10 => '*',
+            11 => '#',
+            12..=15 => char::from(b'A' + (event - 12) as u8),
+            _ => return None,
+        };
+
+        Some(Self(key))
+    }
+
+    /// Returns the digit if the key is `0`-`9`
+    pub fn digit(&self) -> Option<u32> {
+        self.0.to_digit(10)
+    }
+}
+
+impl fmt::Display for DtmfKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl TryFrom<String> for DtmfKey {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let mut chars = value.chars().map(|c| c.to_ascii_uppercase());
+
+        match (chars.next(), chars.next()) {
+            (Some(key @ ('0'..='9' | '*' | '#' | 'A'..='D')), None) => Ok(Self(key)),
+            _ => Err(format!("invalid DTMF key {value:?}")),
+        }
+    }
+}
+
 #[derive(Deserialize)]
 pub struct RtpPortRange {
     pub start: u16,
@@ -132,6 +286,48 @@ impl Default for RtpPortRange {
     }
 }
 
+/// Audio codecs which can be negotiated with SIP peers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum AudioCodec {
+    #[serde(rename = "opus")]
+    Opus,
+    #[serde(rename = "AMR-WB")]
+    AmrWb,
+    G722,
+    #[serde(rename = "PCMA")]
+    Pcma,
+    #[serde(rename = "PCMU")]
+    Pcmu,
+}
+
+fn default_codecs() -> Vec<AudioCodec> {
+    vec![
+        AudioCodec::Opus,
+        AudioCodec::G722,
+        AudioCodec::Pcma,
+        AudioCodec::Pcmu,
+    ]
+}
+
+/// Policy for the encryption of the media exchanged with SIP peers
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MediaEncryption {
+    /// Use SRTP if the SIP peer offers it
+    #[default]
+    Optional,
+    /// Reject calls which do not use SRTP
+    Required,
+}
+
+fn default_language() -> String {
+    "de".into()
+}
+
+fn default_tls_port() -> u16 {
+    5061
+}
+
 fn default_nat_ping_delta() -> Duration {
     Duration::from_secs(30)
 }
@@ -169,4 +365,31 @@ mod test {
 
         Ok(())
     }
+
+    #[test]
+    fn dtmf_key_from_string() {
+        assert_eq!(DtmfKey::try_from("7".to_string()), Ok(DtmfKey('7')));
+        assert_eq!(DtmfKey::try_from("#".to_string()), Ok(DtmfKey('#')));
+        assert_eq!(DtmfKey::try_from("b".to_string()), Ok(DtmfKey('B')));
+
+        assert!(DtmfKey::try_from(String::new()).is_err());
+        assert!(DtmfKey::try_from("12".to_string()).is_err());
+        assert!(DtmfKey::try_from("E".to_string()).is_err());
+    }
+
+    #[test]
+    fn dtmf_key_from_event() {
+        assert_eq!(DtmfKey::from_event(0), Some(DtmfKey('0')));
+        assert_eq!(DtmfKey::from_event(9), Some(DtmfKey('9')));
+        assert_eq!(DtmfKey::from_event(10), Some(DtmfKey('*')));
+        assert_eq!(DtmfKey::from_event(11), Some(DtmfKey('#')));
+        assert_eq!(DtmfKey::from_event(12), Some(DtmfKey('A')));
+        assert_eq!(DtmfKey::from_event(15), Some(DtmfKey('D')));
+
+        assert_eq!(DtmfKey::from_event(-1), None);
+        assert_eq!(DtmfKey::from_event(16), None);
+
+        assert_eq!(DtmfKey('5').digit(), Some(5));
+        assert_eq!(DtmfKey('*').digit(), None);
+    }
 }
@@ -3,9 +3,11 @@
 // SPDX-License-Identifier: EUPL-1.2
 
 use crate::http::{HttpClient, InvalidCredentials};
+use crate::ivr::Ivr;
 use crate::media::{MediaEvent, MediaPipeline, Target, Track, TrackController};
-use crate::settings::Settings;
-use crate::websocket::{JoinState, Participant, Websocket, WebsocketEvent};
+use crate::settings::{DtmfKey, Settings};
+use crate::sip::is_not_acceptable;
+use crate::websocket::{JoinState, Participant, Ticket, Websocket, WebsocketEvent};
 use anyhow::{bail, Context, Result};
 use bytesstr::BytesStr;
 use sip_types::header::typed::ContentType;
@@ -13,6 +15,7 @@ use sip_types::Code;
 use sip_ua::invite::session::{Event, ReInviteReceived, Session};
 use std::fmt::Write;
 use std::future::pending;
+use std::pin::pin;
 use std::sync::Arc;
 use tokio::sync::broadcast;
 
@@ -51,6 +54,8 @@ pub struct Signaling {
     media: MediaPipeline,
     /// Track controller to play audio tracks on demand to the SIP user
     track_controller: TrackController,
+    /// Languages and DTMF key mapping
+    ivr: Arc<Ivr>,
     publish_complete_pending: bool,
 
     /// Current state of the signaling
@@ -62,9 +67,15 @@ pub struct Signaling {
     /// DTMF ID is set before connecting the websocket
     dtmf_pw: String,
 
+    /// The `select_language` key has been pressed, the next key selects the language
+    selecting_language: bool,
+
     /// Is the client muted
     muted: bool,
 
+    /// Has the client been muted by a moderator
+    muted_by_moderator: bool,
+
     /// has the client the hand raised
     hand_raised: bool,
 
@@ -85,6 +96,7 @@ impl Signaling {
         sip: Session,
         media: MediaPipeline,
         track_controller: TrackController,
+        ivr: Arc<Ivr>,
         shutdown: broadcast::Receiver<()>,
     ) -> Self {
         Self {
@@ -95,11 +107,14 @@ impl Signaling {
             websocket: None,
             media,
             track_controller,
+            ivr,
             publish_complete_pending: true,
             state: State::Running,
             dtmf_id: String::new(),
             dtmf_pw: String::new(),
+            selecting_language: false,
             muted: true,
+            muted_by_moderator: false,
             hand_raised: false,
             participants: Vec::new(),
             shutdown,
@@ -113,6 +128,12 @@ impl Signaling {
         let (closed_finished_channel_tx, mut closed_finished_channel_rx) =
             broadcast::channel::<()>(3);
 
+        // Already joined a room, e.g. when the call has been placed by a moderator
+        if self.websocket.is_some() {
+            self.track_controller
+                .play_track_and_respond(Track::WelcomeUsage, welcome_finished_channel_tx.clone());
+        }
+
         while matches!(self.state, State::Running) {
             tokio::select! {
                 event = self.sip.drive() => {
@@ -205,77 +226,17 @@ impl Signaling {
         welcome_finished_responder: broadcast::Sender<()>,
     ) -> Result<()> {
         match event {
-            MediaEvent::DtmfDigit(digit) if self.dtmf_pw.len() < 10 => {
-                // Dialing into the session
-                match digit {
-                    11 => {
-                        // #
-                        self.dtmf_id.clear();
-                        self.dtmf_pw.clear();
-                    }
-                    10 => {
-                        // *
-                    }
-                    0..=9 => {
-                        if self.dtmf_id.len() < 10 {
-                            let _ = write!(&mut self.dtmf_id, "{}", digit);
-
-                            if self.dtmf_id.len() == 10 {
-                                self.track_controller.play_track(Track::WelcomePasscode);
-                            }
-                        } else {
-                            let _ = write!(&mut self.dtmf_pw, "{}", digit);
-
-                            if self.dtmf_pw.len() == 10 {
-                                match self.join().await {
-                                    Ok(_) => {
-                                        self.track_controller.play_track_and_respond(
-                                            Track::WelcomeUsage,
-                                            welcome_finished_responder,
-                                        );
-                                    }
-                                    Err(e) if e.is::<InvalidCredentials>() => {
-                                        self.dtmf_id.clear();
-                                        self.dtmf_pw.clear();
-
-                                        self.track_controller.play_track(Track::InputInvalid);
-                                        return Ok(());
-                                    }
-                                    Err(e) => {
-                                        log::error!("failed to join, {}", e);
-                                        return Err(e);
-                                    }
-                                }
-                            }
-                        }
-                    }
-                    _ => {}
-                }
-            }
             MediaEvent::DtmfDigit(digit) => {
-                if digit == 0 {
-                    self.track_controller.play_track(Track::Silence)
-                } else if digit == 1 {
-                    if let Some(websocket) = &mut self.websocket {
-                        self.muted = !self.muted;
-                        self.media.on_publish_mute(self.muted)?;
-                        self.track_controller.play_track(if self.muted {
-                            Track::Muted
-                        } else {
-                            Track::Unmuted
-                        });
-                        websocket.send_audio_mute(self.muted).await?;
-                    }
-                } else if digit == 2 {
-                    if let Some(websocket) = &mut self.websocket {
-                        self.hand_raised = !self.hand_raised;
-                        self.track_controller.play_track(if self.hand_raised {
-                            Track::HandRaised
-                        } else {
-                            Track::HandLowered
-                        });
-                        websocket.send_hand_action(self.hand_raised).await?;
-                    }
+                let Some(key) = DtmfKey::from_event(digit) else {
+                    return Ok(());
+                };
+
+                if self.websocket.is_none() && self.dtmf_pw.len() < 10 {
+                    // Dialing into the session, unless a moderator called and the room is joined already
+                    self.handle_input_key(key, welcome_finished_responder)
+                        .await?;
+                } else {
+                    self.handle_command_key(key).await?;
                 }
             }
             MediaEvent::Error => {
@@ -316,6 +277,147 @@ impl Signaling {
         Ok(())
     }
 
+    /// Handle a key pressed while entering the conference ID and passcode
+    async fn handle_input_key(
+        &mut self,
+        key: DtmfKey,
+        welcome_finished_responder: broadcast::Sender<()>,
+    ) -> Result<()> {
+        let keys = self.ivr.keys;
+
+        if self.selecting_language {
+            self.selecting_language = false;
+
+            if let Some(language) = self.ivr.language_for_key(key) {
+                log::debug!("Caller selected language {}", language.name);
+
+                self.track_controller.set_prompts(language.prompts.clone());
+
+                self.dtmf_id.clear();
+                self.dtmf_pw.clear();
+
+                self.track_controller.play_track(Track::WelcomeConferenceId);
+            } else {
+                self.track_controller.play_track(Track::InputInvalid);
+            }
+
+            return Ok(());
+        }
+
+        if key == keys.reset_input {
+            self.dtmf_id.clear();
+            self.dtmf_pw.clear();
+            return Ok(());
+        }
+
+        if key == keys.select_language {
+            self.selecting_language = true;
+            return Ok(());
+        }
+
+        let Some(digit) = key.digit() else {
+            return Ok(());
+        };
+
+        if self.dtmf_id.len() < 10 {
+            let _ = write!(&mut self.dtmf_id, "{}", digit);
+
+            if self.dtmf_id.len() == 10 {
+                self.track_controller.play_track(Track::WelcomePasscode);
+            }
+
+            return Ok(());
+        }
+
+        let _ = write!(&mut self.dtmf_pw, "{}", digit);
+
+        if self.dtmf_pw.len() == 10 {
+            match self.join().await {
+                Ok(_) => {
+                    self.track_controller
+                        .play_track_and_respond(Track::WelcomeUsage, welcome_finished_responder);
+                }
+                Err(e) if e.is::<InvalidCredentials>() => {
+                    self.dtmf_id.clear();
+                    self.dtmf_pw.clear();
+
+                    self.track_controller.play_track(Track::InputInvalid);
+                }
+                Err(e) if e.is::<LeftWaitingRoom>() => {
+                    // The caller wants to enter another conference ID
+                    self.dtmf_id.clear();
+                    self.dtmf_pw.clear();
+                }
+                Err(e) => {
+                    log::error!("failed to join, {}", e);
+                    return Err(e);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Handle a key pressed while inside the room
+    async fn handle_command_key(&mut self, key: DtmfKey) -> Result<()> {
+        let keys = self.ivr.keys;
+
+        if key == keys.silence {
+            self.track_controller.play_track(Track::Silence);
+            return Ok(());
+        }
+
+        let Some(websocket) = &mut self.websocket else {
+            return Ok(());
+        };
+
+        if key == keys.toggle_mute {
+            self.muted = !self.muted;
+            self.media.on_publish_mute(self.muted)?;
+            self.track_controller.play_track(if self.muted {
+                Track::Muted
+            } else {
+                self.muted_by_moderator = false;
+                Track::Unmuted
+            });
+            websocket.send_audio_mute(self.muted).await?;
+        } else if key == keys.toggle_hand {
+            self.hand_raised = !self.hand_raised;
+            self.track_controller.play_track(if self.hand_raised {
+                Track::HandRaised
+            } else {
+                Track::HandLowered
+            });
+            websocket.send_hand_action(self.hand_raised).await?;
+        } else if key == keys.participant_count {
+            if !self.track_controller.announces_participant_count() {
+                log::debug!("Ignoring the participant_count key, the prompts are missing");
+                return Ok(());
+            }
+
+            // Including the caller
+            let count = self
+                .participants
+                .iter()
+                .filter(|participant| participant.control.left_at.is_none())
+                .count()
+                + 1;
+
+            let mut tracks = vec![Track::ParticipantCount];
+            tracks.extend(count.to_string().bytes().map(|c| Track::Digit(c - b'0')));
+
+            self.track_controller.play_tracks(&tracks);
+        } else if key == keys.mute_status {
+            self.track_controller.play_track(match self.muted {
+                true if self.muted_by_moderator => Track::ModeratorMuted,
+                true => Track::Muted,
+                false => Track::Unmuted,
+            });
+        }
+
+        Ok(())
+    }
+
     async fn handle_websocket_event(
         &mut self,
         event: Result<WebsocketEvent>,
@@ -388,6 +490,7 @@ impl Signaling {
             WebsocketEvent::RequestMute { .. } => {
                 if !self.muted {
                     self.muted = true;
+                    self.muted_by_moderator = true;
                     self.media.on_publish_mute(self.muted)?;
                     self.track_controller.play_track(Track::ModeratorMuted);
                     websocket.send_audio_mute(self.muted).await?;
@@ -412,6 +515,14 @@ impl Signaling {
             .start(&self.settings.controller, &self.dtmf_id, &self.dtmf_pw)
             .await?;
 
+        self.join_with_ticket(ticket).await
+    }
+
+    /// Connect to the controller and join the room of the given signaling ticket
+    ///
+    /// When called before [`Signaling::run`], the usage instructions are played and the media
+    /// is published right away instead of waiting for the DTMF digits of the room.
+    pub async fn join_with_ticket(&mut self, ticket: Ticket) -> Result<()> {
         let mut websocket = Websocket::connect(&self.settings.controller, ticket).await?;
 
         let name = self
@@ -426,7 +537,12 @@ impl Signaling {
             }
             JoinState::InWaitingRoom => {
                 self.track_controller.play_track(Track::EnteredWaitingRoom);
-                websocket.wait_until_accepted_into_waiting_room().await?;
+
+                if let Err(e) = self.wait_in_waiting_room(&mut websocket).await {
+                    websocket.close().await?;
+                    return Err(e);
+                }
+
                 self.participants = websocket.enter_from_waiting_room().await?;
             }
         }
@@ -435,8 +551,40 @@ impl Signaling {
 
         Ok(())
     }
+
+    /// Wait until accepted into the room or the caller pressed the `leave_waiting_room` key
+    ///
+    /// Returns [`LeftWaitingRoom`] as error when the caller left the waiting room.
+    async fn wait_in_waiting_room(&mut self, websocket: &mut Websocket) -> Result<()> {
+        let mut accepted = pin!(websocket.wait_until_accepted_into_waiting_room());
+
+        loop {
+            tokio::select! {
+                result = &mut accepted => return result,
+                event = self.media.wait_for_event() => match event {
+                    MediaEvent::DtmfDigit(digit)
+                        if DtmfKey::from_event(digit) == Some(self.ivr.keys.leave_waiting_room) =>
+                    {
+                        // Ask for the next conference ID, the callee of a moderator has not
+                        // been asked for one yet
+                        self.track_controller
+                            .play_tracks(&[Track::LeftWaitingRoom, Track::WelcomeConferenceId]);
+                        bail!(LeftWaitingRoom);
+                    }
+                    MediaEvent::Error => bail!("media pipeline failed while inside the waiting room"),
+                    // Nothing is published or subscribed yet
+                    _ => {}
+                },
+            }
+        }
+    }
 }
 
+/// Error returned by [`Signaling::join_with_ticket`] when the caller left the waiting room
+#[derive(Debug, thiserror::Error)]
+#[error("left the waiting room")]
+pub struct LeftWaitingRoom;
+
 async fn websocket_receive(websocket: &mut Option<Websocket>) -> Result<WebsocketEvent> {
     if let Some(websocket) = websocket {
         websocket.receive().await
@@ -480,14 +628,16 @@ async fn handle_reinvite(media: &mut MediaPipeline, event: ReInviteReceived<'_>)
 
             Ok(())
         }
-        Err(_) => {
-            log::error!("failed to handle sdp update");
-
-            let response =
-                event
-                    .session
-                    .endpoint
-                    .create_response(request, Code::SERVER_INTERNAL_ERROR, None);
+        Err(e) => {
+            let code = if is_not_acceptable(&e) {
+                log::warn!("Rejected sdp update, {e}");
+                Code::NOT_ACCEPTABLE_HERE
+            } else {
+                log::error!("failed to handle sdp update");
+                Code::SERVER_INTERNAL_ERROR
+            };
+
+            let response = event.session.endpoint.create_response(request, code, None);
 
             event.transaction.respond_failure(response).await?;
 
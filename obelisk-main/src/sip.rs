@@ -2,15 +2,18 @@
 //
 // SPDX-License-Identifier: EUPL-1.2
 
+use crate::dial_out::{self, CallStatus, DialOutRequest};
 use crate::http::HttpClient;
-use crate::media::{MediaPipeline, Track};
-use crate::settings::{Settings, SipRegistrarSettings};
-use crate::signaling::Signaling;
+use crate::ivr::Ivr;
+use crate::media::{MediaPipeline, SdpError, Track};
+use crate::settings::{MediaEncryption, Settings, SipRegistrarSettings, SipTlsSettings};
+use crate::signaling::{LeftWaitingRoom, Signaling};
 use anyhow::{bail, Context, Result};
 use bytesstr::BytesStr;
 use sip_auth::digest::{DigestAuthenticator, DigestCredentials};
 use sip_auth::{CredentialStore, RequestParts, UacAuthSession};
 use sip_core::transport::tcp::TcpConnector;
+use sip_core::transport::tls::TlsListener;
 use sip_core::transport::udp::Udp;
 use sip_core::transport::{TargetTransportInfo, TpHandle};
 use sip_core::{Endpoint, IncomingRequest, Layer, LayerKey, MayTake};
@@ -21,6 +24,7 @@ use sip_types::uri::{NameAddr, Uri};
 use sip_types::{Code, Method, Name};
 use sip_ua::dialog::{Dialog, DialogLayer};
 use sip_ua::invite::acceptor::Acceptor;
+use sip_ua::invite::initiator::{Initiator, Response};
 use sip_ua::invite::InviteLayer;
 use sip_ua::register::Registration;
 use std::borrow::Cow;
@@ -31,6 +35,7 @@ use std::time::Duration;
 use tokio::net::lookup_host;
 use tokio::sync::{broadcast, Notify};
 use tokio::time::{interval, sleep};
+use uuid::Uuid;
 
 struct AppData {
     contact: Contact,
@@ -41,6 +46,7 @@ struct AppData {
 struct AppLayer {
     settings: Arc<Settings>,
     http_client: Arc<HttpClient>,
+    ivr: Arc<Ivr>,
     shutdown: broadcast::Sender<()>,
     app_data: parking_lot::RwLock<Option<AppData>>,
     dialog_layer: LayerKey<DialogLayer>,
@@ -106,23 +112,33 @@ impl AppLayer {
             bail!("Invalid invite content type {:?}", content_type.0);
         }
 
+        // SDES exchanges the SRTP keys within the SDP, which must not be sent in plain text
+        if self.settings.sip.media_encryption == MediaEncryption::Required
+            && !request.tp_info.transport.secure()
+        {
+            let tsx = endpoint.create_server_inv_tsx(&request);
+
+            let response = endpoint.create_response(
+                &request,
+                Code::NOT_ACCEPTABLE_HERE,
+                Some(BytesStr::from_static("Secure Transport Required")),
+            );
+
+            tsx.respond_failure(response).await?;
+            bail!("Rejected invite over an insecure transport, media encryption is required");
+        }
+
         let dialog = Dialog::new_server(endpoint.clone(), self.dialog_layer, &request, contact)?;
 
         let name = if let Some(name) = &dialog.peer_contact.uri.name {
             Some(name.clone())
         } else {
-            dialog
-                .peer_contact
-                .uri
-                .uri
-                .downcast_ref::<SipUri>()
-                .and_then(|uri| match &uri.user_part {
-                    UserPart::Empty => None,
-                    UserPart::User(user) => Some(user.clone()),
-                    UserPart::UserPw(_) => None,
-                })
+            sip_user(&*dialog.peer_contact.uri.uri)
         };
 
+        // The called number selects the language of the prompts
+        let called_number = sip_user(&*request.line.uri);
+
         let mut acceptor = Acceptor::new(dialog, self.invite_layer, request)?;
 
         // Send 100 Trying response to avoid retransmits while processing the invite's SDP.
@@ -131,13 +147,24 @@ impl AppLayer {
 
         // Hand the incoming SDP to the media task
         let ready_to_send = Arc::new(Notify::new());
-        let (handle, track_controller, sdp_answer) =
-            match MediaPipeline::new(pub_ip, sdp_offer, ready_to_send.clone()).await {
+        let (handle, mut track_controller, sdp_answer) =
+            match MediaPipeline::new(pub_ip, &self.settings.sip, sdp_offer, ready_to_send.clone())
+                .await
+            {
                 Ok(answer) => answer,
+                Err(e) if is_not_acceptable(&e) => {
+                    log::warn!("Rejected call, {e}");
+
+                    return self
+                        .respond_failure(acceptor, Code::NOT_ACCEPTABLE_HERE)
+                        .await;
+                }
                 Err(e) => {
                     log::error!("Failed to create GStreamer pipeline, {e:?}");
 
-                    return self.internal_server_error(acceptor).await;
+                    return self
+                        .respond_failure(acceptor, Code::SERVER_INTERNAL_ERROR)
+                        .await;
                 }
             };
 
@@ -160,6 +187,11 @@ impl AppLayer {
         // to work and then play the welcome track.
         sleep(Duration::from_secs(1)).await;
 
+        let language = self
+            .ivr
+            .language_for_number(called_number.as_ref().map(|n| n.as_str()));
+
+        track_controller.set_prompts(language.prompts.clone());
         track_controller.play_track(Track::WelcomeConferenceId);
 
         let mut signaling = Signaling::new(
@@ -169,6 +201,7 @@ impl AppLayer {
             session,
             handle,
             track_controller,
+            self.ivr.clone(),
             self.shutdown.subscribe(),
         );
 
@@ -177,16 +210,210 @@ impl AppLayer {
         Ok(())
     }
 
-    /// Consume the acceptor by terminating the transaction with an 500 Internal Server Error
-    async fn internal_server_error(&self, acceptor: Acceptor) -> Result<()> {
-        let response = acceptor
-            .create_response(Code::SERVER_INTERNAL_ERROR, None)
-            .await?;
+    /// Consume the acceptor by terminating the transaction with the given failure code
+    async fn respond_failure(&self, acceptor: Acceptor, code: Code) -> Result<()> {
+        let response = acceptor.create_response(code, None).await?;
 
         acceptor.respond_failure(response).await?;
 
         Ok(())
     }
+
+    /// Call the phone number of a request placed by a moderator and join the room once the callee
+    /// answered
+    ///
+    /// Runs until the call failed or has been hung up and reports its progress to the controller.
+    async fn handle_dial_out(&self, endpoint: &Endpoint, request: DialOutRequest) {
+        let call_id = request.call_id;
+
+        let status = match self.dial_out(endpoint, request).await {
+            Ok(status) => status,
+            Err(e) => {
+                log::error!("Failed to dial out, {e:?}");
+                CallStatus::Failed
+            }
+        };
+
+        self.report_progress(call_id, status).await;
+    }
+
+    /// Place the outbound call and run the signaling of the established call
+    ///
+    /// The INVITE carries no SDP, the offer is received with the 200 OK and answered in the ACK.
+    async fn dial_out(&self, endpoint: &Endpoint, request: DialOutRequest) -> Result<CallStatus> {
+        let (contact, pub_ip) = if let Some(app_data) = &*self.app_data.read() {
+            (app_data.contact.clone(), app_data.pub_ip)
+        } else {
+            bail!("Got dial-out request before contact is ready");
+        };
+
+        let registrar = self
+            .settings
+            .sip
+            .registrar
+            .as_ref()
+            .context("Dialing out requires the SIP registrar settings")?;
+
+        // The SRTP keys are exchanged with the 200 OK and ACK, the first hop must be secure
+        if self.settings.sip.media_encryption == MediaEncryption::Required
+            && !uses_tls(
+                registrar
+                    .outbound_proxy
+                    .as_deref()
+                    .unwrap_or(&registrar.registrar),
+            )
+        {
+            bail!("Media encryption is required, but the registrar is not reached over TLS");
+        }
+
+        let target = endpoint
+            .parse_uri(number_uri(&request.number, &registrar.registrar))
+            .context("Failed to parse the called SIP uri")?;
+
+        let route = registrar
+            .outbound_proxy
+            .as_deref()
+            .map(|outbound_proxy| format!("<{};lr>", maybe_add_sip_scheme(outbound_proxy)));
+
+        let (mut auth_sess, credentials) =
+            auth_session(registrar, self.settings.sip.username.clone());
+
+        let mut initiator = Initiator::new(
+            endpoint.clone(),
+            self.dialog_layer,
+            self.invite_layer,
+            contact.uri.clone(),
+            contact,
+            target,
+        );
+
+        let mut ringing = false;
+
+        let (mut session, response) = 'invite: loop {
+            let mut invite = initiator.create_invite();
+
+            if let Some(route) = &route {
+                invite.headers.insert(Name::ROUTE, route.clone());
+            }
+
+            auth_sess.authorize_request(&mut invite.headers);
+
+            let sent = invite.clone();
+            initiator.send_invite(invite).await?;
+
+            loop {
+                match initiator.receive().await? {
+                    Response::Provisional(response) => {
+                        if !ringing && matches!(response.line.code.into_u16(), 180 | 183) {
+                            ringing = true;
+                            self.report_progress(request.call_id, CallStatus::Ringing)
+                                .await;
+                        }
+                    }
+                    Response::Failure(response) => match response.line.code.into_u16() {
+                        401 | 407 => {
+                            auth_sess.handle_authenticate(
+                                &response.headers,
+                                &credentials,
+                                RequestParts {
+                                    line: &sent.line,
+                                    headers: &sent.headers,
+                                    body: &sent.body,
+                                },
+                            )?;
+
+                            continue 'invite;
+                        }
+                        486 | 600 => return Ok(CallStatus::Busy),
+                        603 => return Ok(CallStatus::Declined),
+                        code => {
+                            log::warn!("Dial-out call failed with code {code}");
+                            return Ok(CallStatus::Failed);
+                        }
+                    },
+                    Response::Early(..) => {
+                        // Early media is not played, wait for the final response
+                    }
+                    Response::Session(session, response) => break 'invite (session, response),
+                    Response::Finished => return Ok(CallStatus::Failed),
+                }
+            }
+        };
+
+        let cseq = response.base_headers.cseq.cseq;
+        let sdp_offer = response.body.clone();
+
+        // Hand the SDP offer of the 200 OK to the media task
+        let ready_to_send = Arc::new(Notify::new());
+        let pipeline =
+            MediaPipeline::new(pub_ip, &self.settings.sip, sdp_offer, ready_to_send.clone()).await;
+
+        let mut ack = initiator.create_ack(cseq).await?;
+
+        let (handle, mut track_controller) = match pipeline {
+            Ok((handle, track_controller, sdp_answer)) => {
+                ack.body = sdp_answer.into();
+                ack.headers.insert(Name::CONTENT_TYPE, "application/sdp");
+                initiator.send_ack(ack, cseq).await?;
+
+                (handle, track_controller)
+            }
+            Err(e) => {
+                // The dialog is established with the 200 OK, acknowledge it before hanging up
+                initiator.send_ack(ack, cseq).await?;
+                session.terminate().await?;
+
+                return Err(e.context("Failed to create GStreamer pipeline"));
+            }
+        };
+
+        self.report_progress(request.call_id, CallStatus::Answered)
+            .await;
+
+        let ticket = self
+            .http_client
+            .dial_out_start(&self.settings.controller, request.call_id)
+            .await?;
+
+        track_controller.set_prompts(self.ivr.default_language().prompts.clone());
+
+        let mut signaling = Signaling::new(
+            self.settings.clone(),
+            self.http_client.clone(),
+            Some(BytesStr::from(request.display_name)),
+            session,
+            handle,
+            track_controller,
+            self.ivr.clone(),
+            self.shutdown.subscribe(),
+        );
+
+        // Wait for the media session to work before playing the usage instructions
+        ready_to_send.notified().await;
+        sleep(Duration::from_secs(1)).await;
+
+        // The callee may leave the waiting room to enter another conference ID instead
+        match signaling.join_with_ticket(ticket).await {
+            Ok(()) => {}
+            Err(e) if e.is::<LeftWaitingRoom>() => {}
+            Err(e) => return Err(e),
+        }
+
+        signaling.run().await?;
+
+        Ok(CallStatus::Ended)
+    }
+
+    /// Report the progress of an outbound call, failing to do so does not affect the call
+    async fn report_progress(&self, call_id: Uuid, status: CallStatus) {
+        if let Err(e) = self
+            .http_client
+            .dial_out_progress(&self.settings.controller, call_id, status)
+            .await
+        {
+            log::warn!("Failed to report progress {status:?} of call {call_id}, {e:?}");
+        }
+    }
 }
 
 /// Application main-task
@@ -207,9 +434,12 @@ pub(crate) async fn run(settings: Arc<Settings>) -> Result<()> {
         .await
         .context("failed to discover oidc issuer")?;
 
+    let ivr = Ivr::load(&settings.ivr).context("Invalid IVR settings")?;
+
     let app_layer = builder.add_layer(AppLayer {
         settings: settings.clone(),
         http_client: Arc::new(http_client),
+        ivr: Arc::new(ivr),
         shutdown: shutdown.clone(),
         app_data: parking_lot::RwLock::new(None),
         dialog_layer,
@@ -228,9 +458,20 @@ pub(crate) async fn run(settings: Arc<Settings>) -> Result<()> {
 
     // Add TLS connector to allow the endpoint to create TLS connections
     builder.add_transport_factory(Arc::new(tokio_native_tls::TlsConnector::from(
-        native_tls::TlsConnector::new()?,
+        tls_connector(settings.sip.tls.as_ref())?,
     )));
 
+    // Accept incoming TLS connections, when a certificate is configured
+    if let Some(tls) = &settings.sip.tls {
+        TlsListener::spawn(
+            &mut builder,
+            format!("{}:{}", settings.sip.addr, tls.port),
+            tokio_native_tls::TlsAcceptor::from(tls_acceptor(tls)?),
+        )
+        .await
+        .context("Failed to create TLS listener")?;
+    }
+
     let endpoint = builder.build();
 
     // Find obelisk's public address using the UDP transport, when a stun-server is configured
@@ -289,6 +530,17 @@ pub(crate) async fn run(settings: Arc<Settings>) -> Result<()> {
         log::info!("Incomplete SIP registrar settings, not registering anywhere.");
     }
 
+    if let Some(rabbitmq) = &settings.rabbitmq {
+        let consumer = dial_out::connect(rabbitmq).await?;
+
+        tokio::spawn(receive_dial_out_requests(
+            endpoint.clone(),
+            app_layer,
+            consumer,
+            shutdown.subscribe(),
+        ));
+    }
+
     tokio::signal::ctrl_c().await?;
 
     // ==== SHUTDOWN SEQUENCE
@@ -312,6 +564,67 @@ pub(crate) async fn run(settings: Arc<Settings>) -> Result<()> {
     Ok(())
 }
 
+/// Create the connector for outgoing TLS connections, which additionally trusts the configured CA
+fn tls_connector(settings: Option<&SipTlsSettings>) -> Result<native_tls::TlsConnector> {
+    let mut builder = native_tls::TlsConnector::builder();
+
+    if let Some(ca_certificate) = settings.and_then(|tls| tls.ca_certificate.as_ref()) {
+        let pem = std::fs::read(ca_certificate)
+            .with_context(|| format!("Failed to read CA certificate {ca_certificate:?}"))?;
+
+        builder.add_root_certificate(native_tls::Certificate::from_pem(&pem)?);
+    }
+
+    Ok(builder.build()?)
+}
+
+/// Create the acceptor for incoming TLS connections from the configured certificate and private key
+fn tls_acceptor(settings: &SipTlsSettings) -> Result<native_tls::TlsAcceptor> {
+    let certificate = std::fs::read(&settings.certificate)
+        .with_context(|| format!("Failed to read certificate {:?}", settings.certificate))?;
+    let private_key = std::fs::read(&settings.private_key)
+        .with_context(|| format!("Failed to read private key {:?}", settings.private_key))?;
+
+    let identity = native_tls::Identity::from_pkcs8(&certificate, &private_key)
+        .context("Invalid TLS certificate or private key")?;
+
+    Ok(native_tls::TlsAcceptor::new(identity)?)
+}
+
+/// Receive the outbound calls requested by moderators and spawn a task for each call
+async fn receive_dial_out_requests(
+    endpoint: Endpoint,
+    app_layer: LayerKey<AppLayer>,
+    mut consumer: lapin::Consumer,
+    mut shutdown: broadcast::Receiver<()>,
+) {
+    loop {
+        let request = tokio::select! {
+            request = dial_out::receive(&mut consumer) => request,
+            _ = shutdown.recv() => return,
+        };
+
+        match request {
+            Some(Ok(request)) => {
+                log::info!("Dialing out for call {}", request.call_id);
+
+                let endpoint = endpoint.clone();
+
+                tokio::spawn(async move {
+                    endpoint[app_layer]
+                        .handle_dial_out(&endpoint, request)
+                        .await;
+                });
+            }
+            Some(Err(e)) => log::error!("Failed to receive dial-out request, {e:?}"),
+            None => {
+                log::error!("Dial-out queue has been closed, no longer dialing out");
+                return;
+            }
+        }
+    }
+}
+
 /// Try to register with a SIP registrar, then spawn a task to keep that registration up
 async fn register_with_registrar(
     endpoint: Endpoint,
@@ -349,22 +662,7 @@ async fn register_with_registrar(
 
     let mut registration = Registration::new(NameAddr::uri(id), registrar_uri);
 
-    let mut authenticator = DigestAuthenticator::default();
-    authenticator.enforce_qop = settings.enforce_qop;
-
-    let mut auth_sess = UacAuthSession::new(authenticator);
-    let mut credentials = CredentialStore::new();
-
-    // Add username & password credentials if both are set
-    if let Some((username, password)) = username.zip(settings.password) {
-        let credential = DigestCredentials::new(username, password);
-
-        if let Some(realm) = settings.realm {
-            credentials.add_for_realm(realm, credential);
-        } else {
-            credentials.set_default(credential);
-        }
-    }
+    let (mut auth_sess, credentials) = auth_session(&settings, username);
 
     // Create binding
     let mut target = TargetTransportInfo {
@@ -438,6 +736,31 @@ async fn register_with_registrar(
     Ok(())
 }
 
+/// Create the authentication session and credentials used for requests to the registrar
+fn auth_session(
+    settings: &SipRegistrarSettings,
+    username: Option<String>,
+) -> (UacAuthSession, CredentialStore) {
+    let mut authenticator = DigestAuthenticator::default();
+    authenticator.enforce_qop = settings.enforce_qop;
+
+    let auth_sess = UacAuthSession::new(authenticator);
+    let mut credentials = CredentialStore::new();
+
+    // Add username & password credentials if both are set
+    if let Some((username, password)) = username.zip(settings.password.clone()) {
+        let credential = DigestCredentials::new(username, password);
+
+        if let Some(realm) = settings.realm.clone() {
+            credentials.add_for_realm(realm, credential);
+        } else {
+            credentials.set_default(credential);
+        }
+    }
+
+    (auth_sess, credentials)
+}
+
 /// Send a register request and handle authentication using the given session and credentials
 async fn register(
     endpoint: &Endpoint,
@@ -498,6 +821,47 @@ async fn nat_keep_alive(tp: TpHandle, target: SocketAddr, interval_delta: Durati
     }
 }
 
+/// User part of a SIP URI, usually the phone number
+fn sip_user(uri: &dyn Uri) -> Option<BytesStr> {
+    uri.downcast_ref::<SipUri>()
+        .and_then(|uri| match &uri.user_part {
+            UserPart::Empty => None,
+            UserPart::User(user) => Some(user.clone()),
+            UserPart::UserPw(_) => None,
+        })
+}
+
+/// Returns true if requests to the configured URI are sent over TLS, either because of the `sips`
+/// scheme or the `transport=tls` parameter
+fn uses_tls(uri: &str) -> bool {
+    let uri = maybe_add_sip_scheme(uri);
+    let mut parts = uri.split(';');
+
+    let sips = parts.next().map_or(false, |uri| uri.starts_with("sips:"));
+
+    sips || parts.any(|param| param.trim().eq_ignore_ascii_case("transport=tls"))
+}
+
+/// Returns true if the SDP offer is valid, but cannot be accepted for this call
+///
+/// Such offers are rejected with 488 Not Acceptable Here.
+pub(crate) fn is_not_acceptable(e: &anyhow::Error) -> bool {
+    matches!(
+        e.downcast_ref(),
+        Some(SdpError::EncryptionRequired | SdpError::UnsupportedCodec)
+    )
+}
+
+/// SIP URI of a phone number at the domain of the registrar
+fn number_uri(number: &str, registrar: &str) -> String {
+    let (scheme, domain) = registrar
+        .split_once(':')
+        .filter(|(scheme, _)| matches!(*scheme, "sip" | "sips"))
+        .unwrap_or(("sip", registrar));
+
+    format!("{scheme}:{number}@{domain}")
+}
+
 fn maybe_add_sip_scheme(i: &str) -> Cow<'_, str> {
     if i.starts_with("sip:") || i.starts_with("sips:") {
         Cow::Borrowed(i)
@@ -505,3 +869,35 @@ fn maybe_add_sip_scheme(i: &str) -> Cow<'_, str> {
         Cow::Owned(format!("sip:{i}"))
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn tls_targets() {
+        assert!(uses_tls("sips:registrar.example.org"));
+        assert!(uses_tls("sip:registrar.example.org;transport=TLS"));
+        assert!(uses_tls("registrar.example.org:5061;lr;transport=tls"));
+
+        assert!(!uses_tls("registrar.example.org"));
+        assert!(!uses_tls("sip:registrar.example.org;transport=tcp"));
+        assert!(!uses_tls("sip:sips.example.org"));
+    }
+
+    #[test]
+    fn not_acceptable_offers() {
+        let encryption_required =
+            anyhow::Error::from(SdpError::EncryptionRequired).context("Failed to create sip_bin");
+        assert!(is_not_acceptable(&encryption_required));
+        assert!(is_not_acceptable(&anyhow::Error::from(
+            SdpError::UnsupportedCodec
+        )));
+
+        let invalid_offer = anyhow::Error::from(SdpError::InvalidOffer);
+        assert!(!is_not_acceptable(&invalid_offer));
+        assert!(!is_not_acceptable(&anyhow::anyhow!(
+            "Failed to link elements"
+        )));
+    }
+}
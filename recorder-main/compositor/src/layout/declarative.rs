@@ -0,0 +1,224 @@
+// SPDX-FileCopyrightText: OpenTalk GmbH <mail@opentalk.eu>
+//
+// SPDX-License-Identifier: EUPL-1.2
+
+use anyhow::{bail, Result};
+use serde::Deserialize;
+
+use super::{Fit, Layout, Position, Size, StreamRole, View};
+
+/// Declarative layout
+/// Places the *visible* streams into regions which are described by configuration
+/// (e.g. TOML or JSON) instead of code.
+///
+/// Example in TOML which shows the speaker large with up to two other participants beside:
+///
+/// ```toml
+/// type = "declarative"
+///
+/// [[regions]]
+/// slot = "speaker"
+/// x = 0.0
+/// y = 0.0
+/// width = 0.75
+/// height = 1.0
+///
+/// [[regions]]
+/// slot = "other"
+/// index = 0
+/// x = 0.75
+/// y = 0.0
+/// width = 0.25
+/// height = 0.5
+///
+/// [[regions]]
+/// slot = "other"
+/// index = 1
+/// x = 0.75
+/// y = 0.5
+/// width = 0.25
+/// height = 0.5
+/// ```
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(try_from = "Regions")]
+pub struct Declarative {
+    regions: Vec<Region>,
+    resolution: Size,
+    visibles: usize,
+    roles: Vec<StreamRole>,
+}
+
+/// Region of a [`Declarative`] layout
+#[derive(Debug, Clone, Deserialize)]
+pub struct Region {
+    /// Which stream is shown within this region.
+    pub slot: Slot,
+    /// Index of the stream among all streams which are assigned to the same kind of slot.
+    #[serde(default)]
+    pub index: usize,
+    /// Horizontal position relative to the output width (`0.0` - `1.0`).
+    pub x: f64,
+    /// Vertical position relative to the output height (`0.0` - `1.0`).
+    pub y: f64,
+    /// Width relative to the output width (`0.0` - `1.0`).
+    pub width: f64,
+    /// Height relative to the output height (`0.0` - `1.0`).
+    pub height: f64,
+    /// Stacking order, regions with a higher value are drawn above regions with a lower one.
+    #[serde(default)]
+    pub z_order: u32,
+    /// How the stream's picture is fitted into the region.
+    #[serde(default)]
+    pub fit: Fit,
+    /// The region is only used if at least this number of streams is visible.
+    #[serde(default)]
+    pub min_visibles: usize,
+    /// The region is only used if at most this number of streams is visible.
+    #[serde(default)]
+    pub max_visibles: Option<usize>,
+}
+
+/// Assignment rule of a [`Region`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Slot {
+    /// The current speaker's camera
+    Speaker,
+    /// A screen share
+    Screen,
+    /// Any stream without a matching speaker or screen region, in order of visibility
+    Other,
+}
+
+#[derive(Deserialize)]
+struct Regions {
+    regions: Vec<Region>,
+}
+
+impl TryFrom<Regions> for Declarative {
+    type Error = anyhow::Error;
+
+    fn try_from(value: Regions) -> Result<Self> {
+        Self::new(value.regions)
+    }
+}
+
+impl Declarative {
+    /// Create a new declarative layout out of the given regions.
+    ///
+    /// # Errors
+    ///
+    /// This can fail if a region is not within the output picture.
+    pub fn new(regions: Vec<Region>) -> Result<Self> {
+        for (n, region) in regions.iter().enumerate() {
+            let in_range = |value: f64| (0.0..=1.0).contains(&value);
+            if !in_range(region.x)
+                || !in_range(region.y)
+                || !in_range(region.x + region.width)
+                || !in_range(region.y + region.height)
+            {
+                bail!("region {n} exceeds the output picture");
+            }
+            if region.width <= 0.0 || region.height <= 0.0 {
+                bail!("region {n} has no size");
+            }
+            if matches!(region.max_visibles, Some(max) if max < region.min_visibles) {
+                bail!("region {n} has max_visibles below min_visibles");
+            }
+        }
+
+        Ok(Self {
+            regions,
+            ..Default::default()
+        })
+    }
+
+    fn active_regions(&self) -> impl Iterator<Item = &Region> {
+        self.regions.iter().filter(|region| {
+            self.visibles >= region.min_visibles
+                && region.max_visibles.map_or(true, |max| self.visibles <= max)
+        })
+    }
+
+    fn find_region(&self, slot: Slot, index: usize) -> Option<&Region> {
+        self.active_regions()
+            .find(|region| region.slot == slot && region.index == index)
+    }
+
+    /// Find the region of the stream at the given position.
+    ///
+    /// Speakers and screens without an own region are placed into the `other` regions.
+    fn region(&self, stream_position: usize) -> Option<&Region> {
+        let mut speakers = 0;
+        let mut screens = 0;
+        let mut others = 0;
+
+        for position in 0..=stream_position {
+            let role = self.roles.get(position).copied().unwrap_or_default();
+            let region = match role {
+                StreamRole::Speaker => {
+                    speakers += 1;
+                    self.find_region(Slot::Speaker, speakers - 1)
+                }
+                StreamRole::Screen => {
+                    screens += 1;
+                    self.find_region(Slot::Screen, screens - 1)
+                }
+                StreamRole::Other => None,
+            };
+            let region = match region {
+                Some(region) => Some(region),
+                None => {
+                    others += 1;
+                    self.find_region(Slot::Other, others - 1)
+                }
+            };
+            if position == stream_position {
+                return region;
+            }
+        }
+
+        None
+    }
+}
+
+impl Layout for Declarative {
+    fn set_resolution_changed(&mut self, resolution: Size) {
+        self.resolution = resolution;
+    }
+
+    fn set_amount_of_visibles(&mut self, visibles: usize) {
+        self.visibles = visibles;
+    }
+
+    fn set_stream_roles(&mut self, roles: &[StreamRole]) {
+        self.roles = roles.to_vec();
+    }
+
+    fn arranges_screens(&self) -> bool {
+        self.regions
+            .iter()
+            .any(|region| region.slot == Slot::Screen)
+    }
+
+    fn calculate_stream_view(&self, stream_position: usize) -> Option<View> {
+        if stream_position >= self.visibles {
+            return None;
+        }
+        let region = self.region(stream_position)?;
+        let width = self.resolution.width as f64;
+        let height = self.resolution.height as f64;
+        Some(View {
+            pos: Position {
+                x: (region.x * width) as i64,
+                y: (region.y * height) as i64,
+            },
+            size: Size {
+                width: (region.width * width) as usize,
+                height: (region.height * height) as usize,
+            },
+            z_order: region.z_order,
+            fit: region.fit,
+        })
+    }
+}
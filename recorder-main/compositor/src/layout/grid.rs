@@ -3,12 +3,15 @@
 // SPDX-License-Identifier: EUPL-1.2
 
 use super::{Layout, Position, Size, View};
+use serde::Deserialize;
 
 /// Grid layout
 /// Places all the *visible* participants in a grid on screen.
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Deserialize)]
 pub struct Grid {
+    #[serde(skip)]
     resolution: Size,
+    #[serde(skip)]
     visibles: usize,
 }
 
@@ -30,6 +33,7 @@ impl Layout for Grid {
                 y: (self.height() * row + self.padding()) as i64,
             },
             size: self.uni_size(),
+            ..Default::default()
         })
     }
 }
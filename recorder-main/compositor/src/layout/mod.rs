@@ -2,17 +2,52 @@
 //
 // SPDX-License-Identifier: EUPL-1.2
 
+mod declarative;
 mod grid;
+mod picture_in_picture;
+mod presentation;
 mod speaker;
 
+pub use declarative::*;
 pub use grid::*;
+pub use picture_in_picture::*;
+pub use presentation::*;
 pub use speaker::*;
 
+use serde::Deserialize;
+
 /// View properies of a stream
 #[derive(Debug, Clone, Default)]
 pub struct View {
     pub pos: Position,
     pub size: Size,
+    /// Stacking order, views with a higher value are drawn above views with a lower one.
+    pub z_order: u32,
+    /// How the stream's picture is fitted into the view.
+    pub fit: Fit,
+}
+
+/// How a stream's picture is fitted into its view
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Fit {
+    /// Keep the aspect ratio and fill the remaining space with borders (default)
+    #[default]
+    Contain,
+    /// Scale the picture to the view's size ignoring the aspect ratio
+    Stretch,
+}
+
+/// Role of a visible stream which a layout may use to place it
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StreamRole {
+    /// Any stream which is neither the speaker nor a screen share
+    #[default]
+    Other,
+    /// Camera stream of the current speaker
+    Speaker,
+    /// Screen share
+    Screen,
 }
 
 /// Cartesian pixel position
@@ -107,4 +142,121 @@ pub trait Layout: std::fmt::Debug + Send + Sync + 'static {
     /// Returns Some(view) if the stream should be visible and be shown.
     /// Returns None if the stream should NOT be visible.
     fn calculate_stream_view(&self, stream_position: usize) -> Option<View>;
+
+    /// Update the current layout for changes on the roles of the visible streams.
+    ///
+    /// `roles` is in the same order as the stream positions. Layouts which only arrange by
+    /// position can ignore this.
+    fn set_stream_roles(&mut self, _roles: &[StreamRole]) {}
+
+    /// Return `true` if the layout places screen shares into regions of their own.
+    ///
+    /// Other layouts are replaced by the presentation layout while a screen share is visible.
+    fn arranges_screens(&self) -> bool {
+        false
+    }
+}
+
+/// enum which bundles all types of layouts
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AnyLayout {
+    /// Speaker layout
+    Speaker(Speaker),
+    /// Grid layout
+    Grid(Grid),
+    /// Presentation layout
+    Presentation(Presentation),
+    /// Picture-in-picture layout
+    PictureInPicture(PictureInPicture),
+    /// Layout described by configuration
+    Declarative(Declarative),
+}
+
+impl Default for AnyLayout {
+    fn default() -> Self {
+        Self::Speaker(Speaker::default())
+    }
+}
+
+impl Layout for AnyLayout {
+    fn set_resolution_changed(&mut self, resolution: Size) {
+        match self {
+            Self::Speaker(l) => l.set_resolution_changed(resolution),
+            Self::Grid(l) => l.set_resolution_changed(resolution),
+            Self::Presentation(l) => l.set_resolution_changed(resolution),
+            Self::PictureInPicture(l) => l.set_resolution_changed(resolution),
+            Self::Declarative(l) => l.set_resolution_changed(resolution),
+        }
+    }
+
+    fn set_amount_of_visibles(&mut self, visibles: usize) {
+        match self {
+            Self::Speaker(l) => l.set_amount_of_visibles(visibles),
+            Self::Grid(l) => l.set_amount_of_visibles(visibles),
+            Self::Presentation(l) => l.set_amount_of_visibles(visibles),
+            Self::PictureInPicture(l) => l.set_amount_of_visibles(visibles),
+            Self::Declarative(l) => l.set_amount_of_visibles(visibles),
+        }
+    }
+
+    fn calculate_stream_view(&self, stream_position: usize) -> Option<View> {
+        match self {
+            Self::Speaker(l) => l.calculate_stream_view(stream_position),
+            Self::Grid(l) => l.calculate_stream_view(stream_position),
+            Self::Presentation(l) => l.calculate_stream_view(stream_position),
+            Self::PictureInPicture(l) => l.calculate_stream_view(stream_position),
+            Self::Declarative(l) => l.calculate_stream_view(stream_position),
+        }
+    }
+
+    fn set_stream_roles(&mut self, roles: &[StreamRole]) {
+        match self {
+            Self::Speaker(l) => l.set_stream_roles(roles),
+            Self::Grid(l) => l.set_stream_roles(roles),
+            Self::Presentation(l) => l.set_stream_roles(roles),
+            Self::PictureInPicture(l) => l.set_stream_roles(roles),
+            Self::Declarative(l) => l.set_stream_roles(roles),
+        }
+    }
+
+    fn arranges_screens(&self) -> bool {
+        match self {
+            Self::Speaker(l) => l.arranges_screens(),
+            Self::Grid(l) => l.arranges_screens(),
+            Self::Presentation(l) => l.arranges_screens(),
+            Self::PictureInPicture(l) => l.arranges_screens(),
+            Self::Declarative(l) => l.arranges_screens(),
+        }
+    }
+}
+
+impl From<Speaker> for AnyLayout {
+    fn from(layout: Speaker) -> AnyLayout {
+        AnyLayout::Speaker(layout)
+    }
+}
+
+impl From<Grid> for AnyLayout {
+    fn from(layout: Grid) -> AnyLayout {
+        AnyLayout::Grid(layout)
+    }
+}
+
+impl From<Presentation> for AnyLayout {
+    fn from(layout: Presentation) -> AnyLayout {
+        AnyLayout::Presentation(layout)
+    }
+}
+
+impl From<PictureInPicture> for AnyLayout {
+    fn from(layout: PictureInPicture) -> AnyLayout {
+        AnyLayout::PictureInPicture(layout)
+    }
+}
+
+impl From<Declarative> for AnyLayout {
+    fn from(layout: Declarative) -> AnyLayout {
+        AnyLayout::Declarative(layout)
+    }
 }
@@ -0,0 +1,70 @@
+// SPDX-FileCopyrightText: OpenTalk GmbH <mail@opentalk.eu>
+//
+// SPDX-License-Identifier: EUPL-1.2
+
+use super::{Layout, Position, Size, View};
+use serde::Deserialize;
+
+/// Picture-in-picture layout
+/// Shows the first *visible* stream (usually a screen share) in full size and
+/// the second one (usually the speaker) as a small picture in the lower right corner.
+/// All other streams are hidden.
+#[derive(Debug, Default, Clone, Deserialize)]
+pub struct PictureInPicture {
+    #[serde(skip)]
+    resolution: Size,
+    #[serde(skip)]
+    visibles: usize,
+}
+
+const PICTURE_SCALE: usize = 4;
+const MARGIN_SCALE: usize = 64;
+
+impl Layout for PictureInPicture {
+    fn set_resolution_changed(&mut self, resolution: Size) {
+        self.resolution = resolution;
+    }
+
+    fn set_amount_of_visibles(&mut self, visibles: usize) {
+        self.visibles = visibles;
+    }
+
+    fn calculate_stream_view(&self, stream_position: usize) -> Option<View> {
+        if stream_position >= self.visibles {
+            return None;
+        }
+        match stream_position {
+            0 => Some(View {
+                pos: Position { x: 0, y: 0 },
+                size: self.resolution,
+                ..Default::default()
+            }),
+            1 => Some(View {
+                pos: self.picture_position(),
+                size: self.picture_size(),
+                // draw the picture above the full size stream
+                z_order: 1,
+                ..Default::default()
+            }),
+            _ => None,
+        }
+    }
+}
+
+impl PictureInPicture {
+    fn picture_size(&self) -> Size {
+        let width = self.resolution.width / PICTURE_SCALE;
+        let height = (width as f64 / self.resolution.ratio()) as usize;
+        Size { width, height }
+    }
+
+    fn picture_position(&self) -> Position {
+        // place the picture into the lower right corner leaving a small margin
+        let margin = self.resolution.width / MARGIN_SCALE;
+        let size = self.picture_size();
+        Position {
+            x: (self.resolution.width - size.width - margin) as i64,
+            y: (self.resolution.height - size.height - margin) as i64,
+        }
+    }
+}
@@ -0,0 +1,88 @@
+// SPDX-FileCopyrightText: OpenTalk GmbH <mail@opentalk.eu>
+//
+// SPDX-License-Identifier: EUPL-1.2
+
+use super::{Layout, Position, Size, View};
+use serde::Deserialize;
+
+/// Presentation layout
+/// Places the first *visible* stream (usually a screen share) large on screen
+/// and arranges the following streams in a filmstrip below it.
+#[derive(Debug, Default, Clone, Deserialize)]
+pub struct Presentation {
+    #[serde(skip)]
+    resolution: Size,
+    #[serde(skip)]
+    visibles: usize,
+}
+
+/// Maximum number of thumbnails in the filmstrip.
+const FILMSTRIP_SCALE: usize = 5;
+
+impl Layout for Presentation {
+    fn set_resolution_changed(&mut self, resolution: Size) {
+        self.resolution = resolution;
+    }
+
+    fn set_amount_of_visibles(&mut self, visibles: usize) {
+        self.visibles = visibles;
+    }
+
+    fn calculate_stream_view(&self, stream_position: usize) -> Option<View> {
+        if stream_position >= self.visibles.min(FILMSTRIP_SCALE + 1) {
+            return None;
+        }
+        let view = match stream_position {
+            0 => View {
+                pos: self.presentation_position(),
+                size: self.presentation_size(),
+                ..Default::default()
+            },
+            _ => View {
+                pos: self.thumbnail_position(stream_position - 1),
+                size: self.thumbnail_size(),
+                ..Default::default()
+            },
+        };
+        Some(view)
+    }
+}
+
+impl Presentation {
+    fn thumbnails(&self) -> usize {
+        self.visibles.saturating_sub(1).min(FILMSTRIP_SCALE)
+    }
+
+    fn thumbnail_size(&self) -> Size {
+        if self.thumbnails() == 0 {
+            return Size::default();
+        }
+        let width = self.resolution.width / FILMSTRIP_SCALE;
+        let height = (width as f64 / self.resolution.ratio()) as usize;
+        Size { width, height }
+    }
+
+    fn thumbnail_position(&self, thumbnail: usize) -> Position {
+        // center the filmstrip horizontally at the bottom of the picture
+        let size = self.thumbnail_size();
+        let offset = (self.resolution.width - size.width * self.thumbnails()) / 2;
+        Position {
+            x: (offset + size.width * thumbnail) as i64,
+            y: (self.resolution.height - size.height) as i64,
+        }
+    }
+
+    fn presentation_size(&self) -> Size {
+        let height = self.resolution.height - self.thumbnail_size().height;
+        let width = (height as f64 * self.resolution.ratio()) as usize;
+        Size { width, height }
+    }
+
+    fn presentation_position(&self) -> Position {
+        // center the presentation horizontally above the filmstrip
+        Position {
+            x: ((self.resolution.width - self.presentation_size().width) / 2) as i64,
+            y: 0,
+        }
+    }
+}
@@ -3,12 +3,15 @@
 // SPDX-License-Identifier: EUPL-1.2
 
 use super::{Layout, Position, Size, View};
+use serde::Deserialize;
 
 /// Speaker layout
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Deserialize)]
 pub struct Speaker {
     // Size of the target picture in pixels.
+    #[serde(skip)]
     resolution: Size,
+    #[serde(skip)]
     visibles: usize,
 }
 
@@ -31,10 +34,12 @@ impl Layout for Speaker {
             0 => View {
                 pos: self.speaker_position(),
                 size: self.speaker_size(),
+                ..Default::default()
             },
             _ => View {
                 pos: self.viewers_position(stream_position - 1),
                 size: self.viewers_size(),
+                ..Default::default()
             },
         };
         Some(view)
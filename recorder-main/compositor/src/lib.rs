@@ -30,8 +30,14 @@
       is an universal sink that can be one of the following.
     - [DashSink]
       writes the output into a Dash instance consisting of an MPD file and several audio/video files.
+    - [HlsSink]
+      writes the output into an HLS playlist with TS or CMAF segments, optionally in several renditions.
     - [Mp4Sink]
       writes the output into a MPEG4 file.
+    - [SrtSink]
+      streams the output as MPEG-TS over SRT in caller or listener mode.
+    - [WhipSink]
+      streams the output over WebRTC into a WHIP endpoint.
     - [MatroskaSink]
       listens on a TCP port to write the raw output to, after someone connects.
     - [TestBlinder]
@@ -59,6 +65,10 @@
       Overlay which displays a changeable text.
     - [ClockOverlay]
       Overlay which displays current time.
+    - [ChatOverlay]
+      Overlay which displays the latest chat messages within the sinks whose profile enables it.
+    - [Placeholder]
+      Picture with a participant's initials which replaces a stream without video.
     - [TalkOverlay]
       Combined Text and Clock Overlay which is used in Talk.
 
@@ -2,7 +2,7 @@
 //
 // SPDX-License-Identifier: EUPL-1.2
 
-use crate::mixer::{AUDIO_CHANNELS, AUDIO_SAMPLE_RATE};
+use crate::mixer::{sink_gate::SinkGate, Loudness, AUDIO_CHANNELS, AUDIO_SAMPLE_RATE};
 use anyhow::{Context, Result};
 use glib::BoolError;
 use gst::{
@@ -10,6 +10,7 @@ use gst::{
     GhostPad, Pad, Sample, StreamError,
 };
 use gst_app::{AppSink, AppSinkCallbacks, AppSrc};
+use std::sync::Arc;
 use tokio::sync::broadcast;
 
 const QUEUE_SIZE: usize = 128; // expect a buffers of 10ms -> 1s queue size
@@ -21,23 +22,54 @@ pub(crate) struct AudioMixer {
 }
 
 impl AudioMixer {
+    fn output_caps() -> Caps {
+        Caps::builder("audio/x-raw")
+            .field("format", "S16LE")
+            .field("channels", AUDIO_CHANNELS)
+            .field("layout", "interleaved")
+            .field("rate", AUDIO_SAMPLE_RATE)
+            .build()
+    }
+
     fn build_caps() -> Result<Element, BoolError> {
         ElementFactory::make("capssetter")
-            .property(
-                "caps",
-                Caps::builder("audio/x-raw")
-                    .field("format", "S16LE")
-                    .field("channels", AUDIO_CHANNELS)
-                    .field("layout", "interleaved")
-                    .field("rate", AUDIO_SAMPLE_RATE)
-                    .build(),
-            )
+            .property("caps", Self::output_caps())
             .build()
     }
 
+    /// Build the elements which normalize the loudness of the mixed output.
+    ///
+    /// `audioloudnorm` works on 192 kHz float samples, so the output gets converted back afterwards.
+    fn build_normalization(loudness: &Loudness) -> Result<Vec<Element>> {
+        let make = |factory: &str| {
+            ElementFactory::make(factory)
+                .build()
+                .with_context(|| format!("unable to build {factory}"))
+        };
+        let audioloudnorm = ElementFactory::make("audioloudnorm")
+            .name("loudness-normalization")
+            .property("loudness-target", loudness.target)
+            .property("max-true-peak", loudness.max_true_peak)
+            .build()
+            .context("unable to build audioloudnorm")?;
+        let capsfilter = ElementFactory::make("capsfilter")
+            .property("caps", Self::output_caps())
+            .build()
+            .context("unable to build capsfilter")?;
+
+        Ok(vec![
+            make("audioconvert")?,
+            make("audioresample")?,
+            audioloudnorm,
+            make("audioconvert")?,
+            make("audioresample")?,
+            capsfilter,
+        ])
+    }
+
     #[allow(clippy::too_many_lines)]
-    pub(crate) fn create() -> Result<Self> {
-        let bin = Bin::new(Some("AudioMixer"));
+    pub(crate) fn create(name: &str, loudness: &Loudness) -> Result<Self> {
+        let bin = Bin::new(Some(name));
 
         let audiotestsrc = ElementFactory::make("audiotestsrc")
             .name("Audio Background Source")
@@ -81,14 +113,21 @@ impl AudioMixer {
             .link(&audiomixer_sink_pad)
             .context("unable to link audio_requested_pad with capssetter")?;
 
-        Element::link_many(&[
-            &audiomixer,
-            &audimixer_capssetter,
-            &queue,
-            appsink.upcast_ref(),
-        ])
-        .context(
-            "unable to link 'audiomixer', 'audimixer_capssetter', 'queue', 'clocksync' and 'appsink'",
+        // normalize loudness between mixer and output
+        let normalization = if loudness.normalize {
+            Self::build_normalization(loudness).context("unable to build loudness normalization")?
+        } else {
+            Vec::new()
+        };
+        let mut output = vec![&audiomixer];
+        output.extend(&normalization);
+        output.extend([&audimixer_capssetter, &queue, appsink.upcast_ref()]);
+
+        bin.add_many(&normalization.iter().collect::<Vec<_>>())
+            .context("unable to add loudness normalization to 'bin'")?;
+
+        Element::link_many(&output).context(
+            "unable to link 'audiomixer', loudness normalization, 'audimixer_capssetter', 'queue' and 'appsink'",
         )?;
 
         let buffer = broadcast::Sender::new(QUEUE_SIZE);
@@ -171,11 +210,14 @@ impl AudioMixer {
         Ok(())
     }
 
-    pub(crate) fn link_sink(&self, app_src: &AppSrc) {
+    pub(crate) fn link_sink(&self, app_src: &AppSrc, gate: Arc<SinkGate>) {
         let mut receiver = self.buffer.subscribe();
         let app_src = app_src.clone();
         std::thread::spawn(move || {
             while let Ok(sample) = receiver.blocking_recv() {
+                let Some(sample) = gate.apply(sample) else {
+                    continue;
+                };
                 if let Err(error) = app_src.push_sample(&sample) {
                     let src_name = app_src.name();
                     match error {
@@ -0,0 +1,39 @@
+// SPDX-FileCopyrightText: OpenTalk GmbH <mail@opentalk.eu>
+//
+// SPDX-License-Identifier: EUPL-1.2
+
+//! Loudness settings of the audio mixer.
+
+use serde::Deserialize;
+
+/// Integrated loudness target which EBU R128 recommends for broadcast.
+const DEFAULT_TARGET: f64 = -23.0;
+/// Maximum true peak which EBU R128 recommends for broadcast.
+const DEFAULT_MAX_TRUE_PEAK: f64 = -1.0;
+
+/// Loudness processing of the audio mixer.
+///
+/// Missing fields are taken from the default settings when deserializing.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct Loudness {
+    /// Normalize the mixed output to `target` following EBU R128.
+    pub normalize: bool,
+    /// Integrated loudness target of the mixed output in LUFS.
+    pub target: f64,
+    /// Maximum true peak of the mixed output in dBTP.
+    pub max_true_peak: f64,
+    /// Automatic gain control of every stream before mixing.
+    pub stream_agc: bool,
+}
+
+impl Default for Loudness {
+    fn default() -> Self {
+        Self {
+            normalize: false,
+            target: DEFAULT_TARGET,
+            max_true_peak: DEFAULT_MAX_TRUE_PEAK,
+            stream_agc: false,
+        }
+    }
+}
@@ -12,37 +12,62 @@ use std::{
     collections::HashMap,
     fmt::{Debug, Display},
     hash::Hash,
+    sync::{atomic::Ordering, Arc},
 };
 
 mod audio_mixer;
 pub mod debug;
+mod loudness;
 mod overlay;
+mod placeholder;
+mod profile;
 mod sink;
+mod sink_gate;
 mod source;
+mod statistics;
 mod stream;
 mod talk;
 mod text_style;
+mod theme;
+mod track;
 mod video_mixer;
 
-use self::{audio_mixer::AudioMixer, sink::ActiveSink, video_mixer::VideoMixer};
+use crate::{ChatLog, ChatOverlay, ChatStyle, Image};
+
+use self::{
+    audio_mixer::AudioMixer,
+    sink::ActiveSink,
+    sink_gate::SinkGate,
+    statistics::{count_bytes, Counters},
+    track::TrackSlot,
+    video_mixer::VideoMixer,
+};
 
 pub use super::layout::*;
+pub use loudness::*;
 pub use overlay::*;
+pub use placeholder::*;
+pub use profile::*;
 pub use sink::*;
 pub use source::*;
+pub use statistics::{SinkState, Statistics};
 pub use stream::*;
 pub use talk::*;
 pub use text_style::*;
+pub use theme::*;
+pub use track::TrackOwner;
 
 /// Maximum time a desired but missing re-layout is tolerated
 const MAX_LAYOUT_UPDATE_LATENCY: std::time::Duration = std::time::Duration::from_millis(500);
 
 const AUDIO_SAMPLE_RATE: i32 = 48_000;
+/// Maximum volume factor of `audiomixer`'s sink pads
+const MAX_STREAM_VOLUME: f64 = 10.0;
 const AUDIO_CHANNELS: i32 = 2;
 
 const VIDEO_WIDTH: i32 = 1920;
 const VIDEO_HEIGHT: i32 = 1136;
-const VIDEO_FRAMERATE: i32 = 30;
+pub(crate) const VIDEO_FRAMERATE: i32 = 30;
 
 enum Validation {
     Valid,
@@ -50,6 +75,14 @@ enum Validation {
     Stop,
 }
 
+/// Own layout of a sink which is composed apart from the shared layout
+#[derive(Debug)]
+struct SinkLayout {
+    layout: Box<dyn Layout>,
+    /// Overlay on top of the sink's picture
+    overlay: AnyOverlay,
+}
+
 /// Mixer managing the `GStreamer` pipeline using the given layout and source type
 ///
 /// Here is an example pipeline:
@@ -67,12 +100,14 @@ enum Validation {
 pub struct Mixer<SRC, STREAMID>
 where
     SRC: Source,
-    STREAMID: Eq + Ord + Hash + Copy + Debug + Display,
+    STREAMID: Eq + Ord + Hash + Copy + Debug + Display + TrackOwner,
 {
     /// Current streams.
     streams: HashMap<STREAMID, Stream<SRC>>,
     /// Currently visible streams.
     visibles: Vec<STREAMID>,
+    /// Roles of streams which are not `StreamRole::Other`.
+    roles: HashMap<STREAMID, StreamRole>,
     /// GStreamer element which composes the output video out of the source videos.
     // compositor: Option<gst::Element>,
     audio_mixer: AudioMixer,
@@ -87,13 +122,21 @@ where
     output_resolution: Size,
     valid: std::sync::mpsc::Sender<Validation>,
     layout: Box<dyn Layout>,
+    /// Layouts of the sinks which do not show the shared layout by sink name
+    sink_layouts: HashMap<String, SinkLayout>,
     system_clock: Clock,
+    /// Loudness processing of the audio
+    loudness: Loudness,
+    /// Dropped frames and errors of all pipelines
+    counters: Arc<Counters>,
+    /// Latest chat messages shown by the sinks with a chat overlay
+    chat: ChatLog,
 }
 
 impl<SRC, STREAMID> Mixer<SRC, STREAMID>
 where
     SRC: Source,
-    STREAMID: Eq + Ord + Hash + Copy + Display + Debug + Sync + Send,
+    STREAMID: Eq + Ord + Hash + Copy + Display + Debug + Sync + Send + TrackOwner,
 {
     /// Create a new mixer and setup the initial `GStreamer` pipeline with the given type of sink.
     ///
@@ -103,6 +146,7 @@ where
     /// - `layout`: The layout which will be used.
     /// - `overlay`: List of overlays to attach behind the compositor
     /// - `sink_params`: Output sink parameters.
+    /// - `loudness`: Loudness processing of the audio.
     ///
     /// # Errors
     ///
@@ -113,8 +157,10 @@ where
         layout: impl Layout,
         overlay: AnyOverlay,
         video_support: bool,
+        loudness: Loudness,
     ) -> Result<Self> {
-        let audio_mixer = AudioMixer::create().context("unable to create AudioMixer")?;
+        let audio_mixer =
+            AudioMixer::create("AudioMixer", &loudness).context("unable to create AudioMixer")?;
         pipeline
             .add(audio_mixer.bin())
             .context("unable to add 'audio_mixer' to 'pipeline'")?;
@@ -146,6 +192,7 @@ where
             audio_mixer,
             video_mixer,
             visibles: Vec::new(),
+            roles: HashMap::new(),
             pipeline,
             streams: HashMap::new(),
             overlay,
@@ -153,11 +200,15 @@ where
             output_resolution,
             valid,
             layout: Box::new(layout),
+            sink_layouts: HashMap::new(),
             system_clock,
+            loudness,
+            counters: Arc::default(),
+            chat: ChatLog::default(),
         };
 
         // start reading the pipeline bus
-        mixer.read_bus()?;
+        watch_bus(&mixer.pipeline, mixer.counters.clone())?;
         monitor_layout(valid_receiver);
 
         Ok(mixer)
@@ -168,7 +219,87 @@ where
     /// # Errors
     ///
     /// This can fail if the audio or video sink could not be linked to the mixer.
-    pub fn link_sink(&mut self, name: &str, mut sink: impl Sink) -> Result<()> {
+    pub fn link_sink(&mut self, name: &str, sink: impl Sink) -> Result<()> {
+        self.link_sink_to_scene(name, sink, None)
+    }
+
+    /// Link the given sink to the mixer which composes its picture with an own layout.
+    ///
+    /// The given overlay is rendered on top of the sink's picture. The layout is used as it is,
+    /// also while a screen share is visible, and is not changed by [`Mixer::change_layout()`].
+    ///
+    /// # Errors
+    ///
+    /// This can fail if the composition of the layout cannot be created or the audio or video
+    /// sink could not be linked to the mixer.
+    pub fn link_sink_with_layout(
+        &mut self,
+        name: &str,
+        sink: impl Sink,
+        layout: impl Layout,
+        overlay: AnyOverlay,
+    ) -> Result<()> {
+        if self.sinks.contains_key(name) {
+            bail!("a stream with the name '{name}' already exists");
+        }
+        // without video there is nothing to lay out
+        let Some(video_mixer) = self.video_mixer.as_mut() else {
+            return self.link_sink(name, sink);
+        };
+
+        video_mixer
+            .add_scene(name, &overlay)
+            .context("unable to add composition of the sink's layout")?;
+        self.sink_layouts.insert(
+            name.to_owned(),
+            SinkLayout {
+                layout: Box::new(layout),
+                overlay,
+            },
+        );
+
+        let result = self
+            .link_streams_to_scene(name)
+            .and_then(|()| self.rerender_layout())
+            .and_then(|()| self.link_sink_to_scene(name, sink, Some(name)));
+        if result.is_err() {
+            if let Err(error) = self.remove_sink_layout(name) {
+                error!("unable to remove layout of sink '{name}', error: {error}");
+            }
+        }
+        result
+    }
+
+    /// Link the video of all streams to the composition of the given scene.
+    ///
+    /// # Errors
+    ///
+    /// This can fail if a stream cannot be linked to the scene's compositor.
+    fn link_streams_to_scene(&mut self, scene: &str) -> Result<()> {
+        let Some(video_mixer) = &self.video_mixer else {
+            return Ok(());
+        };
+        for stream in self.streams.values_mut() {
+            if stream.video.is_some() {
+                let video = Self::link_video_tee(video_mixer, &stream.bin, Some(scene))
+                    .context("unable to link stream to the sink's layout")?;
+                stream.scenes.insert(scene.to_owned(), video);
+            }
+        }
+        Ok(())
+    }
+
+    /// Link the given sink to the composition of the given scene or of the shared layout.
+    ///
+    /// # Errors
+    ///
+    /// This can fail if the audio or video sink could not be linked to the mixer.
+    fn link_sink_to_scene(
+        &mut self,
+        name: &str,
+        mut sink: impl Sink,
+        scene: Option<&str>,
+    ) -> Result<()> {
         trace!("link sink, name: {name}, sinke: {sink:?}");
         if self.sinks.contains_key(name) {
             bail!("a stream with the name '{name}' already exists");
@@ -185,10 +316,20 @@ where
             .add(&bin)
             .context("unable to add sink to pipeline")?;
 
-        self.link_audio_sink(&pipeline, &sink)
+        let gate = Arc::new(SinkGate::default());
+        let start = self.running_time()?;
+
+        self.link_audio_sink(&pipeline, &sink, &gate)
             .context("unable to link audio sink")?;
-        self.link_video_sink(&pipeline, &sink)
+        let chat = self
+            .link_video_sink(&pipeline, &sink, &gate, scene)
             .context("unable to link video sink")?;
+        let tracks = self
+            .create_tracks(name, &pipeline, &sink, &gate)
+            .context("unable to create tracks")?;
+
+        let bytes_written = count_bytes(&bin);
+        watch_bus(&pipeline, self.counters.clone())?;
 
         pipeline
             .set_state(gst::State::Playing)
@@ -208,6 +349,11 @@ where
         let sink_state = ActiveSink {
             pipeline,
             sink: Box::new(sink),
+            gate,
+            start,
+            tracks,
+            bytes_written,
+            chat,
         };
 
         self.sinks.insert(name.to_owned(), sink_state);
@@ -220,7 +366,12 @@ where
     /// # Errors
     ///
     /// This can fail if the audio sink could not be linked to the `audio_mixer`.
-    fn link_audio_sink(&self, pipeline: &Pipeline, sink: &impl Sink) -> Result<()> {
+    fn link_audio_sink(
+        &self,
+        pipeline: &Pipeline,
+        sink: &impl Sink,
+        gate: &Arc<SinkGate>,
+    ) -> Result<()> {
         let app_src = AppSrc::builder()
             .name("audiosrc")
             .caps(
@@ -256,62 +407,238 @@ where
             .link(&sink.audio())
             .context("unable to link queue with audio sink")?;
 
-        self.audio_mixer.link_sink(&app_src);
+        self.audio_mixer.link_sink(&app_src, gate.clone());
 
         Ok(())
     }
 
-    /// Link the given sink to the `video_mixer`.
+    /// Create the audio tracks of the given sink and assign the current streams to the tracks of
+    /// their participants.
+    ///
+    /// # Errors
+    ///
+    /// This can fail if a track cannot be created or a stream cannot be assigned.
+    fn create_tracks(
+        &self,
+        name: &str,
+        pipeline: &Pipeline,
+        sink: &impl Sink,
+        gate: &Arc<SinkGate>,
+    ) -> Result<Vec<TrackSlot>> {
+        let mut tracks = sink
+            .tracks()
+            .iter()
+            .enumerate()
+            .map(|(index, pad)| {
+                TrackSlot::create(
+                    &format!("{name}-{index}"),
+                    &self.pipeline,
+                    pipeline,
+                    pad,
+                    gate,
+                )
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        if tracks.is_empty() {
+            return Ok(tracks);
+        }
+
+        // placeholder streams have no audio to assign
+        let mut ids: Vec<STREAMID> = self
+            .streams
+            .iter()
+            .filter(|(_, stream)| stream.has_media())
+            .map(|(id, _)| *id)
+            .collect();
+        ids.sort();
+        for id in &ids {
+            Self::assign_track(name, &mut tracks, id, self.get_stream(id)?)?;
+        }
+
+        Ok(tracks)
+    }
+
+    /// Assign the given stream to the track of its participant within the given tracks.
+    ///
+    /// A participant's track gets labeled with the stream's display name and the participant.
+    /// Streams of participants who find no track are only part of the mixed audio.
+    ///
+    /// # Errors
+    ///
+    /// This can fail if the stream's audio cannot be linked to the track.
+    fn assign_track(
+        sink: &str,
+        tracks: &mut [TrackSlot],
+        id: &STREAMID,
+        stream: &Stream<SRC>,
+    ) -> Result<()> {
+        if tracks.is_empty() {
+            return Ok(());
+        }
+        let owner = id.track_owner();
+        let Some(track) = TrackSlot::find(tracks, &owner) else {
+            warn!("sink '{sink}' has no free track left for participant {owner}");
+            return Ok(());
+        };
+
+        let tee = stream
+            .bin
+            .by_name("audio-tee")
+            .context("unable to find audio tee of stream")?;
+        let tee_pad = tee
+            .request_pad_simple("src_%u")
+            .context("unable to request src pad for audio tee")?;
+        let audio = GhostPad::with_target(None, &tee_pad)
+            .context("unable to create 'GhostPad' for track audio")?;
+        stream
+            .bin
+            .add_pad(&audio)
+            .context("unable to add track audio pad to stream bin")?;
+
+        let mut tags = gst::TagList::new();
+        {
+            let tags = tags.get_mut().context("unable to change track tags")?;
+            tags.add::<gst::tags::Title>(&stream.display_name.as_str(), gst::TagMergeMode::Replace);
+            tags.add::<gst::tags::Comment>(&owner.as_str(), gst::TagMergeMode::Replace);
+        }
+
+        track
+            .assign(&owner, &id.to_string(), &audio, tags)
+            .with_context(|| format!("unable to assign stream {id} to track"))
+    }
+
+    /// Link the given sink to the given scene or the shared layout of the `video_mixer`.
+    ///
+    /// Returns the chat overlay within the sink's video if its profile shows the chat.
     ///
     /// # Errors
     ///
     /// This can fail if the video sink could not be linked to the `video_mixer`.
-    fn link_video_sink(&self, pipeline: &Pipeline, sink: &impl Sink) -> Result<()> {
+    fn link_video_sink(
+        &self,
+        pipeline: &Pipeline,
+        sink: &impl Sink,
+        gate: &Arc<SinkGate>,
+        scene: Option<&str>,
+    ) -> Result<Option<ChatOverlay>> {
         let Some(video_mixer) = &self.video_mixer else {
-            return Ok(());
+            return Ok(None);
         };
         let Some(video_sink) = &sink.video() else {
-            return Ok(());
+            return Ok(None);
         };
 
-        let app_src = AppSrc::builder()
+        // sinks with a scaling profile get fed by a scaled branch of the video mixer
+        let format = sink
+            .profile()
+            .filter(|profile| profile.is_scaled())
+            .map(|profile| profile.video_format(self.output_resolution, VIDEO_FRAMERATE));
+        let (width, height, framerate) = format
+            .map_or((VIDEO_WIDTH, VIDEO_HEIGHT, VIDEO_FRAMERATE), |format| {
+                (format.width as i32, format.height as i32, format.framerate)
+            });
+
+        // the chat is drawn on the sink's own picture so other sinks stay clean
+        let show_chat = sink.profile().is_some_and(|profile| profile.chat);
+        // sinks get encoded video of a shared encoder unless they have an own picture
+        let encoding = sink.video_encoding();
+        let shared_encoding = encoding.filter(|_| !show_chat);
+
+        let mut app_src = AppSrc::builder()
             .name("videosrc")
-            .caps(
+            .format(gst::Format::Time)
+            .max_bytes(1)
+            .block(true);
+        // encoded video gets its caps from the samples
+        if shared_encoding.is_none() {
+            app_src = app_src.caps(
                 &gst::Caps::builder("video/x-raw")
                     .field("format", "RGB")
-                    .field("width", VIDEO_WIDTH)
-                    .field("height", VIDEO_HEIGHT)
-                    .field("framerate", Fraction::new(VIDEO_FRAMERATE, 1))
+                    .field("width", width)
+                    .field("height", height)
+                    .field("framerate", Fraction::new(framerate, 1))
                     .build(),
-            )
-            .format(gst::Format::Time)
-            .max_bytes(1)
-            .block(true)
-            .build();
+            );
+        }
+        let app_src = app_src.build();
         let queue = ElementFactory::make("queue")
-            .property_from_str("leaky", "downstream")
             .build()
             .context("unable to create queue")?;
-        let videoconvert = ElementFactory::make("videoconvert")
-            .build()
-            .context("unable to create videoconvert")?;
+        let mut elements = vec![app_src.clone().upcast(), queue.clone()];
+        // dropped frames of encoded video would break the following ones
+        if shared_encoding.is_none() {
+            queue.set_property_from_str("leaky", "downstream");
+            elements.push(
+                ElementFactory::make("videoconvert")
+                    .build()
+                    .context("unable to create videoconvert")?,
+            );
+        }
 
+        let elements: Vec<&Element> = elements.iter().collect();
         pipeline
-            .add_many(&[app_src.upcast_ref(), &queue, &videoconvert])
+            .add_many(&elements)
             .context("unable to add appsrc, queue and videoconvert to pipeline")?;
 
-        Element::link_many(&[app_src.upcast_ref(), &queue, &videoconvert])
-            .context("unable to link appsrc, queue and videoconvert")?;
+        Element::link_many(&elements).context("unable to link appsrc, queue and videoconvert")?;
 
-        videoconvert
-            .static_pad("src")
-            .context("unable to get static pad src from videoconvert")?
+        let mut video_src = elements
+            .last()
+            .and_then(|element| element.static_pad("src"))
+            .context("unable to get static pad src of the video input")?;
+
+        let chat = if show_chat {
+            let overlay = ChatOverlay::create("chat-overlay", &self.chat)
+                .context("unable to create chat overlay")?;
+            pipeline
+                .add(overlay.element())
+                .context("unable to add chat overlay to pipeline")?;
+            let overlay_sink = overlay
+                .sink()
+                .context("unable to get sink pad of chat overlay")?;
+            video_src
+                .link(&overlay_sink)
+                .context("unable to link videoconvert with chat overlay")?;
+            video_src = overlay
+                .src()
+                .context("unable to get src pad of chat overlay")?;
+            Some(overlay)
+        } else {
+            None
+        };
+
+        // sinks with an own picture get an own encoder
+        if let Some(encoding) = encoding.filter(|_| shared_encoding.is_none()) {
+            let [videoconvert, encoder, capsfilter] = encoding
+                .create_elements()
+                .context("unable to create video encoder")?;
+            pipeline
+                .add_many(&[&videoconvert, &encoder, &capsfilter])
+                .context("unable to add video encoder to pipeline")?;
+            Element::link_many(&[&videoconvert, &encoder, &capsfilter])
+                .context("unable to link video encoder")?;
+            video_src
+                .link(
+                    &videoconvert
+                        .static_pad("sink")
+                        .context("unable to get sink pad of video encoder")?,
+                )
+                .context("unable to link video with video encoder")?;
+            video_src = capsfilter
+                .static_pad("src")
+                .context("unable to get src pad of video encoder")?;
+        }
+
+        video_src
             .link(video_sink)
             .context("unable to link queue with video sink")?;
 
-        video_mixer.link_sink(&app_src);
+        video_mixer
+            .link_sink(&app_src, gate.clone(), format, shared_encoding, scene)
+            .context("unable to link video mixer with sink")?;
 
-        Ok(())
+        Ok(chat)
     }
 
     /// Release the given sink from the mixer.
@@ -319,7 +646,7 @@ where
     /// # Errors
     ///
     /// This can fail if the sink could not be released from the mixer.
-    pub fn release_sink(&mut self, name: &String) -> Result<()> {
+    pub fn release_sink(&mut self, name: &String) -> Result<ReleasedSink> {
         let Some(active_sink) = self.sinks.get_mut(name) else {
             bail!("there is no stream with the name '{name}'");
         };
@@ -348,18 +675,233 @@ where
                 .context("unable to send EOS to audio_src")?;
         }
 
+        for track in std::mem::take(&mut active_sink.tracks) {
+            track
+                .release(&self.pipeline)
+                .context("unable to release track")?;
+        }
+
         active_sink
             .sink
             .on_exit(&self.pipeline)
             .with_context(|| format!("unable to exit sink '{name}'"))?;
 
-        self.sinks
+        let active_sink = self
+            .sinks
             .remove(name)
             .with_context(|| format!("unable to remove sink '{name}' from sinks"))?;
 
+        if self.sink_layouts.contains_key(name) {
+            self.remove_sink_layout(name)
+                .with_context(|| format!("unable to remove layout of sink '{name}'"))?;
+        }
+
+        Ok(ReleasedSink(active_sink))
+    }
+
+    /// Unlink all streams from the composition of the given sink's own layout and remove it.
+    ///
+    /// # Errors
+    ///
+    /// This can fail if the streams cannot be unlinked or the composition cannot be removed.
+    fn remove_sink_layout(&mut self, name: &str) -> Result<()> {
+        self.sink_layouts.remove(name);
+        let Some(video_mixer) = &mut self.video_mixer else {
+            return Ok(());
+        };
+        for stream in self.streams.values_mut() {
+            if let Some(video) = stream.scenes.remove(name) {
+                Self::unlink_video_tee(video_mixer, &stream.bin, &video, name)
+                    .context("unable to unlink stream from the sink's layout")?;
+            }
+        }
+        video_mixer.remove_scene(name)
+    }
+
+    /// Pause all output sinks.
+    ///
+    /// The mixer pipeline keeps running but no samples are forwarded to the sinks until
+    /// [`Mixer::resume_sinks()`] is called. The paused time is cut out of the outputs.
+    ///
+    /// # Errors
+    ///
+    /// This can fail if the sinks are already paused or the clock time is unavailable.
+    pub fn pause_sinks(&mut self) -> Result<()> {
+        if self.sinks_paused() {
+            bail!("sinks are already paused");
+        }
+
+        let now = self.running_time()?;
+
+        for sink in self.sinks.values_mut() {
+            if sink.gate.pause(now) {
+                sink.sink.on_pause();
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Resume all output sinks paused by [`Mixer::pause_sinks()`] or [`Mixer::pause_sink()`].
+    ///
+    /// # Errors
+    ///
+    /// This can fail if no sink is paused or the clock time is unavailable.
+    pub fn resume_sinks(&mut self) -> Result<()> {
+        if !self.sinks.values().any(|sink| sink.gate.is_paused()) {
+            bail!("sinks are not paused");
+        }
+
+        let now = self.running_time()?;
+
+        for (name, sink) in &mut self.sinks {
+            if sink.gate.resume(now) {
+                sink.sink
+                    .on_play()
+                    .with_context(|| format!("unable to resume sink '{name}'"))?;
+            }
+        }
+
         Ok(())
     }
 
+    /// Returns `true` if all output sinks are paused.
+    #[must_use]
+    pub fn sinks_paused(&self) -> bool {
+        !self.sinks.is_empty() && self.sinks.values().all(|sink| sink.gate.is_paused())
+    }
+
+    /// Pause a single output sink while all other sinks continue.
+    ///
+    /// # Errors
+    ///
+    /// This can fail if there is no sink with the given name, the sink is already paused or
+    /// the clock time is unavailable.
+    pub fn pause_sink(&mut self, name: &str) -> Result<()> {
+        let now = self.running_time()?;
+
+        let Some(sink) = self.sinks.get_mut(name) else {
+            bail!("there is no sink with the name '{name}'");
+        };
+
+        if !sink.gate.pause(now) {
+            bail!("sink '{name}' is already paused");
+        }
+
+        sink.sink.on_pause();
+
+        Ok(())
+    }
+
+    /// Resume a single output sink paused by [`Mixer::pause_sink()`].
+    ///
+    /// # Errors
+    ///
+    /// This can fail if there is no sink with the given name, the sink is not paused or the
+    /// clock time is unavailable.
+    pub fn resume_sink(&mut self, name: &str) -> Result<()> {
+        let now = self.running_time()?;
+
+        let Some(sink) = self.sinks.get_mut(name) else {
+            bail!("there is no sink with the name '{name}'");
+        };
+
+        if !sink.gate.resume(now) {
+            bail!("sink '{name}' is not paused");
+        }
+
+        sink.sink
+            .on_play()
+            .with_context(|| format!("unable to resume sink '{name}'"))
+    }
+
+    /// Returns `true` if the sink with the given name is paused.
+    #[must_use]
+    pub fn is_sink_paused(&self, name: &str) -> bool {
+        self.sinks
+            .get(name)
+            .is_some_and(|sink| sink.gate.is_paused())
+    }
+
+    /// Returns `true` if a sink with the given name is linked.
+    #[must_use]
+    pub fn has_sink(&self, name: &str) -> bool {
+        self.sinks.contains_key(name)
+    }
+
+    /// Position within the output of the sink with the given name.
+    ///
+    /// This is the time since the sink was linked without the time it was paused.
+    ///
+    /// # Errors
+    ///
+    /// This can fail if there is no sink with the given name or the clock time is unavailable.
+    pub fn sink_position(&self, name: &str) -> Result<ClockTime> {
+        let Some(sink) = self.sinks.get(name) else {
+            bail!("there is no sink with the name '{name}'");
+        };
+
+        Ok(sink.gate.position(sink.start, self.running_time()?))
+    }
+
+    /// State of all linked sinks ordered by their names.
+    ///
+    /// # Errors
+    ///
+    /// This can fail if the clock time is unavailable.
+    pub fn sinks(&self) -> Result<Vec<SinkState>> {
+        let now = self.running_time()?;
+
+        let mut sinks: Vec<SinkState> = self
+            .sinks
+            .iter()
+            .map(|(name, sink)| SinkState {
+                name: name.clone(),
+                paused: sink.gate.is_paused(),
+                position: sink.gate.position(sink.start, now),
+                bytes_written: sink.bytes_written.load(Ordering::Relaxed),
+            })
+            .collect();
+        sinks.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(sinks)
+    }
+
+    /// Add a chat message to the chat overlays of the sinks.
+    ///
+    /// # Arguments
+    ///
+    /// - `sender`: Display name of the sender.
+    /// - `text`: Content of the message.
+    ///
+    pub fn add_chat_message(&self, sender: &str, text: &str) {
+        self.chat.push(sender, text);
+    }
+
+    /// Change the style of the chat overlays of all sinks.
+    pub fn set_chat_style(&self, style: ChatStyle) {
+        for overlay in self.sinks.values().filter_map(|sink| sink.chat.as_ref()) {
+            overlay.set_style(&style);
+        }
+        self.chat.set_style(style);
+    }
+
+    /// Dropped frames and errors of the mixer's pipelines since the mixer was created.
+    #[must_use]
+    pub fn statistics(&self) -> Statistics {
+        self.counters.get()
+    }
+
+    /// Current running time of the mixer pipeline.
+    fn running_time(&self) -> Result<ClockTime> {
+        let now = self
+            .system_clock
+            .time()
+            .context("unable to get time from system clock")?;
+
+        Ok(now.saturating_sub(self.pipeline.base_time().unwrap_or(ClockTime::ZERO)))
+    }
+
     /// Add a new stream to the mixer.
     ///
     /// New video streams will NOT get visible but audio streams will
@@ -371,6 +913,7 @@ where
     /// - `display_name`: Name to display to user as identifier.
     /// - `params`: Source specific parameters.
     /// - `overlays`: list of overlays to attach behind source
+    /// - `placeholder`: Picture to show while the stream has no video or `None`.
     ///
     /// # Errors
     ///
@@ -381,6 +924,7 @@ where
         display_name: String,
         params: SRC::Parameters,
         overlay: AnyOverlay,
+        placeholder: Option<Placeholder>,
         status: StreamStatus,
     ) -> Result<()> {
         info!("add_stream( {id}, '{display_name}', {params:?} )");
@@ -400,41 +944,44 @@ where
             .context("unable to add source bin to bin")?;
 
         // Setup video in pipeline
-        if self.video_mixer.is_some() {
-            if let Some(video) = source.video() {
-                let videoconvertscale = ElementFactory::make("videoconvertscale")
-                    .name("videoconvertscale")
-                    .build()
-                    .context("unable to build videoconvertscale")?;
-                let capsfilter = ElementFactory::make("capsfilter")
-                    .name("capsfilter")
-                    .build()
-                    .context("unable to build capsfilter")?;
-
-                bin.add_many(&[&videoconvertscale, &capsfilter, overlay.element()])
-                    .context(
-                        "unable to add 'videoconvertscale', 'capsfilter' and 'overlay' to source bin",
-                    )?;
-
-                Element::link_many(&[&videoconvertscale, &capsfilter, &overlay.element()])
-                    .context("unable to link 'videoconvertscale' and 'capsfilter")?;
-
-                let videoconvertscale_sink_pad = videoconvertscale
-                    .static_pad("sink")
-                    .context("unable to get sink pad from videoconvertscale")?;
-                video
-                    .link(&videoconvertscale_sink_pad)
-                    .context("unable to link video_src to videoconvertscale")?;
-            }
-        }
+        let placeholder = placeholder.filter(|_| self.video_mixer.is_some());
+        let has_video_chain = self.video_mixer.is_some()
+            && Self::add_video_chain(&bin, source.video(), placeholder.as_ref(), &overlay)?;
 
         // Add bin to pipeline
         self.pipeline
             .add(&bin)
             .context("failed to add source bin to pipeline")?;
 
+        // Control gain of the stream's audio
+        let audio_src = if self.loudness.stream_agc {
+            Self::add_gain_control(&bin, &source.audio())
+                .context("unable to add gain control to source bin")?
+        } else {
+            source.audio().upcast()
+        };
+
+        // Split audio for the mix and the tracks of single streams
+        let audio_tee = ElementFactory::make("tee")
+            .name("audio-tee")
+            .property("allow-not-linked", true)
+            .build()
+            .context("unable to build audio tee")?;
+        bin.add(&audio_tee)
+            .context("unable to add audio tee to source bin")?;
+        audio_src
+            .link(
+                &audio_tee
+                    .static_pad("sink")
+                    .context("unable to get sink pad from audio tee")?,
+            )
+            .context("unable to link audio with audio tee")?;
+        let audio_tee_pad = audio_tee
+            .request_pad_simple("src_%u")
+            .context("unable to request src pad for audio tee")?;
+
         // Link audio in pipeline
-        let audio_ghost_pad = GhostPad::with_target(None, &source.audio())
+        let audio_ghost_pad = GhostPad::with_target(None, &audio_tee_pad)
             .context("unable to create 'GhostPad' for 'audio'")?;
         bin.add_pad(&audio_ghost_pad)
             .context("unable to add audio_ghost_pad to bin")?;
@@ -444,23 +991,10 @@ where
             .context("unable to add 'audio' pad to 'audio_mixer'")?;
 
         // Link video in pipeline
-        let video = if let (Some(video_mixer), Some(_)) = (&self.video_mixer, source.video()) {
-            let overlay_src_pad = overlay
-                .src()
-                .context("unable to get src pad from overlay")?;
-            let overlay_ghost_pad = GhostPad::with_target(None, &overlay_src_pad)
-                .context("unable to create 'GhostPad' for 'videoconvertscale'")?;
-
-            bin.add_pad(&overlay_ghost_pad)
-                .context("unable to add overlay_ghost_pad to bin")?;
-
-            let video = video_mixer
-                .link_src(&overlay_ghost_pad)
-                .context("unable to add 'video' pad to 'video_mixer'")?;
-
-            Some(video)
+        let (video, scenes) = if has_video_chain {
+            self.link_video_chain(&bin)?
         } else {
-            None
+            (None, HashMap::new())
         };
 
         debug::debug_dot(&self.pipeline, "stream_added");
@@ -472,78 +1006,373 @@ where
             id,
             Stream {
                 display_name,
-                source,
+                source: Some(source),
                 bin,
                 video,
-                audio,
+                scenes,
+                audio: Some(audio),
                 overlay,
+                placeholder,
                 status,
+                volume: 1.0,
             },
         );
 
+        // assign to the participant's track of every sink with tracks
+        let stream = self
+            .streams
+            .get(&id)
+            .context("unable to find added stream")?;
+        for (name, sink) in &mut self.sinks {
+            Self::assign_track(name, &mut sink.tracks, &id, stream)?;
+        }
+
         debug!("Added stream {id}");
 
         Ok(())
     }
 
-    /// Continuously read the bus for errors and EOS.
-    fn read_bus(&mut self) -> Result<()> {
-        // get pipeline bus
-        let bus = self
-            .pipeline
-            .bus()
-            .context("failed to get bus of pipeline")?;
-
-        // add watch which continuous recalculates latency
-        let pipeline_weak = self.pipeline.downgrade();
-        bus.add_watch(move |_, msg| {
-            use gst::MessageView;
-            // check several message types
-            match (msg.view(), &pipeline_weak.upgrade()) {
-                (MessageView::Error(err), Some(pipeline)) => {
-                    error!(
-                        "Error received from element {:?}: {}",
-                        err.src().map(GstObjectExt::path_string),
-                        err.error(),
-                    );
-                    debug::dot(pipeline, "BUS-ERROR");
-                    if let Some(info) = err.debug() {
-                        debug!("Debugging information: {}", info);
-                    }
-                }
-                (MessageView::Warning(warn), Some(pipeline)) => {
-                    warn!(
-                        "Warning received from element {:?}: {}",
-                        warn.src().map(GstObjectExt::path_string),
-                        warn.error(),
-                    );
-                    debug::dot(pipeline, "BUS-WARNING");
-                    if let Some(info) = warn.debug() {
-                        debug!("Debugging information: {}", info);
-                    }
-                }
-                (MessageView::Info(info), Some(pipeline)) => {
-                    info!(
-                        "Info received from element {:?}: {}",
-                        info.src().map(GstObjectExt::path_string),
-                        info.error(),
-                    );
-                    debug::dot(pipeline, "BUS-INFO");
-                    if let Some(info) = info.debug() {
-                        debug!("Debugging information: {}", info);
-                    }
-                }
-                (MessageView::Latency(_), Some(pipeline)) => {
-                    // Recalculate pipeline latency when requested
-                    let _ = pipeline.recalculate_latency();
+    /// Add a stream which has no media but only shows the given placeholder.
+    ///
+    /// Like other streams the new stream will NOT get visible.
+    ///
+    /// # Arguments
+    ///
+    /// - `id`: Unique identifier of the stream.
+    /// - `display_name`: Name to display to user as identifier.
+    /// - `overlay`: Overlay to attach behind the placeholder.
+    /// - `placeholder`: Picture to show.
+    ///
+    /// # Errors
+    ///
+    /// This can fail if the mixer has no video or adding the stream to the `GStreamer` pipeline
+    /// fails.
+    pub fn add_placeholder(
+        &mut self,
+        id: STREAMID,
+        display_name: String,
+        overlay: AnyOverlay,
+        placeholder: Placeholder,
+    ) -> Result<()> {
+        info!("add_placeholder( {id}, '{display_name}' )");
+
+        if self.video_mixer.is_none() {
+            bail!("Cannot add placeholder {id} without video support.");
+        }
+        if self.streams.contains_key(&id) {
+            warn!("Cannot add stream with ID {id} twice.");
+            return Err(anyhow!("Cannot add stream with ID {id} twice."));
+        }
+
+        let bin = Bin::new(Some(format!("Placeholder: {id}").as_str()));
+        Self::add_video_chain(&bin, None, Some(&placeholder), &overlay)?;
+
+        self.pipeline
+            .add(&bin)
+            .context("failed to add placeholder bin to pipeline")?;
+        let (video, scenes) = self.link_video_chain(&bin)?;
+
+        debug::debug_dot(&self.pipeline, "placeholder_added");
+
+        bin.sync_state_with_parent()
+            .context("unable to sync state with parent for bin")?;
+
+        self.streams.insert(
+            id,
+            Stream {
+                display_name,
+                source: None,
+                bin,
+                video,
+                scenes,
+                audio: None,
+                overlay,
+                placeholder: Some(placeholder),
+                status: StreamStatus::none(),
+                volume: 1.0,
+            },
+        );
+
+        debug!("Added placeholder {id}");
+
+        Ok(())
+    }
+
+    /// Add the elements which scale the given video and the placeholder and render the overlay
+    /// on top into the given bin.
+    ///
+    /// If both, video and placeholder, are given an `input-selector` switches between them.
+    ///
+    /// Returns `false` if there is neither video nor placeholder.
+    ///
+    /// # Errors
+    ///
+    /// This can fail if the elements cannot be created, added or linked.
+    fn add_video_chain(
+        bin: &Bin,
+        video: Option<GhostPad>,
+        placeholder: Option<&Placeholder>,
+        overlay: &AnyOverlay,
+    ) -> Result<bool> {
+        if video.is_none() && placeholder.is_none() {
+            return Ok(false);
+        }
+
+        let videoconvertscale = ElementFactory::make("videoconvertscale")
+            .name("videoconvertscale")
+            .build()
+            .context("unable to build videoconvertscale")?;
+        let capsfilter = ElementFactory::make("capsfilter")
+            .name("capsfilter")
+            .build()
+            .context("unable to build capsfilter")?;
+
+        // splits the picture for the shared layout and the layouts of single sinks
+        let video_tee = ElementFactory::make("tee")
+            .name("video-tee")
+            .property("allow-not-linked", true)
+            .build()
+            .context("unable to build video tee")?;
+
+        bin.add_many(&[&videoconvertscale, &capsfilter, overlay.element(), &video_tee])
+            .context(
+                "unable to add 'videoconvertscale', 'capsfilter', 'overlay' and 'video_tee' to source bin",
+            )?;
+
+        Element::link_many(&[
+            &videoconvertscale,
+            &capsfilter,
+            &overlay.element(),
+            &video_tee,
+        ])
+        .context("unable to link 'videoconvertscale', 'capsfilter', 'overlay' and 'video_tee'")?;
+
+        let videoconvertscale_sink_pad = videoconvertscale
+            .static_pad("sink")
+            .context("unable to get sink pad from videoconvertscale")?;
+
+        let placeholder_src = if let Some(placeholder) = placeholder {
+            bin.add(placeholder.bin())
+                .context("unable to add placeholder to source bin")?;
+            Some(
+                placeholder
+                    .bin()
+                    .static_pad("src")
+                    .context("unable to get src pad from placeholder")?,
+            )
+        } else {
+            None
+        };
+
+        match (video, placeholder_src) {
+            (Some(video), Some(placeholder_src)) => {
+                // the video is linked to `sink_0` and the placeholder to `sink_1`
+                let selector = ElementFactory::make("input-selector")
+                    .name("video-selector")
+                    .property("sync-streams", false)
+                    .build()
+                    .context("unable to build input-selector")?;
+                bin.add(&selector)
+                    .context("unable to add input-selector to source bin")?;
+                video
+                    .link(
+                        &selector
+                            .request_pad_simple("sink_%u")
+                            .context("unable to request video pad of input-selector")?,
+                    )
+                    .context("unable to link video_src to input-selector")?;
+                placeholder_src
+                    .link(
+                        &selector
+                            .request_pad_simple("sink_%u")
+                            .context("unable to request placeholder pad of input-selector")?,
+                    )
+                    .context("unable to link placeholder to input-selector")?;
+                selector
+                    .link(&videoconvertscale)
+                    .context("unable to link input-selector to videoconvertscale")?;
+            }
+            (Some(video), None) => {
+                video
+                    .link(&videoconvertscale_sink_pad)
+                    .context("unable to link video_src to videoconvertscale")?;
+            }
+            (None, Some(placeholder_src)) => {
+                placeholder_src
+                    .link(&videoconvertscale_sink_pad)
+                    .context("unable to link placeholder to videoconvertscale")?;
+            }
+            (None, None) => {}
+        }
+
+        Ok(true)
+    }
+
+    /// Link the end of the video chain within the given bin to the shared layout and the layouts
+    /// of single sinks of the `video_mixer`.
+    ///
+    /// Returns the pad linked to the shared layout and the pads linked to the sinks' layouts.
+    ///
+    /// # Errors
+    ///
+    /// This can fail if the video chain cannot be linked to the `video_mixer`.
+    fn link_video_chain(&self, bin: &Bin) -> Result<(Option<GhostPad>, HashMap<String, GhostPad>)> {
+        let Some(video_mixer) = &self.video_mixer else {
+            return Ok((None, HashMap::new()));
+        };
+
+        let video = Self::link_video_tee(video_mixer, bin, None)?;
+        let scenes = self
+            .sink_layouts
+            .keys()
+            .map(|scene| {
+                Self::link_video_tee(video_mixer, bin, Some(scene.as_str()))
+                    .map(|video| (scene.clone(), video))
+            })
+            .collect::<Result<_>>()?;
+
+        Ok((Some(video), scenes))
+    }
+
+    /// Link the video tee within the given bin to the compositor of the given scene or of the
+    /// shared layout.
+    ///
+    /// The layouts of single sinks get a queue of their own behind the tee.
+    ///
+    /// # Errors
+    ///
+    /// This can fail if the tee cannot be linked to the `video_mixer`.
+    fn link_video_tee(
+        video_mixer: &VideoMixer,
+        bin: &Bin,
+        scene: Option<&str>,
+    ) -> Result<GhostPad> {
+        let video_tee = bin
+            .by_name("video-tee")
+            .context("unable to find video tee of stream")?;
+        let tee_pad = video_tee
+            .request_pad_simple("src_%u")
+            .context("unable to request src pad for video tee")?;
+
+        let src_pad = if scene.is_some() {
+            let queue = ElementFactory::make("queue")
+                .build()
+                .context("unable to build queue")?;
+            bin.add(&queue)
+                .context("unable to add queue to source bin")?;
+            tee_pad
+                .link(
+                    &queue
+                        .static_pad("sink")
+                        .context("unable to get sink pad from queue")?,
+                )
+                .context("unable to link video tee with queue")?;
+            queue
+                .sync_state_with_parent()
+                .context("unable to sync queue state")?;
+            queue
+                .static_pad("src")
+                .context("unable to get src pad from queue")?
+        } else {
+            tee_pad
+        };
+
+        let video_ghost_pad = GhostPad::with_target(None, &src_pad)
+            .context("unable to create 'GhostPad' for 'video'")?;
+        bin.add_pad(&video_ghost_pad)
+            .context("unable to add video_ghost_pad to bin")?;
+
+        video_mixer
+            .link_src(&video_ghost_pad, scene)
+            .context("unable to add 'video' pad to 'video_mixer'")
+    }
+
+    /// Unlink the given video pad of a stream from the compositor of the given scene.
+    ///
+    /// Removes the queue behind the video tee within the stream's bin.
+    ///
+    /// # Errors
+    ///
+    /// This can fail if the pad cannot be released from the `video_mixer`.
+    fn unlink_video_tee(
+        video_mixer: &VideoMixer,
+        bin: &Bin,
+        video: &GhostPad,
+        scene: &str,
+    ) -> Result<()> {
+        let src_pad = video.peer().and_then(|pad| pad.downcast::<GhostPad>().ok());
+        if let Some(compositor_sink) = video.target() {
+            video_mixer
+                .release_src(&compositor_sink, Some(scene))
+                .context("unable to release src in video_mixer")?;
+        }
+
+        let Some(src_pad) = src_pad else {
+            return Ok(());
+        };
+        if let Some(queue) = src_pad.target().and_then(|pad| pad.parent_element()) {
+            let queue_sink = queue
+                .static_pad("sink")
+                .context("unable to get sink pad from queue")?;
+            if let Some(tee_pad) = queue_sink.peer() {
+                tee_pad
+                    .unlink(&queue_sink)
+                    .context("unable to unlink video tee from queue")?;
+                if let Some(video_tee) = bin.by_name("video-tee") {
+                    video_tee.release_request_pad(&tee_pad);
                 }
-                _ => (),
             }
-            // stop reading if we are expecting EOS after the following scan
-            Continue(true)
-        })?;
+            queue
+                .set_state(gst::State::Null)
+                .context("unable to stop queue")?;
+            bin.remove(&queue)
+                .context("unable to remove queue from source bin")?;
+        }
+        bin.remove_pad(&src_pad)
+            .context("unable to remove video pad from source bin")
+    }
 
-        Ok(())
+    /// Add automatic gain control behind the given audio pad into the given bin.
+    ///
+    /// Returns the pad which provides the controlled audio.
+    fn add_gain_control(bin: &Bin, audio: &impl IsA<gst::Pad>) -> Result<gst::Pad> {
+        let make = |factory: &str| {
+            ElementFactory::make(factory)
+                .build()
+                .with_context(|| format!("unable to build {factory}"))
+        };
+        // use webrtcdsp for gain control only
+        let webrtcdsp = ElementFactory::make("webrtcdsp")
+            .name("gain-control")
+            .property("echo-cancel", false)
+            .property("noise-suppression", false)
+            .property("high-pass-filter", false)
+            .property("gain-control", true)
+            .build()
+            .context("unable to build webrtcdsp")?;
+        let elements = [
+            make("audioconvert")?,
+            make("audioresample")?,
+            webrtcdsp,
+            make("audioconvert")?,
+        ];
+        let elements: Vec<&Element> = elements.iter().collect();
+
+        bin.add_many(&elements)
+            .context("unable to add gain control to bin")?;
+        Element::link_many(&elements).context("unable to link gain control")?;
+
+        audio
+            .link(
+                &elements[0]
+                    .static_pad("sink")
+                    .context("unable to get sink pad of audioconvert")?,
+            )
+            .context("unable to link audio with gain control")?;
+
+        elements[elements.len() - 1]
+            .static_pad("src")
+            .context("unable to get src pad of audioconvert")
     }
 
     /// Return current pipeline state.
@@ -587,15 +1416,31 @@ where
         if let Some(video_mixer) = &self.video_mixer {
             if let Some(video_src) = &stream.compositor_sink() {
                 video_mixer
-                    .release_src(video_src)
+                    .release_src(video_src, None)
                     .context("unable to release src in video_mixer")?;
             }
+            for (scene, video) in &stream.scenes {
+                if let Some(video_src) = video.target() {
+                    video_mixer
+                        .release_src(&video_src, Some(scene.as_str()))
+                        .context("unable to release src in video_mixer")?;
+                }
+            }
+        }
+
+        let name = id.to_string();
+        for track in self.sinks.values_mut().flat_map(|sink| &mut sink.tracks) {
+            track
+                .end_stream(&name)
+                .context("unable to end stream within track")?;
         }
 
         self.pipeline
             .remove(&stream.bin)
             .context("can not remove stream's bin from pipeline")?;
 
+        self.roles.remove(&id);
+
         // remove stream from visibles
         if let Some(index) = self.visibles.iter().position(|i| *i == id) {
             self.visibles.remove(index);
@@ -693,6 +1538,29 @@ where
         self.rerender_layout().context("unable to rerender layout")
     }
 
+    /// Set the role of a stream which a layout may use to place it.
+    ///
+    /// # Arguments
+    ///
+    /// `id`: ID of stream
+    /// `role`: New role of the stream
+    ///
+    /// # Errors
+    ///
+    /// This can fail if the `rerender_layout` function is failing.
+    pub fn set_role(&mut self, id: &STREAMID, role: StreamRole) -> Result<()> {
+        let old_role = if role == StreamRole::Other {
+            self.roles.remove(id)
+        } else {
+            self.roles.insert(*id, role)
+        };
+
+        if old_role.unwrap_or_default() == role || !self.is_visible(id) {
+            return Ok(());
+        }
+        self.rerender_layout().context("unable to rerender layout")
+    }
+
     /// Return `true`, if stream is currently visible
     ///
     pub fn is_visible(&self, id: &STREAMID) -> bool {
@@ -710,7 +1578,8 @@ where
 
     /// Set status of a stream.
     ///
-    /// This function does not change visibility of a stream but audio presence.
+    /// This function does not change visibility of a stream but audio presence and whether the
+    /// stream's placeholder is shown instead of its video.
     ///
     /// # Arguments
     ///
@@ -732,15 +1601,56 @@ where
         }
 
         let current_stream = self.get_stream_mut(id)?;
-        current_stream
-            .audiomixer_sink()
-            .context("unable to get sink for audiomixer")?
-            .set_property("volume", if new_status.has_audio { 1.0 } else { 0.0 });
+        if current_stream.has_media() {
+            current_stream
+                .audiomixer_sink()
+                .context("unable to get sink for audiomixer")?
+                .set_property(
+                    "volume",
+                    if new_status.has_audio {
+                        current_stream.volume
+                    } else {
+                        0.0
+                    },
+                );
+        }
+        current_stream.show_placeholder(!new_status.has_video);
         current_stream.status = new_status;
 
         Ok(())
     }
 
+    /// Set volume of a stream's audio within the mix.
+    ///
+    /// The volume is kept while the stream has no audio.
+    ///
+    /// # Arguments
+    ///
+    /// - `id`: Describes which stream shall be updated.
+    /// - `volume`: Linear volume factor from `0.0` (silent) over `1.0` (unity gain) to `10.0`.
+    ///
+    /// # Errors
+    ///
+    /// This can fail if the stream isn't in the `streams` list or the volume is out of range.
+    pub fn set_stream_volume(&mut self, id: &STREAMID, volume: f64) -> Result<()> {
+        info!("set_stream_volume( {id}, {volume} )");
+
+        if !(0.0..=MAX_STREAM_VOLUME).contains(&volume) {
+            bail!("stream volume {volume} is not within 0.0 and {MAX_STREAM_VOLUME}");
+        }
+
+        let current_stream = self.get_stream_mut(id)?;
+        if current_stream.status.has_audio {
+            current_stream
+                .audiomixer_sink()
+                .context("unable to get sink for audiomixer")?
+                .set_property("volume", volume);
+        }
+        current_stream.volume = volume;
+
+        Ok(())
+    }
+
     /// Access the mixer's mutable streams.
     ///
     /// # Arguments
@@ -797,6 +1707,31 @@ where
         self.rerender_layout().context("unable to rerender layout")
     }
 
+    /// Exchange the current layout with the given one.
+    ///
+    /// After the call `layout` holds the layout which was used before.
+    ///
+    /// # Errors
+    ///
+    /// This can fail if the `rerender_layout` function is failing.
+    pub fn swap_layout(&mut self, layout: &mut Box<dyn Layout>) -> Result<()> {
+        std::mem::swap(&mut self.layout, layout);
+        self.rerender_layout().context("unable to rerender layout")
+    }
+
+    /// Change the background behind all streams.
+    ///
+    /// # Arguments
+    ///
+    /// - `color`: Color to fill the background with.
+    /// - `image`: Image which is stretched over the whole background or `None`.
+    ///
+    pub fn set_background(&self, color: Color, image: Option<&Image>) {
+        if let Some(video_mixer) = &self.video_mixer {
+            video_mixer.set_background(color, image);
+        }
+    }
+
     /// Re-layout the current compositor scene.
     ///
     /// # Errors
@@ -826,47 +1761,94 @@ where
         );
         self.invalidate().context("unable to invalidate layout")?;
 
-        self.layout.set_resolution_changed(self.output_resolution);
-        self.layout.set_amount_of_visibles(self.visibles.len());
+        let roles: Vec<StreamRole> = self
+            .visibles
+            .iter()
+            .map(|id| self.roles.get(id).copied().unwrap_or_default())
+            .collect();
+        let layouts = std::iter::once(&mut self.layout).chain(
+            self.sink_layouts
+                .values_mut()
+                .map(|sink_layout| &mut sink_layout.layout),
+        );
+        for layout in layouts {
+            layout.set_resolution_changed(self.output_resolution);
+            layout.set_amount_of_visibles(self.visibles.len());
+            layout.set_stream_roles(&roles);
+        }
 
         let mut streams = self.visibles.clone();
         streams.append(&mut self.invisibles());
 
+        let area = |view: &View| view.size.width * view.size.height;
+
         // layout all video streams
         for (n, id) in streams.iter().enumerate() {
             let stream = self.streams.get(id).context("stream not found")?;
-            if let Some(compositor_sink) = stream.compositor_sink() {
-                if let Some(view) = self.layout.calculate_stream_view(n) {
-                    compositor_sink.set_properties(&[
-                        ("xpos", &(view.pos.x as i32).to_value()),
-                        ("ypos", &(view.pos.y as i32).to_value()),
-                        ("width", &(view.size.width as i32).to_value()),
-                        ("height", &(view.size.height as i32).to_value()),
-                        ("alpha", &(1.0).to_value()),
-                    ]);
-                    // Scale down the original video so the text overlay can be rendered properly
-                    stream
-                        .capsfilter()
-                        .context("unable to get capsfilter for stream")?
-                        .set_property(
-                            "caps",
-                            gst::Caps::builder("video/x-raw")
-                                .field("width", view.size.width as i32)
-                                .field("height", view.size.height as i32)
-                                .field("pixel-aspect-ratio", Fraction::new(1, 1))
-                                .build(),
-                        );
-                    // Reconfigure the videoconverscale after changing the size
-                    stream
-                        .videoconvertscale()
-                        .context("unable to get videoconvertsccale for stream")?
-                        .static_pad("src")
-                        .context("unable to get src from videoconvertscale")?
-                        .send_event(Reconfigure::new());
-                } else {
+            let Some(compositor_sink) = stream.compositor_sink() else {
+                continue;
+            };
+
+            // the picture of the stream is scaled to its largest view, the compositors of the
+            // other views scale it down
+            let mut largest: Option<View> = None;
+            let views = std::iter::once((compositor_sink, self.layout.calculate_stream_view(n)))
+                .chain(self.sink_layouts.iter().filter_map(|(scene, sink_layout)| {
+                    let compositor_sink = stream.scenes.get(scene)?.target()?;
+                    Some((compositor_sink, sink_layout.layout.calculate_stream_view(n)))
+                }));
+            for (compositor_sink, view) in views {
+                let Some(view) = view else {
                     compositor_sink.set_property("alpha", 0.0);
+                    continue;
+                };
+                compositor_sink.set_properties(&[
+                    ("xpos", &(view.pos.x as i32).to_value()),
+                    ("ypos", &(view.pos.y as i32).to_value()),
+                    ("width", &(view.size.width as i32).to_value()),
+                    ("height", &(view.size.height as i32).to_value()),
+                    ("alpha", &(1.0).to_value()),
+                    // keep all streams above the background
+                    ("zorder", &(view.z_order + 1).to_value()),
+                ]);
+                compositor_sink.set_property_from_str(
+                    "sizing-policy",
+                    match view.fit {
+                        Fit::Contain => "keep-aspect-ratio",
+                        Fit::Stretch => "none",
+                    },
+                );
+                if largest
+                    .as_ref()
+                    .map_or(true, |largest| area(&view) > area(largest))
+                {
+                    largest = Some(view);
                 }
             }
+
+            if let Some(view) = largest {
+                // Scale down the original video so the text overlay can be rendered properly
+                stream
+                    .capsfilter()
+                    .context("unable to get capsfilter for stream")?
+                    .set_property(
+                        "caps",
+                        gst::Caps::builder("video/x-raw")
+                            .field("width", view.size.width as i32)
+                            .field("height", view.size.height as i32)
+                            .field("pixel-aspect-ratio", Fraction::new(1, 1))
+                            .build(),
+                    );
+                // Reconfigure the videoconverscale after changing the size
+                let videoconvertscale = stream
+                    .videoconvertscale()
+                    .context("unable to get videoconvertsccale for stream")?;
+                videoconvertscale.set_property("add-borders", view.fit == Fit::Contain);
+                videoconvertscale
+                    .static_pad("src")
+                    .context("unable to get src from videoconvertscale")?
+                    .send_event(Reconfigure::new());
+            }
         }
 
         self.validate().context("unable to validate layout")?;
@@ -929,10 +1911,74 @@ fn monitor_layout(receiver: std::sync::mpsc::Receiver<Validation>) {
     });
 }
 
+/// Continuously read the bus of the given pipeline for errors and EOS.
+///
+/// Errors and dropped frames are counted by `counters`.
+fn watch_bus(pipeline: &Pipeline, counters: Arc<Counters>) -> Result<()> {
+    // get pipeline bus
+    let bus = pipeline.bus().context("failed to get bus of pipeline")?;
+
+    // add watch which continuous recalculates latency
+    let pipeline_weak = pipeline.downgrade();
+    bus.add_watch(move |_, msg| {
+        use gst::MessageView;
+        // check several message types
+        match (msg.view(), &pipeline_weak.upgrade()) {
+            (MessageView::Error(err), Some(pipeline)) => {
+                counters.add_pipeline_error();
+                error!(
+                    "Error received from element {:?}: {}",
+                    err.src().map(GstObjectExt::path_string),
+                    err.error(),
+                );
+                debug::dot(pipeline, "BUS-ERROR");
+                if let Some(info) = err.debug() {
+                    debug!("Debugging information: {}", info);
+                }
+            }
+            (MessageView::Warning(warn), Some(pipeline)) => {
+                warn!(
+                    "Warning received from element {:?}: {}",
+                    warn.src().map(GstObjectExt::path_string),
+                    warn.error(),
+                );
+                debug::dot(pipeline, "BUS-WARNING");
+                if let Some(info) = warn.debug() {
+                    debug!("Debugging information: {}", info);
+                }
+            }
+            (MessageView::Info(info), Some(pipeline)) => {
+                info!(
+                    "Info received from element {:?}: {}",
+                    info.src().map(GstObjectExt::path_string),
+                    info.error(),
+                );
+                debug::dot(pipeline, "BUS-INFO");
+                if let Some(info) = info.debug() {
+                    debug!("Debugging information: {}", info);
+                }
+            }
+            (MessageView::Qos(_), _) => {
+                // elements post a QoS message with every buffer they drop
+                counters.add_dropped_frame();
+            }
+            (MessageView::Latency(_), Some(pipeline)) => {
+                // Recalculate pipeline latency when requested
+                let _ = pipeline.recalculate_latency();
+            }
+            _ => (),
+        }
+        // stop reading if we are expecting EOS after the following scan
+        Continue(true)
+    })?;
+
+    Ok(())
+}
+
 impl<SRC, STREAMID> Drop for Mixer<SRC, STREAMID>
 where
     SRC: Source,
-    STREAMID: Eq + Ord + Hash + Copy + Debug + Display,
+    STREAMID: Eq + Ord + Hash + Copy + Debug + Display + TrackOwner,
 {
     /// halt pipeline (can not be played again)
     ///
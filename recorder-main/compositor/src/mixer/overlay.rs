@@ -6,7 +6,7 @@
 
 use gst_base::prelude::ElementExt;
 
-use crate::{ClockOverlay, TalkOverlay, TextOverlay};
+use crate::{ClockOverlay, ImageOverlay, TalkOverlay, TextOverlay};
 
 /// Trait of overlays as the mixer sees it.
 pub trait Overlay {
@@ -31,6 +31,8 @@ pub enum AnyOverlay {
     Clock(ClockOverlay),
     /// Participant overlay
     Talk(TalkOverlay),
+    /// Image overlay
+    Image(ImageOverlay),
 }
 
 impl Overlay for AnyOverlay {
@@ -39,6 +41,7 @@ impl Overlay for AnyOverlay {
             Self::Text(o) => o.element(),
             Self::Clock(o) => o.element(),
             Self::Talk(o) => o.element(),
+            Self::Image(o) => o.element(),
         }
     }
     fn show(&self, show: bool) {
@@ -46,6 +49,7 @@ impl Overlay for AnyOverlay {
             Self::Text(o) => o.show(show),
             Self::Clock(o) => o.show(show),
             Self::Talk(o) => o.show(show),
+            Self::Image(o) => o.show(show),
         }
     }
 }
@@ -67,3 +71,9 @@ impl From<TalkOverlay> for AnyOverlay {
         AnyOverlay::Talk(overlay)
     }
 }
+
+impl From<ImageOverlay> for AnyOverlay {
+    fn from(overlay: ImageOverlay) -> AnyOverlay {
+        AnyOverlay::Image(overlay)
+    }
+}
@@ -0,0 +1,172 @@
+// SPDX-FileCopyrightText: OpenTalk GmbH <mail@opentalk.eu>
+//
+// SPDX-License-Identifier: EUPL-1.2
+
+//! Placeholder pictures of streams without video.
+
+use anyhow::{Context, Result};
+use gst::prelude::*;
+use serde::Deserialize;
+
+use crate::{Align, Color, Font, HAlign, Overlay, TextOverlay, TextStyle, VAlign};
+
+const PLACEHOLDER_WIDTH: i32 = 640;
+const PLACEHOLDER_HEIGHT: i32 = 360;
+const PLACEHOLDER_FRAMERATE: i32 = 5;
+const INITIALS_FONT_SIZE: u32 = 64;
+
+/// Appearance of the placeholder which is shown instead of a stream's video.
+///
+/// Missing fields are taken from the default style when deserializing.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct PlaceholderStyle {
+    /// Color of the placeholder's background.
+    pub background_color: Color,
+    /// Style of the participant's initials.
+    pub initials: TextStyle,
+}
+
+impl Default for PlaceholderStyle {
+    fn default() -> Self {
+        Self {
+            background_color: Color {
+                r: 0x3c,
+                g: 0x3c,
+                b: 0x3c,
+                a: 0xff,
+            },
+            initials: TextStyle {
+                align: Align {
+                    horizontal: HAlign::Center,
+                    vertical: VAlign::Center,
+                },
+                font: Font {
+                    size: INITIALS_FONT_SIZE,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        }
+    }
+}
+
+/// Initials of the given display name.
+///
+/// Takes the first letter of the first and the last word, so `"Jane Q. Doe"` becomes `"JD"`.
+#[must_use]
+pub fn initials(display_name: &str) -> String {
+    let mut words = display_name.split_whitespace();
+    let first = words.next().and_then(|word| word.chars().next());
+    let last = words.last().and_then(|word| word.chars().next());
+    first
+        .into_iter()
+        .chain(last)
+        .flat_map(char::to_uppercase)
+        .collect()
+}
+
+/// Picture which is shown instead of a stream's video.
+///
+/// Displays the initials of the participant on a solid background.
+#[derive(Debug, Clone)]
+pub struct Placeholder {
+    bin: gst::Bin,
+    background: gst::Element,
+    initials: TextOverlay,
+}
+
+impl Placeholder {
+    /// Create a new placeholder.
+    ///
+    /// # Arguments
+    ///
+    /// - `display_name`: Name of the participant whose initials are shown.
+    /// - `style`: Appearance of the placeholder.
+    ///
+    /// # Errors
+    ///
+    /// This can fail if the elements of the placeholder cannot be created or linked in
+    /// `GStreamer`.
+    pub fn create(display_name: &str, style: &PlaceholderStyle) -> Result<Self> {
+        trace!("new( '{display_name}' )");
+
+        let bin = gst::Bin::new(Some("Placeholder"));
+
+        let background = gst::ElementFactory::make("videotestsrc")
+            .name("placeholder-background")
+            .property("is-live", true)
+            .property_from_str("pattern", "solid-color")
+            .build()
+            .context("unable to build placeholder background")?;
+        let capsfilter = gst::ElementFactory::make("capsfilter")
+            .property(
+                "caps",
+                gst::Caps::builder("video/x-raw")
+                    .field("width", PLACEHOLDER_WIDTH)
+                    .field("height", PLACEHOLDER_HEIGHT)
+                    .field("framerate", gst::Fraction::new(PLACEHOLDER_FRAMERATE, 1))
+                    .field("pixel-aspect-ratio", gst::Fraction::new(1, 1))
+                    .build(),
+            )
+            .build()
+            .context("unable to build placeholder capsfilter")?;
+        let initials = TextOverlay::create(
+            "Initials Overlay",
+            &glib::markup_escape_text(&initials(display_name)),
+            style.initials.clone(),
+        )
+        .context("unable to create initials overlay")?;
+
+        bin.add_many(&[&background, &capsfilter, initials.element()])
+            .context("unable to add placeholder elements to bin")?;
+        gst::Element::link_many(&[&background, &capsfilter, initials.element()])
+            .context("unable to link placeholder elements")?;
+
+        let initials_src = initials
+            .src()
+            .context("unable to get src of initials overlay")?;
+        let src = gst::GhostPad::with_target(Some("src"), &initials_src)
+            .context("unable to create ghost pad for placeholder")?;
+        bin.add_pad(&src)
+            .context("unable to add ghost pad to placeholder bin")?;
+
+        let placeholder = Self {
+            bin,
+            background,
+            initials,
+        };
+        placeholder.set_style(style);
+
+        Ok(placeholder)
+    }
+
+    /// Bin which provides the placeholder's video at its `src` pad.
+    #[must_use]
+    pub fn bin(&self) -> &gst::Bin {
+        &self.bin
+    }
+
+    /// Change the appearance of the placeholder.
+    ///
+    /// # Arguments
+    ///
+    /// - `style`: new style
+    ///
+    pub fn set_style(&self, style: &PlaceholderStyle) {
+        self.background
+            .set_property("foreground-color", style.background_color.argb());
+        self.initials.set_style(&style.initials);
+    }
+
+    /// Change the name whose initials are shown.
+    ///
+    /// # Arguments
+    ///
+    /// - `display_name`: new name of the participant
+    ///
+    pub fn set_name(&self, display_name: &str) {
+        self.initials
+            .set(&glib::markup_escape_text(&initials(display_name)));
+    }
+}
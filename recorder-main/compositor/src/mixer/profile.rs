@@ -0,0 +1,109 @@
+// SPDX-FileCopyrightText: OpenTalk GmbH <mail@opentalk.eu>
+//
+// SPDX-License-Identifier: EUPL-1.2
+
+//! Output profiles of sinks.
+
+use anyhow::{Context, Result};
+use gst::{Caps, Element, ElementFactory};
+use serde::Deserialize;
+
+use crate::{AnyLayout, Size, SpeedPreset};
+
+/// Output profile of a sink.
+///
+/// Sinks with the same resolution and framerate share one scaled branch of the mixer output.
+/// Sinks which let the mixer encode their video also share the encoder if bitrate and speed
+/// preset match, see [`crate::Sink::video_encoding()`].
+/// The chat is rendered into the video of every sink on its own.
+/// Sinks with an own layout get a composition of their own.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct OutputProfile {
+    /// Output width in pixels, derived from `height` and the mixer's aspect ratio if `None`.
+    pub width: Option<usize>,
+    /// Output height in pixels, derived from `width` and the mixer's aspect ratio if `None`.
+    pub height: Option<usize>,
+    /// Frames per second, the mixer's framerate if `None`.
+    pub framerate: Option<i32>,
+    /// Video bitrate in kbit/s, the sink's default if `None`.
+    pub video_bitrate: Option<usize>,
+    /// Encoder speed preset, the sink's default if `None`.
+    pub video_speed_preset: Option<SpeedPreset>,
+    /// Show the latest chat messages within the video.
+    pub chat: bool,
+    /// Layout of the sink's picture, the shared layout of the talk if `None`.
+    pub layout: Option<AnyLayout>,
+}
+
+/// Settings of an H.264 encoder within the mixer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct VideoEncoding {
+    /// Video bitrate in kbit/s.
+    pub bitrate: usize,
+    /// Encoder speed preset.
+    pub speed_preset: SpeedPreset,
+    /// Maximum number of frames between two keyframes, the encoder's default if zero.
+    pub key_int_max: u32,
+}
+
+impl VideoEncoding {
+    /// Create `videoconvert`, `x264enc` and a capsfilter which encode raw video to H.264.
+    ///
+    /// The elements are neither added to a bin nor linked.
+    pub(crate) fn create_elements(&self) -> Result<[Element; 3]> {
+        let videoconvert = ElementFactory::make("videoconvert")
+            .build()
+            .context("unable to build videoconvert")?;
+        let encoder = ElementFactory::make("x264enc")
+            .property_from_str("speed-preset", self.speed_preset.as_str())
+            .property_from_str("tune", "zerolatency")
+            .property("bitrate", self.bitrate as u32)
+            .property("key-int-max", self.key_int_max)
+            .build()
+            .context("unable to build x264enc")?;
+        let capsfilter = ElementFactory::make("capsfilter")
+            .property(
+                "caps",
+                Caps::builder("video/x-h264")
+                    .field("profile", "high")
+                    .build(),
+            )
+            .build()
+            .context("unable to build capsfilter")?;
+        Ok([videoconvert, encoder, capsfilter])
+    }
+}
+
+/// Resolution and framerate of a branch of the mixer output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct VideoFormat {
+    pub(crate) width: usize,
+    pub(crate) height: usize,
+    pub(crate) framerate: i32,
+}
+
+impl OutputProfile {
+    /// Returns `true` if the profile changes the mixer's resolution or framerate.
+    #[must_use]
+    pub fn is_scaled(&self) -> bool {
+        self.width.is_some() || self.height.is_some() || self.framerate.is_some()
+    }
+
+    /// Resolve resolution and framerate against the mixer's output.
+    pub(crate) fn video_format(&self, output: Size, framerate: i32) -> VideoFormat {
+        // keep aspect ratio if only one dimension is given and round to even sizes for encoders
+        let even = |value: usize| (value + 1) & !1;
+        let (width, height) = match (self.width, self.height) {
+            (Some(width), Some(height)) => (width, height),
+            (Some(width), None) => (width, even(width * output.height / output.width)),
+            (None, Some(height)) => (even(height * output.width / output.height), height),
+            (None, None) => (output.width, output.height),
+        };
+        VideoFormat {
+            width,
+            height,
+            framerate: self.framerate.unwrap_or(framerate),
+        }
+    }
+}
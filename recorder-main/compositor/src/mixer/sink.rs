@@ -5,11 +5,19 @@
 //! Sink trait.
 
 use anyhow::{Context, Result};
-use gst::{GhostPad, Pipeline};
+use gst::{ClockTime, GhostPad, Pipeline};
 use gst_base::prelude::{ElementExt, GstBinExt};
-use std::fmt::Debug;
-
-use crate::debug;
+use std::{
+    fmt::Debug,
+    sync::{atomic::AtomicU64, Arc},
+};
+
+use super::{
+    profile::{OutputProfile, VideoEncoding},
+    sink_gate::SinkGate,
+    track::TrackSlot,
+};
+use crate::{debug, ChatOverlay};
 
 /// Trait of an output sink.
 pub trait Sink: Send + Debug + 'static {
@@ -21,7 +29,31 @@ pub trait Sink: Send + Debug + 'static {
 
     fn bin(&self) -> gst::Bin;
 
-    /// Called by `Mixer::play()`.
+    /// Get output profile of the sink.
+    ///
+    /// Sinks without a profile get the mixer's output as it is.
+    fn profile(&self) -> Option<&OutputProfile> {
+        None
+    }
+
+    /// Get the settings of the H.264 encoding which the mixer does for the sink.
+    ///
+    /// Sinks which return settings get encoded video at their video pad. All sinks of the same
+    /// output format and encoding share one encoder, unless they show the chat within their
+    /// picture.
+    fn video_encoding(&self) -> Option<VideoEncoding> {
+        None
+    }
+
+    /// Get sink pads of the audio tracks which record single streams besides the mixed audio.
+    ///
+    /// Streams are assigned to the tracks in the order they appear, tracks are silent while no
+    /// stream is assigned.
+    fn tracks(&self) -> Vec<gst::GhostPad> {
+        Vec::new()
+    }
+
+    /// Called by `Mixer::link_sink()` and `Mixer::resume_sinks()`.
     ///
     /// # Errors
     ///
@@ -30,7 +62,7 @@ pub trait Sink: Send + Debug + 'static {
         Ok(())
     }
 
-    /// Called by `Mixer::pause()`.
+    /// Called by `Mixer::pause_sink()` when the sink stops being fed.
     fn on_pause(&mut self) {}
 
     /// Called by `Mixer::drop()`.
@@ -47,6 +79,16 @@ pub trait Sink: Send + Debug + 'static {
 pub(crate) struct ActiveSink {
     pub(crate) pipeline: Pipeline,
     pub(crate) sink: Box<dyn Sink>,
+    /// Gate used to pause this sink
+    pub(crate) gate: Arc<SinkGate>,
+    /// Running time of the mixer when the sink was linked
+    pub(crate) start: ClockTime,
+    /// Audio tracks of single streams
+    pub(crate) tracks: Vec<TrackSlot>,
+    /// Bytes which arrived at the sink elements
+    pub(crate) bytes_written: Arc<AtomicU64>,
+    /// Chat overlay within the video if the sink's profile shows the chat
+    pub(crate) chat: Option<ChatOverlay>,
 }
 
 impl Drop for ActiveSink {
@@ -64,10 +106,25 @@ impl Drop for ActiveSink {
             error!("Unable to set the pipeline to the `Null` state, error: {error}");
         }
 
+        // the bus watch keeps the bus alive otherwise
+        if let Some(bus) = self.pipeline.bus() {
+            if let Err(error) = bus.remove_watch() {
+                error!("Unable to remove the bus watch, error: {error}");
+            }
+        }
+
         debug!("Exited Sink.");
     }
 }
 
+/// Sink which was released from the mixer.
+///
+/// Dropping it waits for the sink to finish its output, e.g. for an encoder process to exit,
+/// so it should be dropped where blocking is fine.
+#[derive(Debug)]
+#[must_use = "dropping a released sink may block until its output is finished"]
+pub struct ReleasedSink(pub(crate) ActiveSink);
+
 /// Adds a `GhostPad` to the given `Bin`.
 ///
 /// # Errors
@@ -0,0 +1,134 @@
+// SPDX-FileCopyrightText: OpenTalk GmbH <mail@opentalk.eu>
+//
+// SPDX-License-Identifier: EUPL-1.2
+
+//! Gate between the mixers and the output sinks which allows to pause the sinks.
+
+use gst::{ClockTime, Sample};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+/// A single pause of the output
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Pause {
+    /// Running time when the pause began
+    start: ClockTime,
+    /// Running time when the pause ended, `None` while still paused
+    end: Option<ClockTime>,
+}
+
+/// Gate which drops mixed samples while paused and shifts the timestamps of all later samples
+///
+/// The sinks therefore receive one continuous stream without the paused gap while the mixer
+/// pipeline keeps running. Every sink owns a gate which is shared between the audio and the
+/// video mixer so both outputs of the sink are cut at the same running time.
+#[derive(Debug, Default)]
+pub(crate) struct SinkGate {
+    pauses: Mutex<Vec<Pause>>,
+}
+
+impl SinkGate {
+    fn pauses(&self) -> MutexGuard<'_, Vec<Pause>> {
+        // the pause list is always consistent, a poisoned lock can be used anyway
+        self.pauses.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Pause the output at the given running time.
+    ///
+    /// Returns `false` if the output is already paused.
+    pub(crate) fn pause(&self, now: ClockTime) -> bool {
+        let mut pauses = self.pauses();
+
+        if pauses.last().is_some_and(|pause| pause.end.is_none()) {
+            return false;
+        }
+
+        pauses.push(Pause {
+            start: now,
+            end: None,
+        });
+
+        true
+    }
+
+    /// Resume the output at the given running time.
+    ///
+    /// Returns `false` if the output is not paused.
+    pub(crate) fn resume(&self, now: ClockTime) -> bool {
+        let mut pauses = self.pauses();
+
+        match pauses.last_mut() {
+            Some(pause) if pause.end.is_none() => {
+                pause.end = Some(now.max(pause.start));
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns `true` while the output is paused.
+    pub(crate) fn is_paused(&self) -> bool {
+        self.pauses()
+            .last()
+            .is_some_and(|pause| pause.end.is_none())
+    }
+
+    /// Returns the offset to subtract from a timestamp or `None` if it lies within a pause.
+    pub(crate) fn offset(&self, timestamp: ClockTime) -> Option<ClockTime> {
+        let pauses = self.pauses();
+
+        let mut offset = ClockTime::ZERO;
+        for pause in pauses.iter() {
+            match pause.end {
+                _ if timestamp < pause.start => break,
+                Some(end) if timestamp >= end => offset += end - pause.start,
+                _ => return None,
+            }
+        }
+
+        Some(offset)
+    }
+
+    /// Returns the duration of the output between `start` and `now` without the paused time.
+    pub(crate) fn position(&self, start: ClockTime, now: ClockTime) -> ClockTime {
+        let pauses = self.pauses();
+
+        let mut paused = ClockTime::ZERO;
+        for pause in pauses.iter().filter(|pause| pause.start < now) {
+            let end = pause.end.unwrap_or(now).min(now);
+            paused += end.saturating_sub(pause.start);
+        }
+
+        now.saturating_sub(start).saturating_sub(paused)
+    }
+
+    /// Applies the gate to the given sample.
+    ///
+    /// Returns `None` if the sample has to be dropped, otherwise the sample with its
+    /// timestamps shifted by the duration of all previous pauses.
+    pub(crate) fn apply(&self, sample: Sample) -> Option<Sample> {
+        let Some(pts) = sample.buffer().and_then(|buffer| buffer.pts()) else {
+            return (!self.is_paused()).then_some(sample);
+        };
+
+        let offset = self.offset(pts)?;
+        if offset.is_zero() {
+            return Some(sample);
+        }
+
+        let mut buffer = sample.buffer_owned()?;
+        {
+            let buffer = buffer.make_mut();
+            buffer.set_pts(pts.saturating_sub(offset));
+            if let Some(dts) = buffer.dts() {
+                buffer.set_dts(dts.saturating_sub(offset));
+            }
+        }
+
+        let mut builder = Sample::builder().buffer(&buffer);
+        if let Some(caps) = sample.caps() {
+            builder = builder.caps(caps);
+        }
+
+        Some(builder.build())
+    }
+}
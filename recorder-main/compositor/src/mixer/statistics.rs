@@ -0,0 +1,84 @@
+// SPDX-FileCopyrightText: OpenTalk GmbH <mail@opentalk.eu>
+//
+// SPDX-License-Identifier: EUPL-1.2
+
+//! Statistics of the mixer's pipelines and sinks.
+
+use gst::{prelude::*, ClockTime, PadProbeData, PadProbeReturn, PadProbeType};
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+
+/// Counters of all pipelines of a mixer since it has been created.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Statistics {
+    /// Buffers dropped by elements which posted quality of service messages.
+    pub dropped_frames: u64,
+    /// Errors posted on the pipeline buses.
+    pub pipeline_errors: u64,
+}
+
+/// State of a linked output sink.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SinkState {
+    /// Name the sink has been linked with.
+    pub name: String,
+    /// `true` while the sink is paused.
+    pub paused: bool,
+    /// Position within the sink's output without the paused time.
+    pub position: ClockTime,
+    /// Bytes which arrived at the sink elements of the sink.
+    pub bytes_written: u64,
+}
+
+/// Counters which are shared with the bus watches of the pipelines.
+#[derive(Debug, Default)]
+pub(crate) struct Counters {
+    dropped_frames: AtomicU64,
+    pipeline_errors: AtomicU64,
+}
+
+impl Counters {
+    pub(crate) fn add_dropped_frame(&self) {
+        self.dropped_frames.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn add_pipeline_error(&self) {
+        self.pipeline_errors.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn get(&self) -> Statistics {
+        Statistics {
+            dropped_frames: self.dropped_frames.load(Ordering::Relaxed),
+            pipeline_errors: self.pipeline_errors.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Count the bytes of all buffers which arrive at the sink elements within the given bin.
+///
+/// Returns the counter which is updated while the bin is running.
+pub(crate) fn count_bytes(bin: &gst::Bin) -> Arc<AtomicU64> {
+    let counter = Arc::new(AtomicU64::new(0));
+
+    for element in bin.iterate_sinks().into_iter().flatten() {
+        for pad in element.sink_pads() {
+            let counter = counter.clone();
+            pad.add_probe(
+                PadProbeType::BUFFER | PadProbeType::BUFFER_LIST,
+                move |_, info| {
+                    let size = match &info.data {
+                        Some(PadProbeData::Buffer(buffer)) => buffer.size(),
+                        Some(PadProbeData::BufferList(list)) => list.calculate_size(),
+                        _ => 0,
+                    };
+                    counter.fetch_add(size as u64, Ordering::Relaxed);
+                    PadProbeReturn::Ok
+                },
+            );
+        }
+    }
+
+    counter
+}
@@ -6,8 +6,9 @@
 
 use core::fmt::{Debug, Display};
 use gst_base::prelude::*;
+use std::collections::HashMap;
 
-use crate::{AnyOverlay, Source};
+use crate::{AnyOverlay, Placeholder, Source};
 
 /// Turns on or off video or audio.
 #[derive(Debug, Clone)]
@@ -75,18 +76,24 @@ where
 {
     /// Name to be displayed within the sub title text.
     pub display_name: String,
-    /// Wrapped AV source of this stream.
-    pub source: SRC,
+    /// Wrapped AV source of this stream or `None` if the stream only shows its placeholder.
+    pub source: Option<SRC>,
     // the bin of the source
     pub bin: gst::Bin,
     // the video src ghost pad
     pub video: Option<gst::GhostPad>,
+    /// video pads linked to the layouts of single sinks by sink name
+    pub scenes: HashMap<String, gst::GhostPad>,
     // the audio src ghost pad
-    pub audio: gst::GhostPad,
+    pub audio: Option<gst::GhostPad>,
     // source's overlay
     pub overlay: AnyOverlay,
+    /// picture which is shown while the stream has no video
+    pub placeholder: Option<Placeholder>,
     /// current stream status
     pub status: StreamStatus,
+    /// volume of the stream's audio within the mix
+    pub volume: f64,
 }
 
 impl<SRC> Stream<SRC>
@@ -111,6 +118,24 @@ where
 
     /// Find audiomixer sink by looking where our ghost pad is connected to.
     pub fn audiomixer_sink(&self) -> Option<gst::Pad> {
-        self.audio.target()
+        self.audio.as_ref().and_then(|audio| audio.target())
+    }
+
+    /// Return `true` if the stream has a source, placeholder streams have none.
+    pub fn has_media(&self) -> bool {
+        self.source.is_some()
+    }
+
+    /// Switch between the stream's video and its placeholder.
+    ///
+    /// Streams without a placeholder or without video are not switched.
+    pub fn show_placeholder(&self, show: bool) {
+        let Some(selector) = self.bin.by_name("video-selector") else {
+            return;
+        };
+        let pad = if show { "sink_1" } else { "sink_0" };
+        if let Some(pad) = selector.static_pad(pad) {
+            selector.set_property("active-pad", &pad);
+        }
     }
 }
@@ -14,12 +14,11 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 use crate::{
-    debug, AnyOverlay, Font, Layout, Mixer, Overlay, Sink, Size, Source, Stream, StreamStatus,
-    TalkOverlay, TextOverlay, TextStyle,
+    debug, AnyOverlay, Layout, Loudness, Mixer, Overlay, Placeholder, Presentation, ReleasedSink,
+    Sink, SinkState, Size, Source, Statistics, Stream, StreamRole, StreamStatus, TalkOverlay,
+    TextOverlay, Theme, TrackOwner,
 };
 
-const NAME_FONT_SIZE: u32 = 16;
-
 /// return available media types
 #[must_use]
 pub fn media_types() -> impl DoubleEndedIterator<Item = MediaSessionType> {
@@ -110,6 +109,16 @@ where
     }
 }
 
+impl<ID> TrackOwner for StreamId<ID>
+where
+    ID: Eq + Ord + Hash + Copy + Debug + Display,
+{
+    /// Camera and screen share of a participant share the participant's ID.
+    fn track_owner(&self) -> String {
+        self.id.to_string()
+    }
+}
+
 impl<ID> StreamId<ID>
 where
     ID: Eq + Ord + Hash + Copy + Debug + Display,
@@ -150,6 +159,16 @@ where
     names: HashMap<StreamId<ID>, String>,
     /// stream who is currently speaking or `None`
     current_speaker: Option<ID>,
+    /// Layout which is not in use currently.
+    ///
+    /// While a screen share is visible this is the regular layout, otherwise the presentation layout.
+    inactive_layout: Box<dyn Layout>,
+    /// `true` while the presentation layout is in use
+    presenting: bool,
+    /// Branding of the output picture
+    theme: Theme,
+    /// `true` if camera streams without video show a placeholder instead of being hidden
+    placeholders: bool,
 }
 
 impl<SRC, ID> Talk<SRC, ID>
@@ -180,6 +199,35 @@ where
             layout,
             max_visibles,
             video_support,
+            Loudness::default(),
+        )
+    }
+
+    /// Create new Talk which creates an own Pipeline and processes the audio's loudness.
+    ///
+    /// # Arguments
+    ///
+    /// - `resolution`: Output video resolution.
+    /// - `max_visibles`: Maximum number of currently visible streams.
+    /// - `loudness`: Loudness processing of the audio.
+    ///
+    /// # Errors
+    ///
+    /// This can fail if the `Mixer` can't be initialized.
+    pub fn new_with_loudness(
+        resolution: Size,
+        layout: impl Layout,
+        max_visibles: usize,
+        video_support: bool,
+        loudness: Loudness,
+    ) -> Result<Self> {
+        Self::new_with_pipeline(
+            Pipeline::new(Some("Compositor")),
+            resolution,
+            layout,
+            max_visibles,
+            video_support,
+            loudness,
         )
     }
 
@@ -191,6 +239,7 @@ where
     /// - `resolution`: Output video resolution.
     /// - `sink_params`: Parameters to create the output sink.
     /// - `max_visibles`: Maximum number of currently visible streams.
+    /// - `loudness`: Loudness processing of the audio.
     ///
     /// # Errors
     ///
@@ -201,6 +250,7 @@ where
         layout: impl Layout,
         max_visibles: usize,
         video_support: bool,
+        loudness: Loudness,
     ) -> Result<Self> {
         debug!("Starting a new talk...");
         trace!("new( {resolution:?}, {max_visibles:?} )");
@@ -213,6 +263,7 @@ where
                 .context("unable to create TalkOverlay")?
                 .into(),
             video_support,
+            loudness,
         )
         .context("unable to create mixer")?;
 
@@ -221,27 +272,132 @@ where
             max_visibles,
             names: HashMap::new(),
             current_speaker: None,
+            inactive_layout: Box::new(Presentation::default()),
+            presenting: false,
+            theme: Theme::default(),
+            placeholders: false,
         })
     }
 
     /// Link the given sink to the mixer.
     ///
+    /// Sinks whose profile has a layout get a picture of their own with that layout, the title
+    /// and clock on top. Their layout is kept while a screen share is visible and is not changed
+    /// by [`Talk::change_layout()`].
+    ///
     /// # Errors
     ///
     /// This can fail if the mixer was unable to link the sink to the mixer.
     pub fn link_sink(&mut self, name: &str, sink: impl Sink) -> Result<()> {
-        self.mixer.link_sink(name, sink)
+        let Some(layout) = sink.profile().and_then(|profile| profile.layout.clone()) else {
+            return self.mixer.link_sink(name, sink);
+        };
+        let AnyOverlay::Talk(overlay) = &self.mixer.overlay else {
+            bail!("talk has no talk overlay!")
+        };
+        let overlay = overlay
+            .duplicate(&self.theme)
+            .context("unable to create overlay of the sink's layout")?;
+        self.mixer
+            .link_sink_with_layout(name, sink, layout, overlay.into())?;
+        self.mixer.set_background(
+            self.theme.background_color,
+            self.theme.background_image.as_ref(),
+        );
+        Ok(())
     }
 
-    /// Link the given sink to the mixer.
+    /// Release the given sink from the mixer.
+    ///
+    /// The returned sink blocks on drop until its output is finished.
     ///
     /// # Errors
     ///
-    /// This can fail if the mixer was unable to link the sink to the mixer.
-    pub fn release_sink(&mut self, name: &String) -> Result<()> {
+    /// This can fail if the sink could not be released from the mixer.
+    pub fn release_sink(&mut self, name: &String) -> Result<ReleasedSink> {
         self.mixer.release_sink(name)
     }
 
+    /// Pause feeding all linked sinks while the talk continues.
+    ///
+    /// The sinks stay linked and receive one continuous stream without the paused time.
+    ///
+    /// # Errors
+    ///
+    /// This can fail if the talk is already paused.
+    pub fn pause(&mut self) -> Result<()> {
+        self.mixer.pause_sinks()
+    }
+
+    /// Resume feeding the linked sinks after [`Talk::pause()`].
+    ///
+    /// # Errors
+    ///
+    /// This can fail if the talk is not paused.
+    pub fn resume(&mut self) -> Result<()> {
+        self.mixer.resume_sinks()
+    }
+
+    /// Returns `true` if all linked sinks are paused.
+    #[must_use]
+    pub fn is_paused(&self) -> bool {
+        self.mixer.sinks_paused()
+    }
+
+    /// Pause feeding a single sink while the other sinks continue.
+    ///
+    /// # Errors
+    ///
+    /// This can fail if the sink does not exist or is already paused.
+    pub fn pause_sink(&mut self, name: &str) -> Result<()> {
+        self.mixer.pause_sink(name)
+    }
+
+    /// Resume feeding a single sink after [`Talk::pause_sink()`].
+    ///
+    /// # Errors
+    ///
+    /// This can fail if the sink does not exist or is not paused.
+    pub fn resume_sink(&mut self, name: &str) -> Result<()> {
+        self.mixer.resume_sink(name)
+    }
+
+    /// Returns `true` if the sink with the given name is paused.
+    #[must_use]
+    pub fn is_sink_paused(&self, name: &str) -> bool {
+        self.mixer.is_sink_paused(name)
+    }
+
+    /// Returns `true` if a sink with the given name is linked.
+    #[must_use]
+    pub fn has_sink(&self, name: &str) -> bool {
+        self.mixer.has_sink(name)
+    }
+
+    /// Position within the output of the sink with the given name without its paused time.
+    ///
+    /// # Errors
+    ///
+    /// This can fail if the sink does not exist.
+    pub fn sink_position(&self, name: &str) -> Result<gst::ClockTime> {
+        self.mixer.sink_position(name)
+    }
+
+    /// State of all linked sinks ordered by their names.
+    ///
+    /// # Errors
+    ///
+    /// This can fail if the clock time is unavailable.
+    pub fn sinks(&self) -> Result<Vec<SinkState>> {
+        self.mixer.sinks()
+    }
+
+    /// Dropped frames and errors of the talk's pipelines since it was created.
+    #[must_use]
+    pub fn statistics(&self) -> Statistics {
+        self.mixer.statistics()
+    }
+
     /// Add a stream with the given ID and media type
     ///
     /// # Arguments
@@ -269,35 +425,128 @@ where
         // prepare title text overlay for the stream
         let overlay = TextOverlay::create(
             "Name Overlay",
-            display_name,
-            TextStyle {
-                font: Font {
-                    size: NAME_FONT_SIZE,
-                    ..Default::default()
-                },
-                ..Default::default()
-            },
+            &Self::name_tag(&self.theme, display_name, !initial.has_audio),
+            self.theme.names.clone(),
         )
         .context("unable to create TextOverlay")?;
 
+        // only cameras are replaced by a placeholder
+        let placeholder = if self.placeholders && id.media_type == MediaSessionType::Camera {
+            Some(
+                Placeholder::create(display_name, &self.theme.placeholder)
+                    .context("unable to create Placeholder")?,
+            )
+        } else {
+            None
+        };
+
         // forward to mixer
         self.mixer.add_stream(
             id,
             display_name.to_string(),
             params,
             overlay.into(),
+            placeholder,
             initial.clone(),
         )?;
 
         // remember display name
         self.names.insert(id, display_name.to_string());
 
+        if id.media_type == MediaSessionType::ScreenCapture {
+            self.mixer.set_role(&id, StreamRole::Screen)?;
+        } else if self.current_speaker == Some(id.id) {
+            self.mixer.set_role(&id, StreamRole::Speaker)?;
+        }
+
         // if available turn on audio but leave video off until `set_visibles()` is used
         self.mixer.set_status(&id, initial)?;
 
         Ok(())
     }
 
+    /// Add a stream which shows a placeholder only.
+    ///
+    /// Used for participants whose media must not be composed, like participants who did not
+    /// consent to be recorded. The stream gets visible like any camera stream.
+    ///
+    /// # Arguments
+    ///
+    /// - `id`: Identifies the stream to add
+    /// - `display_name`: Human readable name which is shown within the placeholder
+    ///
+    /// # Errors
+    ///
+    /// This can fail if the talk has no video or the placeholder can't be added to the `Mixer`.
+    pub fn add_placeholder(&mut self, id: StreamId<ID>, display_name: &str) -> Result<()> {
+        trace!("add_placeholder( {id}, '{display_name}' )");
+
+        let overlay = TextOverlay::create(
+            "Name Overlay",
+            &Self::name_tag(&self.theme, display_name, false),
+            self.theme.names.clone(),
+        )
+        .context("unable to create TextOverlay")?;
+        let placeholder = Placeholder::create(display_name, &self.theme.placeholder)
+            .context("unable to create Placeholder")?;
+
+        self.mixer
+            .add_placeholder(id, display_name.to_string(), overlay.into(), placeholder)?;
+
+        self.names.insert(id, display_name.to_string());
+
+        if self.current_speaker == Some(id.id) {
+            self.mixer.set_role(&id, StreamRole::Speaker)?;
+        }
+
+        self.show_stream(&id)
+    }
+
+    /// Return `true` if the stream with the given ID shows a placeholder only.
+    ///
+    /// # Arguments
+    ///
+    /// - `id`: ID of the stream
+    ///
+    pub fn is_placeholder(&self, id: &StreamId<ID>) -> bool {
+        self.mixer
+            .streams
+            .get(id)
+            .is_some_and(|stream| !stream.has_media())
+    }
+
+    /// Return `true` if the stream with the given ID has a placeholder which is shown while the
+    /// stream has no video.
+    ///
+    /// # Arguments
+    ///
+    /// - `id`: ID of the stream
+    ///
+    pub fn has_placeholder(&self, id: &StreamId<ID>) -> bool {
+        self.mixer
+            .streams
+            .get(id)
+            .is_some_and(|stream| stream.placeholder.is_some())
+    }
+
+    /// Show placeholders for camera streams without video instead of hiding them.
+    ///
+    /// Only affects streams which are added afterwards.
+    ///
+    /// # Arguments
+    ///
+    /// - `show`: Show placeholders if `true`
+    ///
+    pub fn show_placeholders(&mut self, show: bool) {
+        self.placeholders = show;
+    }
+
+    /// Return `true` if camera streams which are added get a placeholder.
+    #[must_use]
+    pub fn shows_placeholders(&self) -> bool {
+        self.placeholders
+    }
+
     /// Remove a stream by stream ID.
     ///
     /// # Arguments
@@ -323,7 +572,7 @@ where
                 .context("unable to set stream with id '{stream_id}' to first position")?;
         }
 
-        Ok(())
+        self.update_presentation()
     }
 
     /// Remove all streams from mixer.
@@ -382,7 +631,10 @@ where
     pub fn set_speaker(&mut self, speaker: ID) -> Result<()> {
         info!("set_speaker( {speaker:?} )");
 
-        self.current_speaker = Some(speaker);
+        if let Some(old_speaker) = self.current_speaker.replace(speaker) {
+            self.set_speaker_role(old_speaker, StreamRole::Other)?;
+        }
+        self.set_speaker_role(speaker, StreamRole::Speaker)?;
 
         let stream_id = StreamId::new(speaker, MediaSessionType::ScreenCapture);
         if let Some(stream) = self.mixer.streams.get(&stream_id) {
@@ -396,8 +648,8 @@ where
 
         let stream_id = StreamId::new(speaker, MediaSessionType::Camera);
         if let Some(stream) = self.mixer.streams.get(&stream_id) {
-            // The speaker has no screen, so it doesn't need to update the position
-            if stream.status.has_video {
+            // The speaker has no picture, so it doesn't need to update the position
+            if Self::has_picture(stream) {
                 // check if noone is sharing their screen or the new speaker is also screen sharing
                 if self.get_first_screen_capture().is_none() {
                     self.mixer
@@ -414,8 +666,16 @@ where
         Ok(())
     }
 
-    pub fn unset_speaker(&mut self) {
-        self.current_speaker = None;
+    /// Unset the current speaker.
+    ///
+    /// # Errors
+    ///
+    /// This can fail if the `Mixer` cannot rerender the layout.
+    pub fn unset_speaker(&mut self) -> Result<()> {
+        if let Some(old_speaker) = self.current_speaker.take() {
+            self.set_speaker_role(old_speaker, StreamRole::Other)?;
+        }
+        Ok(())
     }
 
     /// Get ID of current speaker or `None`
@@ -425,7 +685,9 @@ where
 
     /// Set status of stream with `id`.
     ///
-    /// Makes video streams visible if `max_visibles` hasn't reached.
+    /// Makes video streams visible if `max_visibles` hasn't reached. Streams with a placeholder
+    /// show it instead of being hidden while they have no video. The name tag of a stream
+    /// without audio is preceded by the muted indicator of the theme.
     ///
     /// # Arguments
     ///
@@ -442,9 +704,23 @@ where
             return Ok(());
         };
         let old_status = current_stream.status.clone();
+        let has_placeholder = current_stream.placeholder.is_some();
 
         self.mixer.set_status(id, new_status.clone())?;
 
+        if old_status.has_audio != new_status.has_audio {
+            if let Some(name) = self.names.get(id) {
+                self.set_name_tag(
+                    id,
+                    &Self::name_tag(&self.theme, name, !new_status.has_audio),
+                )?;
+            }
+        }
+
+        // streams with a placeholder stay visible without video
+        if has_placeholder {
+            return Ok(());
+        }
         match (old_status.has_video, new_status.has_video) {
             (false, true) => self
                 .show_stream(id)
@@ -458,6 +734,20 @@ where
         Ok(())
     }
 
+    /// Set volume of the audio of stream with `id` within the mix.
+    ///
+    /// # Arguments
+    ///
+    /// - `id`: ID of the stream
+    /// - `volume`: linear volume factor from `0.0` (silent) over `1.0` (unity gain) to `10.0`
+    ///
+    /// # Errors
+    ///
+    /// This can fail if the stream is unknown or the volume is out of range.
+    pub fn set_stream_volume(&mut self, id: &StreamId<ID>, volume: f64) -> Result<()> {
+        self.mixer.set_stream_volume(id, volume)
+    }
+
     /// Set title of the talk which is displayed in overlay
     ///
     /// # Arguments
@@ -470,6 +760,9 @@ where
     pub fn set_title(&self, title: &str) -> Result<()> {
         if let AnyOverlay::Talk(overlay) = &self.mixer.overlay {
             overlay.set_title(title);
+            for overlay in self.sink_overlays() {
+                overlay.set_title(title);
+            }
             return Ok(());
         }
         bail!("talk has no title overlay!")
@@ -487,6 +780,9 @@ where
     pub fn show_title(&self, show: bool) -> Result<()> {
         if let AnyOverlay::Talk(overlay) = &self.mixer.overlay {
             overlay.show_title(show);
+            for overlay in self.sink_overlays() {
+                overlay.show_title(show);
+            }
             return Ok(());
         }
         bail!("talk has no title overlay!")
@@ -504,11 +800,74 @@ where
     pub fn show_clock(&self, show: bool) -> Result<()> {
         if let AnyOverlay::Talk(overlay) = &self.mixer.overlay {
             overlay.show_clock(show);
+            for overlay in self.sink_overlays() {
+                overlay.show_clock(show);
+            }
             return Ok(());
         }
         bail!("talk has no clock overlay!")
     }
 
+    /// Add a chat message which is shown by the sinks whose profile enables the chat.
+    ///
+    /// Only the latest messages are shown, they fade out after the display time of the theme.
+    ///
+    /// # Arguments
+    ///
+    /// - `sender`: Display name of the sender.
+    /// - `text`: Content of the message.
+    ///
+    pub fn add_chat_message(&self, sender: &str, text: &str) {
+        trace!("add_chat_message( '{sender}', '{text}' )");
+
+        self.mixer.add_chat_message(sender, text);
+    }
+
+    /// Change the branding of the talk.
+    ///
+    /// Applies text styles, logo, background, chat style, placeholder style and muted indicator of
+    /// the given theme to the talk, all its streams and sinks.
+    ///
+    /// # Arguments
+    ///
+    /// - `theme`: New theme
+    ///
+    /// # Errors
+    ///
+    /// This can fail if the `Talk` has no `AnyOverlay::Talk`
+    pub fn set_theme(&mut self, theme: Theme) -> Result<()> {
+        trace!("set_theme( {theme:?} )");
+
+        let AnyOverlay::Talk(overlay) = &self.mixer.overlay else {
+            bail!("talk has no talk overlay!")
+        };
+        overlay.set_theme(&theme);
+        for overlay in self.sink_overlays() {
+            overlay.set_theme(&theme);
+        }
+        for (id, stream) in &self.mixer.streams {
+            if let AnyOverlay::Text(overlay) = &stream.overlay {
+                overlay.set_style(&theme.names);
+                if let Some(name) = self.names.get(id) {
+                    overlay.set(&Self::name_tag(
+                        &theme,
+                        name,
+                        stream.has_media() && !stream.status.has_audio,
+                    ));
+                }
+            }
+            if let Some(placeholder) = &stream.placeholder {
+                placeholder.set_style(&theme.placeholder);
+            }
+        }
+        self.mixer
+            .set_background(theme.background_color, theme.background_image.as_ref());
+        self.mixer.set_chat_style(theme.chat.clone());
+        self.theme = theme;
+
+        Ok(())
+    }
+
     /// Set title in a stream
     ///
     /// # Arguments
@@ -519,10 +878,39 @@ where
     /// # Errors
     ///
     /// This can fail if the `Talk` has no `AnyOverlay::Talk`
-    pub fn set_stream_title(&self, id: &StreamId<ID>, title: &str) -> Result<()> {
+    pub fn set_stream_title(&mut self, id: &StreamId<ID>, title: &str) -> Result<()> {
+        let Some(stream) = self.mixer.streams.get(id) else {
+            bail!("source {id} title overlay missing")
+        };
+        let name_tag = Self::name_tag(
+            &self.theme,
+            title,
+            stream.has_media() && !stream.status.has_audio,
+        );
+        if let Some(placeholder) = &stream.placeholder {
+            placeholder.set_name(title);
+        }
+        self.set_name_tag(id, &name_tag)?;
+        self.names.insert(*id, title.to_string());
+        Ok(())
+    }
+
+    /// Markup of a stream's name tag which is preceded by the theme's muted indicator if the
+    /// stream's microphone is muted.
+    fn name_tag(theme: &Theme, name: &str, muted: bool) -> String {
+        let name = glib::markup_escape_text(name);
+        if muted {
+            format!("{indicator}{name}", indicator = theme.muted_indicator)
+        } else {
+            name.to_string()
+        }
+    }
+
+    /// Change the text of a stream's name tag.
+    fn set_name_tag(&self, id: &StreamId<ID>, markup: &str) -> Result<()> {
         if let Some(stream) = self.mixer.streams.get(id) {
             if let AnyOverlay::Text(overlay) = &stream.overlay {
-                overlay.set(title);
+                overlay.set(markup);
                 return Ok(());
             }
         }
@@ -576,7 +964,9 @@ where
         let position_first = stream_id.media_type == MediaSessionType::ScreenCapture
             && self.get_first_screen_capture().is_none();
 
-        self.mixer.show_stream(stream_id, position_first)
+        self.mixer.show_stream(stream_id, position_first)?;
+
+        self.update_presentation()
     }
 
     /// Try to hide the stream.
@@ -587,7 +977,9 @@ where
     pub fn hide_stream(&mut self, stream_id: &StreamId<ID>) -> Result<()> {
         self.mixer
             .hide_stream(stream_id)
-            .context("unable to hide_stream in mixer")
+            .context("unable to hide_stream in mixer")?;
+
+        self.update_presentation()
     }
 
     /// Return `true`, if a stream is currently visible
@@ -602,15 +994,48 @@ where
 
     /// Change the current layout
     ///
+    /// While a screen share is visible the new layout will be used after the share has ended,
+    /// unless the layout arranges screen shares itself.
+    ///
     /// # Errors
     ///
     /// This can fail if the `Mixer` cannot rerender the new layout.
     pub fn change_layout(&mut self, layout: impl Layout) -> Result<()> {
+        if self.presenting {
+            self.inactive_layout = Box::new(layout);
+            return self.update_presentation();
+        }
+        self.mixer
+            .change_layout(layout)
+            .context("unable to change_layout in mixer")?;
+        self.update_presentation()
+    }
+
+    /// Change the layout which is used while a screen share is visible.
+    ///
+    /// Defaults to [`Presentation`], use [`crate::PictureInPicture`] to show the speaker
+    /// within a corner of the screen share.
+    ///
+    /// # Errors
+    ///
+    /// This can fail if the `Mixer` cannot rerender the new layout.
+    pub fn change_presentation_layout(&mut self, layout: impl Layout) -> Result<()> {
+        if !self.presenting {
+            self.inactive_layout = Box::new(layout);
+            return Ok(());
+        }
         self.mixer
             .change_layout(layout)
             .context("unable to change_layout in mixer")
     }
 
+    /// Return `true` if the presentation layout is in use because a screen share is visible and
+    /// the regular layout does not arrange screen shares.
+    #[must_use]
+    pub fn is_presenting(&self) -> bool {
+        self.presenting
+    }
+
     /// Get mutable access to a source specified by stream ID.
     ///
     /// # Arguments
@@ -621,7 +1046,7 @@ where
         self.mixer
             .streams
             .get_mut(id)
-            .map(|stream| &mut stream.source)
+            .and_then(|stream| stream.source.as_mut())
     }
 
     /// generate DOT file of the current pipeline
@@ -635,6 +1060,72 @@ where
         self.mixer.dot(filename_without_extension, params);
     }
 
+    /// Switch to the presentation layout when a screen share became visible and back to the
+    /// regular layout when the last screen share has been hidden.
+    ///
+    /// Regular layouts which arrange screen shares themselves stay in use.
+    fn update_presentation(&mut self) -> Result<()> {
+        let regular = if self.presenting {
+            &self.inactive_layout
+        } else {
+            &self.mixer.layout
+        };
+        let presenting = !regular.arranges_screens() && self.get_first_screen_capture().is_some();
+        if presenting == self.presenting {
+            return Ok(());
+        }
+        debug!(
+            "switching to {} layout",
+            if presenting {
+                "presentation"
+            } else {
+                "regular"
+            }
+        );
+        self.mixer
+            .swap_layout(&mut self.inactive_layout)
+            .context("unable to swap layout in mixer")?;
+        self.presenting = presenting;
+
+        // keep the current speaker right beside the screen share
+        if let Some(speaker) = self.current_speaker.filter(|_| presenting) {
+            let stream_id = StreamId::camera(speaker);
+            if self.mixer.is_visible(&stream_id) {
+                self.mixer
+                    .set_stream_to_second_position(&stream_id)
+                    .context("unable to set stream with id '{stream_id}' to second position")?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Return `true` if the stream shows its video or its placeholder.
+    fn has_picture(stream: &Stream<SRC>) -> bool {
+        stream.status.has_video || stream.placeholder.is_some()
+    }
+
+    fn set_speaker_role(&mut self, speaker: ID, role: StreamRole) -> Result<()> {
+        let stream_id = StreamId::camera(speaker);
+        if !self.contains_stream(&stream_id) {
+            return Ok(());
+        }
+        self.mixer
+            .set_role(&stream_id, role)
+            .context("unable to set role of stream with id '{stream_id}'")
+    }
+
+    /// Overlays on top of the pictures of sinks with an own layout
+    fn sink_overlays(&self) -> impl Iterator<Item = &TalkOverlay> {
+        self.mixer
+            .sink_layouts
+            .values()
+            .filter_map(|sink_layout| match &sink_layout.overlay {
+                AnyOverlay::Talk(overlay) => Some(overlay),
+                _ => None,
+            })
+    }
+
     fn get_first_screen_capture(&self) -> Option<StreamId<ID>> {
         self.mixer
             .visibles
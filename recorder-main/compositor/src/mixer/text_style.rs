@@ -4,8 +4,14 @@
 
 //! Text styles.
 
+use anyhow::{bail, Context};
+use serde::Deserialize;
+
 /// Text color.
-#[derive(Debug)]
+///
+/// Deserializes from a hex string like `"#rrggbb"` or `"#rrggbbaa"`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
 pub struct Color {
     pub r: u8,
     pub g: u8,
@@ -24,6 +30,49 @@ impl Default for Color {
     }
 }
 
+impl Color {
+    /// Opaque black
+    pub const BLACK: Self = Self {
+        r: 0,
+        g: 0,
+        b: 0,
+        a: 0xff,
+    };
+
+    /// Color as big-endian ARGB value like `videotestsrc` expects it.
+    #[must_use]
+    pub fn argb(&self) -> u32 {
+        (u32::from(self.a)) << 24
+            | (u32::from(self.r)) << 16
+            | (u32::from(self.g)) << 8
+            | (u32::from(self.b))
+    }
+}
+
+impl TryFrom<String> for Color {
+    type Error = anyhow::Error;
+
+    fn try_from(value: String) -> anyhow::Result<Self> {
+        let Some(hex) = value.strip_prefix('#') else {
+            bail!("color '{value}' does not start with '#'");
+        };
+        if hex.len() != 6 && hex.len() != 8 {
+            bail!("color '{value}' must have 6 or 8 hex digits");
+        }
+        let component = |index: usize| {
+            hex.get(index * 2..index * 2 + 2)
+                .and_then(|digits| u8::from_str_radix(digits, 16).ok())
+                .with_context(|| format!("color '{value}' contains invalid hex digits"))
+        };
+        Ok(Self {
+            r: component(0)?,
+            g: component(1)?,
+            b: component(2)?,
+            a: if hex.len() == 8 { component(3)? } else { 0xff },
+        })
+    }
+}
+
 impl From<Color> for u32 {
     fn from(color: Color) -> u32 {
         (u32::from(color.r)) << 24
@@ -50,7 +99,8 @@ impl std::fmt::Display for Color {
 }
 
 /// Text padding.
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(default)]
 pub struct TextPadding {
     pub x: i32,
     pub y: i32,
@@ -69,16 +119,17 @@ impl std::fmt::Display for TextPadding {
 }
 
 /// Text font.
-#[derive(Debug)]
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
 pub struct Font {
-    pub name: &'static str,
+    pub name: String,
     pub size: u32,
 }
 
 impl Default for Font {
     fn default() -> Font {
         Font {
-            name: "Sans",
+            name: "Sans".to_string(),
             size: 10,
         }
     }
@@ -91,7 +142,8 @@ impl std::fmt::Display for Font {
 }
 
 /// Horizontal text alignment.
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum HAlign {
     Left,
     #[default]
@@ -126,7 +178,8 @@ impl HAlign {
 }
 
 /// Vertical text alignment.
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum VAlign {
     #[default]
     Baseline,
@@ -163,7 +216,8 @@ impl VAlign {
 }
 
 /// Horizontal and vertical text alignment.
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone, Copy, Deserialize)]
+#[serde(default)]
 pub struct Align {
     pub horizontal: HAlign,
     pub vertical: VAlign,
@@ -181,7 +235,8 @@ impl std::fmt::Display for Align {
 }
 
 /// Text format.
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default)]
 pub struct TextStyle {
     pub font: Font,
     pub padding: TextPadding,
@@ -0,0 +1,83 @@
+// SPDX-FileCopyrightText: OpenTalk GmbH <mail@opentalk.eu>
+//
+// SPDX-License-Identifier: EUPL-1.2
+
+//! Branding themes.
+
+use serde::Deserialize;
+
+use crate::{Align, ChatStyle, Color, Font, HAlign, Image, PlaceholderStyle, TextStyle, VAlign};
+
+const OVERLAY_FONT_SIZE: u32 = 20;
+const NAME_FONT_SIZE: u32 = 16;
+/// Microphone slash icon of Font Awesome
+const MUTED_INDICATOR: &str =
+    "<span font_family=\"Font Awesome 6 Free\" font_weight=\"900\">\u{f131}</span> ";
+
+/// Bundles the text styles, colors and images which brand the output picture.
+///
+/// Missing fields are taken from the default theme when deserializing.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct Theme {
+    /// Style of the talk's title.
+    pub title: TextStyle,
+    /// Style of the clock.
+    pub clock: TextStyle,
+    /// Style of the name tags within the streams.
+    pub names: TextStyle,
+    /// Color behind all streams.
+    pub background_color: Color,
+    /// Image behind all streams, stretched to the output size.
+    pub background_image: Option<Image>,
+    /// Logo which is placed on top of the output picture.
+    pub logo: Option<Image>,
+    /// Style and region of the chat messages in sinks which show the chat.
+    pub chat: ChatStyle,
+    /// Appearance of the placeholders of streams without video.
+    pub placeholder: PlaceholderStyle,
+    /// Pango markup which precedes the name tag of a stream whose microphone is muted.
+    pub muted_indicator: String,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            title: TextStyle {
+                align: Align {
+                    horizontal: HAlign::Left,
+                    vertical: VAlign::Top,
+                },
+                font: Font {
+                    size: OVERLAY_FONT_SIZE,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            clock: TextStyle {
+                align: Align {
+                    horizontal: HAlign::Right,
+                    vertical: VAlign::Top,
+                },
+                font: Font {
+                    size: OVERLAY_FONT_SIZE,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            names: TextStyle {
+                font: Font {
+                    size: NAME_FONT_SIZE,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            background_color: Color::BLACK,
+            background_image: None,
+            logo: None,
+            chat: ChatStyle::default(),
+            placeholder: PlaceholderStyle::default(),
+            muted_indicator: MUTED_INDICATOR.to_string(),
+        }
+    }
+}
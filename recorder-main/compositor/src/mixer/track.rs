@@ -0,0 +1,211 @@
+// SPDX-FileCopyrightText: OpenTalk GmbH <mail@opentalk.eu>
+//
+// SPDX-License-Identifier: EUPL-1.2
+
+//! Audio tracks of single participants.
+
+use anyhow::{Context, Result};
+use gst::{prelude::*, Element, ElementFactory, GhostPad, Pipeline, TagList};
+use gst_app::AppSrc;
+use std::sync::Arc;
+
+use super::{audio_mixer::AudioMixer, sink_gate::SinkGate, AUDIO_CHANNELS, AUDIO_SAMPLE_RATE};
+use crate::Loudness;
+
+/// Stream IDs which tell the participant a stream belongs to.
+///
+/// All streams of a participant are recorded into the same audio track.
+pub trait TrackOwner {
+    /// Returns the participant the stream belongs to.
+    fn track_owner(&self) -> String;
+}
+
+/// Audio track of a sink which records the isolated audio of a single participant.
+///
+/// The track is silent until a stream of its participant gets assigned and while the
+/// participant has no streams. Streams of a participant who rejoins return to the same track.
+/// Tracks of participants who have left are only reused if no track is free anymore.
+#[derive(Debug)]
+pub(crate) struct TrackSlot {
+    /// Mixes the assigned streams into silence.
+    mixer: AudioMixer,
+    /// Feeds the track within the sink's pipeline.
+    app_src: AppSrc,
+    /// Participant the track belongs to, `None` until a stream has been assigned.
+    owner: Option<String>,
+    /// Names of the assigned streams and the pads of `mixer` they are linked to.
+    streams: Vec<(String, GhostPad)>,
+}
+
+impl TrackSlot {
+    /// Create a track which feeds the given sink pad.
+    ///
+    /// The track's mixer is added to `pipeline`, the elements feeding `sink_pad` are added to
+    /// `sink_pipeline`.
+    ///
+    /// # Errors
+    ///
+    /// This can fail if the elements cannot be created, added or linked.
+    pub(crate) fn create(
+        name: &str,
+        pipeline: &Pipeline,
+        sink_pipeline: &Pipeline,
+        sink_pad: &GhostPad,
+        gate: &Arc<SinkGate>,
+    ) -> Result<Self> {
+        let mixer = AudioMixer::create(&format!("Track: {name}"), &Loudness::default())
+            .context("unable to create AudioMixer for track")?;
+        pipeline
+            .add(mixer.bin())
+            .context("unable to add track mixer to pipeline")?;
+
+        let app_src = AppSrc::builder()
+            .name(format!("tracksrc-{name}"))
+            .caps(
+                &gst::Caps::builder("audio/x-raw")
+                    .field("format", "S16LE")
+                    .field("layout", "interleaved")
+                    .field("rate", AUDIO_SAMPLE_RATE)
+                    .field("channels", AUDIO_CHANNELS)
+                    .build(),
+            )
+            .format(gst::Format::Time)
+            .max_bytes(1)
+            .block(true)
+            .build();
+        let queue = ElementFactory::make("queue")
+            .property_from_str("leaky", "downstream")
+            .build()
+            .context("unable to create queue")?;
+        let audioconvert = ElementFactory::make("audioconvert")
+            .build()
+            .context("unable to create audioconvert")?;
+
+        sink_pipeline
+            .add_many(&[app_src.upcast_ref(), &queue, &audioconvert])
+            .context("unable to add appsrc, queue and audioconvert to pipeline")?;
+        Element::link_many(&[app_src.upcast_ref(), &queue, &audioconvert])
+            .context("unable to link appsrc, queue and audioconvert")?;
+        audioconvert
+            .static_pad("src")
+            .context("unable to get static pad src from audioconvert")?
+            .link(sink_pad)
+            .context("unable to link audioconvert with track sink")?;
+
+        for element in [app_src.upcast_ref(), &queue, &audioconvert] {
+            element
+                .sync_state_with_parent()
+                .context("unable to sync track element state")?;
+        }
+        mixer
+            .bin()
+            .sync_state_with_parent()
+            .context("unable to sync track mixer state")?;
+
+        mixer.link_sink(&app_src, gate.clone());
+
+        Ok(Self {
+            mixer,
+            app_src,
+            owner: None,
+            streams: Vec::new(),
+        })
+    }
+
+    /// Returns the track of the given participant.
+    ///
+    /// Participants without a track get a free one or, if there is none, the track of a
+    /// participant who has left. Returns `None` if all tracks belong to present participants.
+    pub(crate) fn find<'a>(tracks: &'a mut [Self], owner: &str) -> Option<&'a mut Self> {
+        let index = tracks
+            .iter()
+            .position(|track| track.owner.as_deref() == Some(owner))
+            .or_else(|| tracks.iter().position(|track| track.owner.is_none()))
+            .or_else(|| tracks.iter().position(|track| track.streams.is_empty()))?;
+        tracks.get_mut(index)
+    }
+
+    /// Assign a stream of the given participant to the track.
+    ///
+    /// The track gets labeled with the given tags when it changes its participant.
+    ///
+    /// # Errors
+    ///
+    /// This can fail if the stream's audio cannot be linked to the track.
+    pub(crate) fn assign(
+        &mut self,
+        owner: &str,
+        name: &str,
+        audio: &GhostPad,
+        tags: TagList,
+    ) -> Result<()> {
+        let pad = self
+            .mixer
+            .link_src(audio)
+            .context("unable to link stream audio with track")?;
+        self.streams.push((name.to_string(), pad));
+
+        if self.owner.as_deref() != Some(owner) {
+            if let Some(previous) = self.owner.replace(owner.to_string()) {
+                debug!("reusing track of participant {previous} for participant {owner}");
+            }
+            if !self.app_src.send_event(gst::event::Tag::new(tags)) {
+                warn!("unable to label track of participant {owner}");
+            }
+        }
+
+        Ok(())
+    }
+
+    /// End the assigned stream with the given name, if any.
+    ///
+    /// The track continues with the other streams of its participant or silently.
+    ///
+    /// # Errors
+    ///
+    /// This can fail if the stream's audio cannot be released from the track.
+    pub(crate) fn end_stream(&mut self, name: &str) -> Result<()> {
+        let Some(index) = self.streams.iter().position(|(stream, _)| stream == name) else {
+            return Ok(());
+        };
+        let (_, pad) = self.streams.remove(index);
+        if let Some(target) = pad.target() {
+            self.mixer
+                .release_src(&target)
+                .context("unable to release stream audio from track")?;
+        }
+        Ok(())
+    }
+
+    /// End all assigned streams, the track continues silently.
+    ///
+    /// # Errors
+    ///
+    /// This can fail if the audio of a stream cannot be released from the track.
+    fn end(&mut self) -> Result<()> {
+        let names: Vec<String> = self.streams.iter().map(|(name, _)| name.clone()).collect();
+        for name in names {
+            self.end_stream(&name)?;
+        }
+        Ok(())
+    }
+
+    /// Send EOS into the track and remove its mixer from the given pipeline.
+    ///
+    /// # Errors
+    ///
+    /// This can fail if the mixer cannot be stopped or removed.
+    pub(crate) fn release(mut self, pipeline: &Pipeline) -> Result<()> {
+        self.end()?;
+        self.app_src
+            .end_of_stream()
+            .context("unable to send EOS to track")?;
+        self.mixer
+            .bin()
+            .set_state(gst::State::Null)
+            .context("unable to stop track mixer")?;
+        pipeline
+            .remove(self.mixer.bin())
+            .context("unable to remove track mixer from pipeline")
+    }
+}
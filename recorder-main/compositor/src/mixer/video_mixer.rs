@@ -2,32 +2,306 @@
 //
 // SPDX-License-Identifier: EUPL-1.2
 
-use anyhow::{Context, Result};
+use anyhow::{anyhow, bail, Context, Result};
 use gst::{
-    element_error, prelude::*, Bin, Caps, Element, ElementFactory, FlowError, FlowSuccess,
-    GhostPad, Pad, Sample, StreamError,
+    element_error, prelude::*, Bin, BufferFlags, Caps, Element, ElementFactory, FlowError,
+    FlowSuccess, Fraction, GhostPad, Pad, Sample, StreamError,
 };
 use gst_app::{AppSink, AppSinkCallbacks, AppSrc};
-use tokio::sync::broadcast;
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    sync::{Arc, Mutex},
+};
+use tokio::sync::broadcast::{self, error::RecvError};
 
-use crate::mixer::VIDEO_FRAMERATE;
-use crate::{Overlay, Size};
+use crate::mixer::{
+    profile::{VideoEncoding, VideoFormat},
+    sink_gate::SinkGate,
+    VIDEO_FRAMERATE,
+};
+use crate::{Color, Image, ImageOverlay, Overlay, Size};
 
 const QUEUE_SIZE: usize = VIDEO_FRAMERATE as usize;
+
+/// Output format and encoding of a branch of a scene
+type BranchKey = (Option<VideoFormat>, Option<VideoEncoding>);
 #[derive(Debug)]
 pub(crate) struct VideoMixer {
     bin: Bin,
+    output_size: Size,
+    /// Composition of the shared layout
+    scene: Scene,
+    /// Compositions of sinks with an own layout by sink name
+    scenes: HashMap<String, Scene>,
+}
+
+/// Composition of the streams into one picture and its outputs
+#[derive(Debug)]
+struct Scene {
     compositor: Element,
+    background: Element,
+    background_image: ImageOverlay,
+    /// Splits the composed picture into the output branches
+    tee: Element,
+    /// Output of the composed picture as it is
     buffer: broadcast::Sender<Sample>,
+    /// Outputs of scaled or encoded branches, one per resolution, framerate and encoding
+    branches: Mutex<HashMap<BranchKey, Branch>>,
+    /// All elements of the scene within the video mixer's bin
+    elements: Mutex<Vec<Element>>,
+}
+
+/// Output branch of a scene
+#[derive(Debug)]
+struct Branch {
+    buffer: broadcast::Sender<Sample>,
+    /// Encoder of the branch if it outputs encoded video
+    encoder: Option<Element>,
 }
 
 impl VideoMixer {
-    #[allow(clippy::too_many_lines)]
     pub(crate) fn create(output_size: Size, overlay: &impl Overlay) -> Result<Self> {
         let bin = Bin::new(Some("VideoMixer"));
+        let scene = Scene::create(&bin, None, output_size, overlay)?;
+
+        Ok(Self {
+            bin,
+            output_size,
+            scene,
+            scenes: HashMap::new(),
+        })
+    }
+
+    /// Add the composition of a sink with an own layout with the given overlay on top.
+    ///
+    /// The scene's compositor has no streams linked until [`VideoMixer::link_src()`] is called
+    /// with its name.
+    ///
+    /// # Errors
+    ///
+    /// This can fail if a scene with the same name exists or its elements cannot be created.
+    pub(crate) fn add_scene(&mut self, name: &str, overlay: &impl Overlay) -> Result<()> {
+        if self.scenes.contains_key(name) {
+            bail!("a scene with the name '{name}' already exists");
+        }
+        let scene = Scene::create(&self.bin, Some(name), self.output_size, overlay)?;
+        for element in scene.elements()? {
+            element
+                .sync_state_with_parent()
+                .context("unable to sync scene element state")?;
+        }
+        self.scenes.insert(name.to_owned(), scene);
+        Ok(())
+    }
+
+    /// Remove the composition of a sink with an own layout.
+    ///
+    /// The streams have to be released from the scene by [`VideoMixer::release_src()`] before.
+    ///
+    /// # Errors
+    ///
+    /// This can fail if the scene is unknown or its elements cannot be removed.
+    pub(crate) fn remove_scene(&mut self, name: &str) -> Result<()> {
+        let scene = self
+            .scenes
+            .remove(name)
+            .with_context(|| format!("there is no scene with the name '{name}'"))?;
+        for element in scene.elements()? {
+            element
+                .set_state(gst::State::Null)
+                .context("unable to stop scene element")?;
+            self.bin
+                .remove(&element)
+                .context("unable to remove scene element from 'bin'")?;
+        }
+        Ok(())
+    }
+
+    /// Returns the scene with the given name or the scene of the shared layout if `None`.
+    fn scene(&self, scene: Option<&str>) -> Result<&Scene> {
+        match scene {
+            Some(name) => self
+                .scenes
+                .get(name)
+                .with_context(|| format!("there is no scene with the name '{name}'")),
+            None => Ok(&self.scene),
+        }
+    }
+
+    /// Fill the background behind all streams of all scenes with the given color and image.
+    ///
+    /// The image gets stretched to the output size.
+    pub(crate) fn set_background(&self, color: Color, image: Option<&Image>) {
+        let image = image.map(|image| Image {
+            x: 0.0,
+            y: 0.0,
+            width: self.output_size.width as u32,
+            height: self.output_size.height as u32,
+            ..image.clone()
+        });
+        for scene in std::iter::once(&self.scene).chain(self.scenes.values()) {
+            scene
+                .background
+                .set_property_from_str("pattern", "solid-color");
+            scene
+                .background
+                .set_property("foreground-color", color.argb());
+            scene.background_image.set(image.as_ref());
+        }
+    }
+
+    #[must_use]
+    pub(crate) fn bin(&self) -> &Bin {
+        &self.bin
+    }
+
+    /// Link the given stream video to the compositor of the given scene or of the shared layout.
+    pub(crate) fn link_src(&self, src: &impl IsA<Pad>, scene: Option<&str>) -> Result<GhostPad> {
+        let requested_pad = self
+            .scene(scene)?
+            .compositor
+            .request_pad_simple("sink_%u")
+            .context("unable to request 'sink' pad for 'compositor'")?;
+        requested_pad.set_property_from_str("sizing-policy", "keep-aspect-ratio");
+        requested_pad.set_property("alpha", 0.0);
+
+        let ghost_pad = GhostPad::with_target(None, &requested_pad)
+            .context("unable to create 'GhostPad' for 'src'")?;
+
+        self.bin
+            .add_pad(&ghost_pad)
+            .context("unable to add 'ghost_pad' to 'bin'")?;
+
+        src.link(&ghost_pad)
+            .context("unable to link 'ghost_pad' with 'requested_pad'")?;
+
+        Ok(ghost_pad)
+    }
+
+    /// Release the given compositor pad of the given scene or of the shared layout.
+    pub(crate) fn release_src(&self, src: &impl IsA<Pad>, scene: Option<&str>) -> Result<()> {
+        if let Some(proxy_pad) = src.peer() {
+            for ghost_pad in proxy_pad.iterate_internal_links() {
+                let ghost_pad =
+                    ghost_pad.context("unable to get ghost_pad from proxy_pad iterator")?;
+                self.bin
+                    .remove_pad(&ghost_pad)
+                    .context("unable to remove ghost_pad form bin")?;
+            }
+        }
+
+        self.scene(scene)?.compositor.release_request_pad(src);
+
+        Ok(())
+    }
+
+    /// Feed the given `AppSrc` with the composed picture of the given scene or of the shared
+    /// layout in the given format, encoded with the given encoding if any.
+    ///
+    /// Sinks of the same scene with the same format and encoding share one branch of the scene's
+    /// output tee, including its encoder. Encoded video starts with a keyframe and continues with
+    /// one after a pause.
+    pub(crate) fn link_sink(
+        &self,
+        app_src: &AppSrc,
+        gate: Arc<SinkGate>,
+        format: Option<VideoFormat>,
+        encoding: Option<VideoEncoding>,
+        scene: Option<&str>,
+    ) -> Result<()> {
+        let scene = self.scene(scene)?;
+        let (mut receiver, encoder) = if format.is_none() && encoding.is_none() {
+            (scene.buffer.subscribe(), None)
+        } else {
+            let mut branches = scene
+                .branches
+                .lock()
+                .map_err(|_| anyhow!("unable to lock output branches"))?;
+            let branch = match branches.entry((format, encoding)) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => {
+                    let mut elements = scene
+                        .elements
+                        .lock()
+                        .map_err(|_| anyhow!("unable to lock scene elements"))?;
+                    let branch =
+                        Scene::add_branch(&self.bin, &scene.tee, format, encoding, &mut elements)
+                            .context("unable to add output branch")?;
+                    entry.insert(branch)
+                }
+            };
+            (branch.buffer.subscribe(), branch.encoder.clone())
+        };
+        let app_src = app_src.clone();
+        std::thread::spawn(move || {
+            // encoded video has to start with a keyframe
+            let mut needs_keyframe = encoder.is_some();
+            let mut keyframe_requested = false;
+            loop {
+                let sample = match receiver.blocking_recv() {
+                    Ok(sample) => sample,
+                    Err(RecvError::Lagged(skipped)) => {
+                        debug!("app_src {} skipped {skipped} samples", app_src.name());
+                        needs_keyframe = encoder.is_some();
+                        continue;
+                    }
+                    Err(RecvError::Closed) => return,
+                };
+                let Some(sample) = gate.apply(sample) else {
+                    needs_keyframe = encoder.is_some();
+                    continue;
+                };
+                if needs_keyframe {
+                    let is_delta = sample.buffer().map_or(true, |buffer| {
+                        buffer.flags().contains(BufferFlags::DELTA_UNIT)
+                    });
+                    if is_delta {
+                        if let Some(encoder) = encoder.as_ref().filter(|_| !keyframe_requested) {
+                            request_keyframe(encoder);
+                            keyframe_requested = true;
+                        }
+                        continue;
+                    }
+                    needs_keyframe = false;
+                    keyframe_requested = false;
+                }
+                if let Err(error) = app_src.push_sample(&sample) {
+                    let src_name = app_src.name();
+                    match error {
+                        FlowError::Flushing => {
+                            debug!("Flush and exit app_src {src_name}");
+                        }
+                        _ => {
+                            error!("Failed pushing sample to app_src {src_name} with error: {error:?}, sample: {sample:?}");
+                        }
+                    }
+                    return;
+                }
+            }
+        });
+
+        Ok(())
+    }
+}
+
+impl Scene {
+    /// Create the elements of a scene within the given bin.
+    ///
+    /// Elements of named scenes get the scene's name appended to stay unique within the bin.
+    #[allow(clippy::too_many_lines)]
+    fn create(
+        bin: &Bin,
+        scene: Option<&str>,
+        output_size: Size,
+        overlay: &impl Overlay,
+    ) -> Result<Self> {
+        let named = |name: &str| match scene {
+            Some(scene) => format!("{name} {scene}"),
+            None => name.to_owned(),
+        };
 
         let videotestsrc = ElementFactory::make("videotestsrc")
-            .name("Video Background Source")
+            .name(named("Video Background Source"))
             .property_from_str("pattern", "black")
             .property("is-live", true)
             .build()
@@ -43,49 +317,154 @@ impl VideoMixer {
             )
             .build()
             .context("unable to build capssetter")?;
+        let background_image = ImageOverlay::create(&named("Video Background Image"), None)
+            .context("unable to create background image overlay")?;
 
         let compositor = ElementFactory::make("compositor")
-            .name("compositor")
+            .name(named("compositor"))
             .property("ignore-inactive-pads", true)
             .property("zero-size-is-unscaled", true)
             .build()
             .context("unable to build compositor")?;
 
-        let queue = ElementFactory::make("queue")
+        let tee = ElementFactory::make("tee")
+            .name(named("output-tee"))
+            .property("allow-not-linked", true)
             .build()
-            .context("unable to build queue")?;
-        let appsink: AppSink = AppSink::builder().build();
+            .context("unable to build tee")?;
+
+        if scene.is_some() {
+            let element = overlay.element();
+            element.set_property("name", named(&element.name()));
+        }
+
+        let mut elements = vec![
+            videotestsrc.clone(),
+            videotestsrc_capssetter.clone(),
+            background_image.element().clone(),
+            compositor.clone(),
+            overlay.element().clone(),
+            tee.clone(),
+        ];
 
         bin.add_many(&[
             &videotestsrc,
             &videotestsrc_capssetter,
+            background_image.element(),
             &compositor,
             &overlay.element(),
-            &queue,
-            appsink.upcast_ref(),
+            &tee,
         ])
-        .context("unable to add 'videotestsrc', 'videotestsrc_capssetter', 'compositor', 'queue'  and 'appsink' to 'bin'")?;
+        .context("unable to add 'videotestsrc', 'videotestsrc_capssetter', 'background_image', 'compositor' and 'tee' to 'bin'")?;
 
-        videotestsrc
-            .link(&videotestsrc_capssetter)
-            .context("unable to link 'videotestsrc' with 'capssetter'")?;
+        Element::link_many(&[
+            &videotestsrc,
+            &videotestsrc_capssetter,
+            background_image.element(),
+        ])
+        .context("unable to link 'videotestsrc', 'capssetter' and 'background_image'")?;
 
         let compositor_sink_pad = compositor
             .request_pad_simple("sink_%u")
             .context("unable to request sink pad for compositor")?;
-        videotestsrc_capssetter
-            .static_pad("src")
-            .context("unable to get static pad src from capssetter")?
+        background_image
+            .src()
+            .context("unable to get static pad src from background_image")?
             .link(&compositor_sink_pad)
-            .context("unable to link compositor_requested_pad with capssetter")?;
+            .context("unable to link compositor_requested_pad with background_image")?;
 
-        Element::link_many(&[
-            &compositor,
-            &overlay.element(),
-            &queue,
-            appsink.upcast_ref(),
-        ])
-        .context("unable to link 'compositor', 'overlay', 'queue' and 'appsink'")?;
+        Element::link_many(&[&compositor, &overlay.element(), &tee])
+            .context("unable to link 'compositor', 'overlay' and 'tee'")?;
+
+        let buffer = Self::add_branch(bin, &tee, None, None, &mut elements)
+            .context("unable to add output branch")?
+            .buffer;
+
+        Ok(Self {
+            compositor,
+            background: videotestsrc,
+            background_image,
+            tee,
+            buffer,
+            branches: Mutex::new(HashMap::new()),
+            elements: Mutex::new(elements),
+        })
+    }
+
+    /// Returns all elements of the scene.
+    fn elements(&self) -> Result<Vec<Element>> {
+        Ok(self
+            .elements
+            .lock()
+            .map_err(|_| anyhow!("unable to lock scene elements"))?
+            .clone())
+    }
+
+    /// Add a branch to the output tee which provides its samples through a channel.
+    ///
+    /// The picture is scaled and its framerate converted into the given format and encoded with
+    /// the given encoding, if any. The elements of the branch are appended to `scene_elements`.
+    fn add_branch(
+        bin: &Bin,
+        tee: &Element,
+        format: Option<VideoFormat>,
+        encoding: Option<VideoEncoding>,
+        scene_elements: &mut Vec<Element>,
+    ) -> Result<Branch> {
+        let queue = ElementFactory::make("queue")
+            .build()
+            .context("unable to build queue")?;
+        let appsink: AppSink = AppSink::builder().build();
+
+        let mut elements = vec![queue];
+        if let Some(format) = format {
+            let videoscale = ElementFactory::make("videoscale")
+                .build()
+                .context("unable to build videoscale")?;
+            let videorate = ElementFactory::make("videorate")
+                .build()
+                .context("unable to build videorate")?;
+            let capsfilter = ElementFactory::make("capsfilter")
+                .property(
+                    "caps",
+                    Caps::builder("video/x-raw")
+                        .field("width", format.width as i32)
+                        .field("height", format.height as i32)
+                        .field("framerate", Fraction::new(format.framerate, 1))
+                        .build(),
+                )
+                .build()
+                .context("unable to build capsfilter")?;
+            elements.extend([videoscale, videorate, capsfilter]);
+        }
+        let encoder = match encoding {
+            Some(encoding) => {
+                let encoder_elements = encoding
+                    .create_elements()
+                    .context("unable to create video encoder")?;
+                let encoder = encoder_elements[1].clone();
+                elements.extend(encoder_elements);
+                Some(encoder)
+            }
+            None => None,
+        };
+        elements.push(appsink.clone().upcast());
+
+        let elements: Vec<&Element> = elements.iter().collect();
+        bin.add_many(&elements)
+            .context("unable to add branch elements to 'bin'")?;
+        Element::link_many(&elements).context("unable to link branch elements")?;
+
+        let tee_src_pad = tee
+            .request_pad_simple("src_%u")
+            .context("unable to request src pad for tee")?;
+        tee_src_pad
+            .link(
+                &elements[0]
+                    .static_pad("sink")
+                    .context("unable to get sink pad of branch queue")?,
+            )
+            .context("unable to link tee with branch")?;
 
         let buffer = broadcast::Sender::new(QUEUE_SIZE);
         let sender = buffer.clone();
@@ -94,6 +473,10 @@ impl VideoMixer {
                 .new_sample({
                     move |app_sink| match app_sink.pull_sample() {
                         Ok(sample) => {
+                            // nobody listens while no sink uses the branch
+                            if sender.receiver_count() == 0 {
+                                return Ok(FlowSuccess::Ok);
+                            }
                             if let Err(error) = sender.send(sample) {
                                 element_error!(
                                     app_sink,
@@ -121,73 +504,28 @@ impl VideoMixer {
                 .build(),
         );
 
-        Ok(Self {
-            bin,
-            compositor,
-            buffer,
-        })
-    }
-
-    #[must_use]
-    pub(crate) fn bin(&self) -> &Bin {
-        &self.bin
-    }
-
-    pub(crate) fn link_src(&self, src: &impl IsA<Pad>) -> Result<GhostPad> {
-        let requested_pad = self
-            .compositor
-            .request_pad_simple("sink_%u")
-            .context("unable to request 'sink' pad for 'compositor'")?;
-        requested_pad.set_property_from_str("sizing-policy", "keep-aspect-ratio");
-        requested_pad.set_property("alpha", 0.0);
-
-        let ghost_pad = GhostPad::with_target(None, &requested_pad)
-            .context("unable to create 'GhostPad' for 'src'")?;
-
-        self.bin
-            .add_pad(&ghost_pad)
-            .context("unable to add 'ghost_pad' to 'bin'")?;
-
-        src.link(&ghost_pad)
-            .context("unable to link 'ghost_pad' with 'requested_pad'")?;
-
-        Ok(ghost_pad)
-    }
-
-    pub(crate) fn release_src(&self, src: &impl IsA<Pad>) -> Result<()> {
-        if let Some(proxy_pad) = src.peer() {
-            for ghost_pad in proxy_pad.iterate_internal_links() {
-                let ghost_pad =
-                    ghost_pad.context("unable to get ghost_pad from proxy_pad iterator")?;
-                self.bin
-                    .remove_pad(&ghost_pad)
-                    .context("unable to remove ghost_pad form bin")?;
-            }
+        for element in &elements {
+            element
+                .sync_state_with_parent()
+                .context("unable to sync branch element state")?;
         }
+        scene_elements.extend(elements.into_iter().cloned());
 
-        self.compositor.release_request_pad(src);
-
-        Ok(())
+        Ok(Branch { buffer, encoder })
     }
+}
 
-    pub(crate) fn link_sink(&self, app_src: &AppSrc) {
-        let mut receiver = self.buffer.subscribe();
-        let app_src = app_src.clone();
-        std::thread::spawn(move || {
-            while let Ok(sample) = receiver.blocking_recv() {
-                if let Err(error) = app_src.push_sample(&sample) {
-                    let src_name = app_src.name();
-                    match error {
-                        FlowError::Flushing => {
-                            debug!("Flush and exit app_src {src_name}");
-                        }
-                        _ => {
-                            error!("Failed pushing sample to app_src {src_name} with error: {error:?}, sample: {sample:?}");
-                        }
-                    }
-                    return;
-                }
-            }
-        });
+/// Ask the given encoder for a keyframe which carries all headers.
+fn request_keyframe(encoder: &Element) {
+    let event = gst::event::CustomUpstream::new(
+        gst::Structure::builder("GstForceKeyUnit")
+            .field("all-headers", true)
+            .build(),
+    );
+    if !encoder.send_event(event) {
+        debug!(
+            "encoder {} did not take the keyframe request",
+            encoder.name()
+        );
     }
 }
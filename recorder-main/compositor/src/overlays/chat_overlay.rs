@@ -0,0 +1,285 @@
+// SPDX-FileCopyrightText: OpenTalk GmbH <mail@opentalk.eu>
+//
+// SPDX-License-Identifier: EUPL-1.2
+
+//! Overlay displaying the latest chat messages.
+
+use anyhow::{Context, Result};
+use gst::{prelude::*, PadProbeReturn, PadProbeType};
+use serde::Deserialize;
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    time::{Duration, Instant},
+};
+
+use crate::{Align, Font, HAlign, Overlay, TextPadding, TextStyle, VAlign};
+
+const CHAT_FONT_SIZE: u32 = 14;
+const DEFAULT_MAX_MESSAGES: usize = 5;
+const DEFAULT_MAX_LENGTH: usize = 120;
+const DEFAULT_DISPLAY_TIME: f64 = 20.0;
+const DEFAULT_FADE_TIME: f64 = 5.0;
+
+/// Appearance and lifetime of the messages within the chat overlay.
+///
+/// The region of the messages is given by the alignment and padding of the text style.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ChatStyle {
+    /// Style and region of the messages.
+    pub text: TextStyle,
+    /// Maximum number of visible messages, older messages are dropped.
+    pub max_messages: usize,
+    /// Maximum number of characters of a message, longer messages are cut.
+    pub max_length: usize,
+    /// Seconds a message is fully visible.
+    pub display_time: f64,
+    /// Seconds a message takes to fade out after its display time.
+    pub fade_time: f64,
+}
+
+impl Default for ChatStyle {
+    fn default() -> Self {
+        Self {
+            text: TextStyle {
+                align: Align {
+                    horizontal: HAlign::Left,
+                    vertical: VAlign::Bottom,
+                },
+                padding: TextPadding { x: 20, y: 20 },
+                font: Font {
+                    size: CHAT_FONT_SIZE,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            max_messages: DEFAULT_MAX_MESSAGES,
+            max_length: DEFAULT_MAX_LENGTH,
+            display_time: DEFAULT_DISPLAY_TIME,
+            fade_time: DEFAULT_FADE_TIME,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct ChatMessage {
+    sender: String,
+    text: String,
+    received: Instant,
+}
+
+#[derive(Debug, Default)]
+struct ChatLogState {
+    style: ChatStyle,
+    messages: VecDeque<ChatMessage>,
+}
+
+/// Latest chat messages which are shared by the chat overlays of all sinks.
+#[derive(Debug, Clone, Default)]
+pub struct ChatLog {
+    state: Arc<Mutex<ChatLogState>>,
+}
+
+impl ChatLog {
+    fn state(&self) -> MutexGuard<'_, ChatLogState> {
+        // messages are only pushed and dropped, a poisoned lock can be used anyway
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Current style of the messages.
+    #[must_use]
+    pub fn style(&self) -> ChatStyle {
+        self.state().style.clone()
+    }
+
+    /// Change the style of the messages.
+    ///
+    /// # Arguments
+    ///
+    /// - `style`: new style
+    ///
+    pub fn set_style(&self, style: ChatStyle) {
+        let mut state = self.state();
+        let excess = state.messages.len().saturating_sub(style.max_messages);
+        state.messages.drain(..excess);
+        state.style = style;
+    }
+
+    /// Add a message which is received now.
+    ///
+    /// # Arguments
+    ///
+    /// - `sender`: Display name of the sender.
+    /// - `text`: Content of the message.
+    ///
+    pub fn push(&self, sender: &str, text: &str) {
+        self.push_at(sender, text, Instant::now());
+    }
+
+    /// Add a message which was received at the given time.
+    pub(crate) fn push_at(&self, sender: &str, text: &str, received: Instant) {
+        trace!("push( '{sender}', '{text}' )");
+
+        let mut state = self.state();
+        if state.style.max_messages == 0 {
+            return;
+        }
+        if state.messages.len() >= state.style.max_messages {
+            state.messages.pop_front();
+        }
+
+        // the overlay shows one line per message
+        let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        let text = if text.chars().count() > state.style.max_length {
+            let mut text: String = text.chars().take(state.style.max_length).collect();
+            text.push('…');
+            text
+        } else {
+            text
+        };
+
+        state.messages.push_back(ChatMessage {
+            sender: sender.to_owned(),
+            text,
+            received,
+        });
+    }
+
+    /// Pango markup of the messages which are visible at the given time, the newest at the bottom.
+    ///
+    /// Expired messages are removed, fading messages get a lower opacity in steps of 10%.
+    pub(crate) fn markup(&self, now: Instant) -> String {
+        let mut state = self.state();
+        let display_time = Duration::from_secs_f64(state.style.display_time.max(0.0));
+        let fade_time = Duration::from_secs_f64(state.style.fade_time.max(0.0));
+
+        state.messages.retain(|message| {
+            now.saturating_duration_since(message.received) < display_time + fade_time
+        });
+
+        state
+            .messages
+            .iter()
+            .map(|message| {
+                let faded = now
+                    .saturating_duration_since(message.received)
+                    .saturating_sub(display_time);
+                let sender = glib::markup_escape_text(&message.sender);
+                let text = glib::markup_escape_text(&message.text);
+                if faded.is_zero() {
+                    return format!("<b>{sender}</b>: {text}");
+                }
+                let opacity = 1.0 - faded.as_secs_f64() / fade_time.as_secs_f64();
+                #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+                let alpha = (opacity * 10.0).ceil() as u32 * 10;
+                format!("<span alpha=\"{alpha}%\"><b>{sender}</b>: {text}</span>")
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Overlay displaying the latest messages of a chat log.
+#[derive(Debug, Clone)]
+pub struct ChatOverlay {
+    // textoverlay element
+    element: gst::Element,
+}
+
+impl ChatOverlay {
+    /// Create new chat overlay.
+    ///
+    /// The displayed messages are updated with every video frame.
+    ///
+    /// # Arguments
+    ///
+    /// - `name`: Element's name.
+    /// - `log`: Chat log to display.
+    ///
+    /// # Errors
+    ///
+    /// This can fail if the `textoverlay` cannot be created in `GStreamer`.
+    pub fn create(name: &str, log: &ChatLog) -> Result<Self> {
+        trace!("new( {name} )");
+
+        // create text overlay
+        let element = gst::ElementFactory::make_with_name("textoverlay", Some(name))
+            .context("failed to create chat overlay")?;
+
+        // set up properties, the text is rendered as Pango markup
+        element.set_property("auto-resize", false);
+        element.set_property("shaded-background", true);
+
+        let overlay = Self { element };
+        overlay.set_style(&log.style());
+
+        // render the messages before each frame
+        let element = overlay.element.downgrade();
+        let log = log.clone();
+        let rendered = Mutex::new(String::new());
+        overlay
+            .element
+            .static_pad("video_sink")
+            .context("unable to get video sink pad of chat overlay")?
+            .add_probe(PadProbeType::BUFFER, move |_, _| {
+                let Some(element) = element.upgrade() else {
+                    return PadProbeReturn::Remove;
+                };
+                let markup = log.markup(Instant::now());
+                let mut rendered = rendered.lock().unwrap_or_else(PoisonError::into_inner);
+                if *rendered != markup {
+                    element.set_property("text", &markup);
+                    *rendered = markup;
+                }
+                PadProbeReturn::Ok
+            });
+
+        // return Overlay
+        Ok(overlay)
+    }
+
+    /// Change the style of the messages.
+    ///
+    /// # Arguments
+    ///
+    /// - `style`: new style
+    ///
+    pub fn set_style(&self, style: &ChatStyle) {
+        let text = &style.text;
+        self.element.set_property(
+            "font-desc",
+            format!(
+                "{name},{size}",
+                name = text.font.name,
+                size = text.font.size
+            ),
+        );
+        self.element.set_property("xpad", text.padding.x);
+        self.element.set_property("ypad", text.padding.y);
+        self.element.set_property("color", text.color);
+        self.element
+            .set_property_from_str("halignment", text.align.horizontal.into());
+        self.element
+            .set_property_from_str("valignment", text.align.vertical.into());
+        // messages line up at the side of the region
+        self.element.set_property_from_str(
+            "line-alignment",
+            match text.align.horizontal {
+                HAlign::Left => "left",
+                HAlign::Right => "right",
+                _ => "center",
+            },
+        );
+    }
+}
+
+impl Overlay for ChatOverlay {
+    #[must_use]
+    fn element(&self) -> &gst::Element {
+        &self.element
+    }
+    fn show(&self, show: bool) {
+        self.element.set_property("silent", !show);
+    }
+}
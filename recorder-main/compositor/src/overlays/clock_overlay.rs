@@ -37,7 +37,23 @@ impl ClockOverlay {
 
         // set up properties
         element.set_property("time-format", format);
-        element.set_property(
+        element.set_property("auto-resize", false);
+
+        let overlay = Self { element };
+        overlay.set_style(&style);
+
+        // return Overlay
+        Ok(overlay)
+    }
+
+    /// Change the style of the clock display.
+    ///
+    /// # Arguments
+    ///
+    /// - `style`: new style
+    ///
+    pub fn set_style(&self, style: &TextStyle) {
+        self.element.set_property(
             "font-desc",
             format!(
                 "{name},{size}",
@@ -45,15 +61,13 @@ impl ClockOverlay {
                 size = style.font.size
             ),
         );
-        element.set_property("xpad", style.padding.x);
-        element.set_property("ypad", style.padding.y);
-        element.set_property("color", style.color);
-        element.set_property_from_str("halignment", style.align.horizontal.into());
-        element.set_property_from_str("valignment", style.align.vertical.into());
-        element.set_property("auto-resize", false);
-
-        // return Overlay
-        Ok(Self { element })
+        self.element.set_property("xpad", style.padding.x);
+        self.element.set_property("ypad", style.padding.y);
+        self.element.set_property("color", style.color);
+        self.element
+            .set_property_from_str("halignment", style.align.horizontal.into());
+        self.element
+            .set_property_from_str("valignment", style.align.vertical.into());
     }
 }
 
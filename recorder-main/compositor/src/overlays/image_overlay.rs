@@ -0,0 +1,123 @@
+// SPDX-FileCopyrightText: OpenTalk GmbH <mail@opentalk.eu>
+//
+// SPDX-License-Identifier: EUPL-1.2
+
+//! Overlay which displays an image file.
+
+use anyhow::{Context, Result};
+use gst::prelude::*;
+use serde::Deserialize;
+use std::sync::{Arc, Mutex};
+
+use crate::Overlay;
+
+/// Image to display within an `ImageOverlay`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Image {
+    /// Path of a PNG or SVG file.
+    pub location: String,
+    /// Horizontal position of the image's left edge relative to the picture width (`0.0` - `1.0`).
+    #[serde(default)]
+    pub x: f64,
+    /// Vertical position of the image's top edge relative to the picture height (`0.0` - `1.0`).
+    #[serde(default)]
+    pub y: f64,
+    /// Width in pixels or `0` to use the image's width.
+    #[serde(default)]
+    pub width: u32,
+    /// Height in pixels or `0` to use the image's height.
+    #[serde(default)]
+    pub height: u32,
+    /// Opacity from `0.0` (invisible) to `1.0` (opaque).
+    #[serde(default = "default_opacity")]
+    pub opacity: f64,
+}
+
+fn default_opacity() -> f64 {
+    1.0
+}
+
+/// Image overlay.
+///
+/// Loading SVG files needs the `librsvg` loader of `gdk-pixbuf` to be installed.
+#[derive(Debug, Clone)]
+pub struct ImageOverlay {
+    element: gst::Element,
+    // opacity of the current image which is restored by `show()`
+    opacity: Arc<Mutex<f64>>,
+}
+
+impl ImageOverlay {
+    /// Create new image overlay.
+    ///
+    /// # Arguments
+    ///
+    /// - `name`: Element's name.
+    /// - `image`: Image to display or `None` to display nothing until `set()` is used.
+    ///
+    /// # Errors
+    ///
+    /// This can fail if the `gdkpixbufoverlay` cannot be created in `GStreamer`.
+    pub fn create(name: &str, image: Option<&Image>) -> Result<Self> {
+        trace!("new( {image:?} )");
+
+        // create image overlay
+        let element = gst::ElementFactory::make_with_name("gdkpixbufoverlay", Some(name))
+            .context("failed to create image overlay")?;
+
+        let overlay = Self {
+            element,
+            opacity: Arc::new(Mutex::new(0.0)),
+        };
+        overlay.set(image);
+
+        // return Overlay
+        Ok(overlay)
+    }
+
+    /// Change the image to display.
+    ///
+    /// # Arguments
+    ///
+    /// - `image`: new image or `None` to display nothing
+    ///
+    pub fn set(&self, image: Option<&Image>) {
+        trace!("set( {image:?} )");
+
+        let Some(image) = image else {
+            self.set_opacity(0.0);
+            return;
+        };
+
+        self.element.set_property("location", &image.location);
+        self.element.set_property("relative-x", image.x);
+        self.element.set_property("relative-y", image.y);
+        self.element
+            .set_property("overlay-width", image.width as i32);
+        self.element
+            .set_property("overlay-height", image.height as i32);
+        self.set_opacity(image.opacity.clamp(0.0, 1.0));
+    }
+
+    fn set_opacity(&self, opacity: f64) {
+        if let Ok(mut current) = self.opacity.lock() {
+            *current = opacity;
+        }
+        self.element.set_property("alpha", opacity);
+    }
+}
+
+impl Overlay for ImageOverlay {
+    fn element(&self) -> &gst::Element {
+        &self.element
+    }
+    fn show(&self, show: bool) {
+        let opacity = self.opacity.lock().map_or(0.0, |opacity| *opacity);
+        self.element
+            .set_property("alpha", if show { opacity } else { 0.0 });
+    }
+    #[must_use]
+    fn sink(&self) -> Option<gst::Pad> {
+        self.element().static_pad("sink")
+    }
+}
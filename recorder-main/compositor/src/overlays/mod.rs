@@ -3,12 +3,16 @@
 // SPDX-License-Identifier: EUPL-1.2
 
 //! Overlays module.
+mod chat_overlay;
 mod clock_overlay;
+mod image_overlay;
 mod padding_overlay;
 mod talk_overlay;
 mod text_overlay;
 
+pub use chat_overlay::*;
 pub use clock_overlay::*;
+pub use image_overlay::*;
 pub use padding_overlay::*;
 pub use talk_overlay::*;
 pub use text_overlay::*;
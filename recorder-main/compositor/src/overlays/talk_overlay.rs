@@ -6,12 +6,11 @@ use anyhow::{Context, Result};
 use gst_base::prelude::*;
 
 use crate::{
-    Align, ClockOverlay, Font, HAlign, Overlay, Padding, PaddingOverlay, TextOverlay, TextPadding,
-    TextStyle, VAlign,
+    ClockOverlay, ImageOverlay, Overlay, Padding, PaddingOverlay, TextOverlay, TextPadding,
+    TextStyle, Theme,
 };
 
 const TOP_PADDING: i32 = 56;
-const OVERLAY_FONT_SIZE: u32 = 20;
 
 /// Parameters of `TalkOverlay`
 #[allow(dead_code)]
@@ -31,6 +30,7 @@ pub struct TalkOverlay {
     _padding_overlay: PaddingOverlay,
     text_overlay: TextOverlay,
     clock_overlay: ClockOverlay,
+    logo_overlay: ImageOverlay,
     bin: gst::Bin,
 }
 
@@ -42,6 +42,7 @@ impl Overlay for TalkOverlay {
     fn show(&self, show: bool) {
         self.text_overlay.show(show);
         self.clock_overlay.show(show);
+        self.logo_overlay.show(show);
     }
     #[must_use]
     fn sink(&self) -> Option<gst::Pad> {
@@ -49,7 +50,7 @@ impl Overlay for TalkOverlay {
     }
     #[must_use]
     fn src(&self) -> Option<gst::Pad> {
-        self.clock_overlay.src()
+        self.logo_overlay.src()
     }
 }
 
@@ -62,6 +63,7 @@ impl TalkOverlay {
     /// - The `PaddingOverlay` cannot be created.
     /// - The `TextOverlay` cannot be created.
     /// - The `ClockOverlay` cannot be created.
+    /// - The `ImageOverlay` of the logo cannot be created.
     /// - Adding the elements to Gstreamer or linking them.
     pub fn create() -> Result<Self> {
         let bin = gst::Bin::new(Some("Talk Overlay"));
@@ -73,50 +75,31 @@ impl TalkOverlay {
             },
         )
         .context("unable to create PaddingOverlay")?;
-        let text_overlay = TextOverlay::create(
-            "Title Overlay",
-            "",
-            TextStyle {
-                align: Align {
-                    horizontal: HAlign::Left,
-                    vertical: VAlign::Top,
-                },
-                font: Font {
-                    size: OVERLAY_FONT_SIZE,
-                    ..Default::default()
-                },
-                ..Default::default()
-            },
-        )?;
-        let clock_overlay = ClockOverlay::create(
-            "Real Time Clock Overlay",
-            "%x %X %Z",
-            TextStyle {
-                align: Align {
-                    horizontal: HAlign::Right,
-                    vertical: VAlign::Top,
-                },
-                font: Font {
-                    size: OVERLAY_FONT_SIZE,
-                    ..Default::default()
-                },
-                ..Default::default()
-            },
-        )?;
+        let theme = Theme::default();
+        let text_overlay = TextOverlay::create("Title Overlay", "", theme.title)?;
+        let clock_overlay =
+            ClockOverlay::create("Real Time Clock Overlay", "%x %X %Z", theme.clock)?;
+        let logo_overlay = ImageOverlay::create("Logo Overlay", theme.logo.as_ref())?;
 
         bin.add_many(&[
             padding_overlay.element(),
             text_overlay.element(),
             clock_overlay.element(),
+            logo_overlay.element(),
         ])
-        .context("unable to add padding_overlay, text_overlay and clock_overlay to the bin")?;
+        .context(
+            "unable to add padding_overlay, text_overlay, clock_overlay and logo_overlay to the bin",
+        )?;
 
         gst::Element::link_many(&[
             padding_overlay.element(),
             text_overlay.element(),
             clock_overlay.element(),
+            logo_overlay.element(),
         ])
-        .context("unable to link padding_overlay, text_overlay and clock_overlay together")?;
+        .context(
+            "unable to link padding_overlay, text_overlay, clock_overlay and logo_overlay together",
+        )?;
 
         let padding_overlay_sink = padding_overlay
             .sink()
@@ -125,10 +108,10 @@ impl TalkOverlay {
             .context("failed to create video ghost pad for participant overlay sink")?;
         bin.add_pad(&video_sink)
             .context("failed to add video ghost pad to participant overlay sink bin")?;
-        let clock_overlay_src = &clock_overlay
+        let logo_overlay_src = &logo_overlay
             .src()
-            .context("unable to get src for clock_overlay")?;
-        let video_src = gst::GhostPad::with_target(Some("src"), clock_overlay_src)
+            .context("unable to get src for logo_overlay")?;
+        let video_src = gst::GhostPad::with_target(Some("src"), logo_overlay_src)
             .context("failed to create video ghost pad for participant overlay sink")?;
         bin.add_pad(&video_src)
             .context("failed to add video ghost pad to participant overlay sink bin")?;
@@ -137,9 +120,33 @@ impl TalkOverlay {
             _padding_overlay: padding_overlay,
             text_overlay,
             clock_overlay,
+            logo_overlay,
             bin,
         })
     }
+
+    /// Create an overlay which shows the same title and clock as this one, styled by the given
+    /// theme.
+    ///
+    /// # Errors
+    ///
+    /// This can fail if the new overlay cannot be created.
+    pub fn duplicate(&self, theme: &Theme) -> Result<Self> {
+        let overlay = Self::create()?;
+        overlay.set_theme(theme);
+        overlay.set_title(&self.text_overlay.element().property::<String>("text"));
+        for (from, to) in [
+            (self.text_overlay.element(), overlay.text_overlay.element()),
+            (
+                self.clock_overlay.element(),
+                overlay.clock_overlay.element(),
+            ),
+        ] {
+            to.set_property("silent", from.property::<bool>("silent"));
+        }
+        Ok(overlay)
+    }
+
     pub fn set_title(&self, title: &str) {
         self.text_overlay.set(title);
     }
@@ -149,4 +156,10 @@ impl TalkOverlay {
     pub fn show_clock(&self, visible: bool) {
         self.clock_overlay.show(visible);
     }
+    /// Apply the title and clock styles and the logo of the given theme.
+    pub fn set_theme(&self, theme: &Theme) {
+        self.text_overlay.set_style(&theme.title);
+        self.clock_overlay.set_style(&theme.clock);
+        self.logo_overlay.set(theme.logo.as_ref());
+    }
 }
@@ -85,6 +85,7 @@ impl DashSink {
             &MatroskaParameters {
                 // use fixed localhost but with given port
                 address: SocketAddr::from(([127, 0, 0, 1], 0)),
+                ..Default::default()
             },
         )
         .context("unable to create MatroskaSink")?;
@@ -202,47 +203,7 @@ impl Sink for DashSink {
             .canonicalize()
             .context("invalid DASH target path")?;
 
-        // spawn a thread which checks for file updates
-        std::thread::spawn({
-            // initialize inotify
-            let mut inotify = Inotify::init().context("failed to initialize Inotify")?;
-            debug!("Writing DASH files into {}", output_dir.to_string_lossy());
-
-            // add watch to that folder
-            inotify
-                .watches()
-                .add(output_dir, WatchMask::MOVED_TO | WatchMask::CLOSE)
-                .context("Failed to add file watch")?;
-            // get a copy of the callback
-            let update = self.params.update_callback;
-            move || {
-                let mut buffer = [0; 1024];
-
-                loop {
-                    let events = loop {
-                        match inotify.read_events(&mut buffer) {
-                            Ok(events) => break events,
-                            Err(error) if error.kind() == std::io::ErrorKind::WouldBlock => {
-                                continue
-                            }
-                            _ => panic!("Error while reading events"),
-                        }
-                    };
-
-                    let files: Vec<&OsStr> = events
-                        .filter_map(|event| event.name)
-                        .filter(|name| {
-                            Path::new(name)
-                                .extension()
-                                .map_or(false, |ext| ext.eq_ignore_ascii_case("tmp"))
-                        })
-                        .collect();
-                    if !files.is_empty() {
-                        update(&files);
-                    }
-                }
-            }
-        });
+        spawn_file_watcher(&output_dir, self.params.update_callback)?;
 
         Ok(())
     }
@@ -262,3 +223,44 @@ impl Sink for DashSink {
         Ok(())
     }
 }
+
+/// Spawn a thread which calls `update` whenever files within `output_dir` have been written.
+pub(crate) fn spawn_file_watcher(output_dir: &Path, update: fn(files: &[&OsStr])) -> Result<()> {
+    // initialize inotify
+    let mut inotify = Inotify::init().context("failed to initialize Inotify")?;
+    debug!("Writing files into {}", output_dir.to_string_lossy());
+
+    // add watch to that folder
+    inotify
+        .watches()
+        .add(output_dir, WatchMask::MOVED_TO | WatchMask::CLOSE)
+        .context("Failed to add file watch")?;
+
+    std::thread::spawn(move || {
+        let mut buffer = [0; 1024];
+
+        loop {
+            let events = loop {
+                match inotify.read_events(&mut buffer) {
+                    Ok(events) => break events,
+                    Err(error) if error.kind() == std::io::ErrorKind::WouldBlock => continue,
+                    _ => panic!("Error while reading events"),
+                }
+            };
+
+            let files: Vec<&OsStr> = events
+                .filter_map(|event| event.name)
+                .filter(|name| {
+                    Path::new(name)
+                        .extension()
+                        .map_or(false, |ext| ext.eq_ignore_ascii_case("tmp"))
+                })
+                .collect();
+            if !files.is_empty() {
+                update(&files);
+            }
+        }
+    });
+
+    Ok(())
+}
@@ -0,0 +1,368 @@
+// SPDX-FileCopyrightText: OpenTalk GmbH <mail@opentalk.eu>
+//
+// SPDX-License-Identifier: EUPL-1.2
+
+use anyhow::{bail, Context, Result};
+use derivative::Derivative;
+use gst::prelude::*;
+use serde::Deserialize;
+use std::{
+    ffi::OsStr,
+    net::SocketAddr,
+    path::{Path, PathBuf},
+};
+use tempfile::TempDir;
+
+use super::dash::spawn_file_watcher;
+use crate::{MatroskaParameters, MatroskaSink, Sink};
+
+/// Name of the playlist which players open.
+///
+/// With a rendition ladder this is the master playlist which references one playlist per rendition.
+const PLAYLIST_NAME: &str = "playlist.m3u8";
+
+/// Writes out *HLS* A/V files.
+#[derive(Debug)]
+pub struct HlsSink {
+    /// Underlying Matroska sink.
+    matroska_sink: MatroskaSink,
+    /// remember parameters for delayed usage
+    params: HlsParameters,
+    /// FFmpeg process
+    process: Option<std::process::Child>,
+    /// Temporary directory to write HLS files into.
+    /// Is set if no output directory is specified
+    temp_dir: Option<TempDir>,
+}
+
+/// HLS segment type
+#[derive(Clone, Copy, Debug, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HlsSegmentType {
+    /// Use MPEG transport stream segments.
+    #[default]
+    Ts,
+    /// Use CMAF segments (fragmented MP4).
+    Cmaf,
+}
+
+impl HlsSegmentType {
+    /// Get segment type as `FFmpeg` names it.
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Ts => "mpegts",
+            Self::Cmaf => "fmp4",
+        }
+    }
+
+    /// Get file extension of the segments.
+    fn extension(self) -> &'static str {
+        match self {
+            Self::Ts => "ts",
+            Self::Cmaf => "m4s",
+        }
+    }
+}
+
+/// One quality level of a rendition ladder.
+#[derive(Clone, Debug, Deserialize)]
+pub struct HlsRendition {
+    /// Video width in pixels.
+    pub width: usize,
+    /// Video height in pixels.
+    pub height: usize,
+    /// Video bitrate in bits per second.
+    pub video_bitrate: usize,
+    /// Audio bitrate in bits per second.
+    #[serde(default = "default_audio_bitrate")]
+    pub audio_bitrate: usize,
+}
+
+fn default_audio_bitrate() -> usize {
+    128_000
+}
+
+/// Specific parameters needed to create.
+#[derive(Derivative, Deserialize)]
+#[derivative(Debug, Clone)]
+#[serde(default)]
+pub struct HlsParameters {
+    /// Path to write the HLS files to.
+    /// Existing files will be overridden.
+    /// If None a temporary directory will be used.
+    pub output_dir: Option<PathBuf>,
+    /// Bitrate to aim in output if no renditions are given.
+    pub bitrate: usize,
+    /// Segment duration in seconds
+    pub segment_duration: f32,
+    /// Maximum number of segments within a playlist, `0` keeps all segments.
+    pub playlist_length: usize,
+    /// HLS segment type
+    pub segment_type: HlsSegmentType,
+    /// Rendition ladder, a single rendition with the output resolution is written if empty.
+    pub renditions: Vec<HlsRendition>,
+    /// Called when new files are ready
+    #[derivative(Debug = "ignore")]
+    #[serde(skip)]
+    pub update_callback: fn(files: &[&OsStr]),
+}
+
+impl HlsSink {
+    /// Create and add new HLS sink into existing pipeline.
+    ///
+    /// # Errors
+    ///
+    /// This can fail if the `MatroskaSink` cannot be created.
+    pub fn create(name: &str, params: HlsParameters) -> Result<Self> {
+        let matroska_sink = MatroskaSink::create(
+            name,
+            &MatroskaParameters {
+                // use fixed localhost but with given port
+                address: SocketAddr::from(([127, 0, 0, 1], 0)),
+                ..Default::default()
+            },
+        )
+        .context("unable to create MatroskaSink")?;
+
+        Ok(Self {
+            matroska_sink,
+            params,
+            process: None,
+            temp_dir: None,
+        })
+    }
+
+    /// Build the `FFmpeg` arguments which encode the input into the HLS files within `output_dir`.
+    fn ffmpeg_args(&self, output_dir: &Path) -> Result<Vec<String>> {
+        let path = |name: String| -> Result<String> {
+            output_dir
+                .join(name)
+                .to_str()
+                .map(str::to_string)
+                .context("failed to convert HLS path into printable string")
+        };
+        let params = &self.params;
+        let extension = params.segment_type.extension();
+
+        let mut args: Vec<String> = [
+            "-v",
+            "warning",
+            "-y",
+            "-nostdin",
+            "-i",
+            // read from localhost and given port
+            &format!("tcp://{}", self.matroska_sink.address),
+        ]
+        .into_iter()
+        .map(str::to_string)
+        .collect();
+
+        // encode video and audio for every rendition
+        if params.renditions.is_empty() {
+            args.extend(
+                [
+                    "-map",
+                    "0:v",
+                    "-map",
+                    "0:a",
+                    "-c:v",
+                    "libx264",
+                    "-b:v",
+                    &params.bitrate.to_string(),
+                    "-c:a",
+                    "aac",
+                ]
+                .map(str::to_string),
+            );
+        } else {
+            let count = params.renditions.len();
+            let split: String = (0..count).map(|n| format!("[v{n}]")).collect();
+            let scales: String = params
+                .renditions
+                .iter()
+                .enumerate()
+                .map(|(n, rendition)| {
+                    format!(
+                        ";[v{n}]scale={width}:{height}[v{n}out]",
+                        width = rendition.width,
+                        height = rendition.height
+                    )
+                })
+                .collect();
+            args.push("-filter_complex".to_string());
+            args.push(format!("[0:v]split={count}{split}{scales}"));
+
+            for (n, rendition) in params.renditions.iter().enumerate() {
+                args.extend([
+                    "-map".to_string(),
+                    format!("[v{n}out]"),
+                    format!("-c:v:{n}"),
+                    "libx264".to_string(),
+                    format!("-b:v:{n}"),
+                    rendition.video_bitrate.to_string(),
+                    "-map".to_string(),
+                    "0:a".to_string(),
+                    format!("-c:a:{n}"),
+                    "aac".to_string(),
+                    format!("-b:a:{n}"),
+                    rendition.audio_bitrate.to_string(),
+                ]);
+            }
+
+            let stream_map: Vec<String> = (0..count).map(|n| format!("v:{n},a:{n}")).collect();
+            args.extend([
+                "-var_stream_map".to_string(),
+                stream_map.join(" "),
+                "-master_pl_name".to_string(),
+                PLAYLIST_NAME.to_string(),
+            ]);
+        }
+
+        // start every segment with a key frame
+        args.extend([
+            "-force_key_frames".to_string(),
+            format!("expr:gte(t,n_forced*{})", params.segment_duration),
+            "-f".to_string(),
+            "hls".to_string(),
+            "-hls_time".to_string(),
+            params.segment_duration.to_string(),
+            "-hls_list_size".to_string(),
+            params.playlist_length.to_string(),
+            "-hls_segment_type".to_string(),
+            params.segment_type.as_str().to_string(),
+            "-hls_flags".to_string(),
+            if params.playlist_length == 0 {
+                "independent_segments+temp_file"
+            } else {
+                "independent_segments+temp_file+delete_segments"
+            }
+            .to_string(),
+        ]);
+
+        if params.renditions.is_empty() {
+            if matches!(params.segment_type, HlsSegmentType::Cmaf) {
+                args.extend([
+                    "-hls_fmp4_init_filename".to_string(),
+                    "init.mp4".to_string(),
+                ]);
+            }
+            args.extend([
+                "-hls_segment_filename".to_string(),
+                path(format!("segment_%05d.{extension}"))?,
+                path(PLAYLIST_NAME.to_string())?,
+            ]);
+        } else {
+            if matches!(params.segment_type, HlsSegmentType::Cmaf) {
+                args.extend([
+                    "-hls_fmp4_init_filename".to_string(),
+                    "init_%v.mp4".to_string(),
+                ]);
+            }
+            args.extend([
+                "-hls_segment_filename".to_string(),
+                path(format!("stream_%v_%05d.{extension}"))?,
+                path("stream_%v.m3u8".to_string())?,
+            ]);
+        }
+
+        Ok(args)
+    }
+}
+
+fn update(files: &[&OsStr]) {
+    debug!("Updated files: {:?}", files);
+}
+
+impl Default for HlsParameters {
+    /// File parameters default.
+    fn default() -> Self {
+        Self {
+            output_dir: None,
+            bitrate: 0x0010_0000,
+            segment_duration: 4.0,
+            playlist_length: 0,
+            segment_type: HlsSegmentType::Ts,
+            renditions: Vec::new(),
+            update_callback: update,
+        }
+    }
+}
+
+impl Sink for HlsSink {
+    /// Get video sink pad from Matroska sink.
+    #[must_use]
+    fn video(&self) -> Option<gst::GhostPad> {
+        self.matroska_sink.video()
+    }
+
+    /// Get audio sink pad from Matroska sink.
+    #[must_use]
+    fn audio(&self) -> gst::GhostPad {
+        self.matroska_sink.audio()
+    }
+
+    #[must_use]
+    fn bin(&self) -> gst::Bin {
+        self.matroska_sink.bin()
+    }
+
+    /// Starts the `FFmpeg` receiver which catches the output of the matroska sink.
+    fn on_play(&mut self) -> Result<()> {
+        trace!("on_play()");
+
+        // check if FFmpeg process is still running
+        if let Some(process) = &mut self.process {
+            let result = process
+                .try_wait()
+                .context("failed to get FFmpeg process status")?;
+
+            if let Some(code) = result {
+                bail!("ffmpeg process died with code {}", code);
+            }
+
+            return Ok(());
+        }
+
+        let output_dir = if let Some(path) = &self.params.output_dir {
+            std::fs::create_dir_all(path).context("unable to create HLS target path")?;
+            path.clone()
+        } else {
+            let temp_dir = tempfile::tempdir().context("failed to find tmpdir")?;
+            self.temp_dir.insert(temp_dir).path().to_path_buf()
+        };
+
+        debug!("Setting up HLS target path: {output_dir:?}");
+
+        // check if the output directory exists
+        let output_dir = output_dir
+            .canonicalize()
+            .context("invalid HLS target path")?;
+
+        // start ffmpeg to fetch output stream and create HLS files
+        self.process = Some(
+            std::process::Command::new("ffmpeg")
+                .args(self.ffmpeg_args(&output_dir)?)
+                .spawn()
+                .context("failed to spawn FFmpeg process")?,
+        );
+
+        spawn_file_watcher(&output_dir, self.params.update_callback)?;
+
+        Ok(())
+    }
+
+    /// Sends EOS into pipeline to flush output before
+    fn on_exit(&mut self, pipeline: &gst::Pipeline) -> Result<()> {
+        trace!("on_exit()");
+
+        // send EOS into pipeline to flush output
+        pipeline.send_event(gst::event::Eos::new());
+
+        while pipeline.current_state() == gst::State::Null {}
+
+        // Drop temp_dir to delete directory
+        self.temp_dir.take();
+
+        Ok(())
+    }
+}
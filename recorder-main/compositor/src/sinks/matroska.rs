@@ -11,7 +11,7 @@ use std::{
     sync::mpsc,
 };
 
-use crate::{add_ghost_pad, Sink};
+use crate::{add_ghost_pad, OutputProfile, Sink};
 
 /// Writes out *Matroska* mux-ed raw A/V on a TCP port
 #[derive(Debug)]
@@ -21,6 +21,8 @@ pub struct MatroskaSink {
     pub address: SocketAddr,
     video_sink: gst::GhostPad,
     audio_sink: gst::GhostPad,
+    profile: OutputProfile,
+    track_sinks: Vec<gst::GhostPad>,
 }
 
 /// Specific parameters needed to create a Matroska sink
@@ -28,8 +30,17 @@ pub struct MatroskaSink {
 pub struct MatroskaParameters {
     /// address to send output to
     pub address: SocketAddr,
+    /// Output profile, bitrate and speed preset are left to the receiver
+    #[serde(default)]
+    pub profile: OutputProfile,
+    /// Number of audio tracks besides the mixed audio which record single participants
+    #[serde(default)]
+    pub tracks: usize,
 }
 
+/// Framerate of the Matroska output if the profile does not set one
+const DEFAULT_FRAMERATE: i32 = 25;
+
 impl MatroskaSink {
     /// Create and add new Matroska sink into existing pipeline.
     ///
@@ -44,14 +55,40 @@ impl MatroskaSink {
     /// - Cannot create `matroskamux` in `GStreamer`.
     /// - Cannot create `queue` in `GStreamer`.
     /// - Cannot create `multifdsink` in `GStreamer`.
+    /// - Cannot create `audioconvert` for a participant track in `GStreamer`.
     /// - The local address in `params.address` cannot be listened.
     /// - `GhostPad` cannot be created for `video_sink` or `audio_sink`.
     pub fn create(name: &str, params: &MatroskaParameters) -> Result<Self> {
         trace!("new({name})");
 
+        let mut video_caps = format!(
+            "video/x-raw,format=I420,framerate={}/1,pixel-aspect-ratio=1/1,colorimetry=bt709",
+            params.profile.framerate.unwrap_or(DEFAULT_FRAMERATE)
+        );
+        if let Some(width) = params.profile.width {
+            video_caps.push_str(&format!(",width={width}"));
+        }
+        if let Some(height) = params.profile.height {
+            video_caps.push_str(&format!(",height={height}"));
+        }
+
+        // one audio track per participant, all tracks have to exist before the header is written
+        let tracks: String = (0..params.tracks)
+            .map(|index| {
+                format!(
+                    r#"
+                audioconvert
+                    name=track-{index}
+                ! audio/x-raw,format=S16LE,layout=interleaved,rate=48000
+                ! mux.
+                "#
+                )
+            })
+            .collect();
+
         // create bin including codecs and the Matroska sink
         let bin = gst::parse_bin_from_description(
-            &format!(
+            &(format!(
                 r#"
                 name="{name}"
 
@@ -59,7 +96,7 @@ impl MatroskaSink {
                     name=video
                 ! videorate
                 ! videoscale
-                ! video/x-raw,format=I420,framerate=25/1,pixel-aspect-ratio=1/1,colorimetry=bt709
+                ! {video_caps}
                 ! mux.
 
                 audioconvert
@@ -81,7 +118,7 @@ impl MatroskaSink {
                     sync-method=next-keyframe
                 "#,
                 buffers_max = 500
-            ),
+            ) + &tracks),
             false,
         )
         .context("failed to create matroska sink pipeline")?;
@@ -120,6 +157,12 @@ impl MatroskaSink {
             .context("unable to add GhostPad for video sink")?;
         let audio_sink = add_ghost_pad(&bin, "audio", "sink")
             .context("unable to add GhostPad for audio sink")?;
+        let track_sinks = (0..params.tracks)
+            .map(|index| {
+                add_ghost_pad(&bin, &format!("track-{index}"), "sink")
+                    .with_context(|| format!("unable to add GhostPad for track {index}"))
+            })
+            .collect::<Result<Vec<_>>>()?;
 
         // return new Matroska sink
         Ok(Self {
@@ -128,6 +171,8 @@ impl MatroskaSink {
             address,
             video_sink,
             audio_sink,
+            profile: params.profile.clone(),
+            track_sinks,
         })
     }
 }
@@ -137,6 +182,8 @@ impl Default for MatroskaParameters {
     fn default() -> Self {
         Self {
             address: SocketAddr::from(([127, 0, 0, 1], 0)),
+            profile: OutputProfile::default(),
+            tracks: 0,
         }
     }
 }
@@ -159,6 +206,15 @@ impl Sink for MatroskaSink {
         self.bin.clone()
     }
 
+    fn profile(&self) -> Option<&OutputProfile> {
+        Some(&self.profile)
+    }
+
+    /// Get sink pads of the participant tracks.
+    fn tracks(&self) -> Vec<gst::GhostPad> {
+        self.track_sinks.clone()
+    }
+
     fn on_exit(&mut self, _pipeline: &gst::Pipeline) -> Result<()> {
         trace!("on_exit()");
 
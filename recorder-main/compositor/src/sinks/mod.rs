@@ -5,17 +5,23 @@
 mod blinder;
 mod dash;
 mod fake;
+mod hls;
 mod matroska;
 mod mp4;
 mod rtmp;
+mod srt;
 mod system;
 mod test;
+mod whip;
 
 pub use blinder::*;
 pub use dash::*;
 pub use fake::*;
+pub use hls::*;
 pub use matroska::*;
 pub use mp4::*;
 pub use rtmp::*;
+pub use srt::*;
 pub use system::*;
 pub use test::*;
+pub use whip::*;
@@ -4,7 +4,7 @@
 
 use anyhow::{bail, Context, Result};
 
-use crate::{MatroskaParameters, MatroskaSink, Sink};
+use crate::{MatroskaParameters, MatroskaSink, OutputProfile, Sink};
 
 /// Writes out a single MP4 file using `FFmpeg`
 #[derive(Debug)]
@@ -24,6 +24,10 @@ pub struct Mp4Parameters {
     pub name: &'static str,
     /// Output file path
     pub file_path: std::path::PathBuf,
+    /// Output profile
+    pub profile: OutputProfile,
+    /// Number of audio tracks besides the mixed audio which record single participants
+    pub tracks: usize,
 }
 
 impl Mp4Sink {
@@ -36,8 +40,15 @@ impl Mp4Sink {
     /// - `ffmpeg` is missing
     /// - `params.file_path` cannot converted to UTF-8
     pub fn create(name: &str, params: &Mp4Parameters) -> Result<Self> {
-        let matroska_sink =
-            MatroskaSink::create(name, &MatroskaParameters::default()).context("")?;
+        let matroska_sink = MatroskaSink::create(
+            name,
+            &MatroskaParameters {
+                profile: params.profile.clone(),
+                tracks: params.tracks,
+                ..Default::default()
+            },
+        )
+        .context("unable to create MatroskaSink")?;
         let address = &format!("tcp://{}", matroska_sink.address);
 
         // TODO: use free codecs instead of ffmpeg's mp4 default.
@@ -60,13 +71,31 @@ impl Mp4Sink {
                 )
             })?
             .to_string();
+        let mut args: Vec<String> = [
+            "-v", "warning", "-y", "-nostdin", "-i",
+            // read from localhost and given port
+            address,
+        ]
+        .map(str::to_string)
+        .to_vec();
+        // keep the participant tracks besides the mixed audio
+        if params.tracks > 0 {
+            args.extend(["-map", "0:v?", "-map", "0:a"].map(str::to_string));
+        }
+        // encoder settings of the profile, FFmpeg's defaults otherwise
+        if params.profile.video_bitrate.is_some() || params.profile.video_speed_preset.is_some() {
+            args.extend(["-c:v".to_string(), "libx264".to_string()]);
+        }
+        if let Some(bitrate) = params.profile.video_bitrate {
+            args.extend(["-b:v".to_string(), format!("{bitrate}k")]);
+        }
+        if let Some(preset) = params.profile.video_speed_preset {
+            args.extend(["-preset".to_string(), preset.as_str().to_string()]);
+        }
+        args.extend(["-f".to_string(), "mp4".to_string(), filename.clone()]);
         let process = Some(
             std::process::Command::new("ffmpeg")
-                .args([
-                    "-v", "warning", "-y", "-nostdin", "-i",
-                    // read from localhost and given port
-                    address, "-f", "mp4", &filename,
-                ])
+                .args(args)
                 .spawn()
                 .context("failed to spawn FFmpeg process")?,
         );
@@ -98,6 +127,15 @@ impl Sink for Mp4Sink {
         self.matroska_sink.bin()
     }
 
+    fn profile(&self) -> Option<&OutputProfile> {
+        self.matroska_sink.profile()
+    }
+
+    /// Get participant track pads from Matroska sink.
+    fn tracks(&self) -> Vec<gst::GhostPad> {
+        self.matroska_sink.tracks()
+    }
+
     /// Starts the `FFmpeg` receiver which catches the output of the matroska sink.
     fn on_play(&mut self) -> Result<()> {
         trace!("on_play()");
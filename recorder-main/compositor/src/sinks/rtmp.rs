@@ -5,7 +5,7 @@
 use anyhow::{Context, Result};
 use serde::Deserialize;
 
-use crate::{add_ghost_pad, Sink};
+use crate::{add_ghost_pad, mixer::VIDEO_FRAMERATE, OutputProfile, Sink, VideoEncoding};
 
 const DEFAULT_AUDIO_RATE: usize = 48000;
 const DEFAULT_AUDIO_BITRATE: usize = 96000;
@@ -17,6 +17,8 @@ pub struct RTMPSink {
     bin: gst::Bin,
     video_sink_pad: gst::GhostPad,
     audio_sink_pad: gst::GhostPad,
+    profile: OutputProfile,
+    encoding: VideoEncoding,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -26,9 +28,14 @@ pub struct RTMPParameters {
     pub audio_rate: Option<usize>,
     pub video_bitrate: Option<usize>,
     pub video_speed_preset: Option<SpeedPreset>,
+    /// Maximum distance between two keyframes in seconds, the encoder's default if `None`.
+    pub keyframe_interval: Option<u32>,
+    /// Output profile, its bitrate and speed preset take precedence.
+    #[serde(default)]
+    pub profile: OutputProfile,
 }
 
-#[derive(Debug, Clone, Default, Deserialize)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Deserialize)]
 pub enum SpeedPreset {
     Ultrafast = 1,
     Superfast = 2,
@@ -44,14 +51,35 @@ pub enum SpeedPreset {
     None = 0,
 }
 
+impl SpeedPreset {
+    /// Get preset as `x264` names it.
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Ultrafast => "ultrafast",
+            Self::Superfast => "superfast",
+            Self::Veryfast => "veryfast",
+            Self::Faster => "faster",
+            Self::Fast => "fast",
+            Self::Medium => "medium",
+            Self::Slow => "slow",
+            Self::Slower => "slower",
+            Self::Veryslow => "veryslow",
+            Self::Placebo => "placebo",
+            Self::None => "none",
+        }
+    }
+}
+
 impl RTMPSink {
     /// Create and add new rtmp sink into existing pipeline.
     ///
+    /// The video gets encoded by the mixer, sinks with the same profile and encoder settings
+    /// share one encoder.
+    ///
     /// # Errors
     ///
     /// This can fail for the following reasons:
-    /// - Unable to create `videoconvert` for `GStreamer`.
-    /// - Unable to create `x264enc` for `GStreamer`.
     /// - Unable to create `h264parse` for `GStreamer`.
     /// - Unable to create `mux` for `GStreamer`.
     /// - Unable to create `audioconvert` for `GStreamer`.
@@ -69,11 +97,8 @@ impl RTMPSink {
                 r#"
             name="{name}"
                 
-            videoconvert
+            h264parse
                 name=video
-            ! x264enc speed-preset={video_speed_preset} tune=zerolatency bitrate={video_bitrate}
-            ! video/x-h264,profile=high
-            ! h264parse
             ! mux.
 
             audioconvert
@@ -95,8 +120,6 @@ impl RTMPSink {
                 location = parameters.location,
                 audio_bitrate = parameters.audio_bitrate.unwrap_or(DEFAULT_AUDIO_BITRATE),
                 audio_rate = parameters.audio_rate.unwrap_or(DEFAULT_AUDIO_RATE),
-                video_bitrate = parameters.video_bitrate.unwrap_or(DEFAULT_VIDEO_BITRATE),
-                video_speed_preset = parameters.video_speed_preset.unwrap_or_default() as usize,
             )
             .as_str(),
             false,
@@ -108,10 +131,29 @@ impl RTMPSink {
         let audio_sink_pad = add_ghost_pad(&bin, "audio", "sink")
             .context("unable to add GhostPad for audio sink")?;
 
+        let encoding = VideoEncoding {
+            bitrate: parameters
+                .profile
+                .video_bitrate
+                .or(parameters.video_bitrate)
+                .unwrap_or(DEFAULT_VIDEO_BITRATE),
+            speed_preset: parameters
+                .profile
+                .video_speed_preset
+                .or(parameters.video_speed_preset)
+                .unwrap_or_default(),
+            // zero lets the encoder choose
+            key_int_max: parameters.keyframe_interval.map_or(0, |seconds| {
+                seconds * parameters.profile.framerate.unwrap_or(VIDEO_FRAMERATE) as u32
+            }),
+        };
+
         Ok(Self {
             bin,
             video_sink_pad,
             audio_sink_pad,
+            profile: parameters.profile,
+            encoding,
         })
     }
 }
@@ -131,4 +173,12 @@ impl Sink for RTMPSink {
     fn bin(&self) -> gst::Bin {
         self.bin.clone()
     }
+
+    fn profile(&self) -> Option<&OutputProfile> {
+        Some(&self.profile)
+    }
+
+    fn video_encoding(&self) -> Option<VideoEncoding> {
+        Some(self.encoding)
+    }
 }
@@ -0,0 +1,214 @@
+// SPDX-FileCopyrightText: OpenTalk GmbH <mail@opentalk.eu>
+//
+// SPDX-License-Identifier: EUPL-1.2
+
+use anyhow::{bail, Context, Result};
+use gst::prelude::*;
+use serde::Deserialize;
+
+use crate::{add_ghost_pad, Sink, SpeedPreset};
+
+const DEFAULT_AUDIO_RATE: usize = 48000;
+const DEFAULT_AUDIO_BITRATE: usize = 96000;
+const DEFAULT_VIDEO_BITRATE: usize = 6000;
+const DEFAULT_LATENCY: u32 = 125;
+
+/// Valid lengths of an SRT passphrase
+const PASSPHRASE_LENGTH: std::ops::RangeInclusive<usize> = 10..=79;
+
+/// SRT compositor output to stream MPEG-TS over SRT.
+#[derive(Debug)]
+pub struct SrtSink {
+    bin: gst::Bin,
+    video_sink_pad: gst::GhostPad,
+    audio_sink_pad: gst::GhostPad,
+}
+
+/// Connection mode of an `SrtSink`
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SrtMode {
+    /// Connect to a remote listener.
+    #[default]
+    Caller,
+    /// Wait for a remote caller to connect.
+    Listener,
+}
+
+impl SrtMode {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Caller => "caller",
+            Self::Listener => "listener",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SrtParameters {
+    /// Connection mode.
+    #[serde(default)]
+    pub mode: SrtMode,
+    /// Remote host in caller mode or local interface in listener mode (all interfaces if `None`).
+    pub host: Option<String>,
+    /// Remote port in caller mode or local port in listener mode.
+    pub port: u16,
+    /// Stream ID which is sent to the listener in caller mode.
+    pub stream_id: Option<String>,
+    /// Latency in milliseconds.
+    pub latency: Option<u32>,
+    /// Passphrase to encrypt the stream with (10 to 79 characters).
+    pub passphrase: Option<String>,
+    pub audio_bitrate: Option<usize>,
+    pub audio_rate: Option<usize>,
+    pub video_bitrate: Option<usize>,
+    pub video_speed_preset: Option<SpeedPreset>,
+}
+
+impl SrtParameters {
+    /// Build the URI of the `srtsink`.
+    fn uri(&self) -> Result<String> {
+        let host = match (self.mode, &self.host) {
+            (_, Some(host)) => host.as_str(),
+            (SrtMode::Listener, None) => "",
+            (SrtMode::Caller, None) => bail!("SRT caller needs a host to connect to"),
+        };
+        let mut uri = format!(
+            "srt://{host}:{port}?mode={mode}",
+            port = self.port,
+            mode = self.mode.as_str()
+        );
+        if let Some(stream_id) = &self.stream_id {
+            uri.push_str("&streamid=");
+            uri.push_str(&percent_encode(stream_id));
+        }
+        Ok(uri)
+    }
+}
+
+/// Encode all characters of `value` which are not unreserved within URIs.
+fn percent_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| {
+            if byte.is_ascii_alphanumeric() || b"-._~".contains(&byte) {
+                char::from(byte).to_string()
+            } else {
+                format!("%{byte:02X}")
+            }
+        })
+        .collect()
+}
+
+impl SrtSink {
+    /// Create and add new SRT sink into existing pipeline.
+    ///
+    /// The sink does not wait for the connection to be established, so output which is produced
+    /// while no peer is connected gets dropped instead of blocking the other sinks.
+    ///
+    /// # Errors
+    ///
+    /// This can fail for the following reasons:
+    /// - The passphrase has an invalid length.
+    /// - There is no host given in caller mode.
+    /// - Unable to create `videoconvert` for `GStreamer`.
+    /// - Unable to create `x264enc` for `GStreamer`.
+    /// - Unable to create `h264parse` for `GStreamer`.
+    /// - Unable to create `audioconvert` for `GStreamer`.
+    /// - Unable to create `audioresample` for `GStreamer`.
+    /// - Unable to create `fdkaacenc` for `GStreamer`.
+    /// - Unable to create `aacparse` for `GStreamer`.
+    /// - Unable to create `mpegtsmux` for `GStreamer`.
+    /// - Unable to create `srtsink` for `GStreamer`.
+    /// - `GhostPad` cannot be created for the `video_sink_pad` or `audio_sink_pad`.
+    pub fn create(name: &str, parameters: SrtParameters) -> Result<SrtSink> {
+        trace!("new({name})");
+
+        if let Some(passphrase) = &parameters.passphrase {
+            if !PASSPHRASE_LENGTH.contains(&passphrase.len()) {
+                bail!(
+                    "SRT passphrase must have {} to {} characters",
+                    PASSPHRASE_LENGTH.start(),
+                    PASSPHRASE_LENGTH.end()
+                );
+            }
+        }
+
+        let bin = gst::parse_bin_from_description(
+            format!(
+                r#"
+            name="{name}"
+
+            videoconvert
+                name=video
+            ! x264enc speed-preset={video_speed_preset} tune=zerolatency bitrate={video_bitrate} key-int-max=50
+            ! video/x-h264,profile=high
+            ! h264parse
+            ! mux.
+
+            audioconvert
+                name=audio
+            ! audioresample
+            ! audio/x-raw,rate={audio_rate}
+            ! fdkaacenc bitrate={audio_bitrate}
+            ! audio/mpeg
+            ! aacparse
+            ! audio/mpeg, mpegversion=4
+            ! mux.
+
+            mpegtsmux
+                name=mux
+                alignment=7
+            ! srtsink
+                name=srt
+                uri="{uri}"
+                latency={latency}
+                wait-for-connection=false
+            "#,
+                uri = parameters.uri()?,
+                latency = parameters.latency.unwrap_or(DEFAULT_LATENCY),
+                audio_bitrate = parameters.audio_bitrate.unwrap_or(DEFAULT_AUDIO_BITRATE),
+                audio_rate = parameters.audio_rate.unwrap_or(DEFAULT_AUDIO_RATE),
+                video_bitrate = parameters.video_bitrate.unwrap_or(DEFAULT_VIDEO_BITRATE),
+                video_speed_preset = parameters.video_speed_preset.unwrap_or_default() as usize,
+            )
+            .as_str(),
+            false,
+        )
+        .context("failed to create srt sink pipeline")?;
+
+        if let Some(passphrase) = &parameters.passphrase {
+            bin.by_name("srt")
+                .context("unable to find srtsink")?
+                .set_property("passphrase", passphrase);
+        }
+
+        let video_sink_pad = add_ghost_pad(&bin, "video", "sink")
+            .context("unable to add GhostPad for video sink")?;
+        let audio_sink_pad = add_ghost_pad(&bin, "audio", "sink")
+            .context("unable to add GhostPad for audio sink")?;
+
+        Ok(Self {
+            bin,
+            video_sink_pad,
+            audio_sink_pad,
+        })
+    }
+}
+
+impl Sink for SrtSink {
+    #[must_use]
+    fn video(&self) -> Option<gst::GhostPad> {
+        Some(self.video_sink_pad.clone())
+    }
+
+    #[must_use]
+    fn audio(&self) -> gst::GhostPad {
+        self.audio_sink_pad.clone()
+    }
+
+    #[must_use]
+    fn bin(&self) -> gst::Bin {
+        self.bin.clone()
+    }
+}
@@ -0,0 +1,126 @@
+// SPDX-FileCopyrightText: OpenTalk GmbH <mail@opentalk.eu>
+//
+// SPDX-License-Identifier: EUPL-1.2
+
+use anyhow::{Context, Result};
+use gst::prelude::*;
+use serde::Deserialize;
+
+use crate::{add_ghost_pad, Sink, SpeedPreset};
+
+const DEFAULT_AUDIO_BITRATE: usize = 64000;
+const DEFAULT_VIDEO_BITRATE: usize = 2500;
+
+/// WHIP compositor output to stream over WebRTC into a WHIP endpoint.
+///
+/// The HTTP offer/answer exchange with the endpoint is done by `whipsink` of the `webrtchttp`
+/// plugin as soon as the pipeline starts playing.
+#[derive(Debug)]
+pub struct WhipSink {
+    bin: gst::Bin,
+    video_sink_pad: gst::GhostPad,
+    audio_sink_pad: gst::GhostPad,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct WhipParameters {
+    /// URL of the WHIP endpoint to post the SDP offer to.
+    pub endpoint: String,
+    /// Bearer token to authenticate at the WHIP endpoint.
+    pub auth_token: Option<String>,
+    pub audio_bitrate: Option<usize>,
+    pub video_bitrate: Option<usize>,
+    pub video_speed_preset: Option<SpeedPreset>,
+}
+
+impl WhipSink {
+    /// Create and add new WHIP sink into existing pipeline.
+    ///
+    /// # Errors
+    ///
+    /// This can fail for the following reasons:
+    /// - Unable to create `videoconvert` for `GStreamer`.
+    /// - Unable to create `x264enc` for `GStreamer`.
+    /// - Unable to create `h264parse` for `GStreamer`.
+    /// - Unable to create `rtph264pay` for `GStreamer`.
+    /// - Unable to create `audioconvert` for `GStreamer`.
+    /// - Unable to create `audioresample` for `GStreamer`.
+    /// - Unable to create `opusenc` for `GStreamer`.
+    /// - Unable to create `rtpopuspay` for `GStreamer`.
+    /// - Unable to create `whipsink` for `GStreamer`.
+    /// - `GhostPad` cannot be created for the `video_sink_pad` or `audio_sink_pad`.
+    pub fn create(name: &str, parameters: WhipParameters) -> Result<WhipSink> {
+        trace!("new({name})");
+
+        let bin = gst::parse_bin_from_description(
+            format!(
+                r#"
+            name="{name}"
+
+            videoconvert
+                name=video
+            ! x264enc speed-preset={video_speed_preset} tune=zerolatency bitrate={video_bitrate} key-int-max=50
+            ! video/x-h264,profile=constrained-baseline
+            ! h264parse
+            ! rtph264pay config-interval=-1 pt=96
+            ! application/x-rtp,media=video,encoding-name=H264,payload=96
+            ! whip.sink_0
+
+            audioconvert
+                name=audio
+            ! audioresample
+            ! audio/x-raw,rate=48000
+            ! opusenc bitrate={audio_bitrate}
+            ! rtpopuspay pt=97
+            ! application/x-rtp,media=audio,encoding-name=OPUS,payload=97
+            ! whip.sink_1
+
+            whipsink
+                name=whip
+                whip-endpoint="{endpoint}"
+            "#,
+                endpoint = parameters.endpoint,
+                audio_bitrate = parameters.audio_bitrate.unwrap_or(DEFAULT_AUDIO_BITRATE),
+                video_bitrate = parameters.video_bitrate.unwrap_or(DEFAULT_VIDEO_BITRATE),
+                video_speed_preset = parameters.video_speed_preset.unwrap_or_default() as usize,
+            )
+            .as_str(),
+            false,
+        )
+        .context("failed to create whip sink pipeline")?;
+
+        if let Some(auth_token) = &parameters.auth_token {
+            bin.by_name("whip")
+                .context("unable to find whipsink")?
+                .set_property("auth-token", auth_token);
+        }
+
+        let video_sink_pad = add_ghost_pad(&bin, "video", "sink")
+            .context("unable to add GhostPad for video sink")?;
+        let audio_sink_pad = add_ghost_pad(&bin, "audio", "sink")
+            .context("unable to add GhostPad for audio sink")?;
+
+        Ok(Self {
+            bin,
+            video_sink_pad,
+            audio_sink_pad,
+        })
+    }
+}
+
+impl Sink for WhipSink {
+    #[must_use]
+    fn video(&self) -> Option<gst::GhostPad> {
+        Some(self.video_sink_pad.clone())
+    }
+
+    #[must_use]
+    fn audio(&self) -> gst::GhostPad {
+        self.audio_sink_pad.clone()
+    }
+
+    #[must_use]
+    fn bin(&self) -> gst::Bin {
+        self.bin.clone()
+    }
+}
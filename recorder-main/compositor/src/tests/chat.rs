@@ -0,0 +1,112 @@
+// SPDX-FileCopyrightText: OpenTalk GmbH <mail@opentalk.eu>
+//
+// SPDX-License-Identifier: EUPL-1.2
+
+use std::time::{Duration, Instant};
+
+use crate::{
+    testing, ChatLog, ChatStyle, Mp4Parameters, Mp4Sink, OutputProfile, Speaker, Talk, TestSource,
+    Theme,
+};
+
+#[test]
+fn test_chat_log() {
+    let log = ChatLog::default();
+    log.set_style(ChatStyle {
+        max_messages: 2,
+        max_length: 10,
+        display_time: 10.0,
+        fade_time: 4.0,
+        ..Default::default()
+    });
+
+    let start = Instant::now();
+    log.push_at("Alice", "first", start);
+    log.push_at(
+        "Bob",
+        "a <b>\nbold</b> message",
+        start + Duration::from_secs(2),
+    );
+    log.push_at("Carol", "third", start + Duration::from_secs(4));
+
+    // only the latest messages are kept, cut to the maximum length and escaped
+    assert_eq!(
+        log.markup(start + Duration::from_secs(5)),
+        "<b>Bob</b>: a &lt;b&gt; bold…\n<b>Carol</b>: third"
+    );
+
+    // the older message fades out after its display time
+    assert_eq!(
+        log.markup(start + Duration::from_secs(13)),
+        "<span alpha=\"80%\"><b>Bob</b>: a &lt;b&gt; bold…</span>\n<b>Carol</b>: third"
+    );
+
+    // expired messages are dropped
+    assert_eq!(
+        log.markup(start + Duration::from_secs(17)),
+        "<span alpha=\"30%\"><b>Carol</b>: third</span>"
+    );
+    assert_eq!(log.markup(start + Duration::from_secs(18)), "");
+}
+
+#[test]
+fn test_chat() {
+    // initialize for testing
+    testing::init();
+
+    let mut talk = Talk::<TestSource, u32>::new(
+        testing::RESOLUTION,
+        Speaker::default(),
+        testing::MAX_STREAMS,
+        true,
+    )
+    .unwrap();
+
+    // only the second sink shows the chat
+    for (index, (file, chat)) in [("chat_clean.mp4", false), ("chat_overlay.mp4", true)]
+        .into_iter()
+        .enumerate()
+    {
+        let name = format!("mp4_sink_{index}");
+        talk.link_sink(
+            &name,
+            Mp4Sink::create(
+                &name,
+                &Mp4Parameters {
+                    name: "MP4 Sink",
+                    file_path: testing::output_file(file).into(),
+                    profile: OutputProfile {
+                        chat,
+                        ..Default::default()
+                    },
+                    tracks: 0,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+    }
+
+    talk.set_theme(Theme {
+        chat: ChatStyle {
+            display_time: 2.0,
+            fade_time: 1.0,
+            ..Default::default()
+        },
+        ..Default::default()
+    })
+    .unwrap();
+
+    testing::generate_streams(&mut talk, 0, 2, 2, true);
+    talk.set_speaker(0).unwrap();
+
+    talk.dot("test_chat", testing::DOT_PARAMS);
+
+    for n in 0..6 {
+        talk.add_chat_message(&format!("Participant {}", n % 2), &format!("message {n}"));
+        testing::wait_millis(500);
+    }
+
+    // stir until the messages have faded out
+    testing::wait_secs(4);
+}
@@ -0,0 +1,80 @@
+// SPDX-FileCopyrightText: OpenTalk GmbH <mail@opentalk.eu>
+//
+// SPDX-License-Identifier: EUPL-1.2
+
+use crate::{
+    testing, HlsParameters, HlsRendition, HlsSegmentType, HlsSink, Speaker, StreamId, StreamStatus,
+    Talk, TestSource,
+};
+
+fn run_hls(name: &str, params: HlsParameters) {
+    // initialize for testing
+    testing::init();
+
+    // create speaker mixer with test sources for streams and a HlsSink
+    let mut talk = Talk::<TestSource, u32>::new(
+        testing::RESOLUTION,
+        Speaker::default(),
+        testing::MAX_STREAMS,
+        true,
+    )
+    .unwrap();
+
+    talk.link_sink("hls_sink", HlsSink::create(name, params).unwrap())
+        .unwrap();
+
+    talk.set_speaker(0).unwrap();
+    // add a stream
+    talk.add_stream(
+        StreamId::camera(0),
+        "Participant 0",
+        Default::default(),
+        StreamStatus::default(),
+    )
+    .unwrap();
+
+    talk.dot(name, testing::DOT_PARAMS);
+
+    // stir until done
+    testing::wait_secs(10);
+}
+
+#[test]
+fn test_hls() {
+    run_hls(
+        "test_hls",
+        HlsParameters {
+            output_dir: Some(testing::output_file("hls").into()),
+            segment_duration: 1.0,
+            playlist_length: 5,
+            ..Default::default()
+        },
+    );
+}
+
+#[test]
+fn test_hls_renditions() {
+    run_hls(
+        "test_hls_renditions",
+        HlsParameters {
+            output_dir: Some(testing::output_file("hls_renditions").into()),
+            segment_duration: 1.0,
+            segment_type: HlsSegmentType::Cmaf,
+            renditions: vec![
+                HlsRendition {
+                    width: 1280,
+                    height: 720,
+                    video_bitrate: 2_500_000,
+                    audio_bitrate: 128_000,
+                },
+                HlsRendition {
+                    width: 640,
+                    height: 360,
+                    video_bitrate: 800_000,
+                    audio_bitrate: 64_000,
+                },
+            ],
+            ..Default::default()
+        },
+    );
+}
@@ -0,0 +1,165 @@
+// SPDX-FileCopyrightText: OpenTalk GmbH <mail@opentalk.eu>
+//
+// SPDX-License-Identifier: EUPL-1.2
+
+use gst::prelude::*;
+use gst_app::AppSink;
+
+use crate::{add_ghost_pad, testing, Loudness, Sink, Speaker, StreamId, Talk, TestSource};
+
+/// Sink which hands the mixed audio as float samples to an `AppSink`.
+#[derive(Debug)]
+struct LevelSink {
+    bin: gst::Bin,
+    audio: gst::GhostPad,
+}
+
+impl LevelSink {
+    /// Create sink and return the `AppSink` to measure levels with.
+    fn create(name: &str) -> (Self, AppSink) {
+        let bin = gst::parse_bin_from_description(
+            &format!(
+                r#"
+                name="{name}"
+
+                audioconvert
+                    name=audio
+                ! audio/x-raw,format=F32LE,layout=interleaved
+                ! appsink
+                    name=levels
+                    sync=false
+                    max-buffers=1000
+                    drop=true
+                "#
+            ),
+            false,
+        )
+        .unwrap();
+        let appsink = bin
+            .by_name("levels")
+            .unwrap()
+            .downcast::<AppSink>()
+            .unwrap();
+        let audio = add_ghost_pad(&bin, "audio", "sink").unwrap();
+
+        (Self { bin, audio }, appsink)
+    }
+}
+
+impl Sink for LevelSink {
+    fn video(&self) -> Option<gst::GhostPad> {
+        None
+    }
+
+    fn audio(&self) -> gst::GhostPad {
+        self.audio.clone()
+    }
+
+    fn bin(&self) -> gst::Bin {
+        self.bin.clone()
+    }
+}
+
+/// Measure RMS level in dBFS of the audio which arrives within the given seconds.
+fn measure(appsink: &AppSink, secs: u64) -> f64 {
+    // skip what has been received before
+    while appsink.try_pull_sample(gst::ClockTime::ZERO).is_some() {}
+
+    testing::wait_secs(secs);
+
+    let mut sum = 0.0;
+    let mut count = 0_usize;
+    while let Some(sample) = appsink.try_pull_sample(gst::ClockTime::ZERO) {
+        let buffer = sample.buffer().unwrap();
+        let map = buffer.map_readable().unwrap();
+        for bytes in map.as_slice().chunks_exact(4) {
+            let value = f64::from(f32::from_le_bytes(bytes.try_into().unwrap()));
+            sum += value * value;
+            count += 1;
+        }
+    }
+    assert!(count > 0, "no audio received");
+
+    let level = 10.0 * (sum / count as f64).log10();
+    info!("measured level: {level:.1} dBFS");
+    level
+}
+
+/// Create an audio only talk with one test stream and a `LevelSink`.
+fn create_talk(loudness: Loudness) -> (Talk<TestSource, u32>, AppSink) {
+    // initialize for testing
+    testing::init();
+    let mut talk = Talk::<TestSource, u32>::new_with_loudness(
+        testing::RESOLUTION,
+        Speaker::default(),
+        testing::MAX_STREAMS,
+        false,
+        loudness,
+    )
+    .unwrap();
+
+    let (sink, appsink) = LevelSink::create("level_sink");
+    talk.link_sink("level_sink", sink).unwrap();
+
+    testing::generate_streams(&mut talk, 0, 1, 1, false);
+
+    (talk, appsink)
+}
+
+#[test]
+fn test_stream_volume() {
+    let (mut talk, appsink) = create_talk(Loudness::default());
+    let id = StreamId::camera(0);
+
+    // test sources play at -43 dBFS
+    let unity = measure(&appsink, 2);
+    assert!((-47.0..-39.0).contains(&unity), "unity gain at {unity}");
+
+    // amplify by 20 dB
+    talk.set_stream_volume(&id, 10.0).unwrap();
+    let amplified = measure(&appsink, 2);
+    assert!(
+        (17.0..23.0).contains(&(amplified - unity)),
+        "amplified to {amplified}"
+    );
+
+    // mute
+    talk.set_stream_volume(&id, 0.0).unwrap();
+    let muted = measure(&appsink, 2);
+    assert!(muted < -80.0, "muted at {muted}");
+
+    // reject invalid volumes
+    assert!(talk.set_stream_volume(&id, -1.0).is_err());
+    assert!(talk.set_stream_volume(&id, 11.0).is_err());
+}
+
+#[test]
+fn test_loudness_normalization() {
+    let (_talk, appsink) = create_talk(Loudness {
+        normalize: true,
+        target: -23.0,
+        ..Default::default()
+    });
+
+    // wait for the normalization's look ahead
+    testing::wait_secs(4);
+
+    // a stereo sine at -23 LUFS has about -25 dBFS per channel
+    let level = measure(&appsink, 3);
+    assert!((-29.0..-21.0).contains(&level), "normalized to {level}");
+}
+
+#[test]
+fn test_stream_agc() {
+    let (_talk, appsink) = create_talk(Loudness {
+        stream_agc: true,
+        ..Default::default()
+    });
+
+    // give gain control some time to adapt
+    testing::wait_secs(4);
+
+    // quiet test source gets amplified
+    let level = measure(&appsink, 3);
+    assert!(level > -37.0, "controlled to {level}");
+}
@@ -2,7 +2,10 @@
 //
 // SPDX-License-Identifier: EUPL-1.2
 
-use crate::{testing, Grid, Layout, Speaker, StreamId, Talk, TestSink, TestSource};
+use crate::{
+    testing, Declarative, Fit, Grid, Layout, Region, Slot, Speaker, StreamId, StreamRole, Talk,
+    TestSink, TestSource,
+};
 
 #[test]
 fn test_layout_speaker() {
@@ -14,6 +17,68 @@ fn test_layout_grid() {
     test_layout(Grid::default(), "grid");
 }
 
+fn region(slot: Slot, index: usize, x: f64, y: f64, width: f64, height: f64) -> Region {
+    Region {
+        slot,
+        index,
+        x,
+        y,
+        width,
+        height,
+        z_order: 0,
+        fit: Fit::Contain,
+        min_visibles: 0,
+        max_visibles: None,
+    }
+}
+
+/// speaker on the left and up to four other streams in a column on the right
+fn declarative_layout() -> Declarative {
+    Declarative::new(vec![
+        region(Slot::Speaker, 0, 0.0, 0.0, 0.75, 1.0),
+        region(Slot::Other, 0, 0.75, 0.0, 0.25, 0.25),
+        region(Slot::Other, 1, 0.75, 0.25, 0.25, 0.25),
+        region(Slot::Other, 2, 0.75, 0.5, 0.25, 0.25),
+        region(Slot::Other, 3, 0.75, 0.75, 0.25, 0.25),
+    ])
+    .unwrap()
+}
+
+#[test]
+fn test_layout_declarative() {
+    test_layout(declarative_layout(), "declarative");
+}
+
+#[test]
+fn test_layout_declarative_regions() {
+    let mut layout = declarative_layout();
+    layout.set_resolution_changed(testing::RESOLUTION);
+    layout.set_amount_of_visibles(3);
+    layout.set_stream_roles(&[StreamRole::Other, StreamRole::Speaker, StreamRole::Other]);
+
+    // the speaker gets the speaker region regardless of its position
+    let speaker = layout.calculate_stream_view(1).unwrap();
+    assert_eq!((speaker.pos.x, speaker.pos.y), (0, 0));
+    assert_eq!(speaker.size.width, testing::RESOLUTION.width * 3 / 4);
+
+    // the other streams fill the other regions in order
+    let first = layout.calculate_stream_view(0).unwrap();
+    assert_eq!(first.pos.y, 0);
+    let second = layout.calculate_stream_view(2).unwrap();
+    assert_eq!(second.pos.y, testing::RESOLUTION.height as i64 / 4);
+
+    // without a speaker region the speaker is placed like any other stream
+    layout.set_stream_roles(&[StreamRole::Screen, StreamRole::Other, StreamRole::Other]);
+    assert_eq!(layout.calculate_stream_view(0).unwrap().pos.y, 0);
+
+    // streams without a region are not visible
+    layout.set_amount_of_visibles(6);
+    assert!(layout.calculate_stream_view(5).is_none());
+
+    // regions must be within the picture
+    assert!(Declarative::new(vec![region(Slot::Other, 0, 0.5, 0.0, 0.75, 1.0)]).is_err());
+}
+
 fn test_layout(layout: impl Layout, name: &str) {
     // initialize for testing
     testing::init();
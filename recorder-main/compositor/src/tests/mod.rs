@@ -3,16 +3,26 @@
 // SPDX-License-Identifier: EUPL-1.2
 
 mod blinder;
+mod chat;
 mod dash;
 mod generate_example_pipeline_picture;
+mod hls;
+mod loudness;
 mod matroska;
 mod mixer;
 mod mp4;
 mod multi;
+mod multitrack;
 mod overlays;
+mod pause;
+mod placeholder;
+mod presentation;
+mod profiles;
 mod speaker_mode;
+mod srt;
 mod stream_status;
 mod webrtc;
+mod whip;
 
 pub mod testing {
 
@@ -183,6 +193,25 @@ pub mod testing {
         std::thread::sleep(Duration::from_millis(milliseconds));
     }
 
+    /// read the duration of the MP4 file at the given path
+    pub fn mp4_duration(file_path: &str) -> gst::ClockTime {
+        use gst::prelude::*;
+
+        let pipeline = gst::parse_launch(&format!(
+            "filesrc location={file_path} ! qtdemux ! fakesink"
+        ))
+        .unwrap();
+        pipeline.set_state(gst::State::Paused).unwrap();
+
+        // the duration is known once the pipeline has prerolled
+        let (result, _, _) = pipeline.state(gst::ClockTime::from_seconds(10));
+        result.unwrap();
+        let duration = pipeline.query_duration::<gst::ClockTime>().unwrap();
+
+        pipeline.set_state(gst::State::Null).unwrap();
+        duration
+    }
+
     /// like `wait()` but waits 200ms or zero time
     pub fn wait_short() {
         if be_slow() {
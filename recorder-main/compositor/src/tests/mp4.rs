@@ -25,6 +25,8 @@ fn test_mp4() {
                 &Mp4Parameters {
                     name: "MP4 Sink",
                     file_path: testing::output_file("mp4sink.mp4").into(),
+                    profile: Default::default(),
+                    tracks: 0,
                 },
             )
             .unwrap(),
@@ -0,0 +1,95 @@
+// SPDX-FileCopyrightText: OpenTalk GmbH <mail@opentalk.eu>
+//
+// SPDX-License-Identifier: EUPL-1.2
+
+use crate::{
+    testing, Mp4Parameters, Mp4Sink, Pattern, Size, Speaker, StreamId, StreamStatus, Talk,
+    TestSource, TestSourceParameters,
+};
+
+/// Count the audio streams within the given file with `ffprobe`.
+fn audio_streams(file_path: &str) -> usize {
+    let output = std::process::Command::new("ffprobe")
+        .args([
+            "-v",
+            "error",
+            "-select_streams",
+            "a",
+            "-show_entries",
+            "stream=index",
+            "-of",
+            "csv=p=0",
+            file_path,
+        ])
+        .output()
+        .unwrap();
+    String::from_utf8(output.stdout).unwrap().lines().count()
+}
+
+#[test]
+fn test_multitrack() {
+    // initialize for testing
+    testing::init();
+    let mut talk = Talk::<TestSource, u32>::new(
+        testing::RESOLUTION,
+        Speaker::default(),
+        testing::MAX_STREAMS,
+        true,
+    )
+    .unwrap();
+
+    // a stream which exists before the sink gets the first track
+    testing::generate_streams(&mut talk, 0, 1, 1, true);
+
+    let file_path = testing::output_file("multitrack.mp4");
+    talk.link_sink(
+        "mp4_sink",
+        Mp4Sink::create(
+            "test",
+            &Mp4Parameters {
+                name: "Multitrack",
+                file_path: file_path.as_str().into(),
+                profile: Default::default(),
+                tracks: 2,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    testing::wait_secs(2);
+
+    // a joining participant gets the second track, the next one gets none
+    testing::generate_streams(&mut talk, 1, 2, 3, true);
+    testing::wait_secs(2);
+
+    // a screen share is recorded into the track of its participant
+    talk.add_stream(
+        StreamId::screen(1),
+        "screen",
+        TestSourceParameters {
+            resolution: Size::HD,
+            name: Some("screen".into()),
+            pattern: Pattern::Smpte75,
+            has_video: true,
+        },
+        StreamStatus::default(),
+    )
+    .unwrap();
+    testing::wait_secs(2);
+
+    // the first track continues silently
+    talk.remove_stream(StreamId::camera(0)).unwrap();
+    talk.dot("test_multitrack", testing::DOT_PARAMS);
+    testing::wait_secs(2);
+
+    // the track of the participant who has left goes to the participant without a track
+    talk.remove_stream(StreamId::camera(2)).unwrap();
+    testing::generate_streams(&mut talk, 2, 1, 3, true);
+    testing::wait_secs(2);
+
+    // finish file
+    drop(talk);
+
+    // mixed audio and two participant tracks
+    assert_eq!(audio_streams(&file_path), 3);
+}
@@ -2,7 +2,9 @@
 //
 // SPDX-License-Identifier: EUPL-1.2
 
-use crate::{testing, Speaker, StreamId, Talk, TestSink, TestSource};
+use crate::{
+    testing, Color, Font, Image, Speaker, StreamId, Talk, TestSink, TestSource, TextStyle, Theme,
+};
 
 #[test]
 fn test_overlay() {
@@ -51,3 +53,81 @@ fn test_overlay() {
 
     testing::wait_secs(10);
 }
+
+#[test]
+fn test_theme() {
+    // initialize for testing
+    testing::init();
+
+    let mut talk = Talk::<TestSource, u32>::new(
+        testing::RESOLUTION,
+        Speaker::default(),
+        testing::MAX_STREAMS,
+        true,
+    )
+    .unwrap();
+    talk.link_sink("test_sink", TestSink::create("Testing Sink", true).unwrap())
+        .unwrap();
+
+    talk.set_title("test_theme").unwrap();
+
+    // add participants
+    let (_, ids) = testing::generate_streams(&mut talk, 0, 2, 2, true);
+    ids.iter().for_each(|id| {
+        talk.show_stream(&StreamId::camera(*id)).unwrap();
+    });
+    talk.dot("test_theme-0", testing::DOT_PARAMS);
+
+    testing::wait();
+
+    // change theme while streams are visible
+    let theme = Theme {
+        names: TextStyle {
+            font: Font {
+                size: 24,
+                ..Default::default()
+            },
+            color: Color::try_from("#ffff00".to_string()).unwrap(),
+            ..Default::default()
+        },
+        background_color: Color::try_from("#1e2832".to_string()).unwrap(),
+        background_image: Some(Image {
+            location: testing::image_file("screen_SD.png"),
+            x: 0.0,
+            y: 0.0,
+            width: 0,
+            height: 0,
+            opacity: 0.5,
+        }),
+        logo: Some(Image {
+            location: testing::image_file("participant_SD.png"),
+            x: 0.8,
+            y: 0.8,
+            width: 160,
+            height: 90,
+            opacity: 1.0,
+        }),
+        ..Default::default()
+    };
+    talk.set_theme(theme).unwrap();
+    talk.dot("test_theme-1", testing::DOT_PARAMS);
+
+    testing::wait();
+
+    // hide one stream so the background becomes visible
+    talk.hide_stream(&StreamId::camera(ids[1])).unwrap();
+    talk.dot("test_theme-2", testing::DOT_PARAMS);
+
+    testing::wait();
+
+    // new streams use the theme's name style
+    let (_, new_ids) = testing::generate_streams(&mut talk, 2, 1, 1, true);
+    talk.show_stream(&StreamId::camera(new_ids[0])).unwrap();
+    talk.dot("test_theme-3", testing::DOT_PARAMS);
+
+    // restore default theme
+    talk.set_theme(Theme::default()).unwrap();
+    talk.dot("test_theme-4", testing::DOT_PARAMS);
+
+    testing::wait_secs(10);
+}
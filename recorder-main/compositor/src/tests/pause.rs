@@ -0,0 +1,162 @@
+// SPDX-FileCopyrightText: OpenTalk GmbH <mail@opentalk.eu>
+//
+// SPDX-License-Identifier: EUPL-1.2
+
+use crate::{testing, FakeSink, Mp4Parameters, Mp4Sink, Speaker, StreamId, Talk, TestSource};
+
+#[test]
+fn test_pause() {
+    // initialize for testing
+    testing::init();
+
+    let mut talk = Talk::<TestSource, u32>::new(
+        testing::RESOLUTION,
+        Speaker::default(),
+        testing::MAX_STREAMS,
+        true,
+    )
+    .unwrap();
+
+    let file_path = testing::output_file("test_pause.mp4");
+    talk.link_sink(
+        "mp4_sink",
+        Mp4Sink::create(
+            "test",
+            &Mp4Parameters {
+                file_path: file_path.as_str().into(),
+                name: "test_pause",
+                profile: Default::default(),
+                tracks: 0,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+
+    let (_, ids) = testing::generate_streams(&mut talk, 0, 2, 2, true);
+    for id in &ids {
+        talk.show_stream(&StreamId::camera(*id)).unwrap();
+    }
+    talk.set_title("test_pause: recording").unwrap();
+
+    testing::wait_secs(2);
+
+    // pause the output while the talk keeps running
+    assert!(!talk.is_paused());
+    talk.pause().unwrap();
+    assert!(talk.is_paused());
+    assert!(talk.pause().is_err());
+
+    talk.set_title("test_pause: paused (must not be visible)")
+        .unwrap();
+    talk.dot("test_pause-paused", testing::DOT_PARAMS);
+
+    testing::wait_secs(2);
+
+    talk.resume().unwrap();
+    assert!(!talk.is_paused());
+    assert!(talk.resume().is_err());
+
+    talk.set_title("test_pause: resumed").unwrap();
+
+    testing::wait_secs(2);
+
+    // the file is finished when the released sink is dropped
+    drop(talk.release_sink(&"mp4_sink".to_string()).unwrap());
+
+    // four of the six seconds have been recorded
+    let duration = testing::mp4_duration(&file_path);
+    assert!(
+        (gst::ClockTime::from_seconds(3)..gst::ClockTime::from_seconds(5)).contains(&duration),
+        "recorded {duration}"
+    );
+}
+
+#[test]
+fn test_pause_sink() {
+    // initialize for testing
+    testing::init();
+
+    let mut talk = Talk::<TestSource, u32>::new(
+        testing::RESOLUTION,
+        Speaker::default(),
+        testing::MAX_STREAMS,
+        true,
+    )
+    .unwrap();
+
+    let file_path = testing::output_file("test_pause_sink.mp4");
+    talk.link_sink(
+        "mp4_sink",
+        Mp4Sink::create(
+            "test",
+            &Mp4Parameters {
+                file_path: file_path.as_str().into(),
+                name: "test_pause_sink",
+                profile: Default::default(),
+                tracks: 0,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    talk.link_sink("fake_sink", FakeSink::create("fake", true).unwrap())
+        .unwrap();
+
+    let (_, ids) = testing::generate_streams(&mut talk, 0, 2, 2, true);
+    for id in &ids {
+        talk.show_stream(&StreamId::camera(*id)).unwrap();
+    }
+    talk.set_title("test_pause_sink: recording").unwrap();
+
+    testing::wait_secs(2);
+
+    // pause only the fake sink, the mp4 sink keeps recording
+    talk.pause_sink("fake_sink").unwrap();
+    assert!(talk.is_sink_paused("fake_sink"));
+    assert!(!talk.is_sink_paused("mp4_sink"));
+    assert!(!talk.is_paused());
+    assert!(talk.pause_sink("fake_sink").is_err());
+    assert!(talk.pause_sink("unknown_sink").is_err());
+
+    talk.set_title("test_pause_sink: fake sink paused (must be visible)")
+        .unwrap();
+
+    testing::wait_secs(2);
+
+    talk.resume_sink("fake_sink").unwrap();
+    assert!(!talk.is_sink_paused("fake_sink"));
+    assert!(talk.resume_sink("fake_sink").is_err());
+
+    // the paused time is left out of the position of the paused sink
+    let paused = talk
+        .sink_position("mp4_sink")
+        .unwrap()
+        .saturating_sub(talk.sink_position("fake_sink").unwrap());
+    assert!(
+        (gst::ClockTime::from_mseconds(1500)..gst::ClockTime::from_seconds(3)).contains(&paused),
+        "paused for {paused}"
+    );
+    assert!(talk.sink_position("unknown_sink").is_err());
+
+    // both sinks received data since they have been linked
+    let sinks = talk.sinks().unwrap();
+    assert_eq!(
+        sinks
+            .iter()
+            .map(|sink| sink.name.as_str())
+            .collect::<Vec<_>>(),
+        ["fake_sink", "mp4_sink"]
+    );
+    assert!(sinks
+        .iter()
+        .all(|sink| !sink.paused && sink.bytes_written > 0));
+    assert_eq!(talk.statistics().pipeline_errors, 0);
+
+    // release the paused sink at runtime while the talk continues
+    talk.pause_sink("fake_sink").unwrap();
+    drop(talk.release_sink(&"fake_sink".to_string()).unwrap());
+    assert!(!talk.has_sink("fake_sink"));
+
+    testing::wait_secs(2);
+}
@@ -0,0 +1,75 @@
+// SPDX-FileCopyrightText: OpenTalk GmbH <mail@opentalk.eu>
+//
+// SPDX-License-Identifier: EUPL-1.2
+
+use crate::{initials, testing, Grid, StreamId, StreamStatus, Talk, TestSink, TestSource};
+
+#[test]
+fn test_initials() {
+    assert_eq!(initials("Jane Q. Doe"), "JD");
+    assert_eq!(initials("  participant  "), "P");
+    assert_eq!(initials("émile zola"), "ÉZ");
+    assert_eq!(initials(""), "");
+}
+
+#[test]
+fn test_placeholder() {
+    // initialize for testing
+    testing::init();
+
+    let mut talk = Talk::<TestSource, u32>::new(
+        testing::RESOLUTION,
+        Grid::default(),
+        testing::MAX_STREAMS,
+        true,
+    )
+    .unwrap();
+    talk.link_sink("test_sink", TestSink::create("Testing Sink", true).unwrap())
+        .unwrap();
+    talk.show_placeholders(true);
+
+    let (_, ids) = testing::generate_streams(&mut talk, 0, 3, 3, true);
+    for id in &ids {
+        let stream_id = StreamId::camera(*id);
+        assert!(talk.has_placeholder(&stream_id));
+        assert!(!talk.is_placeholder(&stream_id));
+        talk.show_stream(&stream_id).unwrap();
+    }
+
+    testing::wait();
+
+    // camera off keeps the stream visible
+    let stream_id = StreamId::camera(ids[0]);
+    talk.set_status(&stream_id, &StreamStatus::audio()).unwrap();
+    assert!(talk.is_any_visible(&ids[0]));
+    talk.dot("test_placeholder-camera-off", testing::DOT_PARAMS);
+
+    testing::wait();
+
+    // muted microphone
+    talk.set_status(&StreamId::camera(ids[1]), &StreamStatus::video())
+        .unwrap();
+
+    testing::wait();
+
+    // participant without media
+    let placeholder_id = StreamId::camera(100);
+    talk.add_placeholder(placeholder_id, "Not Recorded")
+        .unwrap();
+    assert!(talk.is_placeholder(&placeholder_id));
+    assert!(talk.is_any_visible(&100));
+    talk.dot("test_placeholder-no-media", testing::DOT_PARAMS);
+
+    testing::wait();
+
+    // camera on again
+    talk.set_status(&stream_id, &StreamStatus::default())
+        .unwrap();
+
+    testing::wait();
+
+    talk.remove_stream(placeholder_id).unwrap();
+    assert!(!talk.is_any_visible(&100));
+
+    testing::wait();
+}
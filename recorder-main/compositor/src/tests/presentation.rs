@@ -0,0 +1,161 @@
+// SPDX-FileCopyrightText: OpenTalk GmbH <mail@opentalk.eu>
+//
+// SPDX-License-Identifier: EUPL-1.2
+
+use crate::{
+    testing, Declarative, Fit, Grid, Layout, Pattern, PictureInPicture, Presentation, Region, Size,
+    Slot, StreamId, StreamStatus, Talk, TestSink, TestSource, TestSourceParameters,
+};
+
+#[test]
+fn test_presentation() {
+    test_presentation_layout(Presentation::default(), "presentation");
+}
+
+#[test]
+fn test_picture_in_picture() {
+    test_presentation_layout(PictureInPicture::default(), "picture_in_picture");
+}
+
+fn test_presentation_layout(layout: impl Layout, name: &str) {
+    // initialize for testing
+    testing::init();
+
+    let mut talk = Talk::<TestSource, u32>::new(
+        testing::RESOLUTION,
+        Grid::default(),
+        testing::MAX_STREAMS,
+        true,
+    )
+    .unwrap();
+    talk.change_presentation_layout(layout).unwrap();
+
+    talk.link_sink("test_sink", TestSink::create("Testing Sink", true).unwrap())
+        .unwrap();
+
+    let (streams, ids) = testing::generate_streams(&mut talk, 0, 5, 5, true);
+    for id in &ids {
+        talk.show_stream(&StreamId::camera(*id)).unwrap();
+    }
+    talk.set_speaker(ids[0]).unwrap();
+    assert!(!talk.is_presenting());
+
+    talk.set_title(&format!("test_{name}: grid")).unwrap();
+    talk.dot(&format!("test_{name}-0"), testing::DOT_PARAMS);
+    testing::wait();
+
+    // showing a screen share switches to the presentation layout
+    let screen = StreamId::screen(streams[0].0);
+    talk.add_stream(
+        screen,
+        &format!("{}'s screen", streams[0].1),
+        TestSourceParameters {
+            resolution: Size::HD,
+            name: Some(format!("{}'s screen", streams[0].1)),
+            pattern: Pattern::Smpte75,
+            has_video: true,
+        },
+        StreamStatus::default(),
+    )
+    .unwrap();
+    talk.show_stream(&screen).unwrap();
+    assert!(talk.is_presenting());
+
+    talk.set_title(&format!("test_{name}: screen share"))
+        .unwrap();
+    talk.dot(&format!("test_{name}-1"), testing::DOT_PARAMS);
+    testing::wait();
+
+    // changing the layout while presenting takes effect after the share has ended
+    talk.change_layout(Grid::default()).unwrap();
+    assert!(talk.is_presenting());
+
+    // hiding the screen share switches back to the regular layout
+    talk.hide_stream(&screen).unwrap();
+    assert!(!talk.is_presenting());
+
+    talk.set_title(&format!("test_{name}: share ended"))
+        .unwrap();
+    talk.dot(&format!("test_{name}-2"), testing::DOT_PARAMS);
+    testing::wait();
+
+    // removing a visible screen share also ends the presentation
+    talk.show_stream(&screen).unwrap();
+    assert!(talk.is_presenting());
+    talk.remove_stream(screen).unwrap();
+    assert!(!talk.is_presenting());
+
+    testing::wait();
+}
+
+fn region(slot: Slot, x: f64, width: f64) -> Region {
+    Region {
+        slot,
+        index: 0,
+        x,
+        y: 0.0,
+        width,
+        height: 1.0,
+        z_order: 0,
+        fit: Fit::Contain,
+        min_visibles: 0,
+        max_visibles: None,
+    }
+}
+
+#[test]
+fn test_declarative_screen() {
+    // initialize for testing
+    testing::init();
+
+    // screen share on the left, speaker on the right
+    let layout = Declarative::new(vec![
+        region(Slot::Screen, 0.0, 0.75),
+        region(Slot::Speaker, 0.75, 0.25),
+    ])
+    .unwrap();
+    assert!(layout.arranges_screens());
+    assert!(!Grid::default().arranges_screens());
+
+    let mut talk =
+        Talk::<TestSource, u32>::new(testing::RESOLUTION, layout, testing::MAX_STREAMS, true)
+            .unwrap();
+
+    talk.link_sink("test_sink", TestSink::create("Testing Sink", true).unwrap())
+        .unwrap();
+
+    let (streams, ids) = testing::generate_streams(&mut talk, 0, 2, 2, true);
+    for id in &ids {
+        talk.show_stream(&StreamId::camera(*id)).unwrap();
+    }
+    talk.set_speaker(ids[0]).unwrap();
+
+    // the declarative layout places the screen share itself
+    let screen = StreamId::screen(streams[1].0);
+    talk.add_stream(
+        screen,
+        &format!("{}'s screen", streams[1].1),
+        TestSourceParameters {
+            resolution: Size::HD,
+            name: Some(format!("{}'s screen", streams[1].1)),
+            pattern: Pattern::Smpte75,
+            has_video: true,
+        },
+        StreamStatus::default(),
+    )
+    .unwrap();
+    talk.show_stream(&screen).unwrap();
+    assert!(!talk.is_presenting());
+
+    talk.dot("test_declarative_screen", testing::DOT_PARAMS);
+    testing::wait();
+
+    // a layout without screen regions is replaced while the share is visible
+    talk.change_layout(Grid::default()).unwrap();
+    assert!(talk.is_presenting());
+
+    talk.remove_stream(screen).unwrap();
+    assert!(!talk.is_presenting());
+
+    testing::wait();
+}
@@ -0,0 +1,259 @@
+// SPDX-FileCopyrightText: OpenTalk GmbH <mail@opentalk.eu>
+//
+// SPDX-License-Identifier: EUPL-1.2
+
+use gst::prelude::*;
+use gst_app::AppSink;
+
+use crate::{
+    add_ghost_pad, testing, Grid, Mp4Parameters, Mp4Sink, OutputProfile, Sink, Size, Speaker,
+    SpeedPreset, Talk, TestSource, VideoEncoding,
+};
+
+#[test]
+fn test_profile_format() {
+    let output = Size::FHD;
+
+    // no scaling keeps the output as it is
+    let format = OutputProfile::default().video_format(output, 30);
+    assert_eq!(
+        (format.width, format.height, format.framerate),
+        (1920, 1080, 30)
+    );
+
+    // a single dimension keeps the aspect ratio
+    let profile = OutputProfile {
+        height: Some(360),
+        framerate: Some(15),
+        ..Default::default()
+    };
+    assert!(profile.is_scaled());
+    let format = profile.video_format(output, 30);
+    assert_eq!(
+        (format.width, format.height, format.framerate),
+        (640, 360, 15)
+    );
+
+    // encoder settings do not scale
+    let profile = OutputProfile {
+        video_bitrate: Some(1000),
+        video_speed_preset: Some(SpeedPreset::Veryfast),
+        ..Default::default()
+    };
+    assert!(!profile.is_scaled());
+}
+
+#[test]
+fn test_profiles() {
+    // initialize for testing
+    testing::init();
+    // create grid mixer with test sources for streams
+    let mut talk = Talk::<TestSource, u32>::new(
+        testing::RESOLUTION,
+        Speaker::default(),
+        testing::MAX_STREAMS,
+        true,
+    )
+    .unwrap();
+
+    let profiles = [
+        (
+            "profile_full.mp4",
+            OutputProfile {
+                video_bitrate: Some(4000),
+                video_speed_preset: Some(SpeedPreset::Veryfast),
+                ..Default::default()
+            },
+        ),
+        (
+            "profile_low.mp4",
+            OutputProfile {
+                height: Some(360),
+                framerate: Some(15),
+                video_bitrate: Some(800),
+                ..Default::default()
+            },
+        ),
+        // shares the scaled branch with the previous sink
+        (
+            "profile_low_shared.mp4",
+            OutputProfile {
+                height: Some(360),
+                framerate: Some(15),
+                ..Default::default()
+            },
+        ),
+        // composes a picture of its own
+        (
+            "profile_grid.mp4",
+            OutputProfile {
+                height: Some(360),
+                layout: Some(Grid::default().into()),
+                ..Default::default()
+            },
+        ),
+    ];
+
+    for (index, (file, profile)) in profiles.into_iter().enumerate() {
+        let name = format!("mp4_sink_{index}");
+        talk.link_sink(
+            &name,
+            Mp4Sink::create(
+                &name,
+                &Mp4Parameters {
+                    name: "MP4 Sink",
+                    file_path: testing::output_file(file).into(),
+                    profile,
+                    tracks: 0,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+    }
+
+    testing::generate_streams(&mut talk, 0, 3, 3, true);
+    talk.set_speaker(0).unwrap();
+
+    talk.dot("test_profiles", testing::DOT_PARAMS);
+
+    // stir until done
+    testing::wait_secs(6);
+
+    // releasing the sink removes its composition
+    drop(talk.release_sink(&"mp4_sink_3".to_string()).unwrap());
+    testing::wait_secs(1);
+}
+
+/// Sink which lets the mixer encode its video and hands the H.264 frames to an `AppSink`.
+#[derive(Debug)]
+struct EncodedSink {
+    bin: gst::Bin,
+    video: gst::GhostPad,
+    audio: gst::GhostPad,
+    profile: OutputProfile,
+}
+
+impl EncodedSink {
+    /// Create sink and return the `AppSink` which receives the encoded frames.
+    fn create(name: &str, profile: OutputProfile) -> (Self, AppSink) {
+        let bin = gst::parse_bin_from_description(
+            &format!(
+                r#"
+                name="{name}"
+
+                h264parse
+                    name=video
+                ! appsink
+                    name=frames
+                    sync=false
+                    max-buffers=1000
+                    drop=true
+
+                audioconvert
+                    name=audio
+                ! fakesink
+                "#
+            ),
+            false,
+        )
+        .unwrap();
+        let appsink = bin
+            .by_name("frames")
+            .unwrap()
+            .downcast::<AppSink>()
+            .unwrap();
+        let video = add_ghost_pad(&bin, "video", "sink").unwrap();
+        let audio = add_ghost_pad(&bin, "audio", "sink").unwrap();
+
+        (
+            Self {
+                bin,
+                video,
+                audio,
+                profile,
+            },
+            appsink,
+        )
+    }
+}
+
+impl Sink for EncodedSink {
+    fn video(&self) -> Option<gst::GhostPad> {
+        Some(self.video.clone())
+    }
+
+    fn audio(&self) -> gst::GhostPad {
+        self.audio.clone()
+    }
+
+    fn bin(&self) -> gst::Bin {
+        self.bin.clone()
+    }
+
+    fn profile(&self) -> Option<&OutputProfile> {
+        Some(&self.profile)
+    }
+
+    fn video_encoding(&self) -> Option<VideoEncoding> {
+        Some(VideoEncoding {
+            bitrate: 800,
+            speed_preset: SpeedPreset::Ultrafast,
+            key_int_max: 250,
+        })
+    }
+}
+
+/// Returns the number of received frames and if the first one is a keyframe.
+fn received_frames(appsink: &AppSink) -> (usize, bool) {
+    let mut frames = 0;
+    let mut starts_with_keyframe = false;
+    while let Some(sample) = appsink.try_pull_sample(gst::ClockTime::ZERO) {
+        if frames == 0 {
+            starts_with_keyframe = !sample
+                .buffer()
+                .unwrap()
+                .flags()
+                .contains(gst::BufferFlags::DELTA_UNIT);
+        }
+        frames += 1;
+    }
+    (frames, starts_with_keyframe)
+}
+
+#[test]
+fn test_shared_encoder() {
+    // initialize for testing
+    testing::init();
+    let mut talk = Talk::<TestSource, u32>::new(
+        testing::RESOLUTION,
+        Speaker::default(),
+        testing::MAX_STREAMS,
+        true,
+    )
+    .unwrap();
+
+    let profile = OutputProfile {
+        height: Some(360),
+        ..Default::default()
+    };
+
+    let (sink, first) = EncodedSink::create("encoded_sink_0", profile.clone());
+    talk.link_sink("encoded_sink_0", sink).unwrap();
+    testing::generate_streams(&mut talk, 0, 2, 2, true);
+    testing::wait_secs(2);
+
+    // a sink joining later shares the running encoder and starts with a keyframe anyway
+    let (sink, second) = EncodedSink::create("encoded_sink_1", profile);
+    talk.link_sink("encoded_sink_1", sink).unwrap();
+
+    talk.dot("test_shared_encoder", testing::DOT_PARAMS);
+    testing::wait_secs(2);
+
+    let (frames, starts_with_keyframe) = received_frames(&first);
+    assert!(frames > 0, "first sink received no video");
+    assert!(starts_with_keyframe);
+    let (frames, starts_with_keyframe) = received_frames(&second);
+    assert!(frames > 0, "second sink received no video");
+    assert!(starts_with_keyframe);
+}
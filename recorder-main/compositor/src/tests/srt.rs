@@ -0,0 +1,105 @@
+// SPDX-FileCopyrightText: OpenTalk GmbH <mail@opentalk.eu>
+//
+// SPDX-License-Identifier: EUPL-1.2
+
+use gst::prelude::*;
+use std::net::UdpSocket;
+
+use crate::{
+    testing, Speaker, SrtMode, SrtParameters, SrtSink, StreamId, StreamStatus, Talk, TestSource,
+};
+
+const PASSPHRASE: &str = "opentalk-test";
+
+/// find a free local UDP port
+fn free_port() -> u16 {
+    UdpSocket::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port()
+}
+
+#[test]
+fn test_srt() {
+    // initialize for testing
+    testing::init();
+
+    // start a local SRT listener which receives the output
+    let port = free_port();
+    let listener = gst::parse_launch(&format!(
+        r#"srtsrc uri="srt://127.0.0.1:{port}?mode=listener" passphrase={PASSPHRASE} ! fakesink"#
+    ))
+    .unwrap();
+    listener.set_state(gst::State::Playing).unwrap();
+
+    // create speaker mixer with test sources for streams and a SrtSink
+    let mut talk = Talk::<TestSource, u32>::new(
+        testing::RESOLUTION,
+        Speaker::default(),
+        testing::MAX_STREAMS,
+        true,
+    )
+    .unwrap();
+
+    talk.link_sink(
+        "srt_sink",
+        SrtSink::create(
+            "test_srt",
+            SrtParameters {
+                mode: SrtMode::Caller,
+                host: Some("127.0.0.1".to_string()),
+                port,
+                stream_id: Some("#!::r=test,m=publish".to_string()),
+                latency: Some(200),
+                passphrase: Some(PASSPHRASE.to_string()),
+                audio_bitrate: None,
+                audio_rate: None,
+                video_bitrate: None,
+                video_speed_preset: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+
+    talk.set_speaker(0).unwrap();
+    // add a stream
+    talk.add_stream(
+        StreamId::camera(0),
+        "Participant 0",
+        Default::default(),
+        StreamStatus::default(),
+    )
+    .unwrap();
+
+    talk.dot("test_srt", testing::DOT_PARAMS);
+
+    // stir until done
+    testing::wait_secs(5);
+
+    listener.set_state(gst::State::Null).unwrap();
+}
+
+#[test]
+fn test_srt_invalid_passphrase() {
+    // initialize for testing
+    testing::init();
+
+    assert!(SrtSink::create(
+        "test_srt_invalid_passphrase",
+        SrtParameters {
+            mode: SrtMode::Listener,
+            host: None,
+            port: free_port(),
+            stream_id: None,
+            latency: None,
+            passphrase: Some("short".to_string()),
+            audio_bitrate: None,
+            audio_rate: None,
+            video_bitrate: None,
+            video_speed_preset: None,
+        },
+    )
+    .is_err());
+}
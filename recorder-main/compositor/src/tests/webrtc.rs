@@ -115,7 +115,7 @@ async fn handle_webrtc_event(
                 .and_then(|p| p.publish.as_mut())
                 .unwrap();
 
-            let source = &talk.stream_mut(&StreamId::camera(id)).unwrap().source;
+            let source = talk.get_source(&StreamId::camera(id)).unwrap();
             let webrtcbin = publish.by_name("webrtc").unwrap();
             let response = source.receive_offer(offer).await.unwrap();
             let response = gst_webrtc::WebRTCSessionDescription::new(
@@ -129,11 +129,11 @@ async fn handle_webrtc_event(
             );
         }
         WebRtcBinToMainLoopEvent::SdpCandidate(id, mline, candidate) => {
-            let source = &talk.stream_mut(&StreamId::camera(id)).unwrap().source;
+            let source = talk.get_source(&StreamId::camera(id)).unwrap();
             source.receive_candidate(mline, &candidate);
         }
         WebRtcBinToMainLoopEvent::SdpEndOfCandidates(id) => {
-            let source = &talk.stream_mut(&StreamId::camera(id)).unwrap().source;
+            let source = talk.get_source(&StreamId::camera(id)).unwrap();
             source.receive_end_of_candidates(0);
         }
     }
@@ -0,0 +1,118 @@
+// SPDX-FileCopyrightText: OpenTalk GmbH <mail@opentalk.eu>
+//
+// SPDX-License-Identifier: EUPL-1.2
+
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    net::TcpListener,
+    sync::mpsc,
+    time::Duration,
+};
+
+use crate::{testing, Speaker, StreamId, StreamStatus, Talk, TestSource, WhipParameters, WhipSink};
+
+/// Start a stub WHIP endpoint which answers the first offer and forwards the request.
+///
+/// The answer is the offer with swapped directions, which is enough to complete the exchange.
+fn stub_endpoint() -> (String, mpsc::Receiver<(String, String)>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let endpoint = format!("http://{}/whip", listener.local_addr().unwrap());
+    let (sender, receiver) = mpsc::channel();
+
+    std::thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+        // read request line and headers
+        let mut head = String::new();
+        let mut content_length = 0;
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            if line == "\r\n" || line.is_empty() {
+                break;
+            }
+            if let Some((name, value)) = line.split_once(':') {
+                if name.eq_ignore_ascii_case("content-length") {
+                    content_length = value.trim().parse().unwrap();
+                }
+            }
+            head.push_str(&line);
+        }
+        let mut offer = vec![0; content_length];
+        reader.read_exact(&mut offer).unwrap();
+        let offer = String::from_utf8(offer).unwrap();
+
+        let answer = offer
+            .replace("a=sendrecv", "a=recvonly")
+            .replace("a=sendonly", "a=recvonly")
+            .replace("a=setup:actpass", "a=setup:active");
+        let mut stream = stream;
+        write!(
+            stream,
+            "HTTP/1.1 201 Created\r\nContent-Type: application/sdp\r\nLocation: /whip/resource\r\nContent-Length: {}\r\n\r\n{answer}",
+            answer.len()
+        )
+        .unwrap();
+
+        sender.send((head, offer)).unwrap();
+    });
+
+    (endpoint, receiver)
+}
+
+#[test]
+fn test_whip() {
+    // initialize for testing
+    testing::init();
+
+    let (endpoint, requests) = stub_endpoint();
+
+    // create speaker mixer with test sources for streams and a WhipSink
+    let mut talk = Talk::<TestSource, u32>::new(
+        testing::RESOLUTION,
+        Speaker::default(),
+        testing::MAX_STREAMS,
+        true,
+    )
+    .unwrap();
+
+    talk.link_sink(
+        "whip_sink",
+        WhipSink::create(
+            "test_whip",
+            WhipParameters {
+                endpoint,
+                auth_token: Some("secret".to_string()),
+                audio_bitrate: None,
+                video_bitrate: None,
+                video_speed_preset: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+
+    talk.set_speaker(0).unwrap();
+    // add a stream
+    talk.add_stream(
+        StreamId::camera(0),
+        "Participant 0",
+        Default::default(),
+        StreamStatus::default(),
+    )
+    .unwrap();
+
+    talk.dot("test_whip", testing::DOT_PARAMS);
+
+    // check that the offer has been posted to the endpoint
+    let (head, offer) = requests.recv_timeout(Duration::from_secs(10)).unwrap();
+    assert!(head.starts_with("POST /whip"));
+    assert!(head.contains("Bearer secret"));
+    assert!(offer.starts_with("v=0"));
+    assert!(offer.contains("m=video"));
+    assert!(offer.contains("m=audio"));
+
+    // stir until done
+    testing::wait_secs(3);
+}
@@ -6,12 +6,12 @@
 
 use anyhow::{bail, Result};
 use bytes::Bytes;
-use futures::TryStream;
 use openidconnect::{reqwest::Error, AccessToken, HttpRequest, HttpResponse, OAuth2TokenResponse};
-use reqwest::{Body, StatusCode};
+use reqwest::{RequestBuilder, StatusCode};
 use serde::{Deserialize, Serialize};
 use std::{future::Future, pin::Pin};
 use tokio::sync::RwLock;
+use uuid::Uuid;
 
 use crate::settings::{AuthSettings, ControllerSettings};
 
@@ -89,6 +89,27 @@ impl HttpClient {
     pub async fn start(&self, settings: &ControllerSettings, room_id: &str) -> Result<String> {
         let uri = format!("{}/services/recording/start", settings.v1_api_base_url());
 
+        let response = self
+            .send_authorized(|| self.client.post(&uri).json(&StartRequest { room_id }))
+            .await?;
+
+        match response.status() {
+            StatusCode::OK => {
+                let response = response.json::<StartResponse>().await?;
+
+                Ok(response.ticket)
+            }
+            code => bail!("unexpected status code {code:?}"),
+        }
+    }
+
+    /// Send a request with the access token, the access token is refreshed if it has expired
+    ///
+    /// Returns the response of any other status than `401 Unauthorized`.
+    async fn send_authorized(
+        &self,
+        request: impl Fn() -> RequestBuilder,
+    ) -> Result<reqwest::Response> {
         // max 10 authentication tries
         for _ in 0..10 {
             let token = {
@@ -97,80 +118,106 @@ impl HttpClient {
                 l.clone()
             };
 
-            let response = self
-                .client
-                .post(&uri)
-                .bearer_auth(token.secret())
-                .json(&StartRequest { room_id })
-                .send()
-                .await?;
-
-            match response.status() {
-                StatusCode::OK => {
-                    let response = response.json::<StartResponse>().await?;
-
-                    return Ok(response.ticket);
-                }
-                StatusCode::UNAUTHORIZED => {
-                    let ApiError { code } = response.json::<ApiError>().await?;
-
-                    if code == "unauthorized" {
-                        self.refresh_access_tokens(token).await?;
-                    } else {
-                        bail!(InvalidCredentials);
-                    }
-                }
-                code => bail!("unexpected status code {code:?}"),
+            let response = request().bearer_auth(token.secret()).send().await?;
+
+            if response.status() != StatusCode::UNAUTHORIZED {
+                return Ok(response);
+            }
+
+            let ApiError { code } = response.json::<ApiError>().await?;
+
+            if code == "unauthorized" {
+                self.refresh_access_tokens(token).await?;
+            } else {
+                bail!(InvalidCredentials);
             }
         }
 
         bail!("failed to authorize")
     }
 
-    pub async fn upload_render<S>(
+    /// Start a resumable upload of a file of the recording into the room's assets
+    pub async fn start_upload(
+        &self,
+        settings: &ControllerSettings,
+        query: UploadRenderQuery<'_>,
+    ) -> Result<UploadStatus> {
+        let uri = format!("{}/services/recording/uploads", settings.v1_api_base_url());
+
+        let response = self
+            .send_authorized(|| self.client.post(&uri).query(&query))
+            .await?;
+
+        match response.status() {
+            StatusCode::OK => Ok(response.json::<UploadStatus>().await?),
+            code => bail!("unexpected status code {code:?}"),
+        }
+    }
+
+    /// Get the offset a resumable upload continues at, `None` if the upload has expired
+    pub async fn upload_status(
         &self,
         settings: &ControllerSettings,
-        room_id: &str,
-        stream: S,
-    ) -> Result<()>
-    where
-        S: TryStream + Send + Sync + 'static,
-        S::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
-        Bytes: From<S::Ok>,
-    {
-        // TODO: do not hardcode the filename
+        upload_id: Uuid,
+    ) -> Result<Option<UploadStatus>> {
         let uri = format!(
-            "{}/services/recording/upload_render?room_id={room_id}&filename=recording.mp4",
+            "{}/services/recording/uploads/{upload_id}",
             settings.v1_api_base_url()
         );
 
-        // TODO: do not refresh access-token always here. Cannot refresh on request failure since the body
-        // consumes the stream. So we can only make the request once. We could solve this by having the body stream be
-        // created by a callback or something.
-        let token = {
-            let l = self.access_token.read().await;
-            l.clone()
-        };
+        let response = self.send_authorized(|| self.client.get(&uri)).await?;
 
-        self.refresh_access_tokens(token).await?;
+        match response.status() {
+            StatusCode::OK => Ok(Some(response.json::<UploadStatus>().await?)),
+            StatusCode::NOT_FOUND => Ok(None),
+            code => bail!("unexpected status code {code:?}"),
+        }
+    }
 
-        let token = {
-            let l = self.access_token.read().await;
-            l.clone()
-        };
+    /// Append a chunk at the given offset to a resumable upload
+    pub async fn upload_chunk(
+        &self,
+        settings: &ControllerSettings,
+        upload_id: Uuid,
+        offset: u64,
+        chunk: Bytes,
+    ) -> Result<UploadStatus> {
+        let uri = format!(
+            "{}/services/recording/uploads/{upload_id}",
+            settings.v1_api_base_url()
+        );
 
         let response = self
-            .client
-            .post(&uri)
-            .bearer_auth(token.secret())
-            .body(Body::wrap_stream(stream))
-            .send()
+            .send_authorized(|| {
+                self.client
+                    .put(&uri)
+                    .query(&[("offset", offset)])
+                    .body(chunk.clone())
+            })
             .await?;
 
-        if response.status().is_success() {
-            Ok(())
-        } else {
-            bail!("unexpected status code {:?}", response.status())
+        match response.status() {
+            StatusCode::OK => Ok(response.json::<UploadStatus>().await?),
+            code => bail!("unexpected status code {code:?}"),
+        }
+    }
+
+    /// Complete a resumable upload, returns the ID of the created asset
+    pub async fn complete_upload(
+        &self,
+        settings: &ControllerSettings,
+        upload_id: Uuid,
+    ) -> Result<Uuid> {
+        let uri = format!(
+            "{}/services/recording/uploads/{upload_id}/complete",
+            settings.v1_api_base_url()
+        );
+
+        let response = self.send_authorized(|| self.client.post(&uri)).await?;
+
+        match response.status() {
+            StatusCode::OK => Ok(response.json::<UploadRenderResponse>().await?.asset_id),
+            code => bail!("unexpected status code {code:?}"),
         }
     }
 }
@@ -195,6 +242,52 @@ struct StartResponse {
     ticket: String,
 }
 
+/// Kind of a file uploaded with [`HttpClient::start_upload`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RenderKind {
+    /// The recording itself
+    Render,
+    /// WebVTT chapters of the recording
+    Chapters,
+    /// JSON timeline of speakers and screen shares within the recording
+    SpeakerTimeline,
+    /// JPEG of a frame of the recording
+    Thumbnail,
+}
+
+/// Query of [`HttpClient::start_upload`]
+#[derive(Debug, Serialize)]
+pub struct UploadRenderQuery<'s> {
+    pub room_id: &'s str,
+    pub filename: &'s str,
+    pub kind: RenderKind,
+    /// Asset of the recording the uploaded file belongs to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub linked_asset_id: Option<Uuid>,
+    /// Duration of the recording in milliseconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<u64>,
+    /// Resolution of the recording's video
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub width: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub height: Option<u32>,
+}
+
+#[derive(Deserialize)]
+struct UploadRenderResponse {
+    asset_id: Uuid,
+}
+
+/// Progress of a resumable upload
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct UploadStatus {
+    pub upload_id: Uuid,
+    /// Number of bytes received by the controller, the next chunk has to start here
+    pub offset: u64,
+}
+
 fn async_http_client(
     client: reqwest::Client,
 ) -> impl Fn(
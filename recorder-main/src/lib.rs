@@ -9,7 +9,13 @@
 #![allow(clippy::missing_panics_doc)]
 
 pub mod http;
+pub mod metrics;
+pub mod postprocess;
 pub mod recorder;
 pub mod rmq;
+pub mod server;
 pub mod settings;
 pub mod signaling;
+pub mod status;
+pub mod timeline;
+pub mod upload;
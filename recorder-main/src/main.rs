@@ -22,10 +22,16 @@ use tokio::{
 };
 
 mod http;
+mod metrics;
+mod postprocess;
 mod recorder;
 mod rmq;
+mod server;
 mod settings;
 mod signaling;
+mod status;
+mod timeline;
+mod upload;
 
 use crate::recorder::Recorder;
 
@@ -33,6 +39,9 @@ use crate::recorder::Recorder;
 //mod tests;
 
 const RECONNECT_INTERVAL: Duration = Duration::from_millis(3_000); //ms
+/// Delay before a request over capacity is put back into the queue, so it is not received again
+/// right away
+const REQUEUE_DELAY: Duration = Duration::from_millis(1_000); //ms
 const DOT_OUTPUT_PATH: &str = "./pipelines";
 
 fn check_for_ffmpeg() -> Result<()> {
@@ -133,9 +142,18 @@ async fn rmq_session(
 ) -> Result<()> {
     match rmq::connect_rabbitmq(&recorder_context.settings.rabbitmq).await {
         Ok(mut consumer) => {
+            recorder_context.status.set_ready(true);
             while let Some(delivery) = consumer.next().await {
                 match delivery {
                     Ok(ref delivery) => {
+                        if !recorder_context.has_capacity() {
+                            log::warn!("Recorder is at capacity, requeueing recording request");
+                            recorder_context.metrics.requeued_requests.inc();
+                            sleep(REQUEUE_DELAY).await;
+                            rmq::requeue_delivery(delivery).await?;
+                            continue;
+                        }
+
                         let start_command = rmq::handle_delivery(delivery).await?;
                         let task = recorder_context
                             .spawn_session(start_command)
@@ -174,12 +192,36 @@ async fn main2(mut shutdown_rx: Receiver<bool>) -> Result<()> {
     let recorder_context = Recorder::new(settings, http_client, shutdown_rx.clone());
     let mut tasks: Vec<JoinHandle<Result<()>>> = vec![];
 
+    // uploads are resumed on the next start if they are interrupted by a shutdown
+    tokio::spawn({
+        let recorder_context = recorder_context.clone();
+        async move {
+            if let Err(err) = recorder_context.resume_uploads().await {
+                log::error!("unable to resume uploads, {err:?}");
+            }
+        }
+    });
+
+    if let Some(http_settings) = recorder_context.settings.http.clone() {
+        tokio::spawn({
+            let recorder_context = recorder_context.clone();
+            let shutdown_rx = shutdown_rx.clone();
+            async move {
+                if let Err(err) = server::run(recorder_context, &http_settings, shutdown_rx).await {
+                    log::error!("{err:?}");
+                }
+            }
+        });
+    }
+
     while !*shutdown_rx.borrow() {
         select! {
             result = shutdown_rx.changed() => {
                 result?;
             }
-            _ = rmq_session(&recorder_context, &mut tasks) => {}
+            _ = rmq_session(&recorder_context, &mut tasks) => {
+                recorder_context.status.set_ready(false);
+            }
         }
     }
     tasks.retain(|task| !task.is_finished());
@@ -0,0 +1,123 @@
+// SPDX-FileCopyrightText: OpenTalk GmbH <mail@opentalk.eu>
+//
+// SPDX-License-Identifier: EUPL-1.2
+
+//! Prometheus metrics of the recorder.
+
+use anyhow::{Context, Result};
+use compositor::Statistics;
+use prometheus::{Encoder, Histogram, HistogramOpts, IntCounter, IntGauge, Registry, TextEncoder};
+
+/// Bucket boundaries of the upload durations in seconds
+const UPLOAD_DURATION_BUCKETS: &[f64] = &[1.0, 5.0, 15.0, 60.0, 300.0, 900.0, 3600.0];
+
+/// Metrics of all sessions and uploads of the recorder
+#[derive(Debug)]
+pub struct Metrics {
+    registry: Registry,
+    /// Number of running recording sessions
+    pub sessions: IntGauge,
+    /// Frames dropped within the pipelines of all sessions
+    pub dropped_frames: IntCounter,
+    /// Errors within the pipelines of all sessions
+    pub pipeline_errors: IntCounter,
+    /// Duration of successful uploads
+    pub upload_duration: Histogram,
+    /// Uploads which have been left in the spool directory
+    pub upload_failures: IntCounter,
+    /// Recording requests which have been put back into the queue over capacity
+    pub requeued_requests: IntCounter,
+}
+
+impl Metrics {
+    #[must_use]
+    pub fn new() -> Self {
+        let registry = Registry::new_custom(Some("recorder".to_string()), None)
+            .expect("the namespace is valid");
+
+        let sessions = IntGauge::new("sessions", "Number of running recording sessions")
+            .expect("the metric is valid");
+        let dropped_frames = IntCounter::new(
+            "dropped_frames_total",
+            "Frames dropped within the pipelines of all sessions",
+        )
+        .expect("the metric is valid");
+        let pipeline_errors = IntCounter::new(
+            "pipeline_errors_total",
+            "Errors within the pipelines of all sessions",
+        )
+        .expect("the metric is valid");
+        let upload_duration = Histogram::with_opts(
+            HistogramOpts::new(
+                "upload_duration_seconds",
+                "Duration of successful recording uploads",
+            )
+            .buckets(UPLOAD_DURATION_BUCKETS.to_vec()),
+        )
+        .expect("the metric is valid");
+        let upload_failures = IntCounter::new(
+            "upload_failures_total",
+            "Recording uploads which have been left in the spool directory",
+        )
+        .expect("the metric is valid");
+        let requeued_requests = IntCounter::new(
+            "requeued_requests_total",
+            "Recording requests which have been put back into the queue over capacity",
+        )
+        .expect("the metric is valid");
+
+        for collector in [
+            Box::new(sessions.clone()) as Box<dyn prometheus::core::Collector>,
+            Box::new(dropped_frames.clone()),
+            Box::new(pipeline_errors.clone()),
+            Box::new(upload_duration.clone()),
+            Box::new(upload_failures.clone()),
+            Box::new(requeued_requests.clone()),
+        ] {
+            registry
+                .register(collector)
+                .expect("every metric is registered once");
+        }
+
+        Self {
+            registry,
+            sessions,
+            dropped_frames,
+            pipeline_errors,
+            upload_duration,
+            upload_failures,
+            requeued_requests,
+        }
+    }
+
+    /// Add the statistics of a session's pipelines which have not been reported yet.
+    pub fn report_statistics(&self, reported: &mut Statistics, current: Statistics) {
+        self.dropped_frames.inc_by(
+            current
+                .dropped_frames
+                .saturating_sub(reported.dropped_frames),
+        );
+        self.pipeline_errors.inc_by(
+            current
+                .pipeline_errors
+                .saturating_sub(reported.pipeline_errors),
+        );
+        *reported = current;
+    }
+
+    /// All metrics in the Prometheus text format.
+    pub fn encode(&self) -> Result<String> {
+        let mut buf = Vec::new();
+        TextEncoder::new()
+            .encode(&self.registry.gather(), &mut buf)
+            .context("unable to encode metrics")?;
+
+        String::from_utf8(buf).context("metrics are not valid UTF-8")
+    }
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,165 @@
+// SPDX-FileCopyrightText: OpenTalk GmbH <mail@opentalk.eu>
+//
+// SPDX-License-Identifier: EUPL-1.2
+
+//! Post-processing of finished recordings with ffmpeg before they are uploaded.
+//!
+//! Recordings are remuxed so browsers can play them while they are downloaded, probed for their
+//! duration and resolution and a thumbnail is extracted from their video.
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::{path::Path, process::Output};
+use tokio::{fs, process::Command};
+
+use crate::settings::{Mp4Layout, PostprocessingSettings};
+
+/// Duration and resolution of a recording
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MediaMetadata {
+    pub duration_ms: u64,
+    /// Width of the video, `None` if the recording has no video
+    pub width: Option<u32>,
+    /// Height of the video, `None` if the recording has no video
+    pub height: Option<u32>,
+}
+
+impl MediaMetadata {
+    /// Returns `true` if the recording contains a video.
+    #[must_use]
+    pub fn has_video(&self) -> bool {
+        self.width.is_some() && self.height.is_some()
+    }
+}
+
+/// Output of `ffprobe -show_format -show_streams`
+#[derive(Deserialize)]
+struct Probe {
+    format: ProbeFormat,
+    #[serde(default)]
+    streams: Vec<ProbeStream>,
+}
+
+#[derive(Deserialize)]
+struct ProbeFormat {
+    /// Duration in seconds, ffprobe prints it as string
+    duration: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ProbeStream {
+    codec_type: String,
+    width: Option<u32>,
+    height: Option<u32>,
+}
+
+/// Remux the MP4 file in place without encoding it again.
+pub async fn remux(recording: &Path, layout: Mp4Layout) -> Result<()> {
+    let movflags = match layout {
+        Mp4Layout::Faststart => "+faststart",
+        Mp4Layout::Fragmented => "+frag_keyframe+empty_moov+default_base_moof",
+    };
+
+    // the file is replaced at once, an interrupted remux leaves the recording untouched
+    let remuxed = recording.with_extension("remux.mp4");
+    run(Command::new("ffmpeg")
+        .args(["-nostdin", "-y", "-v", "error", "-i"])
+        .arg(recording)
+        .args(["-map", "0", "-c", "copy", "-movflags", movflags])
+        .arg(&remuxed))
+    .await?;
+
+    fs::rename(&remuxed, recording)
+        .await
+        .with_context(|| format!("unable to replace {recording:?} with the remuxed file"))
+}
+
+/// Read the duration and the resolution of the first video of a recording.
+pub async fn probe(recording: &Path) -> Result<MediaMetadata> {
+    let output = run(Command::new("ffprobe")
+        .args([
+            "-v",
+            "error",
+            "-print_format",
+            "json",
+            "-show_format",
+            "-show_streams",
+        ])
+        .arg(recording))
+    .await?;
+
+    let probe: Probe =
+        serde_json::from_slice(&output.stdout).context("unable to parse ffprobe output")?;
+
+    let duration: f64 = probe
+        .format
+        .duration
+        .context("recording has no duration")?
+        .parse()
+        .context("invalid duration")?;
+
+    let video = probe
+        .streams
+        .iter()
+        .find(|stream| stream.codec_type == "video");
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let duration_ms = (duration.max(0.0) * 1000.0).round() as u64;
+
+    Ok(MediaMetadata {
+        duration_ms,
+        width: video.and_then(|video| video.width),
+        height: video.and_then(|video| video.height),
+    })
+}
+
+/// Write a JPEG of a single frame of the recording's video.
+///
+/// The frame is taken at the configured position, at most at the middle of short recordings.
+pub async fn extract_thumbnail(
+    recording: &Path,
+    thumbnail: &Path,
+    metadata: &MediaMetadata,
+    settings: &PostprocessingSettings,
+) -> Result<()> {
+    #[allow(clippy::cast_precision_loss)]
+    let middle = metadata.duration_ms as f64 / 2000.0;
+    let position = settings.thumbnail_position.min(middle).max(0.0);
+
+    run(Command::new("ffmpeg")
+        .args(["-nostdin", "-y", "-v", "error", "-ss"])
+        .arg(format!("{position:.3}"))
+        .arg("-i")
+        .arg(recording)
+        .args(["-frames:v", "1", "-q:v", "3", "-vf"])
+        .arg(format!("scale={}:-2", settings.thumbnail_width))
+        .arg(thumbnail))
+    .await?;
+
+    Ok(())
+}
+
+/// Run a command to its end, failing with its error output if it does not succeed.
+async fn run(command: &mut Command) -> Result<Output> {
+    let program = command
+        .as_std()
+        .get_program()
+        .to_string_lossy()
+        .into_owned();
+
+    let output = command
+        .kill_on_drop(true)
+        .output()
+        .await
+        .with_context(|| format!("unable to run {program}"))?;
+
+    if !output.status.success() {
+        bail!(
+            "{program} failed with {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    Ok(output)
+}
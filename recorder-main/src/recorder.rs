@@ -2,39 +2,51 @@
 //
 // SPDX-License-Identifier: EUPL-1.2
 
-use anyhow::{bail, Context as ErrorContext, Result};
-use bytes::Bytes;
+use anyhow::{bail, Context, Result};
 use compositor::{
-    MatroskaSink, MediaSessionType, Mp4Parameters, Mp4Sink, RTMPParameters, RTMPSink, StreamId,
-    SystemSink, WebRtcSourceParams,
+    AnyLayout, HlsParameters, HlsSink, MatroskaSink, MediaSessionType, Mp4Parameters, Mp4Sink,
+    OutputProfile, RTMPParameters, RTMPSink, SpeedPreset, SrtParameters, SrtSink, Statistics,
+    StreamId, SystemSink, Theme, WebRtcSourceParams, WhipParameters, WhipSink,
 };
-use core::{
-    pin::Pin,
-    task::{ready, Context, Poll},
-};
-use futures::Stream;
 use log::error;
-use std::{io, path::Path, sync::Arc};
+use std::{
+    path::PathBuf,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 use tempfile::TempDir;
 use tokio::{
-    fs::File,
-    io::{AsyncRead, ReadBuf},
     sync::{mpsc, watch},
     task::{spawn_blocking, JoinHandle},
+    time::interval,
 };
+use uuid::Uuid;
 
 use crate::{
-    http::HttpClient,
+    http::{HttpClient, RenderKind},
+    metrics::Metrics,
     rmq::StartRecording,
     settings::{RecorderSettings, RecorderSink, Settings},
     signaling::{
-        incoming::MediaSessionState, media_types, Event, ParticipantId, Signaling, TrickleCandidate,
+        incoming::{
+            MediaSessionState, RecordingScope, StreamStatus, StreamingKind, StreamingTarget,
+        },
+        media_types, Event, ParticipantId, Signaling, TrickleCandidate,
     },
+    status::{SessionQuery, SessionStatus, SinkStatus, Status},
+    timeline::{EntryKind, Timeline},
+    upload::SpooledUpload,
 };
 
 // TODO; make this configurable
 pub const MAX_VISIBLES: usize = 8;
 
+/// Name of the sink writing the recording file
+const MP4_SINK: &str = "mp4";
+
+/// Interval in which the pipeline statistics of a session are added to the metrics
+const STATISTICS_INTERVAL: Duration = Duration::from_secs(10);
+
 type Talk = compositor::Talk<compositor::WebRtcSource, ParticipantId>;
 
 #[derive(Clone, Debug)]
@@ -42,6 +54,9 @@ pub struct Recorder {
     pub settings: Arc<Settings>,
     pub http_client: Arc<HttpClient>,
     pub shutdown: watch::Receiver<bool>,
+    /// Readiness and running sessions exposed by the HTTP server
+    pub status: Arc<Status>,
+    pub metrics: Arc<Metrics>,
 }
 
 impl Recorder {
@@ -55,9 +70,32 @@ impl Recorder {
             settings: Arc::new(settings),
             http_client: Arc::new(http_client),
             shutdown,
+            status: Arc::default(),
+            metrics: Arc::default(),
         }
     }
 
+    /// Returns `true` if another session fits into the configured limits.
+    #[must_use]
+    pub fn has_capacity(&self) -> bool {
+        let limits = &self.settings.limits;
+        let sessions = self.status.session_count();
+
+        if limits
+            .max_sessions
+            .is_some_and(|max_sessions| sessions >= max_sessions)
+        {
+            return false;
+        }
+
+        #[allow(clippy::cast_precision_loss)]
+        let estimated_cpu = (sessions + 1) as f64 * limits.session_cpu_cost;
+
+        limits
+            .cpu_budget
+            .map_or(true, |cpu_budget| estimated_cpu <= cpu_budget)
+    }
+
     pub async fn spawn_session(&self, command: StartRecording) -> Result<JoinHandle<Result<()>>> {
         let context = Arc::new(self.clone());
         log::debug!("Start Recording session {command:?}");
@@ -65,6 +103,11 @@ impl Recorder {
             .await
             .context("recording session failed to start")?;
 
+        let session_id = session.session_id;
+        self.status
+            .add_session(session_id, &session.room_id, session.query_sender.clone());
+
+        let status = self.status.clone();
         let recording_task = tokio::spawn(async move {
             if let Err(ref recording_err) = session.run().await {
                 error!(
@@ -72,6 +115,7 @@ impl Recorder {
                     recording_err
                 );
             };
+            status.remove_session(session_id);
             session.upload().await.context("recording upload failed")?;
 
             Ok(())
@@ -80,18 +124,47 @@ impl Recorder {
         Ok(recording_task)
     }
 
-    pub async fn upload(&self, room_id: &str, recording_path: &Path) -> Result<()> {
-        let file = File::open(recording_path).await?;
+    /// Post-process and upload a spooled recording, it is kept in the spool directory if the
+    /// upload fails
+    pub async fn upload(&self, mut upload: SpooledUpload) -> Result<()> {
+        upload
+            .postprocess(&self.settings.postprocessing)
+            .await
+            .context("unable to post-process recording")?;
 
-        log::debug!(
-            "upload mp4 file '{:?}' for room: {}",
-            recording_path,
-            room_id
-        );
+        log::debug!("upload recording for room: {}", upload.room_id());
 
-        self.http_client
-            .upload_render(&self.settings.controller, room_id, FileReadStream { file })
-            .await
+        let start = Instant::now();
+        let result = upload
+            .upload(
+                &self.http_client,
+                &self.settings.controller,
+                &self.settings.upload,
+            )
+            .await;
+
+        match result {
+            Ok(()) => self
+                .metrics
+                .upload_duration
+                .observe(start.elapsed().as_secs_f64()),
+            Err(_) => self.metrics.upload_failures.inc(),
+        }
+
+        result
+    }
+
+    /// Resume the uploads left in the spool directory by a previous run
+    pub async fn resume_uploads(&self) -> Result<()> {
+        for upload in SpooledUpload::pending(&self.settings.upload.spool_dir).await? {
+            log::info!("Resume upload of recording for room '{}'", upload.room_id());
+
+            if let Err(err) = self.upload(upload).await {
+                error!("{err:?}");
+            }
+        }
+
+        Ok(())
     }
 }
 
@@ -101,14 +174,30 @@ pub struct RecordingSession {
 
     signaling: Signaling,
 
+    /// Distinguishes concurrent sessions of the same room
+    session_id: Uuid,
     room_id: String,
     temp_dir: TempDir,
 
     talk: Talk,
 
+    /// Speakers and screen shares within the running recording
+    timeline: Timeline,
+    /// Participants whose media is composed while the recording is running
+    scope: RecordingScope,
+
     candidate_receiver: mpsc::Receiver<(StreamId<ParticipantId>, u32, Option<String>)>,
     candidate_sender: mpsc::Sender<(StreamId<ParticipantId>, u32, Option<String>)>,
 
+    /// Status queries of the HTTP server
+    query_receiver: mpsc::Receiver<SessionQuery>,
+    query_sender: mpsc::Sender<SessionQuery>,
+
+    /// Time the session has been started
+    started: Instant,
+    /// Pipeline statistics which have been added to the metrics
+    reported_statistics: Statistics,
+
     done: bool,
 }
 
@@ -126,14 +215,23 @@ impl RecordingSession {
         candidate_sender: mpsc::Sender<(StreamId<ParticipantId>, u32, Option<String>)>,
         done: bool,
     ) -> Self {
+        let (query_sender, query_receiver) = mpsc::channel(4);
+
         Self {
             service_context,
             signaling,
+            session_id: Uuid::new_v4(),
             room_id,
             temp_dir,
             talk,
+            timeline: Timeline::default(),
+            scope: RecordingScope::default(),
             candidate_receiver,
             candidate_sender,
+            query_receiver,
+            query_sender,
+            started: Instant::now(),
+            reported_statistics: Statistics::default(),
             done,
         }
     }
@@ -150,28 +248,40 @@ impl RecordingSession {
         .await?;
 
         let temp_dir = TempDir::new()?;
-        let file_path = temp_dir.path().join("out.mp4");
 
         let (candidate_sender, candidate_receiver) = mpsc::channel(12);
+        let (query_sender, query_receiver) = mpsc::channel(4);
 
-        let recorder_settings = service_context.settings.recorder.as_ref();
-        let recorder_sinks = recorder_settings
-            .unwrap_or(&RecorderSettings { sinks: vec![] })
-            .sinks
-            .clone();
+        let recorder_settings = service_context
+            .settings
+            .recorder
+            .clone()
+            .unwrap_or_default();
 
-        let mut talk = Talk::new(
+        let mut talk = Talk::new_with_loudness(
             compositor::Size::FHD,
-            compositor::layout::Speaker::default(),
+            recorder_settings.layout,
             MAX_VISIBLES,
             true,
+            recorder_settings.loudness,
         )?;
+        talk.show_placeholders(recorder_settings.placeholders);
 
-        for (index, sink) in recorder_sinks.into_iter().enumerate() {
+        if let Some(theme) = command.theme {
+            match serde_json::from_value::<Theme>(theme) {
+                Ok(theme) => talk.set_theme(theme).context("unable to set theme")?,
+                Err(err) => log::warn!("Ignoring invalid theme, {err}"),
+            }
+        }
+
+        for (index, sink) in recorder_settings.sinks.into_iter().enumerate() {
             let tag = match sink {
                 RecorderSink::Display => "Display",
+                RecorderSink::Hls(_) => "HLS",
                 RecorderSink::Matroska(_) => "Matroska",
                 RecorderSink::Rtmp(_) => "RTMP",
+                RecorderSink::Srt(_) => "SRT",
+                RecorderSink::Whip(_) => "WHIP",
             };
             let name = format!("{tag}-Sink-{index}");
             match sink {
@@ -183,6 +293,23 @@ impl RecordingSession {
                     )
                     .context("unable to link sink to talk")?;
                 }
+                RecorderSink::Hls(hls_parameters) => {
+                    let output_dir = hls_parameters.output_dir.as_ref().map(|output_dir| {
+                        PathBuf::from(output_dir.to_string_lossy().replace("$room", &command.room))
+                    });
+                    talk.link_sink(
+                        name.as_str(),
+                        HlsSink::create(
+                            name.as_str(),
+                            HlsParameters {
+                                output_dir,
+                                ..hls_parameters
+                            },
+                        )
+                        .context("HlsSink could not created")?,
+                    )
+                    .context("unable to link sink to talk")?;
+                }
                 RecorderSink::Matroska(matroska_parameters) => {
                     talk.link_sink(
                         name.as_str(),
@@ -206,39 +333,62 @@ impl RecordingSession {
                     )
                     .context("unable to link sink to talk")?;
                 }
+                RecorderSink::Srt(srt_parameters) => {
+                    talk.link_sink(
+                        name.as_str(),
+                        SrtSink::create(
+                            name.as_str(),
+                            SrtParameters {
+                                stream_id: srt_parameters
+                                    .stream_id
+                                    .as_ref()
+                                    .map(|stream_id| stream_id.replace("$room", &command.room)),
+                                ..srt_parameters.clone()
+                            },
+                        )
+                        .context("SrtSink could not created")?,
+                    )
+                    .context("unable to link sink to talk")?;
+                }
+                RecorderSink::Whip(whip_parameters) => {
+                    talk.link_sink(
+                        name.as_str(),
+                        WhipSink::create(
+                            name.as_str(),
+                            WhipParameters {
+                                endpoint: whip_parameters.endpoint.replace("$room", &command.room),
+                                ..whip_parameters.clone()
+                            },
+                        )
+                        .context("WhipSink could not created")?,
+                    )
+                    .context("unable to link sink to talk")?;
+                }
             }
         }
 
-        talk.link_sink(
-            "mp4",
-            Mp4Sink::create(
-                "MP4-Sink",
-                &Mp4Parameters {
-                    file_path: file_path
-                        .to_str()
-                        .context("failed to convert MP4 file path into string")?
-                        .into(),
-                    name: "Recording",
-                },
-            )
-            .context("MP4-Sink could not created")?,
-        )
-        .context("unable to link sink to talk")?;
-
         Ok(Self {
             service_context,
             signaling,
+            session_id: Uuid::new_v4(),
             room_id: command.room,
             temp_dir,
             talk,
+            timeline: Timeline::default(),
+            scope: RecordingScope::default(),
             candidate_receiver,
             candidate_sender,
+            query_receiver,
+            query_sender,
+            started: Instant::now(),
+            reported_statistics: Statistics::default(),
             done: false,
         })
     }
 
     pub async fn run(&mut self) -> Result<()> {
         let mut shutdown_rx = self.service_context.shutdown.clone();
+        let mut statistics_interval = interval(STATISTICS_INTERVAL);
 
         while !self.done {
             tokio::select! {
@@ -253,6 +403,12 @@ impl RecordingSession {
                     };
                     self.handle_candidate(stream_id, mline, candidate).await?;
                 }
+                Some(query) = self.query_receiver.recv() => {
+                    self.handle_query(query);
+                }
+                _ = statistics_interval.tick() => {
+                    self.report_statistics();
+                }
                 result = shutdown_rx.changed() => {
                     if result.is_err() {
                         return result.context("failed to listen to shutdown signal");
@@ -282,7 +438,14 @@ impl RecordingSession {
         )?;
         self.signaling.start_subscribe(stream_id).await?;
 
-        if media_state.video {
+        if stream_id.media_type == MediaSessionType::ScreenCapture {
+            if let Some(position) = self.recording_position() {
+                self.timeline
+                    .start(EntryKind::ScreenShare, stream_id.id, display_name, position);
+            }
+        }
+
+        if media_state.video || self.talk.has_placeholder(&stream_id) {
             self.talk
                 .show_stream(&stream_id)
                 .context("unable to show stream for stream_id '{stream_id}'")?;
@@ -291,41 +454,85 @@ impl RecordingSession {
         Ok(())
     }
 
+    /// Subscribe, update or unsubscribe the streams of a participant, following what the
+    /// participant publishes and the recording scope.
+    ///
+    /// Participants who do not consent to be recorded are represented by a placeholder if
+    /// placeholders are enabled.
+    async fn update_streams(&mut self, id: ParticipantId) -> Result<()> {
+        let participant_state = self.signaling.participant(&id)?.clone();
+
+        let placeholder_id = StreamId::camera(id);
+        let wants_placeholder =
+            self.talk.shows_placeholders() && participant_state.shows_placeholder(id, &self.scope);
+        if self.talk.is_placeholder(&placeholder_id) && !wants_placeholder {
+            log::debug!("remove placeholder of {id}");
+            self.talk.remove_stream(placeholder_id)?;
+        }
+
+        for media_type in media_types() {
+            let stream_id = StreamId::new(id, media_type);
+            let is_subscribed =
+                self.talk.contains_stream(&stream_id) && !self.talk.is_placeholder(&stream_id);
+            let media_state = participant_state.recorded(id, media_type, &self.scope);
+
+            match (is_subscribed, media_state) {
+                (false, Some(media_state)) => {
+                    log::debug!("subscribe stream of {id} {media_type}");
+                    self.subscribe(stream_id, &participant_state.display_name, media_state)
+                        .await?;
+                }
+                (true, None) => {
+                    log::debug!("unsubscribe stream of {id} {media_type}");
+                    self.unsubscribe(stream_id)?;
+                }
+                (true, Some(media_state)) => {
+                    log::debug!("update status of stream of {id} {media_type} to {media_state}");
+                    self.talk.set_status(&stream_id, &media_state.into())?;
+                }
+                (false, None) => {}
+            }
+        }
+
+        if wants_placeholder && !self.talk.contains_stream(&placeholder_id) {
+            log::debug!("add placeholder of {id}");
+            self.talk
+                .add_placeholder(placeholder_id, &participant_state.display_name)?;
+        }
+
+        Ok(())
+    }
+
+    /// Compose only the streams within the given scope.
+    async fn set_scope(&mut self, scope: RecordingScope) -> Result<()> {
+        if self.scope == scope {
+            return Ok(());
+        }
+
+        log::debug!("Set recording scope to {scope:?}");
+        self.scope = scope;
+
+        let participant_ids: Vec<ParticipantId> =
+            self.signaling.participants().keys().copied().collect();
+
+        for id in participant_ids {
+            self.update_streams(id).await?;
+        }
+
+        Ok(())
+    }
+
     // TODO: This makes no sense at the current state, docs will be created after some major refactoring.
     #[allow(clippy::too_many_lines)]
     async fn handle_signaling_event(&mut self, event: Event) -> Result<()> {
         match event {
             Event::JoinSuccess(_id, title) => {
-                // find all active media streams
-
-                let available_media_streams: Vec<(
-                    ParticipantId,
-                    String,
-                    MediaSessionType,
-                    MediaSessionState,
-                )> = self
-                    .signaling
-                    .participants()
-                    .iter()
-                    .flat_map(|(id, participant_state)| {
-                        media_types().filter_map(|media_type| {
-                            participant_state.publishes(media_type).map(|media_state| {
-                                (
-                                    *id,
-                                    participant_state.display_name.clone(),
-                                    media_type,
-                                    media_state,
-                                )
-                            })
-                        })
-                    })
-                    .collect();
-
-                for (id, display_name, media_type, media_state) in available_media_streams {
-                    log::debug!("JoinSuccess: subscribe stream of {id} {media_type}");
-                    let stream_id = StreamId::new(id, media_type);
-                    self.subscribe(stream_id, &display_name, media_state)
-                        .await?;
+                // subscribe all active media streams
+                let participant_ids: Vec<ParticipantId> =
+                    self.signaling.participants().keys().copied().collect();
+
+                for id in participant_ids {
+                    self.update_streams(id).await?;
                 }
 
                 self.talk
@@ -335,62 +542,29 @@ impl RecordingSession {
 
             Event::ParticipantJoined(id) => {
                 log::debug!("Event::ParticipantJoined");
-
-                let participant_state = self.signaling.participant(&id)?.clone();
-                let available_media_streams = media_types().filter_map(|media_type| {
-                    participant_state
-                        .publishes(media_type)
-                        .map(|media_state| (media_type, media_state))
-                });
-
-                for (media_type, media_state) in available_media_streams {
-                    log::debug!("Join: subscribe stream of {id} {media_type}");
-                    let stream_id = StreamId::new(id, media_type);
-                    self.subscribe(stream_id, &participant_state.display_name, media_state)
-                        .await?;
-                }
+                self.update_streams(id).await?;
             }
             Event::ParticipantUpdated(id) => {
                 log::debug!("Event::ParticipantUpdated");
-                let participant_state = self.signaling.participant(&id)?.clone();
 
-                for media_type in media_types() {
-                    let is_subscribed = self.talk.contains_stream(&StreamId::new(id, media_type));
-                    let media_state = participant_state.publishes(media_type);
-
-                    if !is_subscribed {
-                        if let Some(media_state) = media_state {
-                            log::debug!("Update: subscribe stream of {id} {media_type}");
-                            let stream_id = StreamId::new(id, media_type);
-                            self.subscribe(stream_id, &participant_state.display_name, media_state)
-                                .await?;
-                        }
-                    } else if media_state.is_none() {
-                        log::debug!("Update: unsubscribe stream of {id} {media_type}");
-                        self.talk.remove_stream(StreamId::new(id, media_type))?;
-                    } else if let Some(media_state) = media_state {
-                        log::debug!(
-                            "Update: update status of stream of {id} {media_type} to {media_state}"
-                        );
-                        self.talk
-                            .set_status(&StreamId::new(id, media_type), &media_state.into())?;
-                    } else {
-                        log::trace!(
-                            "ignore update for {id}: media_state ({media_state:?}) == is_subscribed ({is_subscribed})"
-                        );
-                        return Ok(());
+                if !self.signaling.participant(&id)?.consents {
+                    if let Some(position) = self.recording_position() {
+                        self.timeline.end_participant(id, position);
                     }
                 }
 
-                return Ok(());
+                self.update_streams(id).await?;
             }
             Event::ParticipantLeft(id) => {
                 log::debug!("Event::ParticipantLeft");
                 for media_type in media_types() {
                     if self.talk.contains_stream(&StreamId::new(id, media_type)) {
-                        self.talk.remove_stream(StreamId::new(id, media_type))?;
+                        self.unsubscribe(StreamId::new(id, media_type))?;
                     }
                 }
+                if let Some(position) = self.recording_position() {
+                    self.timeline.end_participant(id, position);
+                }
                 if self.signaling.participants().is_empty() {
                     self.done = true;
                     log::debug!("Last participant left the session. Stop recording.");
@@ -443,19 +617,339 @@ impl RecordingSession {
                         .set_speaker(speaker)
                         .context("unable to set speaker for '{speaker}'")?;
                 } else {
-                    self.talk.unset_speaker();
+                    self.talk
+                        .unset_speaker()
+                        .context("unable to unset speaker")?;
+                }
+                if let Some(position) = self.recording_position() {
+                    self.timeline_speaker(focus_change, position);
                 }
             }
             Event::MediaConnectionError(error) => {
                 log::debug!("Event::MediaConnectionError");
                 log::warn!("Skipping media connection error: {:?}", error);
             }
+            Event::RecordingStarted(scope) => {
+                log::debug!("Event::RecordingStarted");
+                if self.talk.has_sink(MP4_SINK) {
+                    log::warn!("Recording is already running");
+                } else {
+                    self.set_scope(scope).await?;
+                    self.start_recording()?;
+                }
+            }
+            Event::RecordingStopped => {
+                log::debug!("Event::RecordingStopped");
+                if self.talk.has_sink(MP4_SINK) {
+                    // Livestreams may continue, only finish and upload the recording file
+                    self.finish_timeline();
+                    let sink = self
+                        .talk
+                        .release_sink(&MP4_SINK.to_string())
+                        .context("unable to release recording sink")?;
+                    // waits for ffmpeg to finish the recording file
+                    spawn_blocking(move || drop(sink)).await?;
+                    // Livestreams show all participants again
+                    self.set_scope(RecordingScope::All).await?;
+                    let upload = Self::spool(
+                        &self.service_context,
+                        &self.room_id,
+                        &self.temp_dir,
+                        &self.timeline,
+                    )
+                    .await?;
+
+                    // the session continues while the recording is uploaded
+                    let service_context = self.service_context.clone();
+                    tokio::spawn(async move {
+                        if let Err(err) = service_context.upload(upload).await {
+                            error!("recording upload failed {err:?}");
+                        }
+                    });
+                } else {
+                    log::warn!("Recording is not running");
+                }
+            }
+            Event::RecordingPaused => {
+                log::debug!("Event::RecordingPaused");
+                if !self.talk.has_sink(MP4_SINK) || self.talk.is_sink_paused(MP4_SINK) {
+                    log::warn!("Recording is not running or already paused");
+                } else {
+                    // Keep the talk and its sinks alive, only stop feeding the recording
+                    self.talk
+                        .pause_sink(MP4_SINK)
+                        .context("unable to pause recording")?;
+                }
+            }
+            Event::RecordingResumed => {
+                log::debug!("Event::RecordingResumed");
+                if self.talk.is_sink_paused(MP4_SINK) {
+                    self.talk
+                        .resume_sink(MP4_SINK)
+                        .context("unable to resume recording")?;
+                } else {
+                    log::warn!("Recording is not paused");
+                }
+            }
+            Event::StartStreams(targets) => {
+                log::debug!("Event::StartStreams");
+                for target in targets {
+                    self.start_stream(&target)?;
+                }
+            }
+            Event::StreamUpdated(target_id, status) => {
+                log::debug!("Event::StreamUpdated {target_id} {status:?}");
+                let name = stream_sink_name(target_id);
+
+                if !self.talk.has_sink(&name) {
+                    log::trace!("ignore update for stream {target_id} without sink");
+                    return Ok(());
+                }
+
+                match status {
+                    StreamStatus::Starting => {}
+                    StreamStatus::Active => {
+                        if self.talk.is_sink_paused(&name) {
+                            self.talk
+                                .resume_sink(&name)
+                                .context("unable to resume stream")?;
+                        }
+                    }
+                    StreamStatus::Paused => {
+                        if !self.talk.is_sink_paused(&name) {
+                            self.talk
+                                .pause_sink(&name)
+                                .context("unable to pause stream")?;
+                        }
+                    }
+                    StreamStatus::Inactive => {
+                        let sink = self
+                            .talk
+                            .release_sink(&name)
+                            .context("unable to release stream")?;
+                        spawn_blocking(move || drop(sink));
+                    }
+                }
+            }
+            Event::SetLayout(layout) => {
+                log::debug!("Event::SetLayout");
+                match serde_json::from_value::<AnyLayout>(layout) {
+                    Ok(layout) => self
+                        .talk
+                        .change_layout(layout)
+                        .context("unable to change layout")?,
+                    Err(err) => log::warn!("Ignoring invalid layout, {err}"),
+                }
+            }
+            Event::ChatMessage(sender, content) => {
+                log::debug!("Event::ChatMessage");
+                match self.signaling.participants().get(&sender) {
+                    Some(participant) => self
+                        .talk
+                        .add_chat_message(&participant.display_name, &content),
+                    None => log::warn!("Ignoring chat message of unknown participant {sender}"),
+                }
+            }
             Event::Close => self.done = true,
         }
 
         Ok(())
     }
 
+    /// Output profile from the recorder settings, the default profile if none are configured
+    fn output_profile(&self, profile: fn(&RecorderSettings) -> &OutputProfile) -> OutputProfile {
+        self.service_context
+            .settings
+            .recorder
+            .as_ref()
+            .map(|settings| profile(settings).clone())
+            .unwrap_or_default()
+    }
+
+    /// Link the MP4 sink which writes the recording file
+    fn start_recording(&mut self) -> Result<()> {
+        let file_path = self.temp_dir.path().join("out.mp4");
+
+        self.talk
+            .link_sink(
+                MP4_SINK,
+                Mp4Sink::create(
+                    "MP4-Sink",
+                    &Mp4Parameters {
+                        file_path: file_path
+                            .to_str()
+                            .context("failed to convert MP4 file path into string")?
+                            .into(),
+                        name: "Recording",
+                        profile: self.output_profile(|settings| &settings.recording_profile),
+                        tracks: self
+                            .service_context
+                            .settings
+                            .recorder
+                            .as_ref()
+                            .map_or(0, |settings| settings.recording_tracks),
+                    },
+                )
+                .context("MP4-Sink could not created")?,
+            )
+            .context("unable to link sink to talk")?;
+
+        self.start_timeline();
+
+        Ok(())
+    }
+
+    fn handle_query(&self, query: SessionQuery) {
+        match query {
+            SessionQuery::Status(sender) => {
+                let _ = sender.send(self.status());
+            }
+            SessionQuery::Dot(sender) => {
+                self.talk.dot(
+                    &format!("session-{}-{}", self.room_id, self.session_id),
+                    &compositor::debug::Params::all(),
+                );
+                let _ = sender.send(());
+            }
+        }
+    }
+
+    fn status(&self) -> SessionStatus {
+        let sinks = match self.talk.sinks() {
+            Ok(sinks) => sinks.into_iter().map(SinkStatus::from).collect(),
+            Err(err) => {
+                log::warn!("Unable to get the state of the sinks, {err}");
+                Vec::new()
+            }
+        };
+
+        SessionStatus {
+            session_id: self.session_id,
+            room_id: self.room_id.clone(),
+            duration: self.started.elapsed().as_secs_f64(),
+            recording: self.talk.has_sink(MP4_SINK),
+            sinks,
+        }
+    }
+
+    /// Add the pipeline statistics since the last report to the metrics.
+    fn report_statistics(&mut self) {
+        self.service_context
+            .metrics
+            .report_statistics(&mut self.reported_statistics, self.talk.statistics());
+    }
+
+    /// Position within the running recording, `None` if nothing is recorded
+    fn recording_position(&self) -> Option<Duration> {
+        if !self.talk.has_sink(MP4_SINK) {
+            return None;
+        }
+
+        match self.talk.sink_position(MP4_SINK) {
+            Ok(position) => Some(Duration::from_nanos(position.nseconds())),
+            Err(err) => {
+                log::warn!("Unable to get position within the recording, {err}");
+                None
+            }
+        }
+    }
+
+    /// Begin a new timeline with the current speaker and screen shares
+    fn start_timeline(&mut self) {
+        self.timeline = Timeline::default();
+
+        let screen_shares: Vec<(ParticipantId, String)> = self
+            .signaling
+            .participants()
+            .iter()
+            .filter(|(id, _)| {
+                self.talk
+                    .contains_stream(&StreamId::new(**id, MediaSessionType::ScreenCapture))
+            })
+            .map(|(id, participant_state)| (*id, participant_state.display_name.clone()))
+            .collect();
+
+        for (id, display_name) in screen_shares {
+            self.timeline
+                .start(EntryKind::ScreenShare, id, &display_name, Duration::ZERO);
+        }
+
+        self.timeline_speaker(self.talk.get_current_speaker(), Duration::ZERO);
+    }
+
+    /// Note a speaker change within the timeline
+    ///
+    /// Speakers who do not consent to the recording or whose audio is outside the recording
+    /// scope are not part of the timeline.
+    fn timeline_speaker(&mut self, speaker: Option<ParticipantId>, position: Duration) {
+        let participant_state = speaker.and_then(|id| {
+            self.signaling
+                .participants()
+                .get(&id)
+                .filter(|participant_state| {
+                    participant_state
+                        .recorded(id, MediaSessionType::Camera, &self.scope)
+                        .is_some()
+                })
+                .map(|participant_state| (id, participant_state.display_name.clone()))
+        });
+
+        match participant_state {
+            Some((id, display_name)) => {
+                self.timeline
+                    .start(EntryKind::Speech, id, &display_name, position);
+            }
+            None => self.timeline.end_speech(position),
+        }
+    }
+
+    /// End the timeline at the current position, the recording sink has to be still linked
+    fn finish_timeline(&mut self) {
+        if let Some(position) = self.recording_position() {
+            self.timeline.finish(position);
+        }
+    }
+
+    /// Remove the stream and end its screen share within the timeline
+    fn unsubscribe(&mut self, stream_id: StreamId<ParticipantId>) -> Result<()> {
+        self.talk.remove_stream(stream_id)?;
+
+        if stream_id.media_type == MediaSessionType::ScreenCapture {
+            if let Some(position) = self.recording_position() {
+                self.timeline
+                    .end(EntryKind::ScreenShare, stream_id.id, position);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Link a RTMP sink which streams the conference to the given streaming target
+    fn start_stream(&mut self, target: &StreamingTarget) -> Result<()> {
+        let name = stream_sink_name(target.id);
+
+        if self.talk.has_sink(&name) {
+            log::warn!("Stream to target {} is already running", target.id);
+            return Ok(());
+        }
+
+        log::debug!("Start stream to target '{}' ({})", target.name, target.id);
+
+        self.talk
+            .link_sink(
+                &name,
+                RTMPSink::create(
+                    &name,
+                    rtmp_parameters(
+                        target,
+                        self.output_profile(|settings| &settings.stream_profile),
+                    ),
+                )
+                .context("RTMPSink could not created")?,
+            )
+            .context("unable to link sink to talk")
+    }
+
     /// Handle SDP candidates generated by us
     async fn handle_candidate(
         &mut self,
@@ -478,32 +972,125 @@ impl RecordingSession {
         }
     }
 
-    async fn upload(self) -> Result<()> {
+    async fn upload(mut self) -> Result<()> {
+        let recording = self.talk.has_sink(MP4_SINK);
+        self.finish_timeline();
+        self.report_statistics();
+
         let talk = self.talk;
         spawn_blocking(move || drop(talk)).await?;
 
-        let recording_path = self.temp_dir.path().join("out.mp4");
-
-        let Err(upload_err) = self
-            .service_context
-            .upload(&self.room_id, recording_path.as_ref())
-            .await
-        else {
-            log::debug!("Finished uploading recording for room '{}'", &self.room_id);
+        if !recording {
+            log::debug!("No running recording for room '{}'", &self.room_id);
             return Ok(());
+        }
+
+        let upload = Self::spool(
+            &self.service_context,
+            &self.room_id,
+            &self.temp_dir,
+            &self.timeline,
+        )
+        .await?;
+
+        self.service_context.upload(upload).await
+    }
+
+    /// Move the recording file with its chapters and speaker timeline into the spool directory,
+    /// the recording sink has to be released before
+    async fn spool(
+        service_context: &Recorder,
+        room_id: &str,
+        temp_dir: &TempDir,
+        timeline: &Timeline,
+    ) -> Result<SpooledUpload> {
+        let recording_path = temp_dir.path().join("out.mp4");
+
+        SpooledUpload::create(
+            &service_context.settings.upload.spool_dir,
+            room_id,
+            &recording_path,
+            Self::timeline_files(room_id, timeline),
+        )
+        .await
+        .context("unable to spool recording")
+    }
+
+    /// Chapters and speaker timeline which are uploaded linked to the recording
+    fn timeline_files(
+        room_id: &str,
+        timeline: &Timeline,
+    ) -> Vec<(&'static str, RenderKind, Vec<u8>)> {
+        if timeline.is_empty() {
+            log::debug!("No speakers or screen shares within the recording of room '{room_id}'");
+            return Vec::new();
+        }
+
+        let json = match timeline.to_json() {
+            Ok(json) => json,
+            Err(err) => {
+                error!("{err:?}");
+                return Vec::new();
+            }
         };
 
-        let dump_name = "DUMP.mp4";
-        error!(
-            "upload of file {:?} failed. Saving output in {dump_name}.",
-            recording_path
-        );
-        tokio::fs::copy(recording_path, dump_name).await?;
+        vec![
+            (
+                "chapters.vtt",
+                RenderKind::Chapters,
+                timeline.to_webvtt().into_bytes(),
+            ),
+            ("speaker_timeline.json", RenderKind::SpeakerTimeline, json),
+        ]
+    }
+}
+
+/// Encoder settings for a streaming target, following the recommendations of the platforms
+///
+/// Bitrate and speed preset of the given profile take precedence over the recommendations.
+fn rtmp_parameters(target: &StreamingTarget, profile: OutputProfile) -> RTMPParameters {
+    let (audio_bitrate, video_bitrate, video_speed_preset, keyframe_interval) = match target.kind {
+        StreamingKind::Custom => (None, None, None, None),
+        // YouTube recommends 4500-9000 kbit/s for 1080p30 and a keyframe every 2 seconds
+        StreamingKind::Youtube => (
+            Some(128_000),
+            Some(6000),
+            Some(SpeedPreset::Veryfast),
+            Some(2),
+        ),
+        // Twitch accepts at most 6000 kbit/s, 160 kbit/s AAC audio and a keyframe every 2 seconds
+        StreamingKind::Twitch => (
+            Some(160_000),
+            Some(4500),
+            Some(SpeedPreset::Veryfast),
+            Some(2),
+        ),
+        // Self hosted servers usually transcode the stream and have less bandwidth, short
+        // keyframe intervals keep their HLS segments small
+        StreamingKind::Owncast | StreamingKind::Peertube => (
+            Some(128_000),
+            Some(3000),
+            Some(SpeedPreset::Superfast),
+            Some(2),
+        ),
+    };
 
-        Err(upload_err)
+    RTMPParameters {
+        location: target.location(),
+        audio_bitrate,
+        audio_rate: None,
+        video_bitrate,
+        video_speed_preset,
+        keyframe_interval,
+        profile,
     }
 }
 
+/// Name of the sink streaming to the given target
+fn stream_sink_name(target_id: Uuid) -> String {
+    format!("RTMP-Stream-{target_id}")
+}
+
 fn stream_params(
     id: StreamId<ParticipantId>,
     sender: mpsc::Sender<(StreamId<ParticipantId>, u32, Option<String>)>,
@@ -512,29 +1099,3 @@ fn stream_params(
         let _ = sender.blocking_send((id, mline, candidate));
     })
 }
-
-pin_project_lite::pin_project! {
-    /// Helper struct which reads an opened file and returns chunks of up to 8kb as Stream
-    struct FileReadStream {
-        #[pin]
-        file: tokio::fs::File,
-    }
-}
-
-impl Stream for FileReadStream {
-    type Item = Result<Bytes, io::Error>;
-
-    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        let this = self.project();
-        let mut buf = [0u8; 8192];
-        let mut read_buf = ReadBuf::new(&mut buf);
-        ready!(this.file.poll_read(cx, &mut read_buf))?;
-
-        let buffer = read_buf.filled();
-        if buffer.is_empty() {
-            return Poll::Ready(None);
-        }
-
-        Poll::Ready(Some(Ok(Bytes::copy_from_slice(buffer))))
-    }
-}
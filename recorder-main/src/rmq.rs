@@ -5,7 +5,10 @@
 use anyhow::{Context as ErrorContext, Result};
 use lapin::{
     message::Delivery,
-    options::{BasicAckOptions, BasicConsumeOptions, QueueDeclareOptions},
+    options::{
+        BasicAckOptions, BasicConsumeOptions, BasicNackOptions, BasicQosOptions,
+        QueueDeclareOptions,
+    },
     types::FieldTable,
     Consumer,
 };
@@ -19,6 +22,9 @@ use crate::settings::RabbitMqSettings;
 pub struct StartRecording {
     pub room: String,
     pub breakout: Option<String>,
+    /// Tenant specific theme which is parsed by the compositor
+    #[serde(default)]
+    pub theme: Option<serde_json::Value>,
 }
 
 pub async fn connect_rabbitmq(settings: &RabbitMqSettings) -> Result<Consumer> {
@@ -32,6 +38,13 @@ pub async fn connect_rabbitmq(settings: &RabbitMqSettings) -> Result<Consumer> {
 
     let rmq_channel = rmq_conn.create_channel().await?;
 
+    // requests are only taken from the queue one after another, so the requests a recorder
+    // cannot handle stay available to other recorders
+    rmq_channel
+        .basic_qos(1, BasicQosOptions::default())
+        .await
+        .context("Failed to set prefetch count of RMQ channel")?;
+
     let queue = rmq_channel
         .queue_declare(
             &settings.queue,
@@ -51,6 +64,17 @@ pub async fn connect_rabbitmq(settings: &RabbitMqSettings) -> Result<Consumer> {
         .context("Failed to create consumer for RMQ channel")
 }
 
+/// Put a request back into the queue so another recorder can handle it.
+pub async fn requeue_delivery(delivery: &Delivery) -> Result<()> {
+    delivery
+        .nack(BasicNackOptions {
+            requeue: true,
+            ..Default::default()
+        })
+        .await
+        .context("failed to NACK")
+}
+
 pub async fn handle_delivery(delivery: &Delivery) -> Result<StartRecording> {
     delivery
         .ack(BasicAckOptions::default())
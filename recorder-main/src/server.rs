@@ -0,0 +1,104 @@
+// SPDX-FileCopyrightText: OpenTalk GmbH <mail@opentalk.eu>
+//
+// SPDX-License-Identifier: EUPL-1.2
+
+//! Embedded HTTP server which exposes the health, the running sessions and the metrics of the
+//! recorder.
+
+use anyhow::{Context, Result};
+use axum::{
+    extract::{Path, State},
+    http::{header::CONTENT_TYPE, StatusCode},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use tokio::sync::watch;
+
+use crate::{recorder::Recorder, settings::HttpSettings};
+
+/// Content type of the Prometheus text format
+const METRICS_CONTENT_TYPE: &str = "text/plain; version=0.0.4";
+
+/// Serve the HTTP endpoints until the shutdown signal is received.
+pub async fn run(
+    recorder: Recorder,
+    settings: &HttpSettings,
+    mut shutdown: watch::Receiver<bool>,
+) -> Result<()> {
+    let app = Router::new()
+        .route("/health", get(health))
+        .route("/ready", get(ready))
+        .route("/sessions", get(sessions))
+        .route("/sessions/:room_id/dot", post(dot))
+        .route("/metrics", get(metrics))
+        .with_state(recorder);
+
+    let server = axum::Server::try_bind(&settings.listen)
+        .with_context(|| format!("unable to listen on {}", settings.listen))?;
+    log::info!("HTTP server listening on {}", settings.listen);
+
+    server
+        .serve(app.into_make_service())
+        .with_graceful_shutdown(async move {
+            while !*shutdown.borrow() {
+                if shutdown.changed().await.is_err() {
+                    break;
+                }
+            }
+        })
+        .await
+        .context("HTTP server failed")
+}
+
+/// Healthy while all sessions answer in time.
+async fn health(State(recorder): State<Recorder>) -> (StatusCode, &'static str) {
+    if recorder
+        .status
+        .sessions()
+        .await
+        .iter()
+        .all(|session| session.is_running())
+    {
+        (StatusCode::OK, "OK")
+    } else {
+        (StatusCode::SERVICE_UNAVAILABLE, "unresponsive sessions")
+    }
+}
+
+/// Ready while recording requests are consumed.
+async fn ready(State(recorder): State<Recorder>) -> (StatusCode, &'static str) {
+    if recorder.status.is_ready() && !*recorder.shutdown.borrow() {
+        (StatusCode::OK, "OK")
+    } else {
+        (StatusCode::SERVICE_UNAVAILABLE, "not ready")
+    }
+}
+
+async fn sessions(State(recorder): State<Recorder>) -> impl IntoResponse {
+    Json(recorder.status.sessions().await)
+}
+
+/// Write DOT files of the pipeline of a room's session into `GST_DEBUG_DUMP_DOT_DIR`.
+async fn dot(State(recorder): State<Recorder>, Path(room_id): Path<String>) -> StatusCode {
+    match recorder.status.dot(&room_id).await {
+        Some(true) => StatusCode::NO_CONTENT,
+        Some(false) => StatusCode::SERVICE_UNAVAILABLE,
+        None => StatusCode::NOT_FOUND,
+    }
+}
+
+async fn metrics(State(recorder): State<Recorder>) -> Response {
+    recorder
+        .metrics
+        .sessions
+        .set(i64::try_from(recorder.status.session_count()).unwrap_or(i64::MAX));
+
+    match recorder.metrics.encode() {
+        Ok(metrics) => ([(CONTENT_TYPE, METRICS_CONTENT_TYPE)], metrics).into_response(),
+        Err(err) => {
+            log::error!("{err:?}");
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
@@ -2,16 +2,50 @@
 //
 // SPDX-License-Identifier: EUPL-1.2
 
-use compositor::{MatroskaParameters, RTMPParameters};
+use compositor::{
+    AnyLayout, HlsParameters, Loudness, MatroskaParameters, OutputProfile, RTMPParameters,
+    SrtParameters, WhipParameters,
+};
 use config::{Config, ConfigError, Environment, File, FileFormat};
 use lapin::uri::AMQPUri;
 use openidconnect::{ClientId, ClientSecret, IssuerUrl};
 use serde::{Deserialize, Deserializer};
-use std::{fmt::Display, str::FromStr};
+use std::{fmt::Display, net::SocketAddr, path::PathBuf, str::FromStr};
+
+/// Default directory which keeps recordings until they are uploaded
+const DEFAULT_SPOOL_DIR: &str = "spool";
+/// Default size of the chunks of an upload, 8 MiB
+const DEFAULT_CHUNK_SIZE: usize = 8 * 1024 * 1024;
+/// Default number of retries of an upload before it is left for the next start
+const DEFAULT_MAX_RETRIES: u32 = 10;
+/// Default estimate of the CPU cores a session uses
+const DEFAULT_SESSION_CPU_COST: f64 = 2.0;
+/// Default position of the thumbnail within the recording in seconds
+const DEFAULT_THUMBNAIL_POSITION: f64 = 10.0;
+/// Default width of the thumbnail in pixels
+const DEFAULT_THUMBNAIL_WIDTH: u32 = 640;
 
 #[derive(Clone, Debug, Default, Deserialize)]
 pub struct RecorderSettings {
     pub sinks: Vec<RecorderSink>,
+    /// Layout of the composed picture which is shared by all sinks
+    #[serde(default)]
+    pub layout: AnyLayout,
+    /// Output profile of the recorded MP4 file
+    #[serde(default)]
+    pub recording_profile: OutputProfile,
+    /// Number of audio tracks of single participants within the recorded MP4 file
+    #[serde(default)]
+    pub recording_tracks: usize,
+    /// Output profile of streams to the streaming targets of a room
+    #[serde(default)]
+    pub stream_profile: OutputProfile,
+    /// Loudness processing of the recorded audio
+    #[serde(default)]
+    pub loudness: Loudness,
+    /// Show placeholders for turned off cameras and participants who do not consent to be recorded
+    #[serde(default)]
+    pub placeholders: bool,
 }
 
 #[derive(Clone, Debug, Deserialize)]
@@ -19,8 +53,11 @@ pub struct RecorderSettings {
 #[serde(rename_all = "lowercase")]
 pub enum RecorderSink {
     Display,
+    Hls(HlsParameters),
     Matroska(MatroskaParameters),
     Rtmp(RTMPParameters),
+    Srt(SrtParameters),
+    Whip(WhipParameters),
 }
 
 #[derive(Debug, Deserialize)]
@@ -29,6 +66,14 @@ pub struct Settings {
     pub controller: ControllerSettings,
     pub rabbitmq: RabbitMqSettings,
     pub recorder: Option<RecorderSettings>,
+    #[serde(default)]
+    pub upload: UploadSettings,
+    #[serde(default)]
+    pub postprocessing: PostprocessingSettings,
+    /// HTTP server with health, sessions and metrics endpoints, disabled if missing
+    pub http: Option<HttpSettings>,
+    #[serde(default)]
+    pub limits: LimitSettings,
 }
 
 impl Settings {
@@ -71,6 +116,99 @@ impl ControllerSettings {
     }
 }
 
+/// Limits of the concurrent recording sessions
+///
+/// Requests over the limits are put back into the queue for other recorders.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct LimitSettings {
+    /// Maximum number of concurrent sessions, unlimited if missing
+    pub max_sessions: Option<usize>,
+    /// CPU cores which sessions may use, unlimited if missing
+    pub cpu_budget: Option<f64>,
+    /// Estimated CPU cores a single session uses
+    pub session_cpu_cost: f64,
+}
+
+impl Default for LimitSettings {
+    fn default() -> Self {
+        Self {
+            max_sessions: None,
+            cpu_budget: None,
+            session_cpu_cost: DEFAULT_SESSION_CPU_COST,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct HttpSettings {
+    /// Address and port the HTTP server listens on
+    pub listen: SocketAddr,
+}
+
+/// Resumable uploads of recordings
+///
+/// Missing fields are taken from the default settings when deserializing.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct UploadSettings {
+    /// Directory which keeps recordings until they are uploaded, unfinished uploads are resumed
+    /// on the next start
+    pub spool_dir: PathBuf,
+    /// Size of the chunks in bytes, at least 5 MiB
+    pub chunk_size: usize,
+    /// Number of retries after failed requests before the upload is left for the next start
+    pub max_retries: u32,
+}
+
+impl Default for UploadSettings {
+    fn default() -> Self {
+        Self {
+            spool_dir: PathBuf::from(DEFAULT_SPOOL_DIR),
+            chunk_size: DEFAULT_CHUNK_SIZE,
+            max_retries: DEFAULT_MAX_RETRIES,
+        }
+    }
+}
+
+/// Post-processing of finished recordings before they are uploaded
+///
+/// Missing fields are taken from the default settings when deserializing.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct PostprocessingSettings {
+    /// Extract a thumbnail and the media metadata and remux the recording with ffmpeg
+    pub enabled: bool,
+    /// Arrangement of the remuxed MP4 file
+    pub mp4_layout: Mp4Layout,
+    /// Position of the thumbnail in seconds, at most the middle of the recording
+    pub thumbnail_position: f64,
+    /// Width of the thumbnail in pixels, the height keeps the aspect ratio
+    pub thumbnail_width: u32,
+}
+
+impl Default for PostprocessingSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            mp4_layout: Mp4Layout::default(),
+            thumbnail_position: DEFAULT_THUMBNAIL_POSITION,
+            thumbnail_width: DEFAULT_THUMBNAIL_WIDTH,
+        }
+    }
+}
+
+/// Arrangement of a remuxed MP4 file which lets browsers play it while it is downloaded
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Mp4Layout {
+    /// Index at the start of the file
+    #[default]
+    Faststart,
+    /// Fragments which each carry their own index
+    Fragmented,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct RabbitMqSettings {
     #[serde(deserialize_with = "from_str")]
@@ -18,7 +18,9 @@ use uuid::Uuid;
 use crate::{
     http::HttpClient,
     settings::ControllerSettings,
-    signaling::incoming::{Error, MediaSessionState},
+    signaling::incoming::{
+        Error, MediaSessionState, RecordingScope, StreamStatus, StreamingTarget,
+    },
 };
 
 #[derive(Debug)]
@@ -65,6 +67,51 @@ impl ParticipantState {
         }
         self.publishing.get(&typ).copied()
     }
+
+    /// Return `true` if the participant with the given id does not consent to be recorded but
+    /// would be part of the recording within the given scope.
+    #[must_use]
+    pub fn shows_placeholder(&self, id: ParticipantId, scope: &RecordingScope) -> bool {
+        if self.consents {
+            return false;
+        }
+        match scope {
+            RecordingScope::All => true,
+            RecordingScope::PresenterScreen => false,
+            RecordingScope::Participants { participant_ids } => participant_ids.contains(&id),
+        }
+    }
+
+    /// Media of a stream of the participant with the given id which is part of the recording
+    ///
+    /// Within the presenter scope only the screen shares and the audio of the participants who
+    /// share their screen are recorded.
+    #[must_use]
+    pub fn recorded(
+        &self,
+        id: ParticipantId,
+        typ: MediaSessionType,
+        scope: &RecordingScope,
+    ) -> Option<MediaSessionState> {
+        let media_state = self.publishes(typ)?;
+
+        match scope {
+            RecordingScope::All => Some(media_state),
+            RecordingScope::PresenterScreen => match typ {
+                MediaSessionType::ScreenCapture => Some(media_state),
+                MediaSessionType::Camera => {
+                    self.publishes(MediaSessionType::ScreenCapture)
+                        .map(|_| MediaSessionState {
+                            video: false,
+                            ..media_state
+                        })
+                }
+            },
+            RecordingScope::Participants { participant_ids } => {
+                participant_ids.contains(&id).then_some(media_state)
+            }
+        }
+    }
 }
 
 /// Event emitted by [`Signaling::run`]
@@ -81,6 +128,19 @@ pub enum Event {
 
     FocusUpdate(Option<ParticipantId>),
     MediaConnectionError(Error),
+
+    RecordingStarted(RecordingScope),
+    RecordingStopped,
+    RecordingPaused,
+    RecordingResumed,
+
+    StartStreams(Vec<StreamingTarget>),
+    StreamUpdated(Uuid, StreamStatus),
+
+    SetLayout(serde_json::Value),
+
+    ChatMessage(ParticipantId, String),
+
     Close,
 }
 
@@ -235,6 +295,32 @@ impl Signaling {
                     Ok(Some(Event::MediaConnectionError(error)))
                 }
             },
+            incoming::Message::Recording(msg) => match msg {
+                incoming::RecordingMessage::Started(started) => {
+                    Ok(Some(Event::RecordingStarted(started.scope)))
+                }
+                incoming::RecordingMessage::Stopped(_) => Ok(Some(Event::RecordingStopped)),
+                incoming::RecordingMessage::Paused(_) => Ok(Some(Event::RecordingPaused)),
+                incoming::RecordingMessage::Resumed(_) => Ok(Some(Event::RecordingResumed)),
+                incoming::RecordingMessage::StartStreams(start) => {
+                    Ok(Some(Event::StartStreams(start.targets)))
+                }
+                incoming::RecordingMessage::StreamUpdated(update) => {
+                    Ok(Some(Event::StreamUpdated(update.target_id, update.status)))
+                }
+                incoming::RecordingMessage::SetLayout(set_layout) => {
+                    Ok(Some(Event::SetLayout(set_layout.layout)))
+                }
+            },
+            incoming::Message::Chat(msg) => match msg {
+                // group and private messages are not meant for the audience
+                incoming::ChatMessage::MessageSent(sent)
+                    if sent.scope == incoming::ChatScope::Global =>
+                {
+                    Ok(Some(Event::ChatMessage(sent.source, sent.content)))
+                }
+                incoming::ChatMessage::MessageSent(_) | incoming::ChatMessage::Other => Ok(None),
+            },
         }
     }
 
@@ -327,8 +413,9 @@ impl std::fmt::Display for ParticipantId {
 pub mod incoming {
 
     use super::{MediaSessionType, ParticipantId, TrickleCandidate};
-    use compositor::{StreamId, StreamStatus};
+    use compositor::StreamId;
     use serde::{Deserialize, Serialize};
+    use uuid::Uuid;
 
     #[derive(Debug, Clone, Serialize, Deserialize)]
     pub struct JoinSuccess {
@@ -399,9 +486,9 @@ pub mod incoming {
         }
     }
 
-    impl From<MediaSessionState> for StreamStatus {
+    impl From<MediaSessionState> for compositor::StreamStatus {
         fn from(state: MediaSessionState) -> Self {
-            StreamStatus {
+            compositor::StreamStatus {
                 has_audio: state.audio,
                 has_video: state.video,
             }
@@ -413,6 +500,8 @@ pub mod incoming {
     pub enum Message {
         Control(ControlMessage),
         Media(MediaMessage),
+        Recording(RecordingMessage),
+        Chat(ChatMessage),
     }
 
     #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -444,6 +533,129 @@ pub mod incoming {
         Error(Error),
     }
 
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    #[serde(rename_all = "snake_case", tag = "message")]
+    pub enum RecordingMessage {
+        Started(Started),
+        Stopped(RecordingId),
+        Paused(RecordingId),
+        Resumed(RecordingId),
+        StartStreams(StartStreams),
+        StreamUpdated(StreamUpdated),
+        SetLayout(SetLayout),
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    #[serde(rename_all = "snake_case", tag = "message")]
+    pub enum ChatMessage {
+        MessageSent(MessageSent),
+        /// Chat events which are not shown, like enabling the chat or clearing its history
+        #[serde(other)]
+        Other,
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct MessageSent {
+        pub source: ParticipantId,
+        pub content: String,
+        /// The target of group and private messages is ignored
+        pub scope: ChatScope,
+    }
+
+    #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+    #[serde(rename_all = "snake_case")]
+    pub enum ChatScope {
+        Global,
+        Group,
+        Private,
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct RecordingId {
+        pub recording_id: ParticipantId,
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct Started {
+        pub recording_id: ParticipantId,
+        /// Missing if the recording was started by an older controller
+        #[serde(default)]
+        pub scope: RecordingScope,
+    }
+
+    /// Participants whose media is recorded
+    #[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+    #[serde(tag = "kind", rename_all = "snake_case")]
+    pub enum RecordingScope {
+        /// Cameras, screen shares and audio of all participants
+        #[default]
+        All,
+        /// Screen shares and the audio of the participants who share their screen
+        PresenterScreen,
+        /// Cameras, screen shares and audio of the given participants
+        Participants { participant_ids: Vec<ParticipantId> },
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct StartStreams {
+        pub targets: Vec<StreamingTarget>,
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct StreamUpdated {
+        pub target_id: Uuid,
+        pub status: StreamStatus,
+    }
+
+    /// The layout is parsed by the compositor, so invalid layouts don't break the signaling
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct SetLayout {
+        pub layout: serde_json::Value,
+    }
+
+    #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+    #[serde(rename_all = "snake_case")]
+    pub enum StreamStatus {
+        Starting,
+        Active,
+        Paused,
+        Inactive,
+    }
+
+    /// A streaming target of the room including its credentials
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct StreamingTarget {
+        pub id: Uuid,
+        pub name: String,
+        pub kind: StreamingKind,
+        pub streaming_endpoint: String,
+        pub streaming_key: String,
+        pub public_url: String,
+    }
+
+    /// The platform of a streaming target
+    #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+    #[serde(rename_all = "snake_case")]
+    pub enum StreamingKind {
+        Custom,
+        Youtube,
+        Twitch,
+        Owncast,
+        Peertube,
+    }
+
+    impl StreamingTarget {
+        /// The RTMP location to stream to, the streaming key appended to the endpoint
+        #[must_use]
+        pub fn location(&self) -> String {
+            format!(
+                "{}/{}",
+                self.streaming_endpoint.trim_end_matches('/'),
+                self.streaming_key
+            )
+        }
+    }
+
     #[derive(Debug, Clone, Serialize, Deserialize)]
     pub struct Sdp {
         pub sdp: String,
@@ -0,0 +1,213 @@
+// SPDX-FileCopyrightText: OpenTalk GmbH <mail@opentalk.eu>
+//
+// SPDX-License-Identifier: EUPL-1.2
+
+//! Status of the recorder and its running recording sessions.
+
+use compositor::SinkState;
+use futures::future::join_all;
+use serde::Serialize;
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex, MutexGuard, PoisonError,
+    },
+    time::Duration,
+};
+use tokio::{
+    sync::{mpsc, oneshot},
+    time::timeout,
+};
+use uuid::Uuid;
+
+/// Time a session has to answer a query before it is considered unresponsive
+const QUERY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Query which is answered by a running recording session
+#[derive(Debug)]
+pub enum SessionQuery {
+    /// Current state of the session
+    Status(oneshot::Sender<SessionStatus>),
+    /// Write DOT files of the session's pipeline
+    Dot(oneshot::Sender<()>),
+}
+
+/// State of a running recording session
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionStatus {
+    pub session_id: Uuid,
+    pub room_id: String,
+    /// Time since the session has been started in seconds
+    pub duration: f64,
+    /// `true` while the room is recorded into a file
+    pub recording: bool,
+    /// Linked output sinks
+    pub sinks: Vec<SinkStatus>,
+}
+
+/// State of an output sink of a session
+#[derive(Debug, Clone, Serialize)]
+pub struct SinkStatus {
+    pub name: String,
+    pub paused: bool,
+    /// Position within the output in seconds without the paused time
+    pub position: f64,
+    /// Bytes which arrived at the sink
+    pub bytes_written: u64,
+}
+
+impl From<SinkState> for SinkStatus {
+    fn from(sink: SinkState) -> Self {
+        Self {
+            name: sink.name,
+            paused: sink.paused,
+            position: Duration::from_nanos(sink.position.nseconds()).as_secs_f64(),
+            bytes_written: sink.bytes_written,
+        }
+    }
+}
+
+/// Entry of the session list, sessions which do not answer in time are considered stalled
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum SessionEntry {
+    Running(SessionStatus),
+    Unresponsive { session_id: Uuid, room_id: String },
+}
+
+impl SessionEntry {
+    /// Returns `true` if the session answered in time.
+    #[must_use]
+    pub fn is_running(&self) -> bool {
+        matches!(self, Self::Running(_))
+    }
+}
+
+/// Readiness and running sessions of the recorder
+#[derive(Debug, Default)]
+pub struct Status {
+    /// `true` while the recorder consumes recording requests
+    ready: AtomicBool,
+    /// Running sessions by their id, a room can have several sessions at once
+    sessions: Mutex<HashMap<Uuid, RegisteredSession>>,
+}
+
+/// Room and query channel of a running session
+#[derive(Debug, Clone)]
+struct RegisteredSession {
+    room_id: String,
+    queries: mpsc::Sender<SessionQuery>,
+}
+
+impl Status {
+    fn sessions_lock(&self) -> MutexGuard<'_, HashMap<Uuid, RegisteredSession>> {
+        // the map is always consistent, a poisoned lock can be used anyway
+        self.sessions.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Set whether the recorder consumes recording requests.
+    pub fn set_ready(&self, ready: bool) {
+        self.ready.store(ready, Ordering::Relaxed);
+    }
+
+    /// Returns `true` if the recorder consumes recording requests.
+    #[must_use]
+    pub fn is_ready(&self) -> bool {
+        self.ready.load(Ordering::Relaxed)
+    }
+
+    /// Register the query channel of a running session.
+    pub fn add_session(
+        &self,
+        session_id: Uuid,
+        room_id: &str,
+        queries: mpsc::Sender<SessionQuery>,
+    ) {
+        self.sessions_lock().insert(
+            session_id,
+            RegisteredSession {
+                room_id: room_id.to_string(),
+                queries,
+            },
+        );
+    }
+
+    /// Remove a session, other sessions of the same room are kept.
+    pub fn remove_session(&self, session_id: Uuid) {
+        self.sessions_lock().remove(&session_id);
+    }
+
+    /// Number of running sessions.
+    #[must_use]
+    pub fn session_count(&self) -> usize {
+        self.sessions_lock().len()
+    }
+
+    /// Query the state of all running sessions ordered by their rooms.
+    pub async fn sessions(&self) -> Vec<SessionEntry> {
+        let mut sessions: Vec<(Uuid, RegisteredSession)> = self
+            .sessions_lock()
+            .iter()
+            .map(|(session_id, session)| (*session_id, session.clone()))
+            .collect();
+        sessions.sort_by(|(a_id, a), (b_id, b)| (&a.room_id, a_id).cmp(&(&b.room_id, b_id)));
+
+        join_all(
+            sessions
+                .into_iter()
+                .map(|(session_id, session)| async move {
+                    match query(&session.queries, SessionQuery::Status).await {
+                        Some(status) => SessionEntry::Running(status),
+                        None => SessionEntry::Unresponsive {
+                            session_id,
+                            room_id: session.room_id,
+                        },
+                    }
+                }),
+        )
+        .await
+    }
+
+    /// Let the sessions of the given room write DOT files of their pipelines.
+    ///
+    /// Returns `None` if there is no session of this room, `Some(false)` if a session did not
+    /// answer in time.
+    pub async fn dot(&self, room_id: &str) -> Option<bool> {
+        let queries: Vec<mpsc::Sender<SessionQuery>> = self
+            .sessions_lock()
+            .values()
+            .filter(|session| session.room_id == room_id)
+            .map(|session| session.queries.clone())
+            .collect();
+
+        if queries.is_empty() {
+            return None;
+        }
+
+        let answers = join_all(
+            queries
+                .iter()
+                .map(|queries| query(queries, SessionQuery::Dot)),
+        )
+        .await;
+
+        Some(answers.iter().all(Option::is_some))
+    }
+}
+
+/// Send a query to a session and wait for its answer.
+async fn query<T>(
+    queries: &mpsc::Sender<SessionQuery>,
+    query: impl FnOnce(oneshot::Sender<T>) -> SessionQuery,
+) -> Option<T> {
+    let (sender, receiver) = oneshot::channel();
+
+    timeout(QUERY_TIMEOUT, async {
+        queries.send(query(sender)).await.ok()?;
+        receiver.await.ok()
+    })
+    .await
+    .ok()
+    .flatten()
+}
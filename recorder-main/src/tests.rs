@@ -29,6 +29,7 @@ async fn basic_test() {
         StartRecording {
             room: "0".to_string(),
             breakout: None,
+            theme: None,
         },
     )
     .await
@@ -0,0 +1,227 @@
+// SPDX-FileCopyrightText: OpenTalk GmbH <mail@opentalk.eu>
+//
+// SPDX-License-Identifier: EUPL-1.2
+
+//! Chapters and speaker timeline of a recording.
+
+use anyhow::{Context, Result};
+use serde::{Serialize, Serializer};
+use std::{fmt::Write, time::Duration};
+
+use crate::signaling::ParticipantId;
+
+/// Minimum length of a chapter, shorter chapters are merged into the previous one
+const MIN_CHAPTER_DURATION: Duration = Duration::from_secs(10);
+
+/// Kind of a timeline entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EntryKind {
+    /// The participant was the active speaker
+    Speech,
+    /// The participant shared the screen
+    ScreenShare,
+}
+
+/// Something a participant did within the recording
+#[derive(Debug, Clone, Serialize)]
+pub struct Entry {
+    pub kind: EntryKind,
+    pub participant_id: ParticipantId,
+    pub display_name: String,
+    /// Start within the recording in seconds
+    #[serde(serialize_with = "serialize_secs")]
+    pub start: Duration,
+    /// End within the recording in seconds, `None` while running
+    #[serde(serialize_with = "serialize_optional_secs")]
+    pub end: Option<Duration>,
+}
+
+/// Timeline of speakers and screen shares within a recording
+///
+/// All positions are relative to the start of the recording without the paused time.
+#[derive(Debug, Default, Serialize)]
+pub struct Timeline {
+    /// Length of the recording in seconds, `None` while recording
+    #[serde(serialize_with = "serialize_optional_secs")]
+    duration: Option<Duration>,
+    entries: Vec<Entry>,
+}
+
+impl Timeline {
+    /// Returns `true` if nothing happened within the recording.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Start an entry of the given participant, the running speech ends if a new speaker starts.
+    pub fn start(
+        &mut self,
+        kind: EntryKind,
+        participant_id: ParticipantId,
+        display_name: &str,
+        position: Duration,
+    ) {
+        if self
+            .running(kind)
+            .any(|entry| entry.participant_id == participant_id)
+        {
+            return;
+        }
+
+        if kind == EntryKind::Speech {
+            self.end_all(Some(kind), None, position);
+        }
+
+        self.entries.push(Entry {
+            kind,
+            participant_id,
+            display_name: display_name.to_string(),
+            start: position,
+            end: None,
+        });
+    }
+
+    /// End the running entry of the given kind and participant.
+    pub fn end(&mut self, kind: EntryKind, participant_id: ParticipantId, position: Duration) {
+        self.end_all(Some(kind), Some(participant_id), position);
+    }
+
+    /// End the running speech.
+    pub fn end_speech(&mut self, position: Duration) {
+        self.end_all(Some(EntryKind::Speech), None, position);
+    }
+
+    /// End all running entries of the given participant.
+    pub fn end_participant(&mut self, participant_id: ParticipantId, position: Duration) {
+        self.end_all(None, Some(participant_id), position);
+    }
+
+    /// End all running entries at the end of the recording.
+    pub fn finish(&mut self, duration: Duration) {
+        self.end_all(None, None, duration);
+        self.duration = Some(duration);
+    }
+
+    fn running(&self, kind: EntryKind) -> impl Iterator<Item = &Entry> {
+        self.entries
+            .iter()
+            .filter(move |entry| entry.kind == kind && entry.end.is_none())
+    }
+
+    fn end_all(
+        &mut self,
+        kind: Option<EntryKind>,
+        participant_id: Option<ParticipantId>,
+        position: Duration,
+    ) {
+        for entry in &mut self.entries {
+            if entry.end.is_none()
+                && kind.map_or(true, |kind| entry.kind == kind)
+                && participant_id.map_or(true, |id| entry.participant_id == id)
+            {
+                entry.end = Some(position.max(entry.start));
+            }
+        }
+
+        // entries which ended right away happened while the recording was paused
+        self.entries
+            .retain(|entry| entry.end.map_or(true, |end| end > entry.start));
+    }
+
+    /// Speaker timeline as JSON document.
+    ///
+    /// # Errors
+    ///
+    /// This can fail if the timeline cannot be serialized.
+    pub fn to_json(&self) -> Result<Vec<u8>> {
+        serde_json::to_vec_pretty(self).context("unable to serialize speaker timeline")
+    }
+
+    /// Chapters as WebVTT document.
+    ///
+    /// Every speaker change and screen share starts a new chapter, chapters shorter than
+    /// [`MIN_CHAPTER_DURATION`] are merged into the previous chapter.
+    #[must_use]
+    pub fn to_webvtt(&self) -> String {
+        let mut marks: Vec<(Duration, String)> = Vec::new();
+
+        let mut entries: Vec<&Entry> = self.entries.iter().collect();
+        entries.sort_by_key(|entry| entry.start);
+
+        for entry in entries {
+            let title = match entry.kind {
+                EntryKind::Speech => entry.display_name.clone(),
+                EntryKind::ScreenShare => format!("Screen share of {}", entry.display_name),
+            };
+
+            if marks
+                .last()
+                .is_some_and(|(last, _)| entry.start.saturating_sub(*last) < MIN_CHAPTER_DURATION)
+            {
+                marks.pop();
+            }
+
+            // the first chapter always starts at the beginning of the recording
+            let start = if marks.is_empty() {
+                Duration::ZERO
+            } else {
+                entry.start
+            };
+
+            if marks
+                .last()
+                .map_or(true, |(_, previous)| *previous != title)
+            {
+                marks.push((start, title));
+            }
+        }
+
+        let end = self
+            .duration
+            .or_else(|| self.entries.iter().filter_map(|entry| entry.end).max())
+            .unwrap_or_default();
+
+        let mut vtt = String::from("WEBVTT\n");
+        for (index, (start, title)) in marks.iter().enumerate() {
+            let next = marks.get(index + 1).map_or(end, |(next, _)| *next);
+            let _ = write!(
+                vtt,
+                "\n{number}\n{start} --> {end}\n{title}\n",
+                number = index + 1,
+                start = vtt_timestamp(*start),
+                end = vtt_timestamp(next.max(*start)),
+            );
+        }
+
+        vtt
+    }
+}
+
+/// Format a position as WebVTT timestamp `hh:mm:ss.ttt`
+fn vtt_timestamp(position: Duration) -> String {
+    let secs = position.as_secs();
+    format!(
+        "{:02}:{:02}:{:02}.{:03}",
+        secs / 3600,
+        secs / 60 % 60,
+        secs % 60,
+        position.subsec_millis()
+    )
+}
+
+#[allow(clippy::trivially_copy_pass_by_ref)]
+fn serialize_secs<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_f64(duration.as_secs_f64())
+}
+
+fn serialize_optional_secs<S: Serializer>(
+    duration: &Option<Duration>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match duration {
+        Some(duration) => serializer.serialize_some(&duration.as_secs_f64()),
+        None => serializer.serialize_none(),
+    }
+}
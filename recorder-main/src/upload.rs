@@ -0,0 +1,379 @@
+// SPDX-FileCopyrightText: OpenTalk GmbH <mail@opentalk.eu>
+//
+// SPDX-License-Identifier: EUPL-1.2
+
+//! Resumable uploads of recordings which are spooled on disk until they are uploaded.
+//!
+//! Every recording gets an entry in the spool directory with the recorded file, its sidecar files
+//! and a manifest which keeps the progress. Recordings are post-processed once before their upload
+//! starts. Entries are removed when all files are uploaded, entries left by a previous run are
+//! resumed on start.
+
+use anyhow::{bail, Context, Result};
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
+use std::{
+    io::SeekFrom,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+use tokio::{
+    fs::{self, File},
+    io::{AsyncReadExt, AsyncSeekExt},
+    time::sleep,
+};
+use uuid::Uuid;
+
+use crate::{
+    http::{HttpClient, RenderKind, UploadRenderQuery},
+    postprocess::{self, MediaMetadata},
+    settings::{ControllerSettings, PostprocessingSettings, UploadSettings},
+};
+
+/// Name of the manifest within a spool entry
+const MANIFEST: &str = "manifest.json";
+/// Name of the thumbnail extracted from the recording
+const THUMBNAIL: &str = "thumbnail.jpg";
+/// Smallest chunk size accepted by the controller, except for the last chunk
+const MIN_CHUNK_SIZE: usize = 5 * 1024 * 1024;
+/// Largest chunk size accepted by the controller
+const MAX_CHUNK_SIZE: usize = 64 * 1024 * 1024;
+/// Delay before the first retry, doubled with every further retry
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+/// Longest delay between two retries
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// Progress of a spooled upload, stored next to its files
+#[derive(Debug, Serialize, Deserialize)]
+struct Manifest {
+    room_id: String,
+    /// The recording followed by its sidecar files
+    files: Vec<SpooledFile>,
+    /// Set once the recording has been post-processed
+    #[serde(default)]
+    processed: bool,
+    /// Duration and resolution of the recording, known after post-processing
+    #[serde(default)]
+    metadata: Option<MediaMetadata>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SpooledFile {
+    /// Name of the file within the spool entry and within the room's assets
+    filename: String,
+    kind: RenderKind,
+    /// Resumable upload at the controller, `None` until the upload has been started
+    upload_id: Option<Uuid>,
+    /// Created asset, `None` until the upload has been completed
+    asset_id: Option<Uuid>,
+}
+
+/// Files of a recording within the spool directory
+#[derive(Debug)]
+pub struct SpooledUpload {
+    dir: PathBuf,
+    manifest: Manifest,
+}
+
+impl SpooledUpload {
+    /// Move a recording into a new entry of the spool directory and write its sidecar files
+    /// next to it.
+    pub async fn create(
+        spool_dir: &Path,
+        room_id: &str,
+        recording: &Path,
+        sidecars: Vec<(&str, RenderKind, Vec<u8>)>,
+    ) -> Result<Self> {
+        let dir = spool_dir.join(Uuid::new_v4().to_string());
+        fs::create_dir_all(&dir)
+            .await
+            .with_context(|| format!("unable to create spool entry {dir:?}"))?;
+
+        let mut files = vec![SpooledFile {
+            filename: "recording.mp4".to_string(),
+            kind: RenderKind::Render,
+            upload_id: None,
+            asset_id: None,
+        }];
+
+        // the temporary directory might be on another file system
+        let target = dir.join(&files[0].filename);
+        if fs::rename(recording, &target).await.is_err() {
+            fs::copy(recording, &target)
+                .await
+                .with_context(|| format!("unable to move recording {recording:?} into spool"))?;
+            fs::remove_file(recording).await?;
+        }
+
+        for (filename, kind, data) in sidecars {
+            fs::write(dir.join(filename), data)
+                .await
+                .with_context(|| format!("unable to write {filename} into spool"))?;
+            files.push(SpooledFile {
+                filename: filename.to_string(),
+                kind,
+                upload_id: None,
+                asset_id: None,
+            });
+        }
+
+        let upload = Self {
+            dir,
+            manifest: Manifest {
+                room_id: room_id.to_string(),
+                files,
+                processed: false,
+                metadata: None,
+            },
+        };
+        upload.save().await?;
+
+        Ok(upload)
+    }
+
+    /// Entries of the spool directory which have not been uploaded completely.
+    pub async fn pending(spool_dir: &Path) -> Result<Vec<Self>> {
+        let mut pending = Vec::new();
+
+        let mut entries = match fs::read_dir(spool_dir).await {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(pending),
+            Err(err) => return Err(err).context("unable to read spool directory"),
+        };
+
+        while let Some(entry) = entries.next_entry().await? {
+            let dir = entry.path();
+            let manifest = match fs::read(dir.join(MANIFEST)).await {
+                Ok(manifest) => manifest,
+                Err(err) => {
+                    log::warn!("Skipping spool entry {dir:?} without manifest, {err}");
+                    continue;
+                }
+            };
+
+            match serde_json::from_slice(&manifest) {
+                Ok(manifest) => pending.push(Self { dir, manifest }),
+                Err(err) => log::warn!("Skipping spool entry {dir:?} with invalid manifest, {err}"),
+            }
+        }
+
+        Ok(pending)
+    }
+
+    /// Room the recording belongs to
+    pub fn room_id(&self) -> &str {
+        &self.manifest.room_id
+    }
+
+    /// Remux the recording, read its metadata and extract a thumbnail, once before its upload
+    /// starts.
+    ///
+    /// Failed steps are logged and skipped, the recording is uploaded as it is then.
+    pub async fn postprocess(&mut self, settings: &PostprocessingSettings) -> Result<()> {
+        // a recording which is partly uploaded already must not change anymore
+        if !settings.enabled
+            || self.manifest.processed
+            || self.manifest.files[0].upload_id.is_some()
+        {
+            return Ok(());
+        }
+
+        let recording = self.dir.join(&self.manifest.files[0].filename);
+
+        if let Err(err) = postprocess::remux(&recording, settings.mp4_layout).await {
+            log::warn!("Unable to remux recording {recording:?}, {err:?}");
+        }
+
+        match postprocess::probe(&recording).await {
+            Ok(metadata) => {
+                self.manifest.metadata = Some(metadata);
+
+                if metadata.has_video() {
+                    let thumbnail = self.dir.join(THUMBNAIL);
+                    match postprocess::extract_thumbnail(
+                        &recording, &thumbnail, &metadata, settings,
+                    )
+                    .await
+                    {
+                        Ok(()) => self.manifest.files.push(SpooledFile {
+                            filename: THUMBNAIL.to_string(),
+                            kind: RenderKind::Thumbnail,
+                            upload_id: None,
+                            asset_id: None,
+                        }),
+                        Err(err) => {
+                            log::warn!("Unable to extract thumbnail of {recording:?}, {err:?}");
+                        }
+                    }
+                }
+            }
+            Err(err) => log::warn!("Unable to probe recording {recording:?}, {err:?}"),
+        }
+
+        self.manifest.processed = true;
+        self.save().await
+    }
+
+    /// Upload all files which have not been uploaded yet and remove the spool entry.
+    ///
+    /// Failed requests are retried with an increasing delay, the entry is kept for the next
+    /// start if the retries are exhausted. Sidecar files are linked to the uploaded recording.
+    pub async fn upload(
+        mut self,
+        http_client: &HttpClient,
+        controller: &ControllerSettings,
+        settings: &UploadSettings,
+    ) -> Result<()> {
+        for index in 0..self.manifest.files.len() {
+            if self.manifest.files[index].asset_id.is_some() {
+                continue;
+            }
+
+            let mut retries = 0;
+            let mut backoff = INITIAL_BACKOFF;
+            loop {
+                let mut progressed = false;
+                match self
+                    .upload_file(index, http_client, controller, settings, &mut progressed)
+                    .await
+                {
+                    Ok(()) => break,
+                    Err(err) => {
+                        // only consecutive failures without progress count
+                        if progressed {
+                            retries = 0;
+                            backoff = INITIAL_BACKOFF;
+                        }
+
+                        if retries >= settings.max_retries {
+                            return Err(err).with_context(|| {
+                                format!(
+                                    "upload of {} failed, keeping it in {:?}",
+                                    self.manifest.files[index].filename, self.dir
+                                )
+                            });
+                        }
+
+                        log::warn!(
+                            "Upload of {} failed, retrying in {backoff:?}, {err:?}",
+                            self.manifest.files[index].filename
+                        );
+                        sleep(backoff).await;
+                        retries += 1;
+                        backoff = (backoff * 2).min(MAX_BACKOFF);
+                    }
+                }
+            }
+        }
+
+        fs::remove_dir_all(&self.dir)
+            .await
+            .with_context(|| format!("unable to remove spool entry {:?}", self.dir))
+    }
+
+    /// Upload the remaining chunks of a file, starting a new upload if there is none or it has
+    /// expired.
+    async fn upload_file(
+        &mut self,
+        index: usize,
+        http_client: &HttpClient,
+        controller: &ControllerSettings,
+        settings: &UploadSettings,
+        progressed: &mut bool,
+    ) -> Result<()> {
+        let recording = self.manifest.files[0].asset_id;
+        let file = &self.manifest.files[index];
+
+        let status = match file.upload_id {
+            Some(upload_id) => http_client.upload_status(controller, upload_id).await?,
+            None => None,
+        };
+
+        let status = match status {
+            Some(status) => status,
+            None => {
+                let (linked_asset_id, metadata) = if file.kind == RenderKind::Render {
+                    (None, self.manifest.metadata)
+                } else {
+                    (recording, None)
+                };
+
+                let status = http_client
+                    .start_upload(
+                        controller,
+                        UploadRenderQuery {
+                            room_id: &self.manifest.room_id,
+                            filename: &file.filename,
+                            kind: file.kind,
+                            linked_asset_id,
+                            duration_ms: metadata.map(|metadata| metadata.duration_ms),
+                            width: metadata.and_then(|metadata| metadata.width),
+                            height: metadata.and_then(|metadata| metadata.height),
+                        },
+                    )
+                    .await?;
+
+                self.manifest.files[index].upload_id = Some(status.upload_id);
+                self.save().await?;
+
+                status
+            }
+        };
+
+        let path = self.dir.join(&self.manifest.files[index].filename);
+        let mut file = File::open(&path)
+            .await
+            .with_context(|| format!("unable to open spooled file {path:?}"))?;
+        let len = file.metadata().await?.len();
+
+        if status.offset > len {
+            bail!("controller received more than the {len} bytes of {path:?}");
+        }
+
+        file.seek(SeekFrom::Start(status.offset)).await?;
+
+        let chunk_size = settings.chunk_size.clamp(MIN_CHUNK_SIZE, MAX_CHUNK_SIZE);
+        let mut offset = status.offset;
+        while offset < len {
+            let mut chunk = Vec::with_capacity(chunk_size);
+            (&mut file)
+                .take(chunk_size as u64)
+                .read_to_end(&mut chunk)
+                .await?;
+
+            let status = http_client
+                .upload_chunk(controller, status.upload_id, offset, Bytes::from(chunk))
+                .await?;
+
+            *progressed = true;
+            offset = status.offset;
+            file.seek(SeekFrom::Start(offset)).await?;
+        }
+
+        let asset_id = http_client
+            .complete_upload(controller, status.upload_id)
+            .await?;
+
+        log::debug!(
+            "Uploaded {:?} for room '{}' as asset {asset_id}",
+            path,
+            self.manifest.room_id
+        );
+
+        self.manifest.files[index].asset_id = Some(asset_id);
+        self.save().await
+    }
+
+    /// Write the manifest, replacing the previous one at once
+    async fn save(&self) -> Result<()> {
+        let manifest =
+            serde_json::to_vec_pretty(&self.manifest).context("unable to serialize manifest")?;
+
+        let path = self.dir.join(MANIFEST);
+        let temp_path = path.with_extension("tmp");
+        fs::write(&temp_path, manifest).await?;
+        fs::rename(&temp_path, &path)
+            .await
+            .with_context(|| format!("unable to write manifest {path:?}"))
+    }
+}
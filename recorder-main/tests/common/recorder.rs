@@ -40,6 +40,10 @@ pub(crate) async fn start_recorder(websocket_addr: SocketAddr, shutdown_rx: watc
         controller,
         rabbitmq,
         recorder: None,
+        upload: Default::default(),
+        postprocessing: Default::default(),
+        http: None,
+        limits: Default::default(),
     };
     let client = reqwest::Client::new();
 